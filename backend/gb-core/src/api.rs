@@ -11,16 +11,24 @@ use crate::memory::Memory;
 use crate::ppu::Ppu;
 use crate::serial::SerialPort;
 use crate::sm83::Sm83;
+#[cfg(any(test, feature = "perf"))]
+use crate::speed::CpuSpeed;
 use crate::speed::SpeedRegister;
 use crate::timer::GbTimer;
 use crate::{ppu, HardwareMode};
 use bincode::{Decode, Encode};
 use jgenesis_common::frontend::{
-    AudioOutput, Color, EmulatorTrait, PixelAspectRatio, Renderer, SaveWriter, TickEffect,
-    TickResult, TimingMode,
+    AudioOutput, Color, EmulatorTrait, MemoryRegion, PixelAspectRatio, Renderer, SaveWriter,
+    TickEffect, TickResult, TimingMode,
 };
+#[cfg(any(test, feature = "perf"))]
+use jgenesis_common::frontend::PerfCounters;
+use jgenesis_common::rng::Rng;
+use jgenesis_common::save_state::VersionedState;
 use jgenesis_proc_macros::{EnumDisplay, EnumFromStr, PartialClone};
 use std::fmt::{Debug, Display};
+#[cfg(any(test, feature = "perf"))]
+use std::mem;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -84,6 +92,8 @@ pub struct GameBoyEmulatorConfig {
     pub gb_palette: GbPalette,
     pub gbc_color_correction: GbcColorCorrection,
     pub audio_60hz_hack: bool,
+    /// Seed for all pseudorandom power-on state; currently only the initial RAM contents
+    pub rng_seed: u64,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -111,6 +121,9 @@ pub struct GameBoyEmulator {
     rgba_buffer: RgbaFrameBuffer,
     config: GameBoyEmulatorConfig,
     frame_count: u64,
+    // Always compiled in tests so the counters are exercised without the `perf` feature
+    #[cfg(any(test, feature = "perf"))]
+    perf_counters: PerfCounters,
 }
 
 impl GameBoyEmulator {
@@ -121,6 +134,30 @@ impl GameBoyEmulator {
         rom: Vec<u8>,
         config: GameBoyEmulatorConfig,
         save_writer: &mut S,
+    ) -> Result<Self, GameBoyLoadError> {
+        Self::create_inner(rom, None, config, save_writer)
+    }
+
+    /// Create an emulator instance that starts by executing the given boot ROM instead of jumping
+    /// straight to the cartridge entry point.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if it cannot load the ROM (e.g. unsupported mapper).
+    pub fn create_with_boot_rom<S: SaveWriter>(
+        rom: Vec<u8>,
+        boot_rom: Vec<u8>,
+        config: GameBoyEmulatorConfig,
+        save_writer: &mut S,
+    ) -> Result<Self, GameBoyLoadError> {
+        Self::create_inner(rom, Some(boot_rom), config, save_writer)
+    }
+
+    fn create_inner<S: SaveWriter>(
+        rom: Vec<u8>,
+        boot_rom: Option<Vec<u8>>,
+        config: GameBoyEmulatorConfig,
+        save_writer: &mut S,
     ) -> Result<Self, GameBoyLoadError> {
         let software_type = SoftwareType::from_rom(&rom);
 
@@ -134,12 +171,21 @@ impl GameBoyEmulator {
 
         log::info!("Running with hardware mode {hardware_mode}");
 
+        let mut memory = Memory::new(&mut Rng::new(config.rng_seed));
+        let cpu = match boot_rom {
+            Some(boot_rom) => {
+                memory.map_boot_rom(boot_rom);
+                Sm83::new_with_boot_rom()
+            }
+            None => Sm83::new(hardware_mode, config.pretend_to_be_gba),
+        };
+
         Ok(Self {
             hardware_mode,
-            cpu: Sm83::new(hardware_mode, config.pretend_to_be_gba),
+            cpu,
             ppu: Ppu::new(hardware_mode),
             apu: Apu::new(config, hardware_mode),
-            memory: Memory::new(),
+            memory,
             serial_port: SerialPort::new(hardware_mode),
             interrupt_registers: InterruptRegisters::default(),
             speed_register: SpeedRegister::new(),
@@ -150,6 +196,8 @@ impl GameBoyEmulator {
             rgba_buffer: RgbaFrameBuffer::default(),
             config,
             frame_count: 0,
+            #[cfg(any(test, feature = "perf"))]
+            perf_counters: PerfCounters::default(),
         })
     }
 
@@ -176,6 +224,37 @@ impl GameBoyEmulator {
     pub fn is_cgb_mode(&self) -> bool {
         self.hardware_mode == HardwareMode::Cgb
     }
+
+    /// Return the performance counters accumulated since the last call, and reset them to zero.
+    ///
+    /// Master clock cycles are counted at the single-speed CPU clock rate of 4.19 MHz.
+    #[cfg(any(test, feature = "perf"))]
+    pub fn perf_counters(&mut self) -> PerfCounters {
+        mem::take(&mut self.perf_counters)
+    }
+}
+
+impl VersionedState for GameBoyEmulator {
+    const SYSTEM_ID: [u8; 4] = *b"GMBY";
+    const STATE_VERSION: u32 = 1;
+}
+
+// Memory region IDs for EmulatorTrait::read_region, as indices into MEMORY_REGIONS
+const WORK_RAM_REGION_ID: usize = 0;
+const HIGH_RAM_REGION_ID: usize = 1;
+const CARTRIDGE_RAM_REGION_ID: usize = 2;
+
+// (name, base address) of each memory region, in region ID order
+const MEMORY_REGIONS: [(&str, u32); 3] =
+    [("Work RAM", 0xC000), ("High RAM", 0xFF80), ("Cartridge RAM", 0xA000)];
+
+fn memory_region_bytes(emulator: &GameBoyEmulator, region_id: usize) -> Option<&[u8]> {
+    match region_id {
+        WORK_RAM_REGION_ID => Some(emulator.memory.main_ram()),
+        HIGH_RAM_REGION_ID => Some(emulator.memory.hram()),
+        CARTRIDGE_RAM_REGION_ID => Some(emulator.cartridge.sram()),
+        _ => None,
+    }
 }
 
 impl EmulatorTrait for GameBoyEmulator {
@@ -204,19 +283,42 @@ impl EmulatorTrait for GameBoyEmulator {
     {
         self.input_state.set_inputs(*inputs);
 
-        self.cpu.execute_instruction(&mut Bus {
-            hardware_mode: self.hardware_mode,
-            ppu: &mut self.ppu,
-            apu: &mut self.apu,
-            memory: &mut self.memory,
-            serial_port: &mut self.serial_port,
-            cartridge: &mut self.cartridge,
-            interrupt_registers: &mut self.interrupt_registers,
-            speed_register: &mut self.speed_register,
-            timer: &mut self.timer,
-            dma_unit: &mut self.dma_unit,
-            input_state: &mut self.input_state,
-        });
+        #[cfg(any(test, feature = "perf"))]
+        let cpu_speed = self.speed_register.speed;
+
+        #[cfg_attr(not(any(test, feature = "perf")), allow(unused_variables))]
+        let m_cycles = self.cpu.execute_instruction_traced(
+            &mut Bus {
+                hardware_mode: self.hardware_mode,
+                ppu: &mut self.ppu,
+                apu: &mut self.apu,
+                memory: &mut self.memory,
+                serial_port: &mut self.serial_port,
+                cartridge: &mut self.cartridge,
+                interrupt_registers: &mut self.interrupt_registers,
+                speed_register: &mut self.speed_register,
+                timer: &mut self.timer,
+                dma_unit: &mut self.dma_unit,
+                input_state: &mut self.input_state,
+            },
+            |_| {
+                // Only called for instructions that actually execute, not for interrupts or HALT
+                #[cfg(any(test, feature = "perf"))]
+                {
+                    self.perf_counters.instructions += 1;
+                }
+            },
+        );
+
+        #[cfg(any(test, feature = "perf"))]
+        {
+            // An M-cycle is 4 master clock cycles at normal speed and 2 at double speed
+            let mclk_per_m_cycle = match cpu_speed {
+                CpuSpeed::Normal => 4,
+                CpuSpeed::Double => 2,
+            };
+            self.perf_counters.master_clock_cycles += u64::from(m_cycles) * mclk_per_m_cycle;
+        }
 
         self.input_state.check_for_joypad_interrupt(&mut self.interrupt_registers);
 
@@ -304,4 +406,69 @@ impl EmulatorTrait for GameBoyEmulator {
     fn timing_mode(&self) -> TimingMode {
         TimingMode::Ntsc
     }
+
+    fn memory_regions(&self) -> Vec<MemoryRegion> {
+        MEMORY_REGIONS
+            .iter()
+            .enumerate()
+            .map(|(region_id, &(name, base_address))| {
+                let len = memory_region_bytes(self, region_id).map_or(0, <[u8]>::len);
+                MemoryRegion::new(name, base_address, len)
+            })
+            .collect()
+    }
+
+    fn read_region(&self, region_id: usize, offset: usize) -> Option<u8> {
+        memory_region_bytes(self, region_id)?.get(offset).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jgenesis_common::testing::{NullAudioOutput, NullRenderer, NullSaveWriter};
+
+    fn new_emulator() -> GameBoyEmulator {
+        // 32KB ROM with no mapper and no SRAM; JR -2 (infinite loop) at the entry point
+        let mut rom = vec![0; 32 * 1024];
+        rom[0x0100..0x0102].copy_from_slice(&[0x18, 0xFE]);
+
+        let config = GameBoyEmulatorConfig {
+            force_dmg_mode: false,
+            pretend_to_be_gba: false,
+            aspect_ratio: GbAspectRatio::default(),
+            gb_palette: GbPalette::default(),
+            gbc_color_correction: GbcColorCorrection::default(),
+            audio_60hz_hack: false,
+            rng_seed: 0,
+        };
+        GameBoyEmulator::create(rom, config, &mut NullSaveWriter).unwrap()
+    }
+
+    #[test]
+    fn perf_counters() {
+        let mut emulator = new_emulator();
+        emulator
+            .run_frames(
+                1,
+                &mut NullRenderer,
+                &mut NullAudioOutput,
+                &GameBoyInputs::default(),
+                &mut NullSaveWriter,
+            )
+            .unwrap();
+
+        // Counters must not leak into save states
+        let config = bincode::config::standard();
+        let state = bincode::encode_to_vec(&emulator, config).unwrap();
+
+        let counters = emulator.perf_counters();
+        assert_eq!(state, bincode::encode_to_vec(&emulator, config).unwrap());
+
+        // JR takes 3 M-cycles, or 12 master clock cycles at normal speed
+        assert_ne!(counters.master_clock_cycles, 0);
+        assert!(counters.instructions.abs_diff(counters.master_clock_cycles / 12) <= 1);
+
+        assert_eq!(emulator.perf_counters(), PerfCounters::default());
+    }
 }
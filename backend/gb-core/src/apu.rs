@@ -160,6 +160,11 @@ impl Apu {
     }
 
     fn generate_sample(&mut self) {
+        let (sample_l, sample_r) = self.mix_sample();
+        self.resampler.collect_sample(sample_l, sample_r);
+    }
+
+    fn mix_sample(&self) -> (f64, f64) {
         // Sample values in the range [-15, +15]
         let channel_1_sample = digital_to_analog(self.pulse_1.sample());
         let channel_2_sample = digital_to_analog(self.pulse_2.sample());
@@ -186,11 +191,11 @@ impl Apu {
         sample_l *= i32::from(self.stereo_control.left_volume + 1);
         sample_r *= i32::from(self.stereo_control.right_volume + 1);
 
-        // Convert to floating point and store
+        // Convert to floating point
         // Multiply by 0.5 because otherwise the sound will be way too loud
         let sample_l_f64 = f64::from(sample_l) / 960.0;
         let sample_r_f64 = f64::from(sample_r) / 960.0;
-        self.resampler.collect_sample(sample_l_f64, sample_r_f64);
+        (sample_l_f64, sample_r_f64)
     }
 
     fn clock_length_counters(&mut self) {
@@ -318,3 +323,60 @@ fn digital_to_analog(sample: Option<u8>) -> i32 {
     // until the very end of sample generation
     (2 * i32::from(sample)) - 15
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{GbAspectRatio, GbPalette, GbcColorCorrection};
+
+    fn new_apu() -> Apu {
+        let config = GameBoyEmulatorConfig {
+            force_dmg_mode: false,
+            pretend_to_be_gba: false,
+            aspect_ratio: GbAspectRatio::default(),
+            gb_palette: GbPalette::default(),
+            gbc_color_correction: GbcColorCorrection::default(),
+            audio_60hz_hack: false,
+            rng_seed: 0,
+        };
+        Apu::new(config, HardwareMode::Dmg)
+    }
+
+    #[test]
+    fn stereo_mix() {
+        let mut apu = new_apu();
+
+        // Enable pulse 1 DAC without triggering; channel outputs digital 0
+        apu.write_register(0xFF12, 0xF0);
+
+        // Enable noise DAC and trigger; LFSR is all 1s after trigger so channel outputs digital 0
+        apu.write_register(0xFF21, 0xF0);
+        apu.write_register(0xFF23, 0x80);
+
+        // L volume 7 (8x), R volume 3 (4x)
+        apu.write_register(0xFF24, 0x73);
+
+        // Noise to left only, pulse 1 to right only
+        apu.write_register(0xFF25, 0x81);
+
+        // Digital 0 maps to analog -15
+        assert_eq!(apu.mix_sample(), (-15.0 * 8.0 / 960.0, -15.0 * 4.0 / 960.0));
+
+        // Noise to left only
+        apu.write_register(0xFF25, 0x80);
+        assert_eq!(apu.mix_sample(), (-15.0 * 8.0 / 960.0, 0.0));
+
+        // Both channels to right only
+        apu.write_register(0xFF25, 0x09);
+        assert_eq!(apu.mix_sample(), (0.0, -30.0 * 4.0 / 960.0));
+    }
+
+    #[test]
+    fn disabled_dacs_do_not_contribute() {
+        let mut apu = new_apu();
+        apu.write_register(0xFF24, 0x77);
+        apu.write_register(0xFF25, 0xFF);
+
+        assert_eq!(apu.mix_sample(), (0.0, 0.0));
+    }
+}
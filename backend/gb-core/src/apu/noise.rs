@@ -158,3 +158,68 @@ fn compute_clock_period(divider: u8, shift: u8) -> u32 {
     let base_divisor = if divider == 0 { 8 } else { 16 * u32::from(divider) };
     (base_divisor << shift) / 4
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triggered_channel(nr43: u8) -> NoiseChannel {
+        let mut channel = NoiseChannel::new();
+        channel.write_register_2(0xF0);
+        channel.write_register_3(nr43);
+        channel.write_register_4(0x80, 0);
+        channel
+    }
+
+    fn collect_lfsr_bits(channel: &mut NoiseChannel, len: usize) -> Vec<u8> {
+        // Divider 0 and shift 0 clock the LFSR every 2 M-cycles
+        (0..len)
+            .map(|_| {
+                channel.tick_m_cycle();
+                channel.tick_m_cycle();
+                u8::from(channel.lfsr.bit(0))
+            })
+            .collect()
+    }
+
+    fn parse_bits(s: &str) -> Vec<u8> {
+        s.bytes().map(|b| b - b'0').collect()
+    }
+
+    #[test]
+    fn lfsr_fifteen_bit_sequence() {
+        let mut channel = triggered_channel(0x00);
+        assert_eq!(
+            collect_lfsr_bits(&mut channel, 40),
+            parse_bits("1111111111111100000000000000100000000000")
+        );
+
+        // 15-bit LFSR is maximal length
+        let mut channel = triggered_channel(0x00);
+        collect_lfsr_bits(&mut channel, 32767);
+        assert_eq!(channel.lfsr, 0x7FFF);
+    }
+
+    #[test]
+    fn lfsr_seven_bit_sequence() {
+        let mut channel = triggered_channel(0x08);
+        assert_eq!(
+            collect_lfsr_bits(&mut channel, 40),
+            parse_bits("1111110000001000001100001010001111001000")
+        );
+
+        // 7-bit LFSR is maximal length
+        let mut channel = triggered_channel(0x08);
+        collect_lfsr_bits(&mut channel, 127);
+        assert_eq!(channel.lfsr & 0x7F, 0x7F);
+    }
+
+    #[test]
+    fn sample_inverts_lfsr_output() {
+        let mut channel = triggered_channel(0x00);
+        assert_eq!(channel.sample(), Some(0));
+
+        collect_lfsr_bits(&mut channel, 15);
+        assert_eq!(channel.sample(), Some(15));
+    }
+}
@@ -66,6 +66,7 @@ impl<'a> Bus<'a> {
 
         match address & 0x7F {
             0x00 => self.input_state.read_joyp(),
+            0x01 => self.serial_port.read_data(),
             0x02 => self.serial_port.read_control(),
             0x04 => self.timer.read_div(),
             0x05 => self.timer.read_tima(),
@@ -88,6 +89,7 @@ impl<'a> Bus<'a> {
 
         match address & 0x7F {
             0x00 => self.input_state.write_joyp(value),
+            0x01 => self.serial_port.write_data(value),
             0x02 => self.serial_port.write_control(value),
             0x04 => self.timer.write_div(),
             0x05 => self.timer.write_tima(value),
@@ -100,6 +102,7 @@ impl<'a> Bus<'a> {
             }
             0x46 => self.dma_unit.write_dma_register(value),
             0x4D => cgb_only_write!(self.speed_register.write_key1(value)),
+            0x50 => self.memory.write_boot(value),
             0x51 => cgb_only_write!(self.dma_unit.write_hdma1(value)),
             0x52 => cgb_only_write!(self.dma_unit.write_hdma2(value)),
             0x53 => cgb_only_write!(self.dma_unit.write_hdma3(value)),
@@ -140,7 +143,10 @@ impl<'a> BusInterface for Bus<'a> {
         self.tick_components();
 
         match address {
-            0x0000..=0x7FFF => self.cartridge.read_rom(address),
+            0x0000..=0x7FFF => self
+                .memory
+                .read_boot_rom(address)
+                .unwrap_or_else(|| self.cartridge.read_rom(address)),
             0x8000..=0x9FFF => self.ppu.read_vram(address),
             0xA000..=0xBFFF => self.cartridge.read_ram(address),
             0xC000..=0xFDFF => self.memory.read_main_ram(address),
@@ -364,3 +364,115 @@ fn basic_map_ram_address(
 
     Some(((ram_bank << 13) | u32::from(address & 0x1FFF)) & ram_addr_mask)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mbc1_rom_banking() {
+        // 2MB ROM, 32KB RAM
+        let mut mbc1 = Mbc1::new(2 * 1024 * 1024, 32 * 1024);
+
+        // Bank 0 is treated as bank 1 in $4000-$7FFF
+        assert_eq!(mbc1.map_rom_address(0x4000), 0x4000);
+
+        mbc1.write_rom_address(0x2000, 0x05);
+        mbc1.write_rom_address(0x4000, 0x01);
+        assert_eq!(mbc1.map_rom_address(0x0123), 0x0123);
+        assert_eq!(mbc1.map_rom_address(0x4123), (0x25 << 14) | 0x0123);
+
+        // Complex mode maps the high bank bits into $0000-$3FFF
+        mbc1.write_rom_address(0x6000, 0x01);
+        assert_eq!(mbc1.map_rom_address(0x0123), (0x20 << 14) | 0x0123);
+        assert_eq!(mbc1.map_rom_address(0x4123), (0x25 << 14) | 0x0123);
+    }
+
+    #[test]
+    fn mbc1_ram_banking() {
+        let mut mbc1 = Mbc1::new(2 * 1024 * 1024, 32 * 1024);
+        assert_eq!(mbc1.map_ram_address(0xA123), None);
+
+        mbc1.write_rom_address(0x0000, 0x0A);
+        mbc1.write_rom_address(0x4000, 0x02);
+
+        // RAM bank is ignored in simple mode
+        assert_eq!(mbc1.map_ram_address(0xA123), Some(0x0123));
+
+        mbc1.write_rom_address(0x6000, 0x01);
+        assert_eq!(mbc1.map_ram_address(0xA123), Some((0x02 << 13) | 0x0123));
+
+        mbc1.write_rom_address(0x0000, 0x00);
+        assert_eq!(mbc1.map_ram_address(0xA123), None);
+    }
+
+    #[test]
+    fn mbc3_banking() {
+        // 2MB ROM, 32KB RAM
+        let mut mbc3 = Mbc3::new(2 * 1024 * 1024, 32 * 1024, None);
+        let mut sram = vec![0; 32 * 1024];
+
+        assert_eq!(mbc3.map_rom_address(0x4000), 0x4000);
+        mbc3.write_rom_address(0x2000, 0x7F);
+        assert_eq!(mbc3.map_rom_address(0x4123), (0x7F << 14) | 0x0123);
+
+        // Writes are ignored while RAM is disabled
+        mbc3.write_rom_address(0x4000, 0x02);
+        mbc3.write_ram(0xA123, 0x55, &mut sram);
+        assert_eq!(mbc3.read_ram(0xA123, &sram), 0xFF);
+        assert!(sram.iter().all(|&b| b == 0));
+
+        mbc3.write_rom_address(0x0000, 0x0A);
+        mbc3.write_ram(0xA123, 0x55, &mut sram);
+        assert_eq!(sram[(0x02 << 13) | 0x0123], 0x55);
+        assert_eq!(mbc3.read_ram(0xA123, &sram), 0x55);
+
+        mbc3.write_rom_address(0x4000, 0x01);
+        assert_eq!(mbc3.read_ram(0xA123, &sram), 0x00);
+
+        // RTC registers read as open bus without an RTC
+        mbc3.write_rom_address(0x4000, 0x08);
+        assert_eq!(mbc3.read_ram(0xA000, &sram), 0xFF);
+    }
+
+    #[test]
+    fn mbc3_rtc_latch() {
+        let mut mbc3 = Mbc3::new(2 * 1024 * 1024, 0, Some(Mbc3Rtc::new()));
+        let mut sram = vec![];
+
+        mbc3.write_rom_address(0x0000, 0x0A);
+        mbc3.write_rom_address(0x4000, 0x08);
+        mbc3.write_ram(0xA000, 30, &mut sram);
+
+        // Register reads return the latched time until the latch is updated
+        assert_eq!(mbc3.read_ram(0xA000, &sram), 0);
+
+        mbc3.write_rom_address(0x6000, 0x01);
+        assert_eq!(mbc3.read_ram(0xA000, &sram), 0);
+
+        mbc3.write_rom_address(0x6000, 0x00);
+        mbc3.write_rom_address(0x6000, 0x01);
+        assert_eq!(mbc3.read_ram(0xA000, &sram), 30);
+    }
+
+    #[test]
+    fn mbc5_banking() {
+        // 8MB ROM, 128KB RAM
+        let mut mbc5 = Mbc5::new(8 * 1024 * 1024, 128 * 1024);
+
+        // Bank 0 can be mapped into $4000-$7FFF
+        assert_eq!(mbc5.map_rom_address(0x4123), 0x0123);
+
+        mbc5.write_rom_address(0x2000, 0xFF);
+        mbc5.write_rom_address(0x3000, 0x01);
+        assert_eq!(mbc5.map_rom_address(0x4123), (0x1FF << 14) | 0x0123);
+
+        mbc5.write_rom_address(0x3000, 0x00);
+        assert_eq!(mbc5.map_rom_address(0x4123), (0x0FF << 14) | 0x0123);
+
+        assert_eq!(mbc5.map_ram_address(0xA123), None);
+        mbc5.write_rom_address(0x0000, 0x0A);
+        mbc5.write_rom_address(0x4000, 0x0F);
+        assert_eq!(mbc5.map_ram_address(0xA123), Some((0x0F << 13) | 0x0123));
+    }
+}
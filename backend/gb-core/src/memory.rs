@@ -1,7 +1,7 @@
 //! Game Boy internal memory
 
 use bincode::{Decode, Encode};
-use std::iter;
+use jgenesis_common::rng::Rng;
 
 const MAIN_RAM_LEN: usize = 32 * 1024;
 const HRAM_LEN: usize = 127;
@@ -14,17 +14,46 @@ pub struct Memory {
     main_ram: Box<MainRam>,
     main_ram_bank: u8,
     hram: Box<Hram>,
+    boot_rom: Option<Box<[u8]>>,
 }
 
 impl Memory {
-    pub fn new() -> Self {
+    pub fn new(rng: &mut Rng) -> Self {
         // Randomize RAM contents at power-on
-        let main_ram: Vec<u8> = iter::repeat_with(rand::random).take(MAIN_RAM_LEN).collect();
+        let mut main_ram = vec![0; MAIN_RAM_LEN];
+        rng.fill_bytes(&mut main_ram);
 
         Self {
             main_ram: main_ram.into_boxed_slice().try_into().unwrap(),
             main_ram_bank: 0,
             hram: vec![0; HRAM_LEN].into_boxed_slice().try_into().unwrap(),
+            boot_rom: None,
+        }
+    }
+
+    /// Map a boot ROM over the start of the cartridge address space. The boot ROM stays mapped
+    /// until software unmaps it by writing to $FF50.
+    ///
+    /// DMG boot ROMs are 256 bytes and are mapped to $0000-$00FF. CGB boot ROMs are 2304 bytes and
+    /// are additionally mapped to $0200-$08FF, leaving the cartridge header visible at
+    /// $0100-$01FF.
+    pub fn map_boot_rom(&mut self, boot_rom: Vec<u8>) {
+        self.boot_rom = Some(boot_rom.into_boxed_slice());
+    }
+
+    pub fn read_boot_rom(&self, address: u16) -> Option<u8> {
+        let boot_rom = self.boot_rom.as_ref()?;
+        match address {
+            0x0000..=0x00FF | 0x0200..=0x08FF => boot_rom.get(address as usize).copied(),
+            _ => None,
+        }
+    }
+
+    pub fn write_boot(&mut self, value: u8) {
+        // BOOT: Any non-zero write permanently unmaps the boot ROM
+        if value != 0 && self.boot_rom.is_some() {
+            log::debug!("Unmapping boot ROM");
+            self.boot_rom = None;
         }
     }
 
@@ -38,6 +67,14 @@ impl Memory {
         self.main_ram[ram_addr as usize] = value;
     }
 
+    pub fn main_ram(&self) -> &[u8] {
+        self.main_ram.as_slice()
+    }
+
+    pub fn hram(&self) -> &[u8] {
+        self.hram.as_slice()
+    }
+
     pub fn read_hram(&self, address: u16) -> u8 {
         self.hram[(address & 0x7F) as usize]
     }
@@ -68,3 +105,39 @@ fn map_ram_address(address: u16, ram_bank: u8) -> u16 {
         _ => unreachable!("address & 0x1FFF is always <= 0x1FFF"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boot_rom_unmap() {
+        let mut memory = Memory::new(&mut Rng::new(0));
+        assert_eq!(memory.read_boot_rom(0x0000), None);
+
+        memory.map_boot_rom(vec![0x31; 0x100]);
+        assert_eq!(memory.read_boot_rom(0x0000), Some(0x31));
+        assert_eq!(memory.read_boot_rom(0x00FF), Some(0x31));
+        assert_eq!(memory.read_boot_rom(0x0100), None);
+        assert_eq!(memory.read_boot_rom(0x0200), None);
+
+        // Writing 0 should not unmap the boot ROM
+        memory.write_boot(0x00);
+        assert_eq!(memory.read_boot_rom(0x0000), Some(0x31));
+
+        memory.write_boot(0x01);
+        assert_eq!(memory.read_boot_rom(0x0000), None);
+    }
+
+    #[test]
+    fn cgb_boot_rom_mapping() {
+        let mut memory = Memory::new(&mut Rng::new(0));
+        memory.map_boot_rom((0..0x900).map(|i| (i >> 8) as u8).collect());
+
+        assert_eq!(memory.read_boot_rom(0x0050), Some(0x00));
+        assert_eq!(memory.read_boot_rom(0x0150), None);
+        assert_eq!(memory.read_boot_rom(0x0250), Some(0x02));
+        assert_eq!(memory.read_boot_rom(0x08FF), Some(0x08));
+        assert_eq!(memory.read_boot_rom(0x0900), None);
+    }
+}
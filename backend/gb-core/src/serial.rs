@@ -9,43 +9,85 @@ use crate::HardwareMode;
 use bincode::{Decode, Encode};
 use jgenesis_common::num::GetBit;
 
-// Base serial transfer rate is 8192 bits/second == 1024 bytes/second
+// Base serial transfer rate is 8192 bits/second
 // The normal-speed CPU M-cycle clock is 1.048576 MHz
-// (1048576 cycles/second) / (1024 bytes/second) == 1024 cycles/byte
-const BASE_CYCLES_PER_BYTE: u32 = 1024;
+// (1048576 cycles/second) / (8192 bits/second) == 128 cycles/bit
+const BASE_CYCLES_PER_BIT: u32 = 128;
+
+// GBC high speed transfer rate is 262144 bits/second, 32x faster than the base rate
+const HIGH_SPEED_CYCLES_PER_BIT: u32 = 4;
 
 #[derive(Debug, Clone, Encode, Decode)]
 pub struct SerialPort {
     hardware_mode: HardwareMode,
+    data: u8,
     transfer_enabled: bool,
     gbc_high_speed: bool,
     internal_clock: bool,
-    transfer_cycles_remaining: u32,
+    bits_remaining: u8,
+    bit_cycles_remaining: u32,
 }
 
 impl SerialPort {
     pub fn new(hardware_mode: HardwareMode) -> Self {
         Self {
             hardware_mode,
+            data: 0x00,
             transfer_enabled: false,
             gbc_high_speed: false,
             internal_clock: false,
-            transfer_cycles_remaining: 0,
+            bits_remaining: 0,
+            bit_cycles_remaining: 0,
         }
     }
 
     pub fn tick(&mut self, interrupt_registers: &mut InterruptRegisters) {
-        if !self.transfer_enabled || !self.internal_clock || self.transfer_cycles_remaining == 0 {
+        if !self.transfer_enabled || !self.internal_clock || self.bits_remaining == 0 {
             return;
         }
 
-        self.transfer_cycles_remaining -= 1;
-        if self.transfer_cycles_remaining == 0 {
+        self.bit_cycles_remaining -= 1;
+        if self.bit_cycles_remaining != 0 {
+            return;
+        }
+
+        let bit_in = self.exchange_bit(self.data.bit(7));
+        self.data = (self.data << 1) | u8::from(bit_in);
+
+        self.bits_remaining -= 1;
+        if self.bits_remaining == 0 {
             self.transfer_enabled = false;
             interrupt_registers.set_flag(InterruptType::Serial);
+
+            log::trace!("Serial transfer complete, received {:02X}", self.data);
+        } else {
+            self.bit_cycles_remaining = self.cycles_per_bit();
         }
     }
 
+    // Shift one bit out and one bit in. This is where a link cable peer would connect; with
+    // nothing connected, the input line is pulled high and every received bit is 1
+    #[allow(clippy::unused_self)]
+    fn exchange_bit(&mut self, _bit_out: bool) -> bool {
+        true
+    }
+
+    fn cycles_per_bit(&self) -> u32 {
+        if self.gbc_high_speed { HIGH_SPEED_CYCLES_PER_BIT } else { BASE_CYCLES_PER_BIT }
+    }
+
+    // $FF01: SB (Serial transfer data)
+    pub fn read_data(&self) -> u8 {
+        self.data
+    }
+
+    // $FF01: SB (Serial transfer data)
+    pub fn write_data(&mut self, value: u8) {
+        self.data = value;
+
+        log::trace!("SB write: {value:02X}");
+    }
+
     // $FF02: SC (Serial transfer control)
     pub fn read_control(&self) -> u8 {
         (u8::from(self.transfer_enabled) << 7)
@@ -60,7 +102,8 @@ impl SerialPort {
         self.internal_clock = value.bit(0);
 
         if self.transfer_enabled && self.internal_clock {
-            self.transfer_cycles_remaining = BASE_CYCLES_PER_BYTE >> u8::from(self.gbc_high_speed);
+            self.bits_remaining = 8;
+            self.bit_cycles_remaining = self.cycles_per_bit();
         }
 
         log::trace!("SC write: {value:02X}");
@@ -69,3 +112,56 @@ impl SerialPort {
         log::trace!("  Internal clock: {}", self.internal_clock);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_transfer(serial_port: &mut SerialPort, expected_cycles: u32) {
+        let mut interrupt_registers = InterruptRegisters::default();
+
+        for _ in 0..expected_cycles - 1 {
+            serial_port.tick(&mut interrupt_registers);
+        }
+        assert_eq!(interrupt_registers.read_if() & InterruptType::Serial.register_mask(), 0);
+        assert!(serial_port.read_control().bit(7));
+
+        serial_port.tick(&mut interrupt_registers);
+        assert_ne!(interrupt_registers.read_if() & InterruptType::Serial.register_mask(), 0);
+        assert!(!serial_port.read_control().bit(7));
+        assert_eq!(serial_port.read_data(), 0xFF);
+    }
+
+    #[test]
+    fn internal_clock_transfer() {
+        let mut serial_port = SerialPort::new(HardwareMode::Dmg);
+        serial_port.write_data(0x5A);
+        serial_port.write_control(0x81);
+
+        run_transfer(&mut serial_port, 8 * BASE_CYCLES_PER_BIT);
+    }
+
+    #[test]
+    fn gbc_high_speed_transfer() {
+        let mut serial_port = SerialPort::new(HardwareMode::Cgb);
+        serial_port.write_data(0x5A);
+        serial_port.write_control(0x83);
+
+        run_transfer(&mut serial_port, 8 * HIGH_SPEED_CYCLES_PER_BIT);
+    }
+
+    #[test]
+    fn external_clock_does_not_transfer() {
+        let mut serial_port = SerialPort::new(HardwareMode::Dmg);
+        serial_port.write_data(0x5A);
+        serial_port.write_control(0x80);
+
+        let mut interrupt_registers = InterruptRegisters::default();
+        for _ in 0..16 * BASE_CYCLES_PER_BIT {
+            serial_port.tick(&mut interrupt_registers);
+        }
+
+        assert_eq!(interrupt_registers.read_if() & InterruptType::Serial.register_mask(), 0);
+        assert_eq!(serial_port.read_data(), 0x5A);
+    }
+}
@@ -11,7 +11,7 @@ mod flags;
 mod flow;
 mod load;
 
-use crate::sm83::bus::BusInterface;
+use crate::sm83::bus::{BusInterface, CycleCountingBus};
 use crate::HardwareMode;
 use bincode::{Decode, Encode};
 use jgenesis_common::num::GetBit;
@@ -113,6 +113,23 @@ impl Registers {
         }
     }
 
+    fn new_boot_rom() -> Self {
+        // The boot ROM initializes all registers itself
+        Self {
+            a: 0x00,
+            f: Flags { zero: false, subtract: false, half_carry: false, carry: false },
+            b: 0x00,
+            c: 0x00,
+            d: 0x00,
+            e: 0x00,
+            h: 0x00,
+            l: 0x00,
+            sp: 0x0000,
+            pc: 0x0000,
+            ime: false,
+        }
+    }
+
     fn bc(&self) -> u16 {
         u16::from_be_bytes([self.b, self.c])
     }
@@ -236,7 +253,39 @@ impl Sm83 {
         Self { registers: Registers::new(hardware_mode, pretend_to_be_gba), state: State::new() }
     }
 
-    pub fn execute_instruction<B: BusInterface>(&mut self, bus: &mut B) {
+    /// Create a CPU in its power-on state, with PC=$0000 so that it will start by executing the
+    /// boot ROM.
+    pub fn new_with_boot_rom() -> Self {
+        Self { registers: Registers::new_boot_rom(), state: State::new() }
+    }
+
+    /// Execute a single instruction, or service an interrupt if one is being handled.
+    ///
+    /// Returns the number of M-cycles elapsed. All components are advanced through the bus as
+    /// cycles elapse, so callers do not need to use this to tick other components.
+    #[cfg(test)]
+    pub fn execute_instruction<B: BusInterface>(&mut self, bus: &mut B) -> u32 {
+        self.execute_instruction_traced(bus, |_| {})
+    }
+
+    /// Same as [`Self::execute_instruction`], but calls `trace_hook` with the opcode's PC before
+    /// the instruction is executed. The hook is not called when the CPU is halted, frozen, or
+    /// servicing an interrupt.
+    pub fn execute_instruction_traced<B: BusInterface, F: FnMut(u16)>(
+        &mut self,
+        bus: &mut B,
+        trace_hook: F,
+    ) -> u32 {
+        let mut bus = CycleCountingBus { bus, m_cycles: 0 };
+        self.execute_instruction_inner(&mut bus, trace_hook);
+        bus.m_cycles
+    }
+
+    fn execute_instruction_inner<B: BusInterface, F: FnMut(u16)>(
+        &mut self,
+        bus: &mut B,
+        mut trace_hook: F,
+    ) {
         if self.state.executed_invalid_opcode || bus.halt() {
             // CPU is halted or frozen
             bus.idle();
@@ -272,6 +321,7 @@ impl Sm83 {
             self.state.pending_ime_set = false;
         }
 
+        trace_hook(self.registers.pc);
         let opcode = self.fetch_operand(bus);
 
         log::trace!(
@@ -592,3 +642,294 @@ impl Sm83 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speed::{CpuSpeed, SpeedRegister};
+
+    struct TestBus {
+        memory: Box<[u8; 0x10000]>,
+        ie: u8,
+        if_: u8,
+        speed_register: SpeedRegister,
+    }
+
+    impl TestBus {
+        fn new(program: &[u8]) -> Self {
+            let mut memory: Box<[u8; 0x10000]> =
+                vec![0; 0x10000].into_boxed_slice().try_into().unwrap();
+            memory[ENTRY_POINT as usize..ENTRY_POINT as usize + program.len()]
+                .copy_from_slice(program);
+            Self { memory, ie: 0, if_: 0, speed_register: SpeedRegister::new() }
+        }
+    }
+
+    impl BusInterface for TestBus {
+        fn read(&mut self, address: u16) -> u8 {
+            self.memory[address as usize]
+        }
+
+        fn write(&mut self, address: u16, value: u8) {
+            self.memory[address as usize] = value;
+        }
+
+        fn idle(&mut self) {}
+
+        fn read_ie_register(&self) -> u8 {
+            self.ie & 0x1F
+        }
+
+        fn read_if_register(&self) -> u8 {
+            self.if_ & 0x1F
+        }
+
+        fn acknowledge_interrupt(&mut self, interrupt_type: InterruptType) {
+            self.if_ &= !interrupt_type.register_mask();
+        }
+
+        fn halt(&self) -> bool {
+            false
+        }
+
+        fn speed_switch_armed(&self) -> bool {
+            self.speed_register.switch_armed
+        }
+
+        fn perform_speed_switch(&mut self) {
+            self.speed_register.perform_speed_switch();
+        }
+    }
+
+    fn run_one(program: &[u8], registers: impl FnOnce(&mut Registers)) -> (Sm83, u32) {
+        let mut cpu = Sm83::new(HardwareMode::Dmg, false);
+        registers(&mut cpu.registers);
+
+        let mut bus = TestBus::new(program);
+        let m_cycles = cpu.execute_instruction(&mut bus);
+        (cpu, m_cycles)
+    }
+
+    #[test]
+    fn nop_cycles() {
+        let (cpu, m_cycles) = run_one(&[0x00], |_| {});
+        assert_eq!(m_cycles, 1);
+        assert_eq!(cpu.registers.pc, ENTRY_POINT + 1);
+    }
+
+    #[test]
+    fn ld_hl_immediate_cycles() {
+        // LD (HL), $55
+        let (_, m_cycles) = run_one(&[0x36, 0x55], |registers| registers.set_hl(0xC000));
+        assert_eq!(m_cycles, 3);
+    }
+
+    #[test]
+    fn jr_conditional_cycles() {
+        // JR NZ, +5 (taken)
+        let (cpu, m_cycles) = run_one(&[0x20, 0x05], |registers| registers.f.zero = false);
+        assert_eq!(m_cycles, 3);
+        assert_eq!(cpu.registers.pc, ENTRY_POINT + 2 + 5);
+
+        // JR NZ, +5 (not taken)
+        let (cpu, m_cycles) = run_one(&[0x20, 0x05], |registers| registers.f.zero = true);
+        assert_eq!(m_cycles, 2);
+        assert_eq!(cpu.registers.pc, ENTRY_POINT + 2);
+    }
+
+    #[test]
+    fn call_and_ret_cycles() {
+        // CALL $1234
+        let (_, m_cycles) = run_one(&[0xCD, 0x34, 0x12], |_| {});
+        assert_eq!(m_cycles, 6);
+
+        // RET (PC is popped from the zero-filled stack)
+        let (_, m_cycles) = run_one(&[0xC9], |registers| registers.sp = 0xC000);
+        assert_eq!(m_cycles, 4);
+    }
+
+    #[test]
+    fn interrupt_dispatch_order() {
+        let mut cpu = Sm83::new(HardwareMode::Dmg, false);
+        cpu.registers.sp = 0xD000;
+        cpu.registers.ime = true;
+
+        // All NOPs
+        let mut bus = TestBus::new(&[]);
+        bus.ie = 0x1F;
+        bus.if_ = InterruptType::Joypad.register_mask()
+            | InterruptType::Timer.register_mask()
+            | InterruptType::LcdStatus.register_mask();
+
+        for (interrupt_type, vector) in [
+            (InterruptType::LcdStatus, 0x0048),
+            (InterruptType::Timer, 0x0050),
+            (InterruptType::Joypad, 0x0060),
+        ] {
+            // Interrupts are checked after each instruction
+            assert_eq!(cpu.execute_instruction(&mut bus), 1);
+
+            // Interrupt service routine takes 5 M-cycles
+            assert_eq!(cpu.execute_instruction(&mut bus), 5);
+            assert_eq!(cpu.registers.pc, vector);
+            assert!(!cpu.registers.ime);
+            assert_eq!(bus.if_ & interrupt_type.register_mask(), 0);
+
+            cpu.registers.ime = true;
+        }
+
+        assert_eq!(bus.if_, 0);
+    }
+
+    #[test]
+    fn ei_delay() {
+        let mut cpu = Sm83::new(HardwareMode::Dmg, false);
+        cpu.registers.sp = 0xD000;
+
+        // EI, NOP, NOP
+        let mut bus = TestBus::new(&[0xFB, 0x00, 0x00]);
+        bus.ie = 0x01;
+        bus.if_ = 0x01;
+
+        // Interrupt should not be handled until after the instruction following EI
+        cpu.execute_instruction(&mut bus);
+        assert!(!cpu.registers.ime);
+        cpu.execute_instruction(&mut bus);
+        assert!(cpu.registers.ime);
+        assert_eq!(cpu.registers.pc, ENTRY_POINT + 2);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.registers.pc, 0x0040);
+        assert_eq!(bus.if_, 0x00);
+        assert_eq!(bus.memory[0xCFFE..0xD000], [0x02, 0x01]);
+    }
+
+    #[test]
+    fn ei_di_cancels_ei() {
+        let mut cpu = Sm83::new(HardwareMode::Dmg, false);
+
+        // EI, DI, NOP
+        let mut bus = TestBus::new(&[0xFB, 0xF3, 0x00]);
+        bus.ie = 0x01;
+        bus.if_ = 0x01;
+
+        for _ in 0..3 {
+            cpu.execute_instruction(&mut bus);
+        }
+
+        assert!(!cpu.registers.ime);
+        assert_eq!(cpu.registers.pc, ENTRY_POINT + 3);
+        assert_eq!(bus.if_, 0x01);
+    }
+
+    #[test]
+    fn cgb_init_registers() {
+        let cpu = Sm83::new(HardwareMode::Cgb, false);
+        let registers = &cpu.registers;
+        assert_eq!(registers.af(), 0x1180);
+        assert_eq!(registers.bc(), 0x0000);
+        assert_eq!(registers.de(), 0xFF56);
+        assert_eq!(registers.hl(), 0x000D);
+        assert_eq!(registers.sp, HRAM_END);
+        assert_eq!(registers.pc, ENTRY_POINT);
+
+        // GBA only differs in B bit 0
+        let cpu = Sm83::new(HardwareMode::Cgb, true);
+        assert_eq!(cpu.registers.bc(), 0x0100);
+
+        let cpu = Sm83::new(HardwareMode::Dmg, false);
+        assert_eq!(cpu.registers.af(), 0x0180);
+    }
+
+    #[test]
+    fn stop_speed_switch() {
+        let mut cpu = Sm83::new(HardwareMode::Cgb, false);
+
+        // STOP, NOP, STOP, NOP
+        let mut bus = TestBus::new(&[0x10, 0x00, 0x10, 0x00]);
+        bus.speed_register.write_key1(0x01);
+        assert_eq!(bus.speed_register.read_key1(), 0x7F);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(bus.speed_register.speed, CpuSpeed::Double);
+        assert!(!bus.speed_register.switch_armed);
+        assert_eq!(bus.speed_register.read_key1(), 0xFE);
+
+        // STOP skips over the following byte
+        assert_eq!(cpu.registers.pc, ENTRY_POINT + 2);
+
+        bus.speed_register.write_key1(0x01);
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(bus.speed_register.speed, CpuSpeed::Normal);
+        assert_eq!(bus.speed_register.read_key1(), 0x7E);
+    }
+
+    #[test]
+    fn halt_bug() {
+        let mut cpu = Sm83::new(HardwareMode::Dmg, false);
+        cpu.registers.a = 0x00;
+
+        // HALT, INC A, NOP
+        let mut bus = TestBus::new(&[0x76, 0x3C, 0x00]);
+        bus.ie = 0x04;
+        bus.if_ = 0x04;
+
+        // HALT with IME=0 and a pending interrupt does not halt, and the byte after HALT is read
+        // twice because PC does not increment on the next opcode fetch
+        cpu.execute_instruction(&mut bus);
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.registers.a, 0x01);
+        assert_eq!(cpu.registers.pc, ENTRY_POINT + 1);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.registers.a, 0x02);
+        assert_eq!(cpu.registers.pc, ENTRY_POINT + 2);
+
+        // The interrupt is never serviced
+        assert_eq!(bus.if_, 0x04);
+    }
+
+    #[test]
+    fn halt_without_pending_interrupt() {
+        let mut cpu = Sm83::new(HardwareMode::Dmg, false);
+        cpu.registers.a = 0x00;
+
+        // HALT, INC A, NOP
+        let mut bus = TestBus::new(&[0x76, 0x3C, 0x00]);
+        bus.ie = 0x04;
+
+        cpu.execute_instruction(&mut bus);
+        for _ in 0..5 {
+            assert_eq!(cpu.execute_instruction(&mut bus), 1);
+            assert_eq!(cpu.registers.pc, ENTRY_POINT + 1);
+        }
+
+        // Once an interrupt is requested, the CPU resumes without the HALT bug
+        bus.if_ = 0x04;
+        cpu.execute_instruction(&mut bus);
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.registers.a, 0x01);
+        assert_eq!(cpu.registers.pc, ENTRY_POINT + 3);
+    }
+
+    #[test]
+    fn trace_hook_skips_halted_cycles() {
+        let mut cpu = Sm83::new(HardwareMode::Dmg, false);
+
+        // HALT, INC A, NOP
+        let mut bus = TestBus::new(&[0x76, 0x3C, 0x00]);
+        bus.ie = 0x04;
+
+        let mut trace = Vec::new();
+        for _ in 0..6 {
+            cpu.execute_instruction_traced(&mut bus, |pc| trace.push(pc));
+        }
+        assert_eq!(trace, vec![ENTRY_POINT]);
+
+        bus.if_ = 0x04;
+        for _ in 0..2 {
+            cpu.execute_instruction_traced(&mut bus, |pc| trace.push(pc));
+        }
+        assert_eq!(trace, vec![ENTRY_POINT, ENTRY_POINT + 1, ENTRY_POINT + 2]);
+    }
+}
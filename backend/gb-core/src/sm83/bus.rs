@@ -33,3 +33,54 @@ pub trait BusInterface {
     /// Perform a CGB speed switch
     fn perform_speed_switch(&mut self);
 }
+
+/// Bus wrapper that counts the number of M-cycles elapsed
+pub(super) struct CycleCountingBus<'a, B> {
+    pub(super) bus: &'a mut B,
+    pub(super) m_cycles: u32,
+}
+
+impl<B: BusInterface> BusInterface for CycleCountingBus<'_, B> {
+    fn read(&mut self, address: u16) -> u8 {
+        self.m_cycles += 1;
+        self.bus.read(address)
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        self.m_cycles += 1;
+        self.bus.write(address, value);
+    }
+
+    fn idle(&mut self) {
+        self.m_cycles += 1;
+        self.bus.idle();
+    }
+
+    fn read_ie_register(&self) -> u8 {
+        self.bus.read_ie_register()
+    }
+
+    fn read_if_register(&self) -> u8 {
+        self.bus.read_if_register()
+    }
+
+    fn interrupt_pending(&self) -> bool {
+        self.bus.interrupt_pending()
+    }
+
+    fn acknowledge_interrupt(&mut self, interrupt_type: InterruptType) {
+        self.bus.acknowledge_interrupt(interrupt_type);
+    }
+
+    fn halt(&self) -> bool {
+        self.bus.halt()
+    }
+
+    fn speed_switch_armed(&self) -> bool {
+        self.bus.speed_switch_armed()
+    }
+
+    fn perform_speed_switch(&mut self) {
+        self.bus.perform_speed_switch();
+    }
+}
@@ -1,19 +1,30 @@
 //! Genesis public interface and main loop
 
-use crate::audio::GenesisAudioResampler;
-use crate::input::{GenesisInputs, InputState};
-use crate::memory::{Cartridge, MainBus, MainBusSignals, MainBusWrites, Memory};
-use crate::vdp::{Vdp, VdpConfig, VdpTickEffect};
+use crate::audio::{GenesisAudioResampler, StereoMix};
+use crate::cheats::CheatList;
+use crate::composite::NtscCompositeFilter;
+use crate::deinterlace::{Deinterlacer, DeinterlacingMode};
+use crate::input::{GenesisInputs, InputState, RawControllerFrame};
+use crate::memory::{Cartridge, CartridgeRamError, MainBus, MainBusSignals, MainBusWrites, Memory};
+use crate::vdp::{DebugPlane, SpriteDebugInfo, Vdp, VdpConfig, VdpTickEffect};
 use crate::ym2612::{Ym2612, YmTickEffect};
 use crate::GenesisControllerType;
 use bincode::{Decode, Encode};
+use crc::Crc;
+use jgenesis_common::audio::OUTPUT_FREQUENCY;
 use jgenesis_common::frontend::{
-    AudioOutput, Color, EmulatorTrait, FrameSize, PartialClone, PixelAspectRatio, Renderer,
-    SaveWriter, TickEffect, TimingMode,
+    frame_buffer_to_rgb8, AudioOutput, Color, EmulatorTrait, FrameSize, MemoryRegion,
+    OverscanBuffer, PartialClone, PixelAspectRatio, Renderer, SaveWriter, TickEffect, TimingMode,
 };
+#[cfg(any(test, feature = "perf"))]
+use jgenesis_common::frontend::PerfCounters;
+use jgenesis_common::movie::MoviePlayback;
 use jgenesis_common::num::GetBit;
+use jgenesis_common::ram::RamInitPattern;
+use jgenesis_common::rng::Rng;
+use jgenesis_common::save_state::VersionedState;
 use jgenesis_proc_macros::{EnumDisplay, EnumFromStr};
-use m68000_emu::M68000;
+use m68000_emu::{BreakpointHit, Breakpoints, BusInterface, M68000};
 use smsgg_core::psg::{Psg, PsgTickEffect, PsgVersion};
 use std::fmt::{Debug, Display};
 use std::mem;
@@ -24,6 +35,14 @@ const M68K_MCLK_DIVIDER: u64 = 7;
 const Z80_MCLK_DIVIDER: u64 = 15;
 const PSG_MCLK_DIVIDER: u64 = 15;
 
+const CRC: Crc<u32> = Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
+
+#[derive(Debug, Error)]
+pub enum GenesisLoadError {
+    #[error("Error loading cartridge save file: {0}")]
+    CartridgeRam(#[from] CartridgeRamError),
+}
+
 #[derive(Debug, Error)]
 pub enum GenesisError<RErr, AErr, SErr> {
     #[error("Rendering error: {0}")]
@@ -80,10 +99,25 @@ pub enum GenesisRegion {
     Europe,
 }
 
+/// CRC32 of the full ROM image. This is the checksum used by ROM databases such as No-Intro.
+#[must_use]
+pub fn rom_crc32(rom: &[u8]) -> u32 {
+    CRC.checksum(rom)
+}
+
+/// Serial number from the ROM header ($183-$18A), e.g. "MK-1079", with padding trimmed. Returns
+/// an empty string if the ROM is too small to contain a header.
+#[must_use]
+pub fn rom_serial(rom: &[u8]) -> String {
+    rom.get(0x183..0x18B)
+        .map(|bytes| bytes.iter().map(|&b| b as char).collect::<String>().trim().into())
+        .unwrap_or_default()
+}
+
 impl GenesisRegion {
     #[must_use]
     pub fn from_rom(rom: &[u8]) -> Option<Self> {
-        let region_bytes = &rom[0x1F0..0x1F3];
+        let region_bytes = rom.get(0x1F0..0x1F3)?;
 
         // Prefer Americas if region code contains a 'U'
         if region_bytes.contains(&b'U') {
@@ -130,13 +164,42 @@ pub struct GenesisEmulatorConfig {
     pub p2_controller_type: GenesisControllerType,
     pub forced_timing_mode: Option<TimingMode>,
     pub forced_region: Option<GenesisRegion>,
+    /// Region reported by the version register, independent of the cartridge region. If not set,
+    /// the console region matches the cartridge region
+    pub console_region: Option<GenesisRegion>,
+    /// Emulate a console with TMSS, which locks the VDP until the game writes "SEGA" to $A14000
+    pub tmss_enabled: bool,
+    /// Contents of main RAM and audio RAM at power-on
+    pub ram_init_pattern: RamInitPattern,
+    /// Seed for all pseudorandom power-on state. Currently only consumed by
+    /// [`RamInitPattern::Random`]
+    pub rng_seed: u64,
     pub aspect_ratio: GenesisAspectRatio,
     pub adjust_aspect_ratio_in_2x_resolution: bool,
+    /// How to combine the two fields of interlaced double resolution frames
+    pub deinterlacing_mode: DeinterlacingMode,
     pub remove_sprite_limits: bool,
     pub emulate_non_linear_vdp_dac: bool,
     pub render_vertical_border: bool,
     pub render_horizontal_border: bool,
+    /// Run frames through an NTSC composite video filter, which blends dithered patterns and
+    /// produces artifact colors
+    pub ntsc_composite_filter: bool,
+    /// Width in pixels of an overscan border filled with the backdrop color that is added to every
+    /// edge of the rendered frame; 0 disables
+    pub overscan_border_width: u32,
+    /// Layer toggles for debugging; disabled layers are rendered as transparent
+    pub plane_a_enabled: bool,
+    pub plane_b_enabled: bool,
+    pub window_enabled: bool,
+    pub sprites_enabled: bool,
     pub quantize_ym2612_output: bool,
+    /// Apply a high-pass filter to the final audio output to remove any DC offset
+    pub dc_blocking_filter: bool,
+    /// PSG balance, from -1.0 (left only) to 1.0 (right only)
+    pub psg_pan: f64,
+    /// Downmix audio output to mono
+    pub mono_audio: bool,
 }
 
 impl GenesisEmulatorConfig {
@@ -147,8 +210,27 @@ impl GenesisEmulatorConfig {
             emulate_non_linear_dac: self.emulate_non_linear_vdp_dac,
             render_vertical_border: self.render_vertical_border,
             render_horizontal_border: self.render_horizontal_border,
+            plane_a_enabled: self.plane_a_enabled,
+            plane_b_enabled: self.plane_b_enabled,
+            window_enabled: self.window_enabled,
+            sprites_enabled: self.sprites_enabled,
         }
     }
+
+    #[must_use]
+    pub fn to_stereo_mix(self) -> StereoMix {
+        StereoMix { psg_pan: self.psg_pan, mono: self.mono_audio }
+    }
+}
+
+/// Sound chip that can be muted using [`GenesisEmulator::set_chip_muted`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChipId {
+    Psg,
+    /// All YM2612 channels
+    Ym2612,
+    /// A single YM2612 channel, 0-5
+    Ym2612Channel(u8),
 }
 
 #[derive(Debug, Clone, Default, Encode, Decode)]
@@ -156,6 +238,8 @@ struct WaitStates {
     m68k_cpu_cycles: u32,
     z80_mclk_cycles: u64,
     odd_access: bool,
+    // Total 68K cycles spent halted by VDP FIFO/DMA contention
+    m68k_vdp_stall_cycles: u64,
 }
 
 impl WaitStates {
@@ -182,10 +266,17 @@ pub struct GenesisEmulator {
     main_bus_writes: MainBusWrites,
     aspect_ratio: GenesisAspectRatio,
     adjust_aspect_ratio_in_2x_resolution: bool,
+    render_options: RenderOptions,
     audio_resampler: GenesisAudioResampler,
+    psg_muted: bool,
+    rng_seed: u64,
     z80_mclk_cycles: u64,
     psg_mclk_cycles: u64,
     wait_states: WaitStates,
+    cheats: CheatList,
+    // Always compiled in tests so the counters are exercised without the `perf` feature
+    #[cfg(any(test, feature = "perf"))]
+    perf_counters: PerfCounters,
 }
 
 // This is a macro instead of a function so that it only mutably borrows the needed fields
@@ -198,7 +289,11 @@ macro_rules! new_main_bus {
             &mut $self.ym2612,
             &mut $self.input,
             $self.timing_mode,
-            MainBusSignals { z80_busack: $self.z80.stalled(), m68k_reset: $m68k_reset },
+            MainBusSignals {
+                z80_busack: $self.z80.stalled(),
+                m68k_reset: $m68k_reset,
+                expansion_connected: false,
+            },
             std::mem::take(&mut $self.main_bus_writes),
         )
     };
@@ -209,16 +304,40 @@ impl GenesisEmulator {
     ///
     /// # Errors
     ///
-    /// Returns an error if unable to parse the ROM header.
-    #[must_use]
+    /// Returns an error if the cartridge save file is incompatible with the cartridge.
     pub fn create<S: SaveWriter>(
         rom: Vec<u8>,
         config: GenesisEmulatorConfig,
         save_writer: &mut S,
-    ) -> Self {
+    ) -> Result<Self, GenesisLoadError> {
+        let initial_ram = save_writer.load_bytes("sav").ok();
+        let cartridge = Cartridge::from_rom(rom, initial_ram, config.forced_region)?;
+        Ok(Self::from_cartridge(cartridge, config))
+    }
+
+    /// Initialize the emulator from a Sonic & Knuckles ROM with another cartridge's ROM locked on
+    /// top. See [`Cartridge::from_rom_with_lockon`] for how the two ROMs are mapped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cartridge save file is incompatible with the locked-on cartridge.
+    pub fn create_with_lock_on<S: SaveWriter>(
+        rom: Vec<u8>,
+        lock_on_rom: Vec<u8>,
+        config: GenesisEmulatorConfig,
+        save_writer: &mut S,
+    ) -> Result<Self, GenesisLoadError> {
         let initial_ram = save_writer.load_bytes("sav").ok();
-        let cartridge = Cartridge::from_rom(rom, initial_ram, config.forced_region);
-        let memory = Memory::new(cartridge);
+        let cartridge =
+            Cartridge::from_rom_with_lockon(rom, lock_on_rom, initial_ram, config.forced_region)?;
+        Ok(Self::from_cartridge(cartridge, config))
+    }
+
+    fn from_cartridge(cartridge: Cartridge, config: GenesisEmulatorConfig) -> Self {
+        let mut memory = Memory::new(cartridge);
+        memory.set_forced_console_region(config.console_region);
+        memory.set_tmss_enabled(config.tmss_enabled);
+        memory.init_ram(config.ram_init_pattern, &mut Rng::new(config.rng_seed));
 
         let timing_mode =
             config.forced_timing_mode.unwrap_or_else(|| match memory.hardware_region() {
@@ -249,12 +368,24 @@ impl GenesisEmulator {
             main_bus_writes: MainBusWrites::new(),
             aspect_ratio: config.aspect_ratio,
             adjust_aspect_ratio_in_2x_resolution: config.adjust_aspect_ratio_in_2x_resolution,
-            audio_resampler: GenesisAudioResampler::new(timing_mode),
+            render_options: RenderOptions::new(config),
+            audio_resampler: GenesisAudioResampler::new(
+                timing_mode,
+                OUTPUT_FREQUENCY,
+                config.dc_blocking_filter,
+            ),
+            psg_muted: false,
+            rng_seed: config.rng_seed,
             z80_mclk_cycles: 0,
             psg_mclk_cycles: 0,
             wait_states: WaitStates::default(),
+            cheats: CheatList::new(),
+            #[cfg(any(test, feature = "perf"))]
+            perf_counters: PerfCounters::default(),
         };
 
+        emulator.audio_resampler.set_stereo_mix(config.to_stereo_mix());
+
         // Reset CPU so that execution will start from the right place
         emulator.m68k.execute_instruction(&mut new_main_bus!(emulator, m68k_reset: true));
 
@@ -266,21 +397,46 @@ impl GenesisEmulator {
         self.memory.game_title()
     }
 
+    /// Whether the Z80 has locked up. This can only be cleared by a reset.
+    #[inline]
+    #[must_use]
+    pub fn z80_halted(&self) -> bool {
+        self.memory.z80_halted()
+    }
+
     #[inline]
     #[must_use]
     pub fn has_sram(&self) -> bool {
         self.memory.is_external_ram_persistent()
     }
 
+    #[must_use]
+    pub fn audio_output_sample_rate(&self) -> u64 {
+        self.audio_resampler.output_sample_rate()
+    }
+
+    /// Change the audio output sample rate. See [`GenesisAudioResampler::set_output_sample_rate`].
+    pub fn set_audio_output_sample_rate(&mut self, output_sample_rate: u64) {
+        self.audio_resampler.set_output_sample_rate(output_sample_rate);
+    }
+
     fn render_frame<R: Renderer>(&mut self, renderer: &mut R) -> Result<(), R::Err> {
         render_frame(
             &self.vdp,
             self.aspect_ratio,
             self.adjust_aspect_ratio_in_2x_resolution,
+            &mut self.render_options,
             renderer,
         )
     }
 
+    /// Snapshot the current frame as packed RGB8 pixels, without going through a [`Renderer`].
+    #[must_use]
+    pub fn frame_rgb8(&self) -> (Vec<u8>, FrameSize) {
+        let frame_size = vdp_frame_size(&self.vdp);
+        (frame_buffer_to_rgb8(self.vdp.frame_buffer(), frame_size), frame_size)
+    }
+
     pub fn copy_cram(&self, out: &mut [Color]) {
         self.vdp.copy_cram(out);
     }
@@ -288,9 +444,182 @@ impl GenesisEmulator {
     pub fn copy_vram(&self, out: &mut [Color], palette: u8, row_len: usize) {
         self.vdp.copy_vram(out, palette, row_len);
     }
+
+    /// Render the full nametable for the given plane. See [`Vdp::debug_nametable`].
+    pub fn debug_nametable(&self, plane: DebugPlane, out: &mut [Color]) -> FrameSize {
+        self.vdp.debug_nametable(plane, out)
+    }
+
+    /// Decode the sprite attribute table. See [`Vdp::debug_sprites`].
+    #[must_use]
+    pub fn debug_sprites(&self) -> Vec<SpriteDebugInfo> {
+        self.vdp.debug_sprites()
+    }
+
+    /// Mute or unmute a sound chip. Muted chips keep running but do not contribute to the audio
+    /// output.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if given a YM2612 channel outside of the range 0-5.
+    pub fn set_chip_muted(&mut self, chip: ChipId, muted: bool) {
+        match chip {
+            ChipId::Psg => self.psg_muted = muted,
+            ChipId::Ym2612 => {
+                for channel in 0..6 {
+                    self.ym2612.set_channel_muted(channel, muted);
+                }
+            }
+            ChipId::Ym2612Channel(channel) => {
+                self.ym2612.set_channel_muted(channel.into(), muted);
+            }
+        }
+    }
+
+    /// Enable or disable recording each YM2612 channel's output individually. See
+    /// [`Ym2612::set_channel_taps_enabled`].
+    pub fn set_ym2612_channel_taps_enabled(&mut self, enabled: bool) {
+        self.ym2612.set_channel_taps_enabled(enabled);
+    }
+
+    /// Take the YM2612 per-channel output samples recorded since the last call, or `None` if
+    /// channel taps are disabled.
+    pub fn take_ym2612_channel_taps(&mut self) -> Option<[Vec<(f64, f64)>; 6]> {
+        self.ym2612.take_channel_taps()
+    }
+
+    /// 68000 execute and data breakpoints. When the 68000 stops at a breakpoint,
+    /// [`EmulatorTrait::tick`] returns [`TickEffect::BreakpointHit`].
+    ///
+    /// Breakpoints are not included in save states; they are kept when a state is loaded.
+    pub fn m68k_breakpoints_mut(&mut self) -> &mut Breakpoints {
+        self.m68k.breakpoints_mut()
+    }
+
+    /// The breakpoint hit by the 68000's most recent instruction, if any.
+    #[must_use]
+    pub fn m68k_breakpoint_hit(&self) -> Option<BreakpointHit> {
+        self.m68k.breakpoint_hit()
+    }
+
+    /// Total number of 68000 cycles that the CPU has spent halted because the VDP FIFO was full
+    /// or a memory-to-VRAM DMA was in progress.
+    #[must_use]
+    pub fn m68k_vdp_stall_cycles(&self) -> u64 {
+        self.wait_states.m68k_vdp_stall_cycles
+    }
+
+    /// Read a byte from the 68000 address space without side effects. See [`Memory::debug_read`].
+    #[must_use]
+    pub fn debug_read(&self, address: u32) -> u8 {
+        self.memory.debug_read(address)
+    }
+
+    /// Fill `out` with consecutive bytes read by [`Self::debug_read`], starting at `address`.
+    pub fn debug_read_range(&self, address: u32, out: &mut [u8]) {
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = self.memory.debug_read(address.wrapping_add(i as u32));
+        }
+    }
+
+    /// Write a byte to cartridge RAM, work RAM, or Z80 RAM without side effects, for debuggers and
+    /// cheats. See [`Memory::debug_write`].
+    ///
+    /// Returns `false` if the write was ignored because the address does not map to RAM, e.g. a
+    /// cartridge ROM address.
+    pub fn debug_write(&mut self, address: u32, value: u8) -> bool {
+        let written = self.memory.debug_write(address, value);
+        if !written {
+            log::warn!("Ignoring debug write to non-RAM address {address:06X}");
+        }
+        written
+    }
+
+    #[must_use]
+    pub fn cheats(&self) -> &CheatList {
+        &self.cheats
+    }
+
+    /// Replace the active cheat codes. ROM patches take effect immediately, and RAM writes are
+    /// applied at the end of every frame.
+    pub fn set_cheats(&mut self, cheats: CheatList) {
+        self.memory.medium_mut().set_rom_patches(cheats.rom_patches().collect());
+        self.cheats = cheats;
+    }
+
+    /// Queue the exact controller data port bytes for a future frame, switching the controller
+    /// ports into raw input mode for TAS movie playback. See [`InputState::queue_raw_frame`].
+    pub fn queue_raw_controller_frame(&mut self, frame: RawControllerFrame) {
+        self.input.queue_raw_frame(frame);
+    }
+
+    /// Stop raw controller input playback and go back to reading controller state from
+    /// [`GenesisInputs`].
+    pub fn disable_raw_controller_input(&mut self) {
+        self.input.disable_raw_input();
+    }
+
+    fn apply_ram_cheats(&mut self) {
+        for (address, value) in self.cheats.ram_writes() {
+            let [msb, lsb] = value.to_be_bytes();
+            self.memory.debug_write(address, msb);
+            self.memory.debug_write(address.wrapping_add(1), lsb);
+        }
+    }
+
+    /// Return the performance counters accumulated since the last call, and reset them to zero.
+    #[cfg(any(test, feature = "perf"))]
+    pub fn perf_counters(&mut self) -> PerfCounters {
+        mem::take(&mut self.perf_counters)
+    }
+}
+
+/// Frame post-processing settings and buffers used by [`render_frame`]: deinterlacing, the NTSC
+/// composite filter, and the overscan border.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct RenderOptions {
+    deinterlacer: Deinterlacer,
+    composite_filter: Option<NtscCompositeFilter>,
+    overscan_border_width: u32,
+    overscan_buffer: OverscanBuffer,
+}
+
+impl RenderOptions {
+    #[must_use]
+    pub fn new(config: GenesisEmulatorConfig) -> Self {
+        Self {
+            deinterlacer: Deinterlacer::new(config.deinterlacing_mode),
+            composite_filter: config.ntsc_composite_filter.then(NtscCompositeFilter::new),
+            overscan_border_width: config.overscan_border_width,
+            overscan_buffer: OverscanBuffer::new(),
+        }
+    }
+
+    pub fn reload_config(&mut self, config: GenesisEmulatorConfig) {
+        self.deinterlacer.set_mode(config.deinterlacing_mode);
+        self.composite_filter = config.ntsc_composite_filter.then(NtscCompositeFilter::new);
+        self.overscan_border_width = config.overscan_border_width;
+    }
+
+    #[must_use]
+    pub fn deinterlacing_mode(&self) -> DeinterlacingMode {
+        self.deinterlacer.mode()
+    }
+
+    #[must_use]
+    pub fn ntsc_composite_filter(&self) -> bool {
+        self.composite_filter.is_some()
+    }
+
+    #[must_use]
+    pub fn overscan_border_width(&self) -> u32 {
+        self.overscan_border_width
+    }
 }
 
-/// Render the current VDP frame buffer.
+/// Render the current VDP frame buffer, surrounded by an overscan border in the backdrop color if
+/// the overscan border width is non-zero. Frames in interlaced double resolution mode are
+/// deinterlaced first.
 ///
 /// # Errors
 ///
@@ -299,16 +628,57 @@ pub fn render_frame<R: Renderer>(
     vdp: &Vdp,
     aspect_ratio: GenesisAspectRatio,
     adjust_aspect_ratio_in_2x_resolution: bool,
+    render_options: &mut RenderOptions,
     renderer: &mut R,
 ) -> Result<(), R::Err> {
-    let frame_width = vdp.screen_width();
-    let frame_height = vdp.screen_height();
-
-    let frame_size = FrameSize { width: frame_width, height: frame_height };
+    let frame_size = vdp_frame_size(vdp);
     let pixel_aspect_ratio =
         aspect_ratio.to_pixel_aspect_ratio(frame_size, adjust_aspect_ratio_in_2x_resolution);
 
-    renderer.render_frame(vdp.frame_buffer(), frame_size, pixel_aspect_ratio)
+    let frame_buffer = if vdp.is_interlaced_double() {
+        render_options.deinterlacer.apply(vdp.frame_buffer(), frame_size, vdp.odd_field())
+    } else {
+        vdp.frame_buffer().as_slice()
+    };
+    let frame_buffer = match &mut render_options.composite_filter {
+        Some(composite_filter) => composite_filter.apply(frame_buffer, frame_size),
+        None => frame_buffer,
+    };
+    let (frame_buffer, frame_size) = render_options.overscan_buffer.apply(
+        frame_buffer,
+        frame_size,
+        render_options.overscan_border_width,
+        vdp.backdrop_color(),
+    );
+
+    renderer.render_frame(frame_buffer, frame_size, pixel_aspect_ratio)
+}
+
+fn vdp_frame_size(vdp: &Vdp) -> FrameSize {
+    FrameSize { width: vdp.screen_width(), height: vdp.screen_height() }
+}
+
+impl VersionedState for GenesisEmulator {
+    const SYSTEM_ID: [u8; 4] = *b"GENS";
+    const STATE_VERSION: u32 = 1;
+}
+
+// Memory region IDs for EmulatorTrait::read_region, as indices into MEMORY_REGIONS
+const MAIN_RAM_REGION_ID: usize = 0;
+const AUDIO_RAM_REGION_ID: usize = 1;
+const CARTRIDGE_RAM_REGION_ID: usize = 2;
+
+// (name, base address) of each memory region, in region ID order
+const MEMORY_REGIONS: [(&str, u32); 3] =
+    [("Main RAM", 0xFF0000), ("Audio RAM", 0xA00000), ("Cartridge RAM", 0x200000)];
+
+fn memory_region_bytes(emulator: &GenesisEmulator, region_id: usize) -> Option<&[u8]> {
+    match region_id {
+        MAIN_RAM_REGION_ID => Some(emulator.memory.main_ram()),
+        AUDIO_RAM_REGION_ID => Some(emulator.memory.audio_ram()),
+        CARTRIDGE_RAM_REGION_ID => Some(emulator.memory.external_ram()),
+        _ => None,
+    }
 }
 
 impl EmulatorTrait for GenesisEmulator {
@@ -345,14 +715,33 @@ impl EmulatorTrait for GenesisEmulator {
         S::Err: Debug + Display + Send + Sync + 'static,
     {
         let mut bus = new_main_bus!(self, m68k_reset: false);
+        let mut m68k_breakpoint_hit = false;
         let m68k_cycles = if self.wait_states.m68k_cpu_cycles != 0 {
             mem::take(&mut self.wait_states.m68k_cpu_cycles)
         } else {
-            self.m68k.execute_instruction(&mut bus)
+            // The VDP halts the 68K while the FIFO is full or a memory-to-VRAM DMA is in progress
+            let vdp_stalled = bus.halt();
+            let m68k_cycles = self.m68k.execute_instruction_traced(&mut bus, |_| {
+                // Only called for instructions that actually execute, not for stalls or interrupts
+                #[cfg(any(test, feature = "perf"))]
+                {
+                    self.perf_counters.instructions += 1;
+                }
+            });
+            if vdp_stalled {
+                self.wait_states.m68k_vdp_stall_cycles += u64::from(m68k_cycles);
+            }
+            m68k_breakpoint_hit = self.m68k.breakpoint_hit().is_some();
+            m68k_cycles
         };
 
         let elapsed_mclk_cycles = u64::from(m68k_cycles) * M68K_MCLK_DIVIDER;
 
+        #[cfg(any(test, feature = "perf"))]
+        {
+            self.perf_counters.master_clock_cycles += elapsed_mclk_cycles;
+        }
+
         self.z80_mclk_cycles += elapsed_mclk_cycles;
         if self.z80_mclk_cycles >= self.wait_states.z80_mclk_cycles {
             self.z80_mclk_cycles -= self.wait_states.z80_mclk_cycles;
@@ -363,7 +752,9 @@ impl EmulatorTrait for GenesisEmulator {
         }
 
         while self.z80_mclk_cycles >= Z80_MCLK_DIVIDER {
-            self.z80.tick(&mut bus);
+            if !bus.z80_halted() {
+                self.z80.tick(&mut bus);
+            }
             self.z80_mclk_cycles -= Z80_MCLK_DIVIDER;
         }
 
@@ -371,6 +762,9 @@ impl EmulatorTrait for GenesisEmulator {
             self.wait_states.handle_z80_68k_bus_access();
         }
 
+        // Tick the BUSACK latency before applying writes so that a BUSREQ write made by this
+        // instruction is not immediately aged by the instruction's own cycles
+        bus.tick_z80_signals(elapsed_mclk_cycles);
         self.main_bus_writes = bus.apply_writes();
 
         self.memory.medium_mut().tick(m68k_cycles);
@@ -380,7 +774,8 @@ impl EmulatorTrait for GenesisEmulator {
         self.psg_mclk_cycles += elapsed_mclk_cycles;
         while self.psg_mclk_cycles >= PSG_MCLK_DIVIDER {
             if self.psg.tick() == PsgTickEffect::Clocked {
-                let (psg_sample_l, psg_sample_r) = self.psg.sample();
+                let (psg_sample_l, psg_sample_r) =
+                    if self.psg_muted { (0.0, 0.0) } else { self.psg.sample() };
                 self.audio_resampler.collect_psg_sample(psg_sample_l, psg_sample_r);
             }
 
@@ -402,6 +797,8 @@ impl EmulatorTrait for GenesisEmulator {
 
             self.input.set_inputs(*inputs);
 
+            self.apply_ram_cheats();
+
             if self.memory.is_external_ram_persistent()
                 && self.memory.get_and_clear_external_ram_dirty()
             {
@@ -414,6 +811,10 @@ impl EmulatorTrait for GenesisEmulator {
             return Ok(TickEffect::FrameRendered);
         }
 
+        if m68k_breakpoint_hit {
+            return Ok(TickEffect::BreakpointHit);
+        }
+
         Ok(TickEffect::None)
     }
 
@@ -427,13 +828,22 @@ impl EmulatorTrait for GenesisEmulator {
     fn reload_config(&mut self, config: &Self::Config) {
         self.aspect_ratio = config.aspect_ratio;
         self.adjust_aspect_ratio_in_2x_resolution = config.adjust_aspect_ratio_in_2x_resolution;
+        self.render_options.reload_config(*config);
         self.vdp.reload_config(config.to_vdp_config());
         self.ym2612.set_quantize_output(config.quantize_ym2612_output);
+        self.audio_resampler.set_dc_blocking_enabled(config.dc_blocking_filter);
+        self.audio_resampler.set_stereo_mix(config.to_stereo_mix());
         self.input.reload_config(*config);
+        self.memory.set_forced_console_region(config.console_region);
+        self.memory.set_tmss_enabled(config.tmss_enabled);
     }
 
     fn take_rom_from(&mut self, other: &mut Self) {
         self.memory.take_rom_from(&mut other.memory);
+
+        // Channel taps are not saved in save states
+        self.ym2612.set_channel_taps_enabled(other.ym2612.channel_taps_enabled());
+        self.m68k.take_breakpoints_from(&mut other.m68k);
     }
 
     fn soft_reset(&mut self) {
@@ -444,31 +854,728 @@ impl EmulatorTrait for GenesisEmulator {
         self.ym2612.reset();
     }
 
-    fn hard_reset<S: SaveWriter>(&mut self, save_writer: &mut S) {
+    fn hard_reset<S: SaveWriter>(&mut self, _save_writer: &mut S) {
         log::info!("Hard resetting console");
 
-        let rom = self.memory.take_rom();
+        // Reuse the in-memory cartridge RAM rather than reloading the save file, which may have
+        // changed on disk since the game was loaded
+        let cartridge = self.memory.take_cartridge_for_hard_reset();
         let vdp_config = self.vdp.config();
         let (p1_controller_type, p2_controller_type) = self.input.controller_types();
 
+        let stereo_mix = self.audio_resampler.stereo_mix();
         let config = GenesisEmulatorConfig {
             forced_timing_mode: Some(self.timing_mode),
             forced_region: Some(self.memory.hardware_region()),
+            console_region: self.memory.forced_console_region(),
+            tmss_enabled: self.memory.tmss_enabled(),
+            ram_init_pattern: self.memory.ram_init_pattern(),
+            rng_seed: self.rng_seed,
             aspect_ratio: self.aspect_ratio,
             adjust_aspect_ratio_in_2x_resolution: self.adjust_aspect_ratio_in_2x_resolution,
+            deinterlacing_mode: self.render_options.deinterlacing_mode(),
             remove_sprite_limits: !vdp_config.enforce_sprite_limits,
             emulate_non_linear_vdp_dac: vdp_config.emulate_non_linear_dac,
             render_vertical_border: vdp_config.render_vertical_border,
             render_horizontal_border: vdp_config.render_horizontal_border,
+            ntsc_composite_filter: self.render_options.ntsc_composite_filter(),
+            overscan_border_width: self.render_options.overscan_border_width(),
+            plane_a_enabled: vdp_config.plane_a_enabled,
+            plane_b_enabled: vdp_config.plane_b_enabled,
+            window_enabled: vdp_config.window_enabled,
+            sprites_enabled: vdp_config.sprites_enabled,
             quantize_ym2612_output: self.ym2612.get_quantize_output(),
+            dc_blocking_filter: self.audio_resampler.dc_blocking_enabled(),
+            psg_pan: stereo_mix.psg_pan,
+            mono_audio: stereo_mix.mono,
             p1_controller_type,
             p2_controller_type,
         };
 
-        *self = GenesisEmulator::create(rom, config, save_writer);
+        let cheats = mem::take(&mut self.cheats);
+        let psg_muted = self.psg_muted;
+        let ym2612_muted_channels = self.ym2612.muted_channels();
+        let ym2612_channel_taps_enabled = self.ym2612.channel_taps_enabled();
+        let output_sample_rate = self.audio_resampler.output_sample_rate();
+
+        // Breakpoints belong to the debugger session rather than the console, so move them into
+        // the rebuilt CPU
+        let mut emulator = GenesisEmulator::from_cartridge(cartridge, config);
+        emulator.m68k.take_breakpoints_from(&mut self.m68k);
+
+        *self = emulator;
+        self.set_cheats(cheats);
+        self.audio_resampler.set_output_sample_rate(output_sample_rate);
+
+        self.psg_muted = psg_muted;
+        for (channel, muted) in ym2612_muted_channels.into_iter().enumerate() {
+            self.ym2612.set_channel_muted(channel, muted);
+        }
+        self.ym2612.set_channel_taps_enabled(ym2612_channel_taps_enabled);
     }
 
     fn timing_mode(&self) -> TimingMode {
         self.timing_mode
     }
+
+    fn memory_regions(&self) -> Vec<MemoryRegion> {
+        MEMORY_REGIONS
+            .iter()
+            .enumerate()
+            .map(|(region_id, &(name, base_address))| {
+                let len = memory_region_bytes(self, region_id).map_or(0, <[u8]>::len);
+                MemoryRegion::new(name, base_address, len)
+            })
+            .collect()
+    }
+
+    fn read_region(&self, region_id: usize, offset: usize) -> Option<u8> {
+        memory_region_bytes(self, region_id)?.get(offset).copied()
+    }
+}
+
+impl MoviePlayback<GenesisInputs> for GenesisEmulator {
+    fn prepare_movie_frame(&mut self, frame: &GenesisInputs) -> GenesisInputs {
+        *frame
+    }
+}
+
+// Raw controller movies bypass the controller emulation; see InputState::queue_raw_frame
+impl MoviePlayback<RawControllerFrame> for GenesisEmulator {
+    fn prepare_movie_frame(&mut self, frame: &RawControllerFrame) -> GenesisInputs {
+        self.queue_raw_controller_frame(frame.clone());
+        GenesisInputs::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cheats::CheatCode;
+    use jgenesis_common::movie::{Movie, MovieError, MoviePlayer};
+    use jgenesis_common::save_state;
+    use jgenesis_common::testing::{
+        CapturingAudioOutput, CapturingRenderer, NullAudioOutput, NullRenderer, NullSaveWriter,
+    };
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn new_emulator() -> GenesisEmulator {
+        // MOVE.B #$42, $FF1234
+        // BRA.s -2 (infinite loop)
+        new_emulator_with_program(&[0x13, 0xFC, 0x00, 0x42, 0x00, 0xFF, 0x12, 0x34, 0x60, 0xFE])
+    }
+
+    fn new_emulator_with_program(program: &[u8]) -> GenesisEmulator {
+        new_emulator_with_config(program, test_config())
+    }
+
+    fn new_emulator_with_config(program: &[u8], config: GenesisEmulatorConfig) -> GenesisEmulator {
+        GenesisEmulator::create(test_rom(program), config, &mut NullSaveWriter).unwrap()
+    }
+
+    fn test_rom(program: &[u8]) -> Vec<u8> {
+        // Initial SP = $00FF0000, initial PC = $00000200
+        let mut rom = vec![0; 0x10000];
+        rom[0..8].copy_from_slice(&[0x00, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00]);
+        rom[0x200..0x200 + program.len()].copy_from_slice(program);
+        rom
+    }
+
+    fn test_config() -> GenesisEmulatorConfig {
+        GenesisEmulatorConfig {
+            p1_controller_type: GenesisControllerType::default(),
+            p2_controller_type: GenesisControllerType::default(),
+            forced_timing_mode: Some(TimingMode::Ntsc),
+            forced_region: Some(GenesisRegion::Americas),
+            console_region: None,
+            tmss_enabled: false,
+            ram_init_pattern: RamInitPattern::default(),
+            rng_seed: 0,
+            aspect_ratio: GenesisAspectRatio::Ntsc,
+            adjust_aspect_ratio_in_2x_resolution: true,
+            deinterlacing_mode: DeinterlacingMode::default(),
+            remove_sprite_limits: false,
+            emulate_non_linear_vdp_dac: false,
+            render_vertical_border: false,
+            render_horizontal_border: false,
+            ntsc_composite_filter: false,
+            overscan_border_width: 0,
+            plane_a_enabled: true,
+            plane_b_enabled: true,
+            window_enabled: true,
+            sprites_enabled: true,
+            quantize_ym2612_output: true,
+            dc_blocking_filter: true,
+            psg_pan: 0.0,
+            mono_audio: false,
+        }
+    }
+
+    fn run_frame(emulator: &mut GenesisEmulator) {
+        run_frame_with_inputs(emulator, &GenesisInputs::default());
+    }
+
+    fn run_frame_with_inputs(emulator: &mut GenesisEmulator, inputs: &GenesisInputs) {
+        emulator
+            .run_frames(1, &mut NullRenderer, &mut NullAudioOutput, inputs, &mut NullSaveWriter)
+            .unwrap();
+    }
+
+    #[test]
+    fn same_rng_seed_is_reproducible() {
+        // MOVE.W $FF8000, $FF0000
+        // BRA.s -2 (infinite loop)
+        let program = [0x33, 0xF9, 0x00, 0xFF, 0x80, 0x00, 0x00, 0xFF, 0x00, 0x00, 0x60, 0xFE];
+        let config = |rng_seed| GenesisEmulatorConfig {
+            ram_init_pattern: RamInitPattern::Random,
+            rng_seed,
+            ..test_config()
+        };
+
+        let mut emulators =
+            [1234, 1234, 5678].map(|rng_seed| new_emulator_with_config(&program, config(rng_seed)));
+        for emulator in &mut emulators {
+            for _ in 0..5 {
+                run_frame(emulator);
+            }
+        }
+
+        let ram: Vec<_> = emulators
+            .iter()
+            .map(|emulator| {
+                let mut ram = vec![0; 0x10000];
+                emulator.debug_read_range(0xFF0000, &mut ram);
+                ram
+            })
+            .collect();
+        assert_eq!(ram[0], ram[1]);
+        assert_ne!(ram[0], ram[2]);
+        assert_eq!(ram[0][0..2], ram[0][0x8000..0x8002]);
+
+        assert_eq!(emulators[0].frame_rgb8(), emulators[1].frame_rgb8());
+    }
+
+    #[test]
+    fn memory_regions() {
+        let mut emulator = new_emulator();
+        run_frame(&mut emulator);
+
+        let regions = emulator.memory_regions();
+        assert_eq!(regions[MAIN_RAM_REGION_ID], MemoryRegion::new("Main RAM", 0xFF0000, 64 * 1024));
+        assert_eq!(regions[AUDIO_RAM_REGION_ID].len, 8 * 1024);
+
+        // Written by MOVE.B #$42, $FF1234
+        assert_eq!(emulator.read_region(MAIN_RAM_REGION_ID, 0x1234), Some(0x42));
+        assert_eq!(emulator.read_region(MAIN_RAM_REGION_ID, 0x1235), Some(0x00));
+        assert_eq!(emulator.read_region(MAIN_RAM_REGION_ID, 0x10000), None);
+        assert_eq!(emulator.read_region(MEMORY_REGIONS.len(), 0), None);
+
+        emulator.debug_write(0xA00100, 0x66);
+        assert_eq!(emulator.read_region(AUDIO_RAM_REGION_ID, 0x0100), Some(0x66));
+    }
+
+    // Save writer whose save file is too large for any cartridge RAM
+    struct OversizedSaveWriter;
+
+    impl SaveWriter for OversizedSaveWriter {
+        type Err = std::io::Error;
+
+        fn load_bytes(&mut self, _extension: &str) -> Result<Vec<u8>, Self::Err> {
+            Ok(vec![0xFF; 0x20000])
+        }
+
+        fn persist_bytes(&mut self, _extension: &str, _bytes: &[u8]) -> Result<(), Self::Err> {
+            Ok(())
+        }
+
+        fn load_serialized<D: Decode>(&mut self, extension: &str) -> Result<D, Self::Err> {
+            NullSaveWriter.load_serialized(extension)
+        }
+
+        fn persist_serialized<E: Encode>(
+            &mut self,
+            _extension: &str,
+            _data: E,
+        ) -> Result<(), Self::Err> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn hard_reset_keeps_cartridge_ram() {
+        // BRA.s -2 (infinite loop)
+        let mut rom = test_rom(&[0x60, 0xFE]);
+        rom[0x1B0..0x1B4].copy_from_slice(&[b'R', b'A', 0xE0, 0x20]);
+        rom[0x1B4..0x1B8].copy_from_slice(&0x200000_u32.to_be_bytes());
+        rom[0x1B8..0x1BC].copy_from_slice(&0x203FFF_u32.to_be_bytes());
+
+        assert!(matches!(
+            GenesisEmulator::create(rom.clone(), test_config(), &mut OversizedSaveWriter),
+            Err(GenesisLoadError::CartridgeRam(CartridgeRamError::SaveTooLarge { .. }))
+        ));
+
+        let mut emulator =
+            GenesisEmulator::create(rom, test_config(), &mut NullSaveWriter).unwrap();
+        run_frame(&mut emulator);
+        emulator.debug_write(0x200010, 0x5A);
+
+        // The save file is not reloaded, so a save that changed on disk cannot fail the reset
+        emulator.hard_reset(&mut OversizedSaveWriter);
+        run_frame(&mut emulator);
+
+        assert_eq!(emulator.debug_read(0x200010), 0x5A);
+        assert_eq!(emulator.debug_read(0x000200), 0x60);
+    }
+
+    #[test]
+    fn hard_reset_keeps_output_sample_rate() {
+        fn first_frame_sample_count(emulator: &mut GenesisEmulator) -> usize {
+            let mut audio_output = CapturingAudioOutput::new();
+            emulator
+                .run_frames(
+                    1,
+                    &mut NullRenderer,
+                    &mut audio_output,
+                    &GenesisInputs::default(),
+                    &mut NullSaveWriter,
+                )
+                .unwrap();
+            audio_output.take_samples().len()
+        }
+
+        // BRA.s -2 (infinite loop)
+        let rom = test_rom(&[0x60, 0xFE]);
+        let mut emulator =
+            GenesisEmulator::create(rom, test_config(), &mut NullSaveWriter).unwrap();
+        assert_eq!(emulator.audio_output_sample_rate(), OUTPUT_FREQUENCY);
+        let samples_48000 = first_frame_sample_count(&mut emulator);
+
+        emulator.set_audio_output_sample_rate(44100);
+        emulator.hard_reset(&mut NullSaveWriter);
+        assert_eq!(emulator.audio_output_sample_rate(), 44100);
+
+        // The resampler output rate is actually applied, not just reported
+        let samples_44100 = first_frame_sample_count(&mut emulator);
+        let expected = samples_48000 * 44100 / 48000;
+        assert!(samples_44100.abs_diff(expected) <= 2, "{samples_44100} != {expected}");
+    }
+
+    #[test]
+    fn lock_on_cartridge() {
+        // BRA.s -2 (infinite loop)
+        let rom = test_rom(&[0x60, 0xFE]);
+        let lock_on_rom = vec![0x22; 0x200000];
+
+        let mut emulator = GenesisEmulator::create_with_lock_on(
+            rom,
+            lock_on_rom,
+            test_config(),
+            &mut NullSaveWriter,
+        )
+        .unwrap();
+        run_frame(&mut emulator);
+
+        assert_eq!(emulator.debug_read(0x000200), 0x60);
+        assert_eq!(emulator.debug_read(0x200000), 0x22);
+
+        // The locked-on ROM is still mapped after a hard reset
+        emulator.hard_reset(&mut NullSaveWriter);
+        run_frame(&mut emulator);
+
+        assert_eq!(emulator.debug_read(0x000200), 0x60);
+        assert_eq!(emulator.debug_read(0x3FFFFF), 0x22);
+    }
+
+    #[test]
+    fn rom_identification() {
+        assert_eq!(rom_crc32(b"123456789"), 0xCBF43926);
+
+        let mut rom = vec![0; 0x200];
+        rom[0x180..0x18E].copy_from_slice(b"GM MK-1079 -00");
+        assert_eq!(rom_serial(&rom), "MK-1079");
+
+        let crc = rom_crc32(&rom);
+        rom[0x183..0x18B].copy_from_slice(b"T-120096");
+        assert_eq!(rom_serial(&rom), "T-120096");
+        assert_ne!(rom_crc32(&rom), crc);
+
+        assert_eq!(rom_serial(&[0; 0x100]), "");
+    }
+
+    #[test]
+    fn frame_rgb8() {
+        let mut emulator = new_emulator();
+        run_frame(&mut emulator);
+
+        let (rgb, frame_size) = emulator.frame_rgb8();
+        assert_eq!(frame_size, FrameSize { width: 256, height: 224 });
+        assert_eq!(rgb.len(), (frame_size.width * frame_size.height * 3) as usize);
+    }
+
+    #[test]
+    fn overscan_border() {
+        #[rustfmt::skip]
+        let program = [
+            // MOVE.L #$C0420000, $C00004 (CRAM write to color $21)
+            0x23, 0xFC, 0xC0, 0x42, 0x00, 0x00, 0x00, 0xC0, 0x00, 0x04,
+            // MOVE.W #$000E, $C00000 (red)
+            0x33, 0xFC, 0x00, 0x0E, 0x00, 0xC0, 0x00, 0x00,
+            // MOVE.W #$8721, $C00004 (backdrop color = palette 2, color 1)
+            0x33, 0xFC, 0x87, 0x21, 0x00, 0xC0, 0x00, 0x04,
+            // BRA.s -2
+            0x60, 0xFE,
+        ];
+        let config = GenesisEmulatorConfig { overscan_border_width: 8, ..test_config() };
+        let mut emulator = new_emulator_with_config(&program, config);
+
+        let mut renderer = CapturingRenderer::new();
+        for _ in 0..2 {
+            emulator
+                .run_frames(
+                    1,
+                    &mut renderer,
+                    &mut NullAudioOutput,
+                    &GenesisInputs::default(),
+                    &mut NullSaveWriter,
+                )
+                .unwrap();
+        }
+
+        let backdrop_color = emulator.vdp.backdrop_color();
+        assert_eq!(backdrop_color, Color::rgb(255, 0, 0));
+
+        assert_eq!(renderer.last_frame_size(), Some(FrameSize { width: 272, height: 240 }));
+        let frame = renderer.last_frame();
+        for row in 0..240 {
+            for col in (0..8).chain(264..272) {
+                assert_eq!(frame[row * 272 + col], backdrop_color, "row {row} col {col}");
+            }
+        }
+        for row in (0..8).chain(232..240) {
+            assert!(frame[row * 272..(row + 1) * 272].iter().all(|&color| color == backdrop_color));
+        }
+
+        // The original frame is inside the border, unchanged
+        let (rgb, _) = emulator.frame_rgb8();
+        let inner_rgb: Vec<u8> = (8..232)
+            .flat_map(|row| &frame[row * 272 + 8..row * 272 + 264])
+            .flat_map(|color| [color.r, color.g, color.b])
+            .collect();
+        assert_eq!(inner_rgb, rgb);
+    }
+
+    #[test]
+    fn movie_playback() {
+        #[rustfmt::skip]
+        let program = [
+            // MOVE.L #$C0000000, $C00004 (CRAM write to color 0)
+            0x23, 0xFC, 0xC0, 0x00, 0x00, 0x00, 0x00, 0xC0, 0x00, 0x04,
+            // MOVE.B $A10003, D0 (P1 data port)
+            0x10, 0x39, 0x00, 0xA1, 0x00, 0x03,
+            // MOVE.W D0, $C00000 (backdrop color = P1 data port)
+            0x33, 0xC0, 0x00, 0xC0, 0x00, 0x00,
+            // BRA.s -24
+            0x60, 0xE8,
+        ];
+        let rom = test_rom(&program);
+        let rom_crc32 = rom_crc32(&rom);
+        let state_config = bincode::config::standard();
+
+        let frame_hash = |emulator: &GenesisEmulator| {
+            let mut hasher = DefaultHasher::new();
+            emulator.frame_rgb8().hash(&mut hasher);
+            hasher.finish()
+        };
+        let frame_inputs = |i: usize| {
+            let mut inputs = GenesisInputs::default();
+            inputs.p1.down = i % 2 == 0;
+            inputs.p1.right = i % 3 == 0;
+            inputs.p1.c = i >= 5;
+            inputs
+        };
+
+        // Record one movie from power-on and one from a save state taken partway through
+        let mut emulator = new_emulator_with_program(&program);
+        let mut power_on_movie = Movie::new(GenesisEmulator::SYSTEM_ID, rom_crc32, None);
+        let mut state_movie = None;
+        let mut recorded_hashes = Vec::new();
+        for i in 0..10 {
+            if i == 5 {
+                let mut state = Vec::new();
+                save_state::write_state(&emulator, &mut state, state_config).unwrap();
+                state_movie = Some(Movie::new(GenesisEmulator::SYSTEM_ID, rom_crc32, Some(state)));
+            }
+
+            let inputs = frame_inputs(i);
+            power_on_movie.record_frame(inputs);
+            if let Some(state_movie) = &mut state_movie {
+                state_movie.record_frame(inputs);
+            }
+
+            run_frame_with_inputs(&mut emulator, &inputs);
+            recorded_hashes.push(frame_hash(&emulator));
+        }
+
+        // Inputs are visible in the output
+        assert_ne!(recorded_hashes[1], recorded_hashes[2]);
+
+        let replay = |movie: &Movie<GenesisInputs>| {
+            let mut bytes = Vec::new();
+            movie.write(&mut bytes).unwrap();
+            let movie: Movie<GenesisInputs> =
+                Movie::read(&mut bytes.as_slice(), GenesisEmulator::SYSTEM_ID, rom_crc32).unwrap();
+
+            let mut emulator = new_emulator_with_program(&program);
+            let mut player = MoviePlayer::new(movie);
+            player.start(&mut emulator, state_config).unwrap();
+
+            let mut hashes = Vec::new();
+            while player
+                .play_frame(
+                    &mut emulator,
+                    &mut NullRenderer,
+                    &mut NullAudioOutput,
+                    &mut NullSaveWriter,
+                )
+                .unwrap()
+            {
+                hashes.push(frame_hash(&emulator));
+            }
+            assert!(player.is_finished());
+            hashes
+        };
+
+        assert_eq!(replay(&power_on_movie), recorded_hashes);
+        assert_eq!(replay(&state_movie.unwrap()), recorded_hashes[5..]);
+
+        let mut bytes = Vec::new();
+        power_on_movie.write(&mut bytes).unwrap();
+        let err = Movie::<GenesisInputs>::read(
+            &mut bytes.as_slice(),
+            GenesisEmulator::SYSTEM_ID,
+            rom_crc32 ^ 1,
+        )
+        .unwrap_err();
+        assert!(matches!(err, MovieError::RomMismatch { .. }));
+    }
+
+    #[test]
+    fn raw_controller_movie_playback() {
+        #[rustfmt::skip]
+        let program = [
+            // LEA $FF0000, A0
+            0x41, 0xF9, 0x00, 0xFF, 0x00, 0x00,
+            // MOVE.B $A10003, (A0)+ (log every P1 data port read)
+            0x10, 0xF9, 0x00, 0xA1, 0x00, 0x03,
+            // BRA.s -8
+            0x60, 0xF8,
+        ];
+        let mut emulator = new_emulator_with_program(&program);
+
+        let mut movie = Movie::new(GenesisEmulator::SYSTEM_ID, 0, None);
+        movie.record_frame(RawControllerFrame { p1: vec![0x12, 0x34, 0x56], p2: vec![] });
+        movie.record_frame(RawControllerFrame { p1: vec![0x9A], p2: vec![] });
+        movie.record_frame(RawControllerFrame::default());
+
+        let mut player = MoviePlayer::new(movie);
+        player.start(&mut emulator, bincode::config::standard()).unwrap();
+        while player
+            .play_frame(&mut emulator, &mut NullRenderer, &mut NullAudioOutput, &mut NullSaveWriter)
+            .unwrap()
+        {}
+
+        // Each frame's bytes are read in order at the start of the following frame
+        let mut ram = vec![0; 0x8000];
+        emulator.debug_read_range(0xFF0000, &mut ram);
+        let first_frame = ram.windows(3).position(|window| window == [0x12, 0x34, 0x56]).unwrap();
+        let second_frame = ram.iter().position(|&byte| byte == 0x9A).unwrap();
+        assert!(first_frame < second_frame);
+    }
+
+    #[test]
+    fn m68k_breakpoints() {
+        // MOVEQ #1, D0; MOVE.B D0, $FF0000; BRA.s -8
+        let program = [0x70, 0x01, 0x13, 0xC0, 0x00, 0xFF, 0x00, 0x00, 0x60, 0xF8];
+        let mut emulator = new_emulator_with_program(&program);
+        emulator.m68k_breakpoints_mut().add_execute(0x202);
+
+        let tick = |emulator: &mut GenesisEmulator| {
+            emulator
+                .tick(
+                    &mut NullRenderer,
+                    &mut NullAudioOutput,
+                    &GenesisInputs::default(),
+                    &mut NullSaveWriter,
+                )
+                .unwrap()
+        };
+
+        // Stops before the first write to $FF0000
+        while tick(&mut emulator) != TickEffect::BreakpointHit {}
+        assert_eq!(emulator.m68k_breakpoint_hit(), Some(BreakpointHit::Execute(0x202)));
+        assert_eq!(emulator.m68k.pc(), 0x202);
+        assert_eq!(emulator.debug_read(0xFF0000), 0x00);
+
+        // Resuming executes the instruction at the breakpoint
+        assert_eq!(tick(&mut emulator), TickEffect::None);
+        assert_eq!(emulator.debug_read(0xFF0000), 0x01);
+
+        // Breakpoints are not saved in save states, but loading a state keeps the current ones
+        let config = bincode::config::standard();
+        let state = bincode::encode_to_vec(&emulator, config).unwrap();
+        let (mut loaded, _): (GenesisEmulator, _) =
+            bincode::decode_from_slice(&state, config).unwrap();
+        loaded.take_rom_from(&mut emulator);
+        while tick(&mut loaded) != TickEffect::BreakpointHit {}
+        assert_eq!(loaded.m68k_breakpoint_hit(), Some(BreakpointHit::Execute(0x202)));
+
+        // Hard resets also keep the current breakpoints
+        loaded.hard_reset(&mut NullSaveWriter);
+        while tick(&mut loaded) != TickEffect::BreakpointHit {}
+        assert_eq!(loaded.m68k_breakpoint_hit(), Some(BreakpointHit::Execute(0x202)));
+        assert_eq!(loaded.debug_read(0xFF0000), 0x00);
+    }
+
+    #[test]
+    fn snapshot_restore() {
+        // ADDQ.W #1, $FF0000
+        // BRA.s -8
+        let program = [0x52, 0x79, 0x00, 0xFF, 0x00, 0x00, 0x60, 0xF8];
+        let mut emulator = new_emulator_with_program(&program);
+        run_frame(&mut emulator);
+
+        let encode = |emulator: &GenesisEmulator| {
+            bincode::encode_to_vec(emulator, bincode::config::standard()).unwrap()
+        };
+        let counter = |emulator: &GenesisEmulator| {
+            let mut bytes = [0; 2];
+            emulator.debug_read_range(0xFF0000, &mut bytes);
+            u16::from_be_bytes(bytes)
+        };
+
+        let snapshot = emulator.snapshot();
+        let snapshot_state = encode(&emulator);
+        let snapshot_counter = counter(&emulator);
+
+        for _ in 0..3 {
+            run_frame(&mut emulator);
+        }
+        assert_ne!(encode(&emulator), snapshot_state);
+        assert_ne!(counter(&emulator), snapshot_counter);
+
+        emulator.restore(snapshot);
+        assert_eq!(encode(&emulator), snapshot_state);
+        assert_eq!(counter(&emulator), snapshot_counter);
+
+        // ROM is reattached, so the program keeps running
+        assert_eq!(emulator.debug_read(0x000200), 0x52);
+        run_frame(&mut emulator);
+        assert_ne!(counter(&emulator), snapshot_counter);
+    }
+
+    #[test]
+    fn vdp_dma_stalls_m68k() {
+        let mut emulator = new_emulator();
+        run_frame(&mut emulator);
+        assert_eq!(emulator.m68k_vdp_stall_cycles(), 0);
+
+        #[rustfmt::skip]
+        let mut emulator = new_emulator_with_program(&[
+            // MOVE.L #$81548F02, $C00004 (enable display and DMA, auto-increment 2)
+            0x23, 0xFC, 0x81, 0x54, 0x8F, 0x02, 0x00, 0xC0, 0x00, 0x04,
+            // MOVE.L #$93009410, $C00004 (DMA length $1000 words)
+            0x23, 0xFC, 0x93, 0x00, 0x94, 0x10, 0x00, 0xC0, 0x00, 0x04,
+            // MOVE.L #$95009600, $C00004 (DMA source $000000)
+            0x23, 0xFC, 0x95, 0x00, 0x96, 0x00, 0x00, 0xC0, 0x00, 0x04,
+            // MOVE.W #$9700, $C00004 (memory-to-VRAM DMA)
+            0x33, 0xFC, 0x97, 0x00, 0x00, 0xC0, 0x00, 0x04,
+            // MOVE.L #$40000080, $C00004 (start DMA to VRAM $0000)
+            0x23, 0xFC, 0x40, 0x00, 0x00, 0x80, 0x00, 0xC0, 0x00, 0x04,
+            // BRA.s -2
+            0x60, 0xFE,
+        ]);
+        run_frame(&mut emulator);
+        assert_ne!(emulator.m68k_vdp_stall_cycles(), 0);
+    }
+
+    #[test]
+    fn mute_ym2612() {
+        fn new_emulator_with_audio(ym2612_dac: bool) -> GenesisEmulator {
+            let mut emulator = new_emulator();
+
+            // PSG square wave on channel 0 at max volume
+            emulator.psg.write(0x8F);
+            emulator.psg.write(0x03);
+            emulator.psg.write(0x90);
+
+            if ym2612_dac {
+                // Enable the DAC and output a constant sample
+                emulator.ym2612.write_address_1(0x2B);
+                emulator.ym2612.write_data(0x80);
+                emulator.ym2612.write_address_1(0x2A);
+                emulator.ym2612.write_data(0xFF);
+            }
+
+            emulator
+        }
+
+        fn run_frame_samples(emulator: &mut GenesisEmulator) -> Vec<(f64, f64)> {
+            let mut audio_output = CapturingAudioOutput::new();
+            emulator
+                .run_frames(
+                    1,
+                    &mut NullRenderer,
+                    &mut audio_output,
+                    &GenesisInputs::default(),
+                    &mut NullSaveWriter,
+                )
+                .unwrap();
+            audio_output.take_samples()
+        }
+
+        let psg_only = run_frame_samples(&mut new_emulator_with_audio(false));
+        assert!(psg_only.iter().any(|&(sample_l, _)| sample_l.abs() > 0.0));
+
+        let mixed = run_frame_samples(&mut new_emulator_with_audio(true));
+        assert_ne!(mixed, psg_only);
+
+        let mut emulator = new_emulator_with_audio(true);
+        emulator.set_chip_muted(ChipId::Ym2612, true);
+        assert_eq!(run_frame_samples(&mut emulator), psg_only);
+    }
+
+    #[test]
+    fn ram_write_cheats() {
+        let mut emulator = new_emulator();
+
+        let mut cheats = CheatList::new();
+        cheats.add(CheatCode::parse("FF1234:9876").unwrap());
+        emulator.set_cheats(cheats);
+
+        // The cheat should override the game's own write to $FF1234
+        run_frame(&mut emulator);
+        assert_eq!(emulator.debug_read(0xFF1234), 0x98);
+        assert_eq!(emulator.debug_read(0xFF1235), 0x76);
+    }
+
+    #[test]
+    fn perf_counters() {
+        let mut emulator = new_emulator();
+        run_frame(&mut emulator);
+
+        // Counters must not leak into save states
+        let config = bincode::config::standard();
+        let state = bincode::encode_to_vec(&emulator, config).unwrap();
+
+        let counters = emulator.perf_counters();
+        assert_eq!(state, bincode::encode_to_vec(&emulator, config).unwrap());
+        assert_ne!(counters.master_clock_cycles, 0);
+        assert_ne!(counters.instructions, 0);
+
+        // Every 68000 instruction takes at least 4 cycles; stalled ticks are not instructions
+        assert!(counters.instructions * 4 * M68K_MCLK_DIVIDER <= counters.master_clock_cycles);
+
+        assert_eq!(emulator.perf_counters(), PerfCounters::default());
+    }
 }
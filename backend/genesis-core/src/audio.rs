@@ -3,16 +3,17 @@
 #![allow(clippy::excessive_precision)]
 
 use bincode::{Decode, Encode};
-use jgenesis_common::audio::SignalResampler;
+use jgenesis_common::audio::{SignalResampler, OUTPUT_FREQUENCY};
 use jgenesis_common::frontend::{AudioOutput, TimingMode};
 use smsgg_core::audio::PsgResampler;
 use std::cmp;
+use std::f64::consts::PI;
 
 pub const NTSC_GENESIS_MCLK_FREQUENCY: f64 = 53_693_175.0;
 pub const PAL_GENESIS_MCLK_FREQUENCY: f64 = 53_203_424.0;
 
 const YM2612_LPF_COEFFICIENT_0: f64 = -0.001478342773457343;
-const YM2612_LPF_COEFFICIENTS: [f64; 25] = [
+const YM2612_LPF_COEFFICIENTS: [f64; YM2612_LPF_TAPS] = [
     -0.001478342773457343,
     -0.002579939173264984,
     -0.001815391014296705,
@@ -40,12 +41,18 @@ const YM2612_LPF_COEFFICIENTS: [f64; 25] = [
     -0.002579939173264985,
 ];
 
+const YM2612_LPF_TAPS: usize = 25;
+const YM2612_ZERO_PADDING: usize = 2;
+
 const YM2612_HPF_CHARGE_FACTOR: f64 = 0.9966982656608827;
 
 // -8dB (10 ^ -8/20)
 pub const PSG_COEFFICIENT: f64 = 0.3981071705534972;
 
-pub type Ym2612Resampler = SignalResampler<25, 2>;
+// Corner frequency for the optional DC blocking filter applied to the final mixed output
+pub const DC_BLOCKING_CUTOFF: f64 = 5.0;
+
+pub type Ym2612Resampler = SignalResampler<YM2612_LPF_TAPS, YM2612_ZERO_PADDING>;
 
 #[must_use]
 pub fn new_ym2612_resampler(genesis_mclk_frequency: f64) -> Ym2612Resampler {
@@ -58,15 +65,111 @@ pub fn new_ym2612_resampler(genesis_mclk_frequency: f64) -> Ym2612Resampler {
     )
 }
 
+// The precomputed YM2612 and PSG filters have their cutoff at the Nyquist frequency of the
+// default 48000 Hz output; other output rates get a windowed-sinc filter with the same relative
+// cutoff. The YM2612 cutoff is also capped at the YM2612's own Nyquist frequency so that the
+// images created by zero padding are always filtered out
+fn ym2612_lpf_coefficients(
+    ym2612_frequency: f64,
+    output_frequency: u64,
+) -> (f64, [f64; YM2612_LPF_TAPS]) {
+    if output_frequency == OUTPUT_FREQUENCY {
+        return (YM2612_LPF_COEFFICIENT_0, YM2612_LPF_COEFFICIENTS);
+    }
+
+    let cutoff = (output_frequency as f64 / 2.0).min(ym2612_frequency / 2.0);
+    let padded_frequency = ym2612_frequency * (YM2612_ZERO_PADDING + 1) as f64;
+    let coefficients = smsgg_core::audio::windowed_sinc_lpf(padded_frequency, cutoff);
+    (coefficients[0], coefficients)
+}
+
+fn psg_lpf_coefficients(
+    psg_frequency: f64,
+    output_frequency: u64,
+) -> (f64, [f64; smsgg_core::audio::PSG_LPF_TAPS]) {
+    let cutoff = (output_frequency / 2) as u32;
+    smsgg_core::audio::psg_lpf_coefficients(psg_frequency, Some(cutoff))
+}
+
+/// First-order high-pass filter that removes DC offset from a stereo signal.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct DcBlockingFilter {
+    pole: f64,
+    prev_input: (f64, f64),
+    prev_output: (f64, f64),
+}
+
+impl DcBlockingFilter {
+    #[must_use]
+    pub fn new(sample_rate: u64) -> Self {
+        Self {
+            pole: dc_blocking_pole(sample_rate),
+            prev_input: (0.0, 0.0),
+            prev_output: (0.0, 0.0),
+        }
+    }
+
+    pub fn update_sample_rate(&mut self, sample_rate: u64) {
+        self.pole = dc_blocking_pole(sample_rate);
+    }
+
+    #[must_use]
+    pub fn filter(&mut self, sample_l: f64, sample_r: f64) -> (f64, f64) {
+        let output_l = sample_l - self.prev_input.0 + self.pole * self.prev_output.0;
+        let output_r = sample_r - self.prev_input.1 + self.pole * self.prev_output.1;
+
+        self.prev_input = (sample_l, sample_r);
+        self.prev_output = (output_l, output_r);
+
+        (output_l, output_r)
+    }
+}
+
+fn dc_blocking_pole(sample_rate: u64) -> f64 {
+    (-2.0 * PI * DC_BLOCKING_CUTOFF / sample_rate as f64).exp()
+}
+
+/// Stereo mixing settings applied when combining sound sources.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Encode, Decode)]
+pub struct StereoMix {
+    /// PSG balance, from -1.0 (left only) to 1.0 (right only)
+    pub psg_pan: f64,
+    /// Downmix the final output to mono
+    pub mono: bool,
+}
+
+impl StereoMix {
+    #[must_use]
+    pub fn pan_psg(self, psg_l: f64, psg_r: f64) -> (f64, f64) {
+        let pan = self.psg_pan.clamp(-1.0, 1.0);
+        ((1.0 - pan).min(1.0) * psg_l, (1.0 + pan).min(1.0) * psg_r)
+    }
+
+    #[must_use]
+    pub fn downmix(self, sample_l: f64, sample_r: f64) -> (f64, f64) {
+        if self.mono {
+            let sample = f64::midpoint(sample_l, sample_r);
+            (sample, sample)
+        } else {
+            (sample_l, sample_r)
+        }
+    }
+}
+
 #[derive(Debug, Clone, Encode, Decode)]
 pub struct GenesisAudioResampler {
     ym2612_resampler: Ym2612Resampler,
     psg_resampler: PsgResampler,
+    dc_blocking_filter: DcBlockingFilter,
+    dc_blocking_enabled: bool,
+    stereo_mix: StereoMix,
 }
 
 impl GenesisAudioResampler {
+    /// Create a resampler that outputs at the given sample rate, e.g.
+    /// [`OUTPUT_FREQUENCY`](jgenesis_common::audio::OUTPUT_FREQUENCY) for 48000 Hz.
     #[must_use]
-    pub fn new(timing_mode: TimingMode) -> Self {
+    pub fn new(timing_mode: TimingMode, output_frequency: u64, dc_blocking_enabled: bool) -> Self {
         let genesis_mclk_frequency = match timing_mode {
             TimingMode::Ntsc => NTSC_GENESIS_MCLK_FREQUENCY,
             TimingMode::Pal => PAL_GENESIS_MCLK_FREQUENCY,
@@ -75,7 +178,56 @@ impl GenesisAudioResampler {
         let ym2612_resampler = new_ym2612_resampler(genesis_mclk_frequency);
         let psg_resampler = smsgg_core::audio::new_psg_resampler(genesis_mclk_frequency);
 
-        Self { ym2612_resampler, psg_resampler }
+        let dc_blocking_filter = DcBlockingFilter::new(output_frequency);
+
+        let mut resampler = Self {
+            ym2612_resampler,
+            psg_resampler,
+            dc_blocking_filter,
+            dc_blocking_enabled,
+            stereo_mix: StereoMix::default(),
+        };
+        resampler.set_output_sample_rate(output_frequency);
+        resampler
+    }
+
+    #[must_use]
+    pub fn output_sample_rate(&self) -> u64 {
+        self.ym2612_resampler.output_frequency()
+    }
+
+    /// Change the output sample rate, including the low-pass filter cutoffs. Takes effect starting
+    /// with the next collected sample.
+    pub fn set_output_sample_rate(&mut self, output_frequency: u64) {
+        self.ym2612_resampler.update_output_frequency(output_frequency);
+        let (ym2612_coefficient_0, ym2612_coefficients) =
+            ym2612_lpf_coefficients(self.ym2612_resampler.source_frequency(), output_frequency);
+        self.ym2612_resampler.update_lpf_coefficients(ym2612_coefficient_0, ym2612_coefficients);
+
+        self.psg_resampler.update_output_frequency(output_frequency);
+        let (psg_coefficient_0, psg_coefficients) =
+            psg_lpf_coefficients(self.psg_resampler.source_frequency(), output_frequency);
+        self.psg_resampler.update_lpf_coefficients(psg_coefficient_0, psg_coefficients);
+
+        self.dc_blocking_filter.update_sample_rate(output_frequency);
+    }
+
+    #[must_use]
+    pub fn dc_blocking_enabled(&self) -> bool {
+        self.dc_blocking_enabled
+    }
+
+    pub fn set_dc_blocking_enabled(&mut self, dc_blocking_enabled: bool) {
+        self.dc_blocking_enabled = dc_blocking_enabled;
+    }
+
+    #[must_use]
+    pub fn stereo_mix(&self) -> StereoMix {
+        self.stereo_mix
+    }
+
+    pub fn set_stereo_mix(&mut self, stereo_mix: StereoMix) {
+        self.stereo_mix = stereo_mix;
     }
 
     pub fn collect_ym2612_sample(&mut self, sample_l: f64, sample_r: f64) {
@@ -102,9 +254,20 @@ impl GenesisAudioResampler {
         for _ in 0..sample_count {
             let (ym2612_l, ym2612_r) = self.ym2612_resampler.output_buffer_pop_front().unwrap();
             let (psg_l, psg_r) = self.psg_resampler.output_buffer_pop_front().unwrap();
+            let (psg_l, psg_r) = self.stereo_mix.pan_psg(psg_l, psg_r);
+
+            let (mut sample_l, mut sample_r) = self
+                .stereo_mix
+                .downmix(ym2612_l + PSG_COEFFICIENT * psg_l, ym2612_r + PSG_COEFFICIENT * psg_r);
 
-            let sample_l = (ym2612_l + PSG_COEFFICIENT * psg_l).clamp(-1.0, 1.0);
-            let sample_r = (ym2612_r + PSG_COEFFICIENT * psg_r).clamp(-1.0, 1.0);
+            // Always run the filter so that its state stays current if it's toggled mid-game
+            let (filtered_l, filtered_r) = self.dc_blocking_filter.filter(sample_l, sample_r);
+            if self.dc_blocking_enabled {
+                (sample_l, sample_r) = (filtered_l, filtered_r);
+            }
+
+            let sample_l = sample_l.clamp(-1.0, 1.0);
+            let sample_r = sample_r.clamp(-1.0, 1.0);
 
             audio_output.push_sample(sample_l, sample_r)?;
         }
@@ -112,3 +275,153 @@ impl GenesisAudioResampler {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Collect one second of YM2612 samples and return how many output samples were produced
+    fn ym2612_samples_per_second(resampler: &mut GenesisAudioResampler) -> usize {
+        let ym2612_frequency = NTSC_GENESIS_MCLK_FREQUENCY / 7.0 / 6.0 / 24.0;
+        for _ in 0..ym2612_frequency.round() as u64 {
+            resampler.collect_ym2612_sample(0.0, 0.0);
+        }
+
+        let len = resampler.ym2612_resampler.output_buffer_len();
+        while resampler.ym2612_resampler.output_buffer_pop_front().is_some() {}
+        len
+    }
+
+    #[test]
+    fn output_sample_rate_44100() {
+        let mut resampler = GenesisAudioResampler::new(TimingMode::Ntsc, 44100, false);
+        assert_eq!(resampler.output_sample_rate(), 44100);
+        assert_eq!(resampler.psg_resampler.output_frequency(), 44100);
+        assert!(ym2612_samples_per_second(&mut resampler).abs_diff(44100) <= 1);
+    }
+
+    #[test]
+    fn output_sample_rate_96000() {
+        let mut resampler = GenesisAudioResampler::new(TimingMode::Ntsc, 96000, false);
+        assert_eq!(resampler.output_sample_rate(), 96000);
+        assert_eq!(resampler.psg_resampler.output_frequency(), 96000);
+        assert!(ym2612_samples_per_second(&mut resampler).abs_diff(96000) <= 1);
+    }
+
+    #[test]
+    fn change_output_sample_rate() {
+        let mut resampler = GenesisAudioResampler::new(TimingMode::Ntsc, 48000, false);
+        assert!(ym2612_samples_per_second(&mut resampler).abs_diff(48000) <= 1);
+
+        resampler.set_output_sample_rate(44100);
+        assert!(ym2612_samples_per_second(&mut resampler).abs_diff(44100) <= 1);
+    }
+
+    // Magnitude of the filter's frequency response at the given frequency
+    fn lpf_gain(coefficients: &[f64], sample_rate: f64, frequency: f64) -> f64 {
+        let (re, im) = coefficients.iter().enumerate().fold((0.0, 0.0), |(re, im), (n, &c)| {
+            let phase = 2.0 * PI * frequency * n as f64 / sample_rate;
+            (re + c * phase.cos(), im - c * phase.sin())
+        });
+        re.hypot(im)
+    }
+
+    #[test]
+    fn lpf_cutoff_follows_output_sample_rate() {
+        let ym2612_frequency = NTSC_GENESIS_MCLK_FREQUENCY / 7.0 / 6.0 / 24.0;
+        let padded_ym2612_frequency = ym2612_frequency * (YM2612_ZERO_PADDING + 1) as f64;
+
+        // 48000 Hz uses the precomputed filter
+        let (_, coefficients) = ym2612_lpf_coefficients(ym2612_frequency, 48000);
+        assert!(
+            coefficients.iter().zip(YM2612_LPF_COEFFICIENTS).all(|(&a, b)| (a - b).abs() < 1e-12)
+        );
+
+        // Other rates cut off at half the output rate, with unity gain at DC
+        let (_, coefficients) = ym2612_lpf_coefficients(ym2612_frequency, 44100);
+        assert!((lpf_gain(&coefficients, padded_ym2612_frequency, 0.0) - 1.0).abs() < 1e-9);
+        let cutoff_gain = lpf_gain(&coefficients, padded_ym2612_frequency, 22050.0);
+        assert!((cutoff_gain - 0.5).abs() < 0.05, "44100 Hz cutoff gain {cutoff_gain}");
+
+        // ...but never above the YM2612's Nyquist frequency
+        let (_, coefficients) = ym2612_lpf_coefficients(ym2612_frequency, 96000);
+        let cutoff_gain = lpf_gain(&coefficients, padded_ym2612_frequency, ym2612_frequency / 2.0);
+        assert!((cutoff_gain - 0.5).abs() < 0.05, "96000 Hz cutoff gain {cutoff_gain}");
+
+        let psg_frequency = smsgg_core::audio::compute_psg_frequency(NTSC_GENESIS_MCLK_FREQUENCY);
+        let (_, coefficients) = psg_lpf_coefficients(psg_frequency, 96000);
+        let cutoff_gain = lpf_gain(&coefficients, psg_frequency, 48000.0);
+        assert!((cutoff_gain - 0.5).abs() < 0.05, "PSG cutoff gain {cutoff_gain}");
+    }
+
+    #[test]
+    fn set_output_sample_rate_updates_lpf() {
+        let mut resampler = GenesisAudioResampler::new(TimingMode::Ntsc, 48000, false);
+        resampler.set_output_sample_rate(44100);
+
+        // Expected YM2612 resampler: same output rate and the 44100 Hz filter
+        let mut expected = new_ym2612_resampler(NTSC_GENESIS_MCLK_FREQUENCY);
+        expected.update_output_frequency(44100);
+        let (coefficient_0, coefficients) =
+            ym2612_lpf_coefficients(expected.source_frequency(), 44100);
+        expected.update_lpf_coefficients(coefficient_0, coefficients);
+
+        // 20 kHz tone, close enough to the cutoff that the two filters produce different output
+        let ym2612_frequency = NTSC_GENESIS_MCLK_FREQUENCY / 7.0 / 6.0 / 24.0;
+        for i in 0..1000 {
+            let sample = 0.5 * (2.0 * PI * 20000.0 * f64::from(i) / ym2612_frequency).sin();
+            resampler.collect_ym2612_sample(sample, sample);
+            expected.collect_sample(sample, sample);
+        }
+
+        assert_eq!(resampler.ym2612_resampler.output_buffer_len(), expected.output_buffer_len());
+        while let Some(sample) = expected.output_buffer_pop_front() {
+            assert_eq!(resampler.ym2612_resampler.output_buffer_pop_front(), Some(sample));
+        }
+    }
+
+    #[test]
+    fn dc_blocking_filter_removes_offset() {
+        let mut filter = DcBlockingFilter::new(48000);
+
+        // 2 seconds of a 440 Hz tone with a constant 0.5 offset
+        let outputs: Vec<_> = (0..2 * 48000)
+            .map(|i| {
+                let sample = 0.5 + 0.25 * (2.0 * PI * 440.0 * f64::from(i) / 48000.0).sin();
+                filter.filter(sample, -sample)
+            })
+            .collect();
+
+        // Mean of the last 100ms (exactly 44 periods of the tone) should be close to 0
+        let tail = &outputs[outputs.len() - 4800..];
+        let mean_l = tail.iter().map(|&(l, _)| l).sum::<f64>() / tail.len() as f64;
+        let mean_r = tail.iter().map(|&(_, r)| r).sum::<f64>() / tail.len() as f64;
+        assert!(mean_l.abs() < 1e-3, "left mean {mean_l}");
+        assert!(mean_r.abs() < 1e-3, "right mean {mean_r}");
+
+        // The tone itself should pass through nearly unchanged
+        let max_l = tail.iter().map(|&(l, _)| l).fold(f64::MIN, f64::max);
+        assert!((max_l - 0.25).abs() < 0.01, "left max {max_l}");
+    }
+
+    #[test]
+    fn psg_pan() {
+        let center = StereoMix::default();
+        assert_eq!(center.pan_psg(0.5, 0.5), (0.5, 0.5));
+
+        let left = StereoMix { psg_pan: -1.0, mono: false };
+        assert_eq!(left.pan_psg(0.5, 0.5), (0.5, 0.0));
+
+        let right = StereoMix { psg_pan: 0.5, mono: false };
+        assert_eq!(right.pan_psg(0.5, 0.5), (0.25, 0.5));
+    }
+
+    #[test]
+    fn mono_downmix() {
+        let stereo = StereoMix { psg_pan: 0.0, mono: false };
+        assert_eq!(stereo.downmix(0.5, 0.0), (0.5, 0.0));
+
+        let mono = StereoMix { psg_pan: 0.0, mono: true };
+        assert_eq!(mono.downmix(0.5, 0.0), (0.25, 0.25));
+    }
+}
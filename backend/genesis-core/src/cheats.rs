@@ -0,0 +1,187 @@
+//! Game Genie and Pro Action Replay cheat codes
+
+use bincode::{Decode, Encode};
+use thiserror::Error;
+
+const GAME_GENIE_ALPHABET: &[u8; 32] = b"ABCDEFGHJKLMNPRSTVWXYZ0123456789";
+
+// Cartridge ROM occupies $000000-$3FFFFF in the 68000 address space
+const ROM_END: u32 = 0x3FFFFF;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CheatCodeError {
+    #[error("Invalid cheat code format, expected AAAA-AAAA or FFFFFF:VVVV: '{0}'")]
+    InvalidFormat(String),
+    #[error("Invalid Game Genie character '{0}'")]
+    InvalidGameGenieChar(char),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum CheatCode {
+    /// Replace the word at the given ROM address whenever it is read
+    RomPatch { address: u32, value: u16 },
+    /// Write the word to the given RAM address once per frame
+    RamWrite { address: u32, value: u16 },
+}
+
+impl CheatCode {
+    /// Parse either a Game Genie code (`AAAA-AAAA`) or a Pro Action Replay code (`FFFFFF:VVVV`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the code is not valid in either format.
+    pub fn parse(code: &str) -> Result<Self, CheatCodeError> {
+        let code = code.trim();
+        if code.contains(':') {
+            Self::from_pro_action_replay(code)
+        } else {
+            Self::from_game_genie(code)
+        }
+    }
+
+    /// Decode a Game Genie code, which always patches ROM.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the code is not 8 characters from the Game Genie alphabet, with an
+    /// optional dash in the middle.
+    pub fn from_game_genie(code: &str) -> Result<Self, CheatCodeError> {
+        let chars: Vec<char> = code.chars().filter(|&c| c != '-').collect();
+        if chars.len() != 8 || code.len() - chars.len() > 1 {
+            return Err(CheatCodeError::InvalidFormat(code.into()));
+        }
+
+        // Each character encodes 5 bits. With address bits A-X and data bits a-p, the 40 bits are:
+        //   ijklm nopIJ KLMNO PABCD EFGHd efgha bcQRS TUVWX
+        let mut address = 0_u32;
+        let mut value = 0_u16;
+        for (i, c) in chars.into_iter().enumerate() {
+            let n = GAME_GENIE_ALPHABET
+                .iter()
+                .position(|&alphabet_c| char::from(alphabet_c) == c.to_ascii_uppercase())
+                .ok_or(CheatCodeError::InvalidGameGenieChar(c))? as u32;
+
+            match i {
+                0 => value |= (n << 3) as u16,
+                1 => {
+                    value |= (n >> 2) as u16;
+                    address |= (n & 0x03) << 14;
+                }
+                2 => address |= n << 9,
+                3 => address |= ((n & 0x0F) << 20) | ((n >> 4) << 8),
+                4 => {
+                    value |= ((n & 0x01) << 12) as u16;
+                    address |= (n >> 1) << 16;
+                }
+                5 => value |= (((n & 0x01) << 15) | ((n >> 1) << 8)) as u16,
+                6 => {
+                    value |= ((n >> 3) << 13) as u16;
+                    address |= (n & 0x07) << 5;
+                }
+                7 => address |= n,
+                _ => unreachable!("Game Genie code length is checked above"),
+            }
+        }
+
+        Ok(Self::RomPatch { address, value })
+    }
+
+    /// Decode a Pro Action Replay code. Codes that target ROM addresses patch ROM, and all other
+    /// codes are applied as RAM writes every frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the code is not a 6-digit hex address and a 4-digit hex value separated
+    /// by a colon.
+    pub fn from_pro_action_replay(code: &str) -> Result<Self, CheatCodeError> {
+        let invalid = || CheatCodeError::InvalidFormat(code.into());
+
+        let (address, value) = code.split_once(':').ok_or_else(invalid)?;
+        if address.len() != 6 || value.len() != 4 {
+            return Err(invalid());
+        }
+
+        let address = u32::from_str_radix(address, 16).map_err(|_| invalid())?;
+        let value = u16::from_str_radix(value, 16).map_err(|_| invalid())?;
+
+        if address <= ROM_END {
+            Ok(Self::RomPatch { address, value })
+        } else {
+            Ok(Self::RamWrite { address, value })
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Encode, Decode)]
+pub struct CheatList {
+    codes: Vec<CheatCode>,
+}
+
+impl CheatList {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, code: CheatCode) {
+        self.codes.push(code);
+    }
+
+    pub fn clear(&mut self) {
+        self.codes.clear();
+    }
+
+    #[must_use]
+    pub fn codes(&self) -> &[CheatCode] {
+        &self.codes
+    }
+
+    pub(crate) fn rom_patches(&self) -> impl Iterator<Item = (u32, u16)> + '_ {
+        self.codes.iter().filter_map(|&code| match code {
+            CheatCode::RomPatch { address, value } => Some((address & !1, value)),
+            CheatCode::RamWrite { .. } => None,
+        })
+    }
+
+    pub(crate) fn ram_writes(&self) -> impl Iterator<Item = (u32, u16)> + '_ {
+        self.codes.iter().filter_map(|&code| match code {
+            CheatCode::RamWrite { address, value } => Some((address, value)),
+            CheatCode::RomPatch { .. } => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn game_genie() {
+        assert_eq!(
+            CheatCode::from_game_genie("RFAA-A6VR"),
+            Ok(CheatCode::RomPatch { address: 0x00402E, value: 0x4E71 })
+        );
+        assert_eq!(
+            CheatCode::parse("acvt-ca8g"),
+            Ok(CheatCode::RomPatch { address: 0x01A3C6, value: 0x6000 })
+        );
+
+        assert_eq!(CheatCode::parse("RFAA-A6VI"), Err(CheatCodeError::InvalidGameGenieChar('I')));
+        assert!(CheatCode::parse("RFAA-A6V").is_err());
+    }
+
+    #[test]
+    fn pro_action_replay() {
+        assert_eq!(
+            CheatCode::parse("FFA2C4:0063"),
+            Ok(CheatCode::RamWrite { address: 0xFFA2C4, value: 0x0063 })
+        );
+        assert_eq!(
+            CheatCode::parse("00402E:4E71"),
+            Ok(CheatCode::RomPatch { address: 0x00402E, value: 0x4E71 })
+        );
+
+        assert!(CheatCode::parse("FFA2C4:63").is_err());
+        assert!(CheatCode::parse("FFA2CG:0063").is_err());
+    }
+}
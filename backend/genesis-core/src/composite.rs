@@ -0,0 +1,165 @@
+//! Optional NTSC composite video filter
+//!
+//! Each line is encoded as a composite signal (luma plus QAM-modulated chroma) and then decoded
+//! back to RGB using simple low-pass filters. Because luma and chroma share the same band, this
+//! blends dithered patterns and produces the artifact colors that many games relied on.
+
+use bincode::de::{BorrowDecoder, Decoder};
+use bincode::enc::Encoder;
+use bincode::error::{DecodeError, EncodeError};
+use bincode::{BorrowDecode, Decode, Encode};
+use jgenesis_common::frontend::{Color, FrameSize};
+use std::f64::consts::PI;
+
+// The color subcarrier runs at mclk/15, and the pixel clock is mclk/8 in H40 mode and mclk/10 in
+// H32 mode
+const H40_SUBCARRIER_CYCLES_PER_PIXEL: f64 = 8.0 / 15.0;
+const H32_SUBCARRIER_CYCLES_PER_PIXEL: f64 = 10.0 / 15.0;
+
+// Frames at least this wide are in H40 mode (H32 mode is at most 283 pixels wide with borders)
+const H40_MIN_WIDTH: u32 = 320;
+
+const LUMA_KERNEL: [f64; 3] = [0.25, 0.5, 0.25];
+const CHROMA_KERNEL: [f64; 5] = [1.0 / 9.0, 2.0 / 9.0, 3.0 / 9.0, 2.0 / 9.0, 1.0 / 9.0];
+
+#[derive(Debug, Clone, Default)]
+pub struct NtscCompositeFilter {
+    frame_buffer: Vec<Color>,
+    signal: Vec<f64>,
+    odd_frame: bool,
+}
+
+impl NtscCompositeFilter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run the first (`width` * `height`) colors of the given frame buffer through the composite
+    /// filter and return the filtered frame.
+    pub fn apply(&mut self, frame_buffer: &[Color], frame_size: FrameSize) -> &[Color] {
+        let width = frame_size.width as usize;
+        let height = frame_size.height as usize;
+
+        self.frame_buffer.clear();
+        self.frame_buffer.resize(width * height, Color::default());
+        self.signal.resize(width, 0.0);
+
+        let cycles_per_pixel = if frame_size.width >= H40_MIN_WIDTH {
+            H40_SUBCARRIER_CYCLES_PER_PIXEL
+        } else {
+            H32_SUBCARRIER_CYCLES_PER_PIXEL
+        };
+
+        // Invert the subcarrier phase every frame to produce dot crawl
+        let frame_phase = if self.odd_frame { PI } else { 0.0 };
+        self.odd_frame = !self.odd_frame;
+
+        let subcarrier_phase = |x: usize| 2.0 * PI * cycles_per_pixel * x as f64 + frame_phase;
+
+        for row in 0..height {
+            let line = &frame_buffer[row * width..(row + 1) * width];
+
+            for (x, &color) in line.iter().enumerate() {
+                let (luma, i, q) = rgb_to_yiq(color);
+                let phase = subcarrier_phase(x);
+                self.signal[x] = luma + i * phase.cos() + q * phase.sin();
+            }
+
+            for x in 0..width {
+                let luma: f64 = LUMA_KERNEL
+                    .iter()
+                    .enumerate()
+                    .map(|(tap, &weight)| weight * self.signal[clamp_index(x, tap, 1, width)])
+                    .sum();
+
+                let (mut i, mut q) = (0.0, 0.0);
+                for (tap, &weight) in CHROMA_KERNEL.iter().enumerate() {
+                    let sample_x = clamp_index(x, tap, 2, width);
+                    let sample = self.signal[sample_x];
+                    let phase = subcarrier_phase(sample_x);
+                    i += weight * 2.0 * sample * phase.cos();
+                    q += weight * 2.0 * sample * phase.sin();
+                }
+
+                self.frame_buffer[row * width + x] = yiq_to_rgb(luma, i, q);
+            }
+        }
+
+        &self.frame_buffer
+    }
+}
+
+// Index of kernel tap `tap` centered on `x`, clamped to the line
+fn clamp_index(x: usize, tap: usize, radius: usize, width: usize) -> usize {
+    (x + tap).saturating_sub(radius).min(width - 1)
+}
+
+fn rgb_to_yiq(color: Color) -> (f64, f64, f64) {
+    let red = f64::from(color.r) / 255.0;
+    let green = f64::from(color.g) / 255.0;
+    let blue = f64::from(color.b) / 255.0;
+
+    let y = 0.299 * red + 0.587 * green + 0.114 * blue;
+    let i = 0.596 * red - 0.274 * green - 0.322 * blue;
+    let q = 0.211 * red - 0.523 * green + 0.312 * blue;
+    (y, i, q)
+}
+
+fn yiq_to_rgb(y: f64, i: f64, q: f64) -> Color {
+    let red = y + 0.956 * i + 0.621 * q;
+    let green = y - 0.272 * i - 0.647 * q;
+    let blue = y - 1.106 * i + 1.703 * q;
+
+    let to_u8 = |component: f64| (component.clamp(0.0, 1.0) * 255.0).round() as u8;
+    Color::rgb(to_u8(red), to_u8(green), to_u8(blue))
+}
+
+// The filter only holds scratch buffers, so don't bloat save states with them
+impl Encode for NtscCompositeFilter {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.odd_frame.encode(encoder)
+    }
+}
+
+impl Decode for NtscCompositeFilter {
+    fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let odd_frame = bool::decode(decoder)?;
+        Ok(Self { odd_frame, ..Self::default() })
+    }
+}
+
+impl<'de> BorrowDecode<'de> for NtscCompositeFilter {
+    fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let odd_frame = bool::borrow_decode(decoder)?;
+        Ok(Self { odd_frame, ..Self::default() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dithered_pattern() {
+        const BLUE: Color = Color::rgb(0, 0, 255);
+        const RED: Color = Color::rgb(255, 0, 0);
+
+        let frame_size = FrameSize { width: 320, height: 2 };
+        let frame_buffer: Vec<Color> =
+            (0..640).map(|i| if i % 2 == 0 { BLUE } else { RED }).collect();
+
+        let mut filter = NtscCompositeFilter::new();
+        let filtered = filter.apply(&frame_buffer, frame_size).to_vec();
+        assert_eq!(filtered.len(), frame_buffer.len());
+        assert_ne!(filtered, frame_buffer);
+
+        // Dithered columns should blend together away from the edges of the line
+        let center = filtered[160];
+        assert!(center != BLUE && center != RED);
+
+        // Subcarrier phase alternates every frame
+        let next_frame = filter.apply(&frame_buffer, frame_size);
+        assert_ne!(next_frame, filtered.as_slice());
+    }
+}
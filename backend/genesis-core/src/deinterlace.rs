@@ -0,0 +1,128 @@
+//! Deinterlacing for frames rendered in interlaced double resolution mode
+//!
+//! The VDP renders both fields of an interlaced 2x frame every frame, with even field lines in
+//! even frame buffer rows and odd field lines in odd rows. Weaving the fields together is the
+//! default and gives the sharpest image; the other modes trade resolution for output that looks
+//! closer to a CRT or avoids combing.
+
+use bincode::de::{BorrowDecoder, Decoder};
+use bincode::enc::Encoder;
+use bincode::error::{DecodeError, EncodeError};
+use bincode::{BorrowDecode, Decode, Encode};
+use jgenesis_common::frontend::{Color, FrameSize};
+use jgenesis_proc_macros::{EnumDisplay, EnumFromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Encode, Decode, EnumDisplay, EnumFromStr)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DeinterlacingMode {
+    /// Interleave both fields into a single full-resolution frame
+    #[default]
+    Weave,
+    /// Show only the current field, alternating every frame, with each line doubled
+    Bob,
+    /// Always show the even field with each line doubled, which avoids flicker at the cost of
+    /// half vertical resolution
+    FieldDoubling,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Deinterlacer {
+    mode: DeinterlacingMode,
+    frame_buffer: Vec<Color>,
+}
+
+impl Deinterlacer {
+    #[must_use]
+    pub fn new(mode: DeinterlacingMode) -> Self {
+        Self { mode, frame_buffer: Vec::new() }
+    }
+
+    #[must_use]
+    pub fn mode(&self) -> DeinterlacingMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: DeinterlacingMode) {
+        self.mode = mode;
+    }
+
+    /// Deinterlace the first (`width` * `height`) colors of the given interlaced frame buffer and
+    /// return the resulting frame, which has the same size. `odd_field` selects the field that is
+    /// shown in [`DeinterlacingMode::Bob`].
+    pub fn apply<'a>(
+        &'a mut self,
+        frame_buffer: &'a [Color],
+        frame_size: FrameSize,
+        odd_field: bool,
+    ) -> &'a [Color] {
+        let show_odd_field = match self.mode {
+            DeinterlacingMode::Weave => return frame_buffer,
+            DeinterlacingMode::Bob => odd_field,
+            DeinterlacingMode::FieldDoubling => false,
+        };
+
+        let width = frame_size.width as usize;
+        let len = width * frame_size.height as usize;
+
+        self.frame_buffer.clear();
+        self.frame_buffer.extend_from_slice(&frame_buffer[..len]);
+
+        for line_pair in self.frame_buffer.chunks_exact_mut(2 * width) {
+            let (even, odd) = line_pair.split_at_mut(width);
+            if show_odd_field {
+                even.copy_from_slice(odd);
+            } else {
+                odd.copy_from_slice(even);
+            }
+        }
+
+        &self.frame_buffer
+    }
+}
+
+// The deinterlacer only holds a scratch buffer, so don't bloat save states with it
+impl Encode for Deinterlacer {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.mode.encode(encoder)
+    }
+}
+
+impl Decode for Deinterlacer {
+    fn decode<D: Decoder>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let mode = DeinterlacingMode::decode(decoder)?;
+        Ok(Self::new(mode))
+    }
+}
+
+impl<'de> BorrowDecode<'de> for Deinterlacer {
+    fn borrow_decode<D: BorrowDecoder<'de>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let mode = DeinterlacingMode::borrow_decode(decoder)?;
+        Ok(Self::new(mode))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EVEN: Color = Color::rgb(255, 0, 0);
+    const ODD: Color = Color::rgb(0, 0, 255);
+
+    #[test]
+    fn deinterlacing_modes() {
+        let frame_size = FrameSize { width: 4, height: 4 };
+        let frame_buffer: Vec<Color> =
+            (0..16).map(|i| if (i / 4) % 2 == 0 { EVEN } else { ODD }).collect();
+
+        let mut deinterlacer = Deinterlacer::new(DeinterlacingMode::Weave);
+        assert_eq!(deinterlacer.apply(&frame_buffer, frame_size, true), frame_buffer.as_slice());
+
+        deinterlacer.set_mode(DeinterlacingMode::Bob);
+        assert_eq!(deinterlacer.apply(&frame_buffer, frame_size, false), [EVEN; 16].as_slice());
+        assert_eq!(deinterlacer.apply(&frame_buffer, frame_size, true), [ODD; 16].as_slice());
+
+        deinterlacer.set_mode(DeinterlacingMode::FieldDoubling);
+        assert_eq!(deinterlacer.apply(&frame_buffer, frame_size, false), [EVEN; 16].as_slice());
+        assert_eq!(deinterlacer.apply(&frame_buffer, frame_size, true), [EVEN; 16].as_slice());
+    }
+}
@@ -4,6 +4,7 @@ use crate::GenesisEmulatorConfig;
 use bincode::{Decode, Encode};
 use jgenesis_common::num::GetBit;
 use jgenesis_proc_macros::{define_controller_inputs, EnumDisplay, EnumFromStr};
+use std::collections::VecDeque;
 
 define_controller_inputs! {
     enum GenesisButton {
@@ -167,6 +168,29 @@ impl PinDirections {
     }
 }
 
+/// The exact bytes that the game reads from each controller data port during one frame, in read
+/// order. Used for TAS movie playback, which bypasses the controller emulation entirely.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Encode, Decode)]
+pub struct RawControllerFrame {
+    pub p1: Vec<u8>,
+    pub p2: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Default, Encode, Decode)]
+struct RawInputStream {
+    queued_frames: VecDeque<RawControllerFrame>,
+    p1: VecDeque<u8>,
+    p2: VecDeque<u8>,
+}
+
+impl RawInputStream {
+    fn next_frame(&mut self) {
+        let frame = self.queued_frames.pop_front().unwrap_or_default();
+        self.p1 = frame.p1.into();
+        self.p2 = frame.p2.into();
+    }
+}
+
 #[derive(Debug, Clone, Default, Encode, Decode)]
 pub struct InputState {
     inputs: GenesisInputs,
@@ -174,6 +198,7 @@ pub struct InputState {
     p2_controller_type: GenesisControllerType,
     p1_pin_directions: PinDirections,
     p2_pin_directions: PinDirections,
+    raw_input: Option<RawInputStream>,
 }
 
 impl InputState {
@@ -182,8 +207,34 @@ impl InputState {
         Self::default()
     }
 
+    /// Set the button states for the next frame. In raw input mode this also advances to the next
+    /// queued [`RawControllerFrame`], discarding any unread bytes from the current frame.
     pub fn set_inputs(&mut self, inputs: GenesisInputs) {
         self.inputs = inputs;
+
+        if let Some(raw_input) = &mut self.raw_input {
+            raw_input.next_frame();
+        }
+    }
+
+    /// Queue the raw data port bytes for a future frame and enable raw input mode if it is not
+    /// already enabled. Frames become active one at a time on each call to [`Self::set_inputs`].
+    ///
+    /// While raw input mode is enabled, each data port read returns the next byte from the active
+    /// frame. Once a frame's bytes for a port are exhausted, reads from that port fall back to
+    /// the value synthesized from the current button states.
+    pub fn queue_raw_frame(&mut self, frame: RawControllerFrame) {
+        self.raw_input.get_or_insert_with(RawInputStream::default).queued_frames.push_back(frame);
+    }
+
+    /// Disable raw input mode and drop all queued raw frames.
+    pub fn disable_raw_input(&mut self) {
+        self.raw_input = None;
+    }
+
+    #[must_use]
+    pub fn raw_input_enabled(&self) -> bool {
+        self.raw_input.is_some()
     }
 
     pub fn reload_config(&mut self, config: GenesisEmulatorConfig) {
@@ -197,12 +248,20 @@ impl InputState {
     }
 
     #[must_use]
-    pub fn read_p1_data(&self) -> u8 {
+    pub fn read_p1_data(&mut self) -> u8 {
+        if let Some(byte) = self.raw_input.as_mut().and_then(|raw_input| raw_input.p1.pop_front()) {
+            return byte;
+        }
+
         self.p1_pin_directions.to_data_byte(self.inputs.p1)
     }
 
     #[must_use]
-    pub fn read_p2_data(&self) -> u8 {
+    pub fn read_p2_data(&mut self) -> u8 {
+        if let Some(byte) = self.raw_input.as_mut().and_then(|raw_input| raw_input.p2.pop_front()) {
+            return byte;
+        }
+
         self.p2_pin_directions.to_data_byte(self.inputs.p2)
     }
 
@@ -237,3 +296,105 @@ impl InputState {
         self.p2_pin_directions.tick(m68k_cycles);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn six_button_th_sequence() {
+        let mut input_state = InputState::new();
+        input_state.p1_controller_type = GenesisControllerType::SixButton;
+
+        let mut inputs = GenesisInputs::default();
+        inputs.p1.x = true;
+        inputs.p1.mode = true;
+        input_state.set_inputs(inputs);
+
+        // Only TH is an output
+        input_state.write_p1_ctrl(0x40);
+
+        input_state.write_p1_data(0x00);
+        input_state.write_p1_data(0x40);
+        assert_eq!(input_state.read_p1_data(), 0x7F);
+        input_state.write_p1_data(0x00);
+        assert_eq!(input_state.read_p1_data(), 0x33);
+
+        // Third TH low read: D0-D3 are all 0
+        input_state.write_p1_data(0x40);
+        input_state.write_p1_data(0x00);
+        assert_eq!(input_state.read_p1_data(), 0x30);
+
+        // Fourth TH high read: Mode/X/Y/Z replace Right/Left/Down/Up
+        input_state.write_p1_data(0x40);
+        assert_eq!(input_state.read_p1_data(), 0x73);
+
+        // Fourth TH low read: D0-D3 are all 1
+        input_state.write_p1_data(0x00);
+        assert_eq!(input_state.read_p1_data(), 0x3F);
+
+        // Sequence resets after ~1.5ms without a TH flip
+        input_state.tick(FLIP_COUNTER_CYCLES);
+        input_state.write_p1_data(0x40);
+        input_state.write_p1_data(0x00);
+        assert_eq!(input_state.read_p1_data(), 0x33);
+    }
+
+    #[test]
+    fn three_button_ignores_th_sequence() {
+        let mut input_state = InputState::new();
+        input_state.p1_controller_type = GenesisControllerType::ThreeButton;
+
+        let mut inputs = GenesisInputs::default();
+        inputs.p1.mode = true;
+        input_state.set_inputs(inputs);
+
+        input_state.write_p1_ctrl(0x40);
+        for _ in 0..4 {
+            input_state.write_p1_data(0x00);
+            input_state.write_p1_data(0x40);
+            assert_eq!(input_state.read_p1_data(), 0x7F);
+        }
+    }
+
+    #[test]
+    fn raw_input_playback() {
+        let mut input_state = InputState::new();
+        input_state.write_p1_ctrl(0x40);
+        input_state.write_p1_data(0x40);
+
+        let mut inputs = GenesisInputs::default();
+        inputs.p1.start = true;
+        input_state.set_inputs(inputs);
+
+        input_state.queue_raw_frame(RawControllerFrame { p1: vec![0x12, 0x34], p2: vec![0x56] });
+        input_state.queue_raw_frame(RawControllerFrame { p1: vec![0x78], p2: vec![] });
+        assert!(input_state.raw_input_enabled());
+
+        // Queued frames do not take effect until the next frame starts
+        assert_eq!(input_state.read_p1_data(), 0x7F);
+
+        input_state.set_inputs(inputs);
+        assert_eq!(input_state.read_p1_data(), 0x12);
+        assert_eq!(input_state.read_p2_data(), 0x56);
+        assert_eq!(input_state.read_p1_data(), 0x34);
+
+        // Exhausted ports fall back to the button states
+        assert_eq!(input_state.read_p1_data(), 0x7F);
+        assert_eq!(input_state.read_p2_data(), 0x7F);
+
+        input_state.set_inputs(inputs);
+        assert_eq!(input_state.read_p1_data(), 0x78);
+        assert_eq!(input_state.read_p1_data(), 0x7F);
+
+        // No frames left in the queue
+        input_state.set_inputs(inputs);
+        assert_eq!(input_state.read_p1_data(), 0x7F);
+
+        input_state.queue_raw_frame(RawControllerFrame { p1: vec![0x9A], p2: vec![] });
+        input_state.disable_raw_input();
+        input_state.set_inputs(inputs);
+        assert!(!input_state.raw_input_enabled());
+        assert_eq!(input_state.read_p1_data(), 0x7F);
+    }
+}
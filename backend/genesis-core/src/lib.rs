@@ -1,5 +1,8 @@
 mod api;
 pub mod audio;
+pub mod cheats;
+pub mod composite;
+pub mod deinterlace;
 pub mod input;
 pub mod memory;
 mod svp;
@@ -7,7 +10,8 @@ pub mod vdp;
 pub mod ym2612;
 
 pub use api::{
-    render_frame, GenesisAspectRatio, GenesisEmulator, GenesisEmulatorConfig, GenesisError,
-    GenesisRegion, GenesisResult,
+    render_frame, rom_crc32, rom_serial, ChipId, GenesisAspectRatio, GenesisEmulator,
+    GenesisEmulatorConfig, GenesisError, GenesisLoadError, GenesisRegion, GenesisResult,
+    RenderOptions,
 };
 pub use input::{GenesisControllerType, GenesisInputs, GenesisJoypadState};
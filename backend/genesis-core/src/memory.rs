@@ -3,6 +3,8 @@
 mod eeprom;
 mod external;
 
+pub use external::CartridgeRamError;
+
 use crate::api::GenesisRegion;
 use crate::input::InputState;
 use crate::memory::external::ExternalMemory;
@@ -12,12 +14,14 @@ use crate::ym2612::Ym2612;
 use bincode::{Decode, Encode};
 use jgenesis_common::frontend::TimingMode;
 use jgenesis_common::num::{GetBit, U16Ext};
+use jgenesis_common::ram::RamInitPattern;
+use jgenesis_common::rng::Rng;
 use jgenesis_proc_macros::{FakeDecode, FakeEncode, PartialClone};
 use regex::Regex;
 use smsgg_core::psg::Psg;
 use std::ops::Index;
 use std::sync::OnceLock;
-use std::{array, mem};
+use std::{array, cmp, mem};
 use z80_emu::traits::InterruptLine;
 
 #[derive(Debug, Clone, Default, FakeEncode, FakeDecode)]
@@ -61,8 +65,8 @@ impl SegaMapper {
     }
 
     fn map_address(self, address: u32) -> u32 {
-        if address <= 0x07FFFF {
-            // $000000-$07FFFF is not banked
+        if address <= 0x07FFFF || address >= 0x400000 {
+            // $000000-$07FFFF is not banked, and the mapper only covers the 4MB cartridge space
             return address;
         }
 
@@ -81,13 +85,55 @@ pub struct Cartridge {
     mapper: Option<SegaMapper>,
     svp: Option<Svp>,
     region: GenesisRegion,
+    title: String,
+    // (Word-aligned address, value) pairs from cheat codes
+    rom_patches: Vec<(u32, u16)>,
 }
 
 impl Cartridge {
+    /// Create a cartridge from a ROM image and optional save file contents.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the save file does not match the cartridge's external memory and cannot
+    /// be safely padded or truncated.
     pub fn from_rom(
         rom_bytes: Vec<u8>,
         initial_ram_bytes: Option<Vec<u8>>,
         forced_region: Option<GenesisRegion>,
+    ) -> Result<Self, CartridgeRamError> {
+        let external_memory = ExternalMemory::from_rom(&rom_bytes, initial_ram_bytes)?;
+        Ok(Self::new(rom_bytes, external_memory, forced_region))
+    }
+
+    /// Create a cartridge from a Sonic & Knuckles ROM with another cartridge locked on top.
+    ///
+    /// The S&K ROM is mapped to $000000-$1FFFFF and the locked-on ROM is mapped to
+    /// $200000-$3FFFFF, mirrored if it is smaller than 2MB. If the S&K image contains the 256KB
+    /// Sonic 2 patch ROM after the main 2MB and the locked-on ROM is 1MB or smaller, the patch ROM
+    /// is mapped to $300000-$3FFFFF.
+    ///
+    /// The hardware region is determined from the S&K header, while external memory (e.g. Sonic 3
+    /// SRAM, toggled through $A130F1) is determined from the locked-on cartridge's header.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as [`Self::from_rom`].
+    pub fn from_rom_with_lockon(
+        base_rom_bytes: Vec<u8>,
+        lock_on_rom_bytes: Vec<u8>,
+        initial_ram_bytes: Option<Vec<u8>>,
+        forced_region: Option<GenesisRegion>,
+    ) -> Result<Self, CartridgeRamError> {
+        let external_memory = ExternalMemory::from_rom(&lock_on_rom_bytes, initial_ram_bytes)?;
+        let rom_bytes = combine_lock_on_roms(base_rom_bytes, &lock_on_rom_bytes);
+        Ok(Self::new(rom_bytes, external_memory, forced_region))
+    }
+
+    fn new(
+        rom_bytes: Vec<u8>,
+        external_memory: ExternalMemory,
+        forced_region: Option<GenesisRegion>,
     ) -> Self {
         let region = forced_region.unwrap_or_else(|| {
             GenesisRegion::from_rom(&rom_bytes).unwrap_or_else(|| {
@@ -97,18 +143,17 @@ impl Cartridge {
         });
         log::info!("Genesis hardware region: {region:?}");
 
-        let external_memory = ExternalMemory::from_rom(&rom_bytes, initial_ram_bytes);
-
         // Initialize ram_mapped to true if external memory is present
         // Only one game ever unmaps RAM (Phantasy Star 4)
         let ram_mapped = !matches!(external_memory, ExternalMemory::None);
 
         // Only one game uses the bank switching Sega mapper, Super Street Fighter 2
-        let serial_number = &rom_bytes[0x183..0x18B];
+        let serial_number = rom_bytes.get(0x183..0x18B).unwrap_or(&[]);
         let is_ssf2 = is_super_street_fighter_2(serial_number);
 
         // Additionally enable the bank switching mapper for any cartridge that declares its system type as "SEGA SSF"
-        let is_ssf_system = &rom_bytes[0x100..0x110] == b"SEGA SSF        ";
+        let is_ssf_system =
+            rom_bytes.get(0x100..0x110).is_some_and(|system| system == b"SEGA SSF        ");
 
         let mapper = (is_ssf2 || is_ssf_system).then(SegaMapper::new);
         log::info!("Using Sega banked mapper: {}", mapper.is_some());
@@ -116,7 +161,19 @@ impl Cartridge {
         // Only one game uses the SVP, Virtua Racing
         let svp = is_virtua_racing(serial_number).then(Svp::new);
 
-        Self { rom: Rom(rom_bytes), external_memory, ram_mapped, mapper, svp, region }
+        let title = parse_title(&rom_bytes, region);
+        log::info!("Cartridge title: {title}");
+
+        Self {
+            rom: Rom(rom_bytes),
+            external_memory,
+            ram_mapped,
+            mapper,
+            svp,
+            region,
+            title,
+            rom_patches: vec![],
+        }
     }
 
     #[inline]
@@ -126,6 +183,55 @@ impl Cartridge {
         }
     }
 
+    /// Replace the ROM patches applied by cheat codes. Each patch replaces the word at the given
+    /// address whenever it is read.
+    pub fn set_rom_patches(&mut self, patches: Vec<(u32, u16)>) {
+        self.rom_patches = patches;
+    }
+
+    fn rom_patch(&self, address: u32) -> Option<u16> {
+        if self.rom_patches.is_empty() {
+            return None;
+        }
+
+        let word_address = address & !1;
+        self.rom_patches
+            .iter()
+            .find_map(|&(patch_address, value)| (patch_address == word_address).then_some(value))
+    }
+
+    fn read_rom_byte(&self, address: u32) -> u8 {
+        if let Some(word) = self.rom_patch(address) {
+            return if address.bit(0) { word.lsb() } else { word.msb() };
+        }
+
+        let rom_addr = self.mapper.map_or(address, |mapper| mapper.map_address(address));
+        self.rom.get(rom_addr as usize).unwrap_or(0xFF)
+    }
+
+    // Read a byte from cartridge ROM or RAM without side effects. SVP cartridges always read
+    // from ROM
+    fn debug_read_byte(&self, address: u32) -> u8 {
+        if self.svp.is_none() && self.ram_mapped {
+            if let Some(byte) = self.external_memory.read_byte(address) {
+                return byte;
+            }
+        }
+
+        if (0xA13000..=0xA130FF).contains(&address) {
+            return 0xFF;
+        }
+
+        self.read_rom_byte(address)
+    }
+
+    // Write a byte to cartridge RAM without side effects. ROM writes are ignored
+    fn debug_write_byte(&mut self, address: u32, value: u8) -> bool {
+        self.svp.is_none()
+            && self.ram_mapped
+            && self.external_memory.debug_write_byte(address, value)
+    }
+
     fn write_cartridge_register(&mut self, address: u32, value: u8) {
         match address {
             0xA130F1 => {
@@ -136,20 +242,39 @@ impl Cartridge {
                     mapper.write(address, value);
                 }
             }
-            _ => panic!(
-                "unexpected cartridge register write; address={address:06X}, value={value:02X}"
-            ),
+            _ => {
+                // The rest of the $A130xx "time" register range is only used by cartridges with
+                // custom hardware; standard cartridges ignore writes
+                log::debug!(
+                    "Ignoring cartridge time register write; address={address:06X}, value={value:02X}"
+                );
+            }
         }
     }
 
-    fn take_rom(&mut self) -> Vec<u8> {
-        mem::take(&mut self.rom).0
-    }
-
     fn take_rom_from(&mut self, other: &mut Self) {
         self.rom = mem::take(&mut other.rom);
     }
 
+    // Move the ROM and external memory contents into a cartridge in its power-on state, leaving
+    // this cartridge without a ROM
+    fn take_for_hard_reset(&mut self) -> Self {
+        let rom = mem::take(&mut self.rom);
+        let mut cartridge = Self { rom, ..self.clone() };
+
+        cartridge.external_memory.power_cycle();
+        cartridge.ram_mapped = !matches!(cartridge.external_memory, ExternalMemory::None);
+        if let Some(mapper) = &mut cartridge.mapper {
+            *mapper = SegaMapper::new();
+        }
+        if let Some(svp) = &mut cartridge.svp {
+            *svp = Svp::new();
+        }
+        cartridge.rom_patches.clear();
+
+        cartridge
+    }
+
     fn external_ram(&self) -> &[u8] {
         self.external_memory.get_memory()
     }
@@ -162,19 +287,51 @@ impl Cartridge {
         self.external_memory.get_and_clear_dirty_bit()
     }
 
-    fn program_title(&self) -> String {
-        static RE: OnceLock<Regex> = OnceLock::new();
+    fn program_title(&self) -> &str {
+        &self.title
+    }
+}
+
+fn parse_title(rom: &[u8], region: GenesisRegion) -> String {
+    static RE: OnceLock<Regex> = OnceLock::new();
 
-        let addr = match self.region {
-            GenesisRegion::Americas | GenesisRegion::Europe => 0x0150,
-            GenesisRegion::Japan => 0x0120,
-        };
-        let bytes = &self.rom.0[addr..addr + 48];
-        let title = bytes.iter().copied().map(|b| b as char).collect::<String>();
+    let addr = match region {
+        GenesisRegion::Americas | GenesisRegion::Europe => 0x0150,
+        GenesisRegion::Japan => 0x0120,
+    };
+    let bytes = rom.iter().copied().skip(addr).take(48);
+    let title = bytes.map(|b| b as char).collect::<String>();
+
+    let re = RE.get_or_init(|| Regex::new(r" +").unwrap());
+    re.replace_all(title.trim(), " ").into()
+}
 
-        let re = RE.get_or_init(|| Regex::new(r" +").unwrap());
-        re.replace_all(title.trim(), " ").into()
+const LOCK_ON_ROM_ADDRESS: usize = 0x200000;
+const LOCK_ON_PATCH_ADDRESS: usize = 0x300000;
+const LOCK_ON_ROM_END: usize = 0x400000;
+
+fn combine_lock_on_roms(mut base_rom: Vec<u8>, lock_on_rom: &[u8]) -> Vec<u8> {
+    // Anything past the first 2MB of the S&K image is the Sonic 2 patch ROM
+    let patch_rom = base_rom.split_off(cmp::min(LOCK_ON_ROM_ADDRESS, base_rom.len()));
+
+    let mut rom = base_rom;
+    rom.resize(LOCK_ON_ROM_ADDRESS, 0xFF);
+
+    if lock_on_rom.is_empty() {
+        return rom;
     }
+
+    rom.extend(lock_on_rom.iter().copied().cycle().take(LOCK_ON_ROM_END - LOCK_ON_ROM_ADDRESS));
+
+    if !patch_rom.is_empty() && lock_on_rom.len() <= LOCK_ON_PATCH_ADDRESS - LOCK_ON_ROM_ADDRESS {
+        for (rom_byte, &patch_byte) in
+            rom[LOCK_ON_PATCH_ADDRESS..].iter_mut().zip(patch_rom.iter().cycle())
+        {
+            *rom_byte = patch_byte;
+        }
+    }
+
+    rom
 }
 
 fn is_super_street_fighter_2(serial_number: &[u8]) -> bool {
@@ -215,8 +372,12 @@ impl PhysicalMedium for Cartridge {
             }
         }
 
-        let rom_addr = self.mapper.map_or(address, |mapper| mapper.map_address(address));
-        self.rom.get(rom_addr as usize).unwrap_or(0xFF)
+        if (0xA13000..=0xA130FF).contains(&address) {
+            // Cartridge registers are write-only
+            return 0xFF;
+        }
+
+        self.read_rom_byte(address)
     }
 
     #[inline]
@@ -231,6 +392,15 @@ impl PhysicalMedium for Cartridge {
             }
         }
 
+        if (0xA13000..=0xA130FF).contains(&address) {
+            // Cartridge registers are write-only
+            return 0xFFFF;
+        }
+
+        if let Some(word) = self.rom_patch(address) {
+            return word;
+        }
+
         let rom_addr = self.mapper.map_or(address, |mapper| mapper.map_address(address));
         let msb = self.rom.get(rom_addr as usize).unwrap_or(0xFF);
         let lsb = self.rom.get((rom_addr + 1) as usize).unwrap_or(0xFF);
@@ -319,14 +489,24 @@ impl Z80BankRegister {
 struct Signals {
     z80_busreq: bool,
     z80_reset: bool,
+    z80_halted: bool,
+    // Remaining master clock cycles before a BUSREQ change is reflected in the BUSACK status
+    z80_busack_latency: u64,
 }
 
 impl Default for Signals {
     fn default() -> Self {
-        Self { z80_busreq: false, z80_reset: true }
+        Self { z80_busreq: false, z80_reset: true, z80_halted: false, z80_busack_latency: 0 }
     }
 }
 
+// Approximate delay (in master clock cycles, ~8 68K cycles) between the 68K's write to BUSREQ and
+// the BUSACK status reflecting the Z80 granting or releasing the bus
+const Z80_BUSACK_LATENCY_MCLK_CYCLES: u64 = 56;
+
+// "SEGA" must be written to $A14000 to unlock the VDP on consoles with TMSS
+const TMSS_UNLOCK_VALUE: u32 = u32::from_be_bytes(*b"SEGA");
+
 #[derive(Debug, Encode, Decode, PartialClone)]
 pub struct Memory<Medium> {
     #[partial_clone(partial)]
@@ -335,6 +515,12 @@ pub struct Memory<Medium> {
     audio_ram: Box<[u8; AUDIO_RAM_LEN]>,
     z80_bank_register: Z80BankRegister,
     signals: Signals,
+    forced_console_region: Option<GenesisRegion>,
+    // Last value driven onto the 68000 data bus, returned by reads from unmapped addresses
+    open_bus: u16,
+    tmss_enabled: bool,
+    tmss_register: u32,
+    ram_init_pattern: RamInitPattern,
 }
 
 impl<Medium: PhysicalMedium> Memory<Medium> {
@@ -347,6 +533,11 @@ impl<Medium: PhysicalMedium> Memory<Medium> {
             audio_ram: vec![0; AUDIO_RAM_LEN].into_boxed_slice().try_into().unwrap(),
             z80_bank_register: Z80BankRegister::default(),
             signals: Signals::default(),
+            forced_console_region: None,
+            open_bus: 0,
+            tmss_enabled: false,
+            tmss_register: 0,
+            ram_init_pattern: RamInitPattern::default(),
         }
     }
 
@@ -371,6 +562,55 @@ impl<Medium: PhysicalMedium> Memory<Medium> {
         self.physical_medium.region()
     }
 
+    /// The region reported by the console's version register. This is the hardware region unless
+    /// a console region has been forced.
+    #[inline]
+    #[must_use]
+    pub fn console_region(&self) -> GenesisRegion {
+        self.forced_console_region.unwrap_or_else(|| self.hardware_region())
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn forced_console_region(&self) -> Option<GenesisRegion> {
+        self.forced_console_region
+    }
+
+    #[inline]
+    pub fn set_forced_console_region(&mut self, console_region: Option<GenesisRegion>) {
+        self.forced_console_region = console_region;
+    }
+
+    /// Fill main RAM and audio RAM with the given power-on pattern.
+    pub fn init_ram(&mut self, ram_init_pattern: RamInitPattern, rng: &mut Rng) {
+        ram_init_pattern.fill(self.main_ram.as_mut_slice(), rng);
+        ram_init_pattern.fill(self.audio_ram.as_mut_slice(), rng);
+        self.ram_init_pattern = ram_init_pattern;
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn ram_init_pattern(&self) -> RamInitPattern {
+        self.ram_init_pattern
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn tmss_enabled(&self) -> bool {
+        self.tmss_enabled
+    }
+
+    /// Enable or disable emulating a console with TMSS (Trademark Security System). With TMSS
+    /// enabled, the VDP ignores all access until "SEGA" has been written to $A14000.
+    #[inline]
+    pub fn set_tmss_enabled(&mut self, tmss_enabled: bool) {
+        self.tmss_enabled = tmss_enabled;
+    }
+
+    fn vdp_locked_by_tmss(&self) -> bool {
+        self.tmss_enabled && self.tmss_register != TMSS_UNLOCK_VALUE
+    }
+
     #[inline]
     #[must_use]
     pub fn medium(&self) -> &Medium {
@@ -383,25 +623,114 @@ impl<Medium: PhysicalMedium> Memory<Medium> {
         &mut self.physical_medium
     }
 
+    /// Whether the Z80 has locked up, e.g. by attempting to access its own address space through
+    /// the 68000 bus. The Z80 remains halted until the next reset.
+    #[inline]
+    #[must_use]
+    pub fn z80_halted(&self) -> bool {
+        self.signals.z80_halted
+    }
+
     #[inline]
     pub fn reset_z80_signals(&mut self) {
         self.signals = Signals::default();
     }
-}
 
-impl Memory<Cartridge> {
-    #[must_use]
-    pub fn take_rom(&mut self) -> Vec<u8> {
-        self.physical_medium.take_rom()
+    /// Advance the Z80 BUSREQ/BUSACK handshake by the given number of master clock cycles.
+    ///
+    /// This should be called before applying the 68000's pending writes for the same cycles, so that
+    /// the BUSACK latency is counted from the end of the instruction that wrote BUSREQ.
+    #[inline]
+    pub fn tick_z80_signals(&mut self, mclk_cycles: u64) {
+        self.signals.z80_busack_latency =
+            self.signals.z80_busack_latency.saturating_sub(mclk_cycles);
     }
 
+    fn set_z80_busreq(&mut self, busreq: bool) {
+        if busreq != self.signals.z80_busreq {
+            self.signals.z80_busack_latency = Z80_BUSACK_LATENCY_MCLK_CYCLES;
+        }
+        self.signals.z80_busreq = busreq;
+        log::trace!("Set Z80 BUSREQ to {busreq}");
+    }
+
+    // Value of bit 0 when reading $A11100; 0 = bus granted, 1 = Z80 has the bus
+    fn z80_busreq_status(&self, z80_busack: bool) -> u8 {
+        if self.signals.z80_busack_latency != 0 {
+            // The grant/release has not taken effect yet, so BUSACK still reports the previous state
+            return self.signals.z80_busreq.into();
+        }
+
+        (!z80_busack).into()
+    }
+}
+
+impl Memory<Cartridge> {
     pub fn take_rom_from(&mut self, other: &mut Self) {
         self.physical_medium.take_rom_from(&mut other.physical_medium);
     }
 
+    /// Take the cartridge for a hard reset. The returned cartridge keeps the current ROM and
+    /// cartridge RAM contents, but all other cartridge state is reset to power-on values.
+    #[must_use]
+    pub fn take_cartridge_for_hard_reset(&mut self) -> Cartridge {
+        self.physical_medium.take_for_hard_reset()
+    }
+
     #[must_use]
     pub fn game_title(&self) -> String {
-        self.physical_medium.program_title()
+        self.physical_medium.program_title().into()
+    }
+
+    /// Read a byte from the 68000 address space without side effects, for debuggers.
+    ///
+    /// Cartridge ROM/RAM, work RAM, and Z80 RAM are readable. I/O and VDP ports are never read
+    /// because reading them can change emulator state (e.g. the VDP data port address
+    /// increment), so those addresses read as $FF.
+    #[must_use]
+    pub fn debug_read(&self, address: u32) -> u8 {
+        let address = address & ADDRESS_MASK;
+        match address {
+            0x000000..=0x3FFFFF => self.physical_medium.debug_read_byte(address),
+            0xA00000..=0xA0FFFF if address & 0x7FFF <= 0x3FFF => {
+                // Z80 RAM (mirrored at $2000-$3FFF in the Z80 address space)
+                self.audio_ram[(address & 0x1FFF) as usize]
+            }
+            0xE00000..=0xFFFFFF => self.main_ram[(address & 0xFFFF) as usize],
+            _ => 0xFF,
+        }
+    }
+
+    /// Write a byte to the 68000 address space without side effects, for debuggers and cheats.
+    ///
+    /// Only cartridge RAM, work RAM, and Z80 RAM are writable. Returns `false` if the write was
+    /// ignored, e.g. because the address maps to cartridge ROM or to an I/O port.
+    pub fn debug_write(&mut self, address: u32, value: u8) -> bool {
+        let address = address & ADDRESS_MASK;
+        match address {
+            0x000000..=0x3FFFFF => self.physical_medium.debug_write_byte(address, value),
+            0xA00000..=0xA0FFFF if address & 0x7FFF <= 0x3FFF => {
+                self.audio_ram[(address & 0x1FFF) as usize] = value;
+                true
+            }
+            0xE00000..=0xFFFFFF => {
+                self.main_ram[(address & 0xFFFF) as usize] = value;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn main_ram(&self) -> &[u8] {
+        self.main_ram.as_slice()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn audio_ram(&self) -> &[u8] {
+        self.audio_ram.as_slice()
     }
 
     #[inline]
@@ -427,6 +756,8 @@ impl Memory<Cartridge> {
 pub struct MainBusSignals {
     pub z80_busack: bool,
     pub m68k_reset: bool,
+    /// Whether an expansion unit (i.e. the Sega CD) is connected to the expansion port
+    pub expansion_connected: bool,
 }
 
 #[derive(Debug, Clone, Default, Encode, Decode)]
@@ -486,12 +817,17 @@ impl<'a, Medium: PhysicalMedium> MainBus<'a, Medium> {
         }
     }
 
-    fn read_io_register(&self, address: u32) -> u8 {
+    fn read_io_register(&mut self, address: u32) -> u8 {
         match address {
             // Version register
             0xA10000 | 0xA10001 => {
-                0x20 | (u8::from(self.memory.hardware_region().version_bit()) << 7)
+                // Bit 5 is the /DISK line, which is pulled low when an expansion unit is connected.
+                // Bits 0-3 are the hardware version, which is 0 for a console without TMSS and 1 for
+                // a console with TMSS
+                (u8::from(self.memory.console_region().version_bit()) << 7)
                     | (u8::from(self.timing_mode == TimingMode::Pal) << 6)
+                    | (u8::from(!self.signals.expansion_connected) << 5)
+                    | u8::from(self.memory.tmss_enabled)
             }
             0xA10002 | 0xA10003 => self.input.read_p1_data(),
             0xA10004 | 0xA10005 => self.input.read_p2_data(),
@@ -585,6 +921,12 @@ impl<'a, Medium: PhysicalMedium> MainBus<'a, Medium> {
         let address = address & ADDRESS_MASK;
         log::trace!("Main bus byte write: address={address:06X}, value={value:02X}");
         match address {
+            0xA14000..=0xA14003 if self.memory.tmss_enabled => {
+                let shift = 8 * (3 - (address & 3));
+                self.memory.tmss_register =
+                    (self.memory.tmss_register & !(0xFF << shift)) | (u32::from(value) << shift);
+                log::trace!("TMSS register: {:08X}", self.memory.tmss_register);
+            }
             0x000000..=0x7FFFFF | 0xA12000..=0xA1500F => {
                 self.memory.physical_medium.write_byte(address, value);
             }
@@ -601,14 +943,13 @@ impl<'a, Medium: PhysicalMedium> MainBus<'a, Medium> {
                 self.write_io_register(address, value);
             }
             0xA11100..=0xA11101 => {
-                self.memory.signals.z80_busreq = value.bit(0);
-                log::trace!("Set Z80 BUSREQ to {}", self.memory.signals.z80_busreq);
+                self.memory.set_z80_busreq(value.bit(0));
             }
             0xA11200..=0xA11201 => {
                 self.memory.signals.z80_reset = !value.bit(0);
                 log::trace!("Set Z80 RESET to {}", self.memory.signals.z80_reset);
             }
-            0xC00000..=0xC0001F => {
+            0xC00000..=0xC0001F if !self.memory.vdp_locked_by_tmss() => {
                 self.write_vdp_byte(address, value);
             }
             0xE00000..=0xFFFFFF => {
@@ -622,6 +963,10 @@ impl<'a, Medium: PhysicalMedium> MainBus<'a, Medium> {
         let address = address & ADDRESS_MASK;
         log::trace!("Main bus word write: address={address:06X}, value={value:02X}");
         match address {
+            0xA14000..=0xA14003 if self.memory.tmss_enabled => {
+                self.apply_byte_write(address & !1, value.msb());
+                self.apply_byte_write(address | 1, value.lsb());
+            }
             0x000000..=0x7FFFFF | 0xA12000..=0xA1500F => {
                 self.memory.physical_medium.write_word(address, value);
             }
@@ -633,20 +978,19 @@ impl<'a, Medium: PhysicalMedium> MainBus<'a, Medium> {
                 self.write_io_register(address, value.lsb());
             }
             0xA11100..=0xA11101 => {
-                self.memory.signals.z80_busreq = value.bit(8);
-                log::trace!("Set Z80 BUSREQ to {}", self.memory.signals.z80_busreq);
+                self.memory.set_z80_busreq(value.bit(8));
             }
             0xA11200..=0xA11201 => {
                 self.memory.signals.z80_reset = !value.bit(8);
                 log::trace!("Set Z80 RESET to {}", self.memory.signals.z80_reset);
             }
-            0xC00000..=0xC00003 => {
+            0xC00000..=0xC00003 if !self.memory.vdp_locked_by_tmss() => {
                 self.vdp.write_data(value);
             }
-            0xC00004..=0xC00007 => {
+            0xC00004..=0xC00007 if !self.memory.vdp_locked_by_tmss() => {
                 self.vdp.write_control(value);
             }
-            0xC0001C => {
+            0xC0001C if !self.memory.vdp_locked_by_tmss() => {
                 self.vdp.write_debug_register(value);
             }
             0xE00000..=0xFFFFFF => {
@@ -663,6 +1007,25 @@ impl<'a, Medium: PhysicalMedium> MainBus<'a, Medium> {
     pub fn z80_accessed_68k_bus(&self) -> bool {
         self.z80_accessed_68k_bus
     }
+
+    #[inline]
+    #[must_use]
+    pub fn z80_halted(&self) -> bool {
+        self.memory.signals.z80_halted
+    }
+
+    /// See [`Memory::tick_z80_signals`].
+    #[inline]
+    pub fn tick_z80_signals(&mut self, mclk_cycles: u64) {
+        self.memory.tick_z80_signals(mclk_cycles);
+    }
+
+    fn lock_up_z80(&mut self, z80_address: u16) {
+        if !self.memory.signals.z80_halted {
+            log::error!("Z80 locked up after an illegal access at {z80_address:04X}");
+            self.memory.signals.z80_halted = true;
+        }
+    }
 }
 
 // The Genesis has a 24-bit bus, not 32-bit
@@ -673,7 +1036,7 @@ impl<'a, Medium: PhysicalMedium> m68000_emu::BusInterface for MainBus<'a, Medium
     fn read_byte(&mut self, address: u32) -> u8 {
         let address = address & ADDRESS_MASK;
         log::trace!("Main bus byte read, address={address:06X}");
-        match address {
+        let value = match address {
             0x000000..=0x7FFFFF | 0xA12000..=0xA1500F => {
                 self.memory.physical_medium.read_byte(address)
             }
@@ -683,18 +1046,30 @@ impl<'a, Medium: PhysicalMedium> m68000_emu::BusInterface for MainBus<'a, Medium
                 <Self as z80_emu::BusInterface>::read_memory(self, (address & 0x7FFF) as u16)
             }
             0xA10000..=0xA1001F => self.read_io_register(address),
-            0xA11100..=0xA11101 => (!self.signals.z80_busack).into(),
-            0xC00000..=0xC0001F => self.read_vdp_byte(address),
+            0xA11100..=0xA11101 => self.memory.z80_busreq_status(self.signals.z80_busack),
+            0xC00000..=0xC0001F if !self.memory.vdp_locked_by_tmss() => self.read_vdp_byte(address),
             0xE00000..=0xFFFFFF => self.memory.main_ram[(address & 0xFFFF) as usize],
-            _ => 0xFF,
+            _ => {
+                // Unmapped; nothing drives the data bus, so it still holds the last value read
+                let open_bus = self.memory.open_bus;
+                return if address.bit(0) { open_bus.lsb() } else { open_bus.msb() };
+            }
+        };
+
+        if address.bit(0) {
+            self.memory.open_bus.set_lsb(value);
+        } else {
+            self.memory.open_bus.set_msb(value);
         }
+
+        value
     }
 
     #[inline]
     fn read_word(&mut self, address: u32) -> u16 {
         let address = address & ADDRESS_MASK;
         log::trace!("Main bus word read, address={address:06X}");
-        match address {
+        let value = match address {
             0x000000..=0x7FFFFF | 0xA12000..=0xA1500F => {
                 self.memory.physical_medium.read_word(address)
             }
@@ -706,12 +1081,12 @@ impl<'a, Medium: PhysicalMedium> m68000_emu::BusInterface for MainBus<'a, Medium
             0xA10000..=0xA1001F => self.read_io_register(address).into(),
             0xA11100..=0xA11101 => {
                 // Word reads of Z80 BUSREQ signal mirror the byte in both MSB and LSB
-                let byte: u8 = (!self.signals.z80_busack).into();
+                let byte = self.memory.z80_busreq_status(self.signals.z80_busack);
                 u16::from_le_bytes([byte, byte])
             }
-            0xC00000..=0xC00003 => self.vdp.read_data(),
-            0xC00004..=0xC00007 => self.vdp.read_status(),
-            0xC00008..=0xC0000F => self.vdp.hv_counter(),
+            0xC00000..=0xC00003 if !self.memory.vdp_locked_by_tmss() => self.vdp.read_data(),
+            0xC00004..=0xC00007 if !self.memory.vdp_locked_by_tmss() => self.vdp.read_status(),
+            0xC00008..=0xC0000F if !self.memory.vdp_locked_by_tmss() => self.vdp.hv_counter(),
             0xE00000..=0xFFFFFF => {
                 let ram_addr = (address & 0xFFFF) as usize;
                 u16::from_be_bytes([
@@ -719,8 +1094,14 @@ impl<'a, Medium: PhysicalMedium> m68000_emu::BusInterface for MainBus<'a, Medium
                     self.memory.main_ram[(ram_addr + 1) & 0xFFFF],
                 ])
             }
-            _ => 0xFFFF,
-        }
+            _ => {
+                // Unmapped; nothing drives the data bus, so it still holds the last value read
+                return self.memory.open_bus;
+            }
+        };
+
+        self.memory.open_bus = value;
+        value
     }
 
     #[inline]
@@ -796,10 +1177,12 @@ impl<'a, Medium: PhysicalMedium> z80_emu::BusInterface for MainBus<'a, Medium> {
                 if !(0xA00000..=0xA0FFFF).contains(&m68k_addr) {
                     <Self as m68000_emu::BusInterface>::read_byte(self, m68k_addr)
                 } else {
-                    // TODO this should lock up the system
-                    panic!(
-                        "Z80 attempted to read its own memory from the 68k bus; z80_addr={address:04X}, m68k_addr={m68k_addr:08X}"
+                    // Accessing Z80 memory through the 68k bus locks up the Z80
+                    log::debug!(
+                        "Z80 attempted to read its own memory through the 68k bus; m68k_addr={m68k_addr:08X}"
                     );
+                    self.lock_up_z80(address);
+                    0xFF
                 }
             }
         }
@@ -827,9 +1210,12 @@ impl<'a, Medium: PhysicalMedium> z80_emu::BusInterface for MainBus<'a, Medium> {
             0x6000..=0x60FF => {
                 self.memory.z80_bank_register.write_bit(value.bit(0));
             }
-            0x6100..=0x7EFF | 0x7F20..=0x7FFF => {
-                // Unused / invalid addresses
-                // TODO writes to $7F20-$7FFF should halt the system
+            0x6100..=0x7EFF => {
+                // Unused addresses
+            }
+            0x7F20..=0x7FFF => {
+                // Invalid addresses; writes here lock up the Z80
+                self.lock_up_z80(address);
             }
             0x7F00..=0x7F1F => {
                 // VDP addresses
@@ -842,10 +1228,11 @@ impl<'a, Medium: PhysicalMedium> z80_emu::BusInterface for MainBus<'a, Medium> {
                 if !(0xA00000..=0xA0FFFF).contains(&m68k_addr) {
                     self.apply_byte_write(m68k_addr, value);
                 } else {
-                    // TODO this should lock up the system
-                    panic!(
-                        "Z80 attempted to read its own memory from the 68k bus; z80_addr={address:04X}, m68k_addr={m68k_addr:08X}"
+                    // Accessing Z80 memory through the 68k bus locks up the Z80
+                    log::debug!(
+                        "Z80 attempted to write its own memory through the 68k bus; m68k_addr={m68k_addr:08X}"
                     );
+                    self.lock_up_z80(address);
                 }
             }
         }
@@ -883,3 +1270,414 @@ impl<'a, Medium: PhysicalMedium> z80_emu::BusInterface for MainBus<'a, Medium> {
         self.memory.signals.z80_reset
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cheats::{CheatCode, CheatList};
+    use crate::vdp::VdpConfig;
+    use smsgg_core::psg::PsgVersion;
+
+    // Every 512KB bank is filled with its bank number
+    fn ssf_mapper_rom(len: usize) -> Vec<u8> {
+        let mut rom: Vec<u8> = (0..len).map(|i| (i >> 19) as u8).collect();
+        rom[0x100..0x110].copy_from_slice(b"SEGA SSF        ");
+        rom
+    }
+
+    #[test]
+    fn game_genie_rom_patch() {
+        let mut rom = vec![0; 0x8000];
+        rom[0x402E..0x4030].copy_from_slice(&[0x12, 0x34]);
+        let mut cartridge = Cartridge::from_rom(rom, None, Some(GenesisRegion::Americas)).unwrap();
+
+        let mut cheats = CheatList::new();
+        cheats.add(CheatCode::parse("RFAA-A6VR").unwrap());
+        cartridge.set_rom_patches(cheats.rom_patches().collect());
+
+        assert_eq!(cartridge.read_word(0x402E), 0x4E71);
+        assert_eq!(cartridge.read_byte(0x402E), 0x4E);
+        assert_eq!(cartridge.read_byte(0x402F), 0x71);
+        assert_eq!(cartridge.read_word(0x4030), 0x0000);
+
+        cartridge.set_rom_patches(vec![]);
+        assert_eq!(cartridge.read_word(0x402E), 0x1234);
+    }
+
+    #[test]
+    fn cartridge_registers_do_not_panic() {
+        let mut cartridge =
+            Cartridge::from_rom(ssf_mapper_rom(0x500000), None, Some(GenesisRegion::Americas))
+                .unwrap();
+
+        assert_eq!(cartridge.read_byte(0xA13000), 0xFF);
+        assert_eq!(cartridge.read_word(0xA13000), 0xFFFF);
+        assert_eq!(cartridge.read_byte(0x580000), 0xFF);
+
+        cartridge.write_byte(0xA13001, 0x12);
+        cartridge.write_word(0xA13000, 0x1234);
+    }
+
+    #[test]
+    fn mapper_exposes_upper_banks() {
+        let mut cartridge =
+            Cartridge::from_rom(ssf_mapper_rom(0x500000), None, Some(GenesisRegion::Americas))
+                .unwrap();
+
+        assert_eq!(cartridge.read_byte(0x080000), 1);
+
+        cartridge.write_byte(0xA130F3, 8);
+        assert_eq!(cartridge.read_byte(0x080000), 8);
+        assert_eq!(cartridge.read_word(0x0FFFFE), 0x0808);
+
+        cartridge.write_word(0xA130FE, 9);
+        assert_eq!(cartridge.read_byte(0x380000), 9);
+    }
+
+    #[test]
+    fn mapper_bank_registers() {
+        let mut cartridge =
+            Cartridge::from_rom(ssf_mapper_rom(0x800000), None, Some(GenesisRegion::Americas))
+                .unwrap();
+
+        // $A130F3-$A130FF (odd addresses) select the banks at $080000-$3FFFFF in 512KB steps
+        for (i, register) in (0xA130F3..=0xA130FF).step_by(2).enumerate() {
+            let bank_address = 0x080000 * (i as u32 + 1);
+            assert_eq!(cartridge.read_byte(bank_address), i as u8 + 1);
+
+            let bank_number = 15 - i as u8;
+            cartridge.write_byte(register, bank_number);
+            assert_eq!(cartridge.read_byte(bank_address), bank_number);
+            assert_eq!(cartridge.read_byte(bank_address + 0x07FFFF), bank_number);
+        }
+
+        // $000000-$07FFFF is fixed
+        cartridge.write_byte(0xA130F1, 5);
+        assert_eq!(cartridge.read_byte(0x000200), 0);
+    }
+
+    #[test]
+    fn bank_registers_ignored_without_mapper() {
+        let mut rom = ssf_mapper_rom(0x400000);
+        rom[0x100..0x110].copy_from_slice(b"SEGA GENESIS    ");
+        let mut cartridge = Cartridge::from_rom(rom, None, Some(GenesisRegion::Americas)).unwrap();
+
+        cartridge.write_byte(0xA130F3, 5);
+        assert_eq!(cartridge.read_byte(0x080000), 1);
+        assert_eq!(cartridge.read_byte(0x380000), 7);
+    }
+
+    #[test]
+    fn lock_on_maps_both_roms() {
+        let mut base_rom = vec![0x11; 0x200000];
+        base_rom[0x1F0] = b'J';
+        let lock_on_rom = vec![0x22; 0x200000];
+
+        let mut cartridge =
+            Cartridge::from_rom_with_lockon(base_rom, lock_on_rom, None, None).unwrap();
+
+        assert_eq!(cartridge.region(), GenesisRegion::Japan);
+        assert_eq!(cartridge.read_byte(0x000000), 0x11);
+        assert_eq!(cartridge.read_byte(0x1FFFFF), 0x11);
+        assert_eq!(cartridge.read_word(0x200000), 0x2222);
+        assert_eq!(cartridge.read_byte(0x3FFFFF), 0x22);
+        assert_eq!(cartridge.read_byte(0x400000), 0xFF);
+    }
+
+    #[test]
+    fn lock_on_maps_patch_rom() {
+        let mut base_rom = vec![0x11; 0x200000 + 0x40000];
+        base_rom[0x200000..].fill(0x33);
+        let lock_on_rom: Vec<u8> = (0..0x100000).map(|i| (i >> 16) as u8).collect();
+
+        let mut cartridge = Cartridge::from_rom_with_lockon(
+            base_rom,
+            lock_on_rom,
+            None,
+            Some(GenesisRegion::Americas),
+        )
+        .unwrap();
+
+        assert_eq!(cartridge.read_byte(0x1FFFFF), 0x11);
+        assert_eq!(cartridge.read_byte(0x200000), 0x00);
+        assert_eq!(cartridge.read_byte(0x2F0000), 0x0F);
+        assert_eq!(cartridge.read_byte(0x300000), 0x33);
+        assert_eq!(cartridge.read_byte(0x3FFFFF), 0x33);
+    }
+
+    struct TestComponents {
+        signals: MainBusSignals,
+        memory: Memory<Cartridge>,
+        vdp: Vdp,
+        psg: Psg,
+        ym2612: Ym2612,
+        input: InputState,
+    }
+
+    impl TestComponents {
+        fn new() -> Self {
+            let cartridge =
+                Cartridge::from_rom(vec![0; 0x200], None, Some(GenesisRegion::Americas)).unwrap();
+            let vdp_config = VdpConfig {
+                enforce_sprite_limits: true,
+                emulate_non_linear_dac: false,
+                render_vertical_border: false,
+                render_horizontal_border: false,
+                plane_a_enabled: true,
+                plane_b_enabled: true,
+                window_enabled: true,
+                sprites_enabled: true,
+            };
+
+            Self {
+                signals: MainBusSignals {
+                    z80_busack: false,
+                    m68k_reset: false,
+                    expansion_connected: false,
+                },
+                memory: Memory::new(cartridge),
+                vdp: Vdp::new(TimingMode::Ntsc, vdp_config),
+                psg: Psg::new(PsgVersion::Standard),
+                ym2612: Ym2612::new(false),
+                input: InputState::new(),
+            }
+        }
+
+        fn main_bus(&mut self) -> MainBus<'_, Cartridge> {
+            MainBus::new(
+                &mut self.memory,
+                &mut self.vdp,
+                &mut self.psg,
+                &mut self.ym2612,
+                &mut self.input,
+                TimingMode::Ntsc,
+                self.signals,
+                MainBusWrites::new(),
+            )
+        }
+    }
+
+    #[test]
+    fn z80_self_access_locks_up_z80() {
+        let mut components = TestComponents::new();
+
+        // Bank $A00000-$A07FFF into the Z80's $8000-$FFFF window
+        components.memory.z80_bank_register.bank_number = 0xA00000 >> 15;
+
+        {
+            let mut bus = components.main_bus();
+            assert!(!bus.z80_halted());
+            assert_eq!(z80_emu::BusInterface::read_memory(&mut bus, 0x8000), 0xFF);
+            assert!(bus.z80_halted());
+        }
+        assert!(components.memory.z80_halted());
+
+        components.memory.reset_z80_signals();
+        assert!(!components.memory.z80_halted());
+
+        let mut bus = components.main_bus();
+        z80_emu::BusInterface::write_memory(&mut bus, 0x8123, 0x55);
+        assert!(bus.z80_halted());
+    }
+
+    #[test]
+    fn z80_invalid_range_write_locks_up_z80() {
+        let mut components = TestComponents::new();
+
+        let mut bus = components.main_bus();
+        z80_emu::BusInterface::write_memory(&mut bus, 0x6100, 0x00);
+        assert!(!bus.z80_halted());
+        assert_eq!(z80_emu::BusInterface::read_memory(&mut bus, 0x7F20), 0xFF);
+        assert!(!bus.z80_halted());
+
+        z80_emu::BusInterface::write_memory(&mut bus, 0x7F20, 0x00);
+        assert!(bus.z80_halted());
+    }
+
+    #[test]
+    fn z80_busack_latency() {
+        fn write_busreq(components: &mut TestComponents, busreq: bool) {
+            let mut bus = components.main_bus();
+            m68000_emu::BusInterface::write_word(&mut bus, 0xA11100, u16::from(busreq) << 8);
+            let _ = bus.apply_writes();
+        }
+
+        fn read_busreq(components: &mut TestComponents) -> u8 {
+            m68000_emu::BusInterface::read_byte(&mut components.main_bus(), 0xA11100) & 1
+        }
+
+        let mut components = TestComponents::new();
+
+        // The Z80 stalls immediately, but BUSACK has not asserted yet
+        write_busreq(&mut components, true);
+        components.signals.z80_busack = true;
+        assert_eq!(read_busreq(&mut components), 1);
+
+        components.memory.tick_z80_signals(Z80_BUSACK_LATENCY_MCLK_CYCLES - 1);
+        assert_eq!(read_busreq(&mut components), 1);
+
+        components.memory.tick_z80_signals(1);
+        assert_eq!(read_busreq(&mut components), 0);
+
+        // Releasing the bus is also delayed
+        write_busreq(&mut components, false);
+        components.signals.z80_busack = false;
+        assert_eq!(read_busreq(&mut components), 0);
+
+        components.memory.tick_z80_signals(Z80_BUSACK_LATENCY_MCLK_CYCLES);
+        assert_eq!(read_busreq(&mut components), 1);
+    }
+
+    #[test]
+    fn tmss_locks_vdp() {
+        fn write_word(components: &mut TestComponents, address: u32, value: u16) {
+            let mut bus = components.main_bus();
+            m68000_emu::BusInterface::write_word(&mut bus, address, value);
+            let _ = bus.apply_writes();
+        }
+
+        let mut components = TestComponents::new();
+        components.memory.set_tmss_enabled(true);
+
+        // Hardware version is 1 on consoles with TMSS
+        let version = m68000_emu::BusInterface::read_byte(&mut components.main_bus(), 0xA10001);
+        assert_eq!(version & 0x0F, 0x01);
+
+        // Set H40 mode; this should be ignored while the VDP is locked
+        write_word(&mut components, 0xC00004, 0x8C81);
+        assert_eq!(components.vdp.screen_width(), 256);
+
+        // Partially written unlock value does not unlock the VDP
+        write_word(&mut components, 0xA14000, u16::from_be_bytes(*b"SE"));
+        write_word(&mut components, 0xC00004, 0x8C81);
+        assert_eq!(components.vdp.screen_width(), 256);
+
+        write_word(&mut components, 0xA14002, u16::from_be_bytes(*b"GA"));
+        write_word(&mut components, 0xC00004, 0x8C81);
+        assert_eq!(components.vdp.screen_width(), 320);
+    }
+
+    #[test]
+    fn ram_init_patterns() {
+        let mut rng = Rng::new(12345);
+        let mut memory = TestComponents::new().memory;
+        assert!(memory.main_ram.iter().all(|&byte| byte == 0x00));
+
+        memory.init_ram(RamInitPattern::AllOnes, &mut rng);
+        assert!(memory.main_ram.iter().all(|&byte| byte == 0xFF));
+        assert!(memory.audio_ram.iter().all(|&byte| byte == 0xFF));
+
+        memory.init_ram(RamInitPattern::Alternating, &mut rng);
+        assert_eq!(&memory.main_ram[..4], &[0x00, 0xFF, 0x00, 0xFF]);
+        assert_eq!(&memory.audio_ram[..4], &[0x00, 0xFF, 0x00, 0xFF]);
+
+        memory.init_ram(RamInitPattern::AllZeros, &mut rng);
+        assert!(memory.main_ram.iter().all(|&byte| byte == 0x00));
+        assert!(memory.audio_ram.iter().all(|&byte| byte == 0x00));
+
+        memory.init_ram(RamInitPattern::Random, &mut rng);
+        assert!(memory.main_ram.iter().any(|&byte| byte != 0x00));
+        assert_eq!(memory.ram_init_pattern(), RamInitPattern::Random);
+
+        let mut other = TestComponents::new().memory;
+        other.init_ram(RamInitPattern::Random, &mut Rng::new(12345));
+        assert_eq!(memory.main_ram, other.main_ram);
+        assert_eq!(memory.audio_ram, other.audio_ram);
+    }
+
+    #[test]
+    fn debug_read_write() {
+        let mut rom = vec![0; 0x400];
+        rom[0x200..0x204].copy_from_slice(&[0x12, 0x34, 0x56, 0x78]);
+        let cartridge = Cartridge::from_rom(rom, None, Some(GenesisRegion::Americas)).unwrap();
+        let mut memory = Memory::new(cartridge);
+
+        // Work RAM and its mirrors
+        assert!(memory.debug_write(0xFF1234, 0x42));
+        assert_eq!(memory.debug_read(0xFF1234), 0x42);
+        assert_eq!(memory.debug_read(0xE01234), 0x42);
+        assert_eq!(memory.debug_read(0xFF1235), 0x00);
+
+        // Z80 RAM and its mirror
+        assert!(memory.debug_write(0xA00100, 0x66));
+        assert_eq!(memory.debug_read(0xA00100), 0x66);
+        assert_eq!(memory.debug_read(0xA02100), 0x66);
+
+        // Cartridge ROM is readable but writes are ignored
+        assert_eq!(memory.debug_read(0x000201), 0x34);
+        assert!(!memory.debug_write(0x000200, 0x00));
+        assert_eq!(memory.debug_read(0x000200), 0x12);
+
+        // I/O and VDP ports are neither read nor written
+        assert_eq!(memory.debug_read(0xC00000), 0xFF);
+        assert!(!memory.debug_write(0xA10003, 0x00));
+    }
+
+    #[test]
+    fn unmapped_reads_return_open_bus() {
+        let mut components = TestComponents::new();
+        components.memory.main_ram[0x1234..0x1236].copy_from_slice(&[0x12, 0x34]);
+        components.memory.main_ram[0x0000] = 0xAB;
+
+        let mut bus = components.main_bus();
+        assert_eq!(m68000_emu::BusInterface::read_word(&mut bus, 0xFF1234), 0x1234);
+        assert_eq!(m68000_emu::BusInterface::read_word(&mut bus, 0x900000), 0x1234);
+        assert_eq!(m68000_emu::BusInterface::read_byte(&mut bus, 0x900001), 0x34);
+
+        // Byte reads only replace the half of the bus that they drive
+        assert_eq!(m68000_emu::BusInterface::read_byte(&mut bus, 0xFF0000), 0xAB);
+        assert_eq!(m68000_emu::BusInterface::read_word(&mut bus, 0x900000), 0xAB34);
+    }
+
+    #[test]
+    fn title_is_parsed_from_header() {
+        let mut rom = vec![0; 0x200];
+        rom[0x120..0x150].copy_from_slice(&[b' '; 48]);
+        rom[0x120..0x12E].copy_from_slice(b"DOMESTIC  NAME");
+        rom[0x150..0x180].copy_from_slice(&[b' '; 48]);
+        rom[0x150..0x15E].copy_from_slice(b"OVERSEAS  NAME");
+
+        let cartridge =
+            Cartridge::from_rom(rom.clone(), None, Some(GenesisRegion::Americas)).unwrap();
+        assert_eq!(cartridge.program_title(), "OVERSEAS NAME");
+
+        let cartridge = Cartridge::from_rom(rom, None, Some(GenesisRegion::Japan)).unwrap();
+        assert_eq!(cartridge.program_title(), "DOMESTIC NAME");
+    }
+
+    #[test]
+    fn truncated_rom_does_not_panic() {
+        let mut rom = vec![b' '; 0x158];
+        rom[0x150..0x158].copy_from_slice(b"SHORT   ");
+
+        let cartridge = Cartridge::from_rom(rom, None, None).unwrap();
+        assert_eq!(cartridge.program_title(), "SHORT");
+
+        let cartridge =
+            Cartridge::from_rom(vec![0; 0x100], None, Some(GenesisRegion::Japan)).unwrap();
+        assert_eq!(cartridge.program_title(), "");
+    }
+
+    #[test]
+    fn version_register_expansion_bit() {
+        let mut components = TestComponents::new();
+
+        assert_eq!(components.main_bus().read_io_register(0xA10001), 0xA0);
+
+        components.signals.expansion_connected = true;
+        assert_eq!(components.main_bus().read_io_register(0xA10001), 0x80);
+    }
+
+    #[test]
+    fn version_register_console_region() {
+        let mut components = TestComponents::new();
+        assert_eq!(components.memory.hardware_region(), GenesisRegion::Americas);
+        assert_eq!(components.main_bus().read_io_register(0xA10001), 0xA0);
+
+        components.memory.set_forced_console_region(Some(GenesisRegion::Japan));
+        assert_eq!(components.main_bus().read_io_register(0xA10001), 0x20);
+
+        components.memory.set_forced_console_region(None);
+        assert_eq!(components.main_bus().read_io_register(0xA10001), 0xA0);
+    }
+}
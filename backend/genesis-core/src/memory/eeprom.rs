@@ -341,6 +341,13 @@ impl<State: EepromState + Debug, const N: usize> EepromChip<State, N> {
     pub fn get_memory(&self) -> &[u8] {
         &self.memory
     }
+
+    // Clear the serial interface state while retaining memory contents
+    pub fn power_cycle(&mut self) {
+        self.state = State::default();
+        self.last_data = false;
+        self.last_clock = false;
+    }
 }
 
 pub type X24C01Chip = EepromChip<X24C01State, 128>;
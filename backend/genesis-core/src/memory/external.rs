@@ -6,6 +6,54 @@ use crate::memory::eeprom::{X24C01Chip, X24C02Chip, X24C08Chip, X24C16Chip};
 use crate::memory::external::metadata::{EepromMetadata, EepromType};
 use bincode::{Decode, Encode};
 use jgenesis_common::num::GetBit;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CartridgeRamError {
+    #[error(
+        "Save file is {actual} bytes, which looks like an EEPROM save, but cartridge has {expected} bytes of SRAM"
+    )]
+    EepromSaveForSramCartridge { expected: u32, actual: u32 },
+    #[error(
+        "Save file is {actual} bytes, which looks like an SRAM save, but cartridge has a {expected}-byte EEPROM"
+    )]
+    SramSaveForEepromCartridge { expected: u32, actual: u32 },
+    #[error(
+        "Save file is {actual} bytes but cartridge has {expected} bytes of SRAM; truncating would discard save data"
+    )]
+    SaveTooLarge { expected: u32, actual: u32 },
+}
+
+// Sizes of all supported EEPROM chips; a save of one of these sizes is almost certainly not SRAM
+const EEPROM_SIZES: [usize; 4] = [128, 256, 1024, 2048];
+
+// Used if the RAM header's end address is before its start address; 8KB is by far the most common
+// SRAM size
+const DEFAULT_RAM_LEN: u32 = 8 * 1024;
+
+/// Pad or truncate a save file to the expected length. Returns None if truncating would discard
+/// non-zero bytes.
+fn resize_save(mut save: Vec<u8>, expected_len: usize) -> Option<Vec<u8>> {
+    if save.len() < expected_len {
+        log::warn!(
+            "Save file is {} bytes but cartridge expects {expected_len} bytes; padding with zeros",
+            save.len()
+        );
+        save.resize(expected_len, 0);
+    } else if save.len() > expected_len {
+        if save[expected_len..].iter().any(|&b| b != 0) {
+            return None;
+        }
+
+        log::warn!(
+            "Save file is {} bytes but cartridge expects {expected_len} bytes; truncating",
+            save.len()
+        );
+        save.truncate(expected_len);
+    }
+
+    Some(save)
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
 pub(crate) enum RamType {
@@ -26,12 +74,15 @@ pub(crate) struct Ram {
 }
 
 impl Ram {
-    pub(crate) fn from_rom_header(rom: &[u8], initial_ram: &mut Option<Vec<u8>>) -> Option<Self> {
-        let ram_header_bytes = &rom[0x1B0..0x1BC];
-
-        // RAM header should always start with ASCII "RA" followed by $F8 and $20
-        if ram_header_bytes[..4] != [b'R', b'A', 0xF8, 0x20] {
-            return None;
+    pub(crate) fn from_rom_header(
+        rom: &[u8],
+        initial_ram: &mut Option<Vec<u8>>,
+    ) -> Result<Option<Self>, CartridgeRamError> {
+        let Some(ram_header_bytes) = rom.get(0x1B0..0x1BC) else { return Ok(None) };
+
+        // RAM header should always start with ASCII "RA", followed by the RAM type byte and $20
+        if ram_header_bytes[..2] != [b'R', b'A'] || ram_header_bytes[3] != 0x20 {
+            return Ok(None);
         }
 
         // Third byte indicates RAM type and whether or not it is persistent memory
@@ -43,7 +94,7 @@ impl Ram {
             0xF0 => (RamType::EightBitEvenAddress, true),
             0xF8 => (RamType::EightBitOddAddress, true),
             _ => {
-                return None;
+                return Ok(None);
             }
         };
 
@@ -54,7 +105,7 @@ impl Ram {
             ram_header_bytes[6],
             ram_header_bytes[7],
         ]);
-        let end_address = u32::from_be_bytes([
+        let mut end_address = u32::from_be_bytes([
             ram_header_bytes[8],
             ram_header_bytes[9],
             ram_header_bytes[10],
@@ -65,19 +116,41 @@ impl Ram {
             "RAM header information: type={ram_type:?}, persistent={persistent}, start_address={start_address:06X}, end_address={end_address:06X}"
         );
 
-        let ram_len = if ram_type == RamType::SixteenBit {
-            end_address - start_address + 1
-        } else {
-            (end_address - start_address) / 2 + 1
+        let ram_len = match end_address.checked_sub(start_address) {
+            Some(address_len) if ram_type == RamType::SixteenBit => address_len + 1,
+            Some(address_len) => address_len / 2 + 1,
+            None => {
+                log::warn!(
+                    "RAM header end address {end_address:06X} is before start address {start_address:06X}; defaulting to {DEFAULT_RAM_LEN} bytes"
+                );
+                end_address = if ram_type == RamType::SixteenBit {
+                    start_address.saturating_add(DEFAULT_RAM_LEN - 1)
+                } else {
+                    start_address.saturating_add(2 * (DEFAULT_RAM_LEN - 1))
+                };
+                DEFAULT_RAM_LEN
+            }
         };
 
         let ram = match initial_ram.take() {
             Some(ram) if ram.len() as u32 == ram_len => ram,
-            _ => vec![0; ram_len as usize],
+            Some(ram) => {
+                let actual = ram.len() as u32;
+                if actual < ram_len && EEPROM_SIZES.contains(&ram.len()) {
+                    return Err(CartridgeRamError::EepromSaveForSramCartridge {
+                        expected: ram_len,
+                        actual,
+                    });
+                }
+
+                resize_save(ram, ram_len as usize)
+                    .ok_or(CartridgeRamError::SaveTooLarge { expected: ram_len, actual })?
+            }
+            None => vec![0; ram_len as usize],
         };
 
         // TODO support RAM persistence
-        Some(Self {
+        Ok(Some(Self {
             ram,
             address_mask: ram_len - 1,
             ram_type,
@@ -85,7 +158,7 @@ impl Ram {
             dirty: false,
             start_address,
             end_address,
-        })
+        }))
     }
 
     fn map_address(&self, address: u32) -> Option<u32> {
@@ -183,6 +256,10 @@ impl Eeprom {
     fn get_memory(&self) -> &[u8] {
         match_each_eeprom!(self, chip => chip.get_memory())
     }
+
+    fn power_cycle(&mut self) {
+        match_each_eeprom!(self, chip => chip.power_cycle());
+    }
 }
 
 #[derive(Debug, Clone, Encode, Decode)]
@@ -201,17 +278,43 @@ pub(crate) enum ExternalMemory {
 }
 
 impl ExternalMemory {
-    pub(crate) fn from_rom(rom: &[u8], mut initial_ram: Option<Vec<u8>>) -> Self {
-        if let Some(ram) = Ram::from_rom_header(rom, &mut initial_ram) {
-            return Self::Ram(ram);
+    /// Determine the cartridge's external memory from the ROM header or known EEPROM games.
+    ///
+    /// If `initial_ram` does not match the expected memory size, it will be zero-padded or
+    /// truncated with a warning when this cannot lose data.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `initial_ram` appears to be for a different type of external memory
+    /// (EEPROM vs. SRAM), or if truncating it would discard non-zero bytes.
+    pub(crate) fn from_rom(
+        rom: &[u8],
+        mut initial_ram: Option<Vec<u8>>,
+    ) -> Result<Self, CartridgeRamError> {
+        if let Some(ram) = Ram::from_rom_header(rom, &mut initial_ram)? {
+            return Ok(Self::Ram(ram));
         }
 
         if let Some(eeprom_metadata) = metadata::eeprom(rom) {
             log::info!("EEPROM metadata: {eeprom_metadata:X?}");
-            return new_eeprom(rom, initial_ram, eeprom_metadata);
+
+            let expected_len = eeprom_metadata.eeprom_type.memory_len();
+            let initial_ram = initial_ram
+                .map(|ram| {
+                    let actual = ram.len() as u32;
+                    resize_save(ram, expected_len).ok_or(
+                        CartridgeRamError::SramSaveForEepromCartridge {
+                            expected: expected_len as u32,
+                            actual,
+                        },
+                    )
+                })
+                .transpose()?;
+
+            return Ok(new_eeprom(rom, initial_ram, eeprom_metadata));
         }
 
-        Self::None
+        Ok(Self::None)
     }
 
     pub(crate) fn read_byte(&self, address: u32) -> Option<u8> {
@@ -271,6 +374,17 @@ impl ExternalMemory {
         }
     }
 
+    // Write to SRAM without side effects; EEPROM is not memory-mapped and is never written
+    pub(crate) fn debug_write_byte(&mut self, address: u32, value: u8) -> bool {
+        match self {
+            Self::Ram(ram) if ram.map_address(address).is_some() => {
+                ram.write_byte(address, value);
+                true
+            }
+            _ => false,
+        }
+    }
+
     pub(crate) fn get_memory(&self) -> &[u8] {
         const EMPTY_SLICE: &[u8] = &[];
 
@@ -289,6 +403,13 @@ impl ExternalMemory {
         }
     }
 
+    // Reset any volatile chip state; RAM and EEPROM contents are retained
+    pub(crate) fn power_cycle(&mut self) {
+        if let Self::Eeprom { chip, .. } = self {
+            chip.power_cycle();
+        }
+    }
+
     pub(crate) fn get_and_clear_dirty_bit(&mut self) -> bool {
         match self {
             Self::None => false,
@@ -333,3 +454,226 @@ fn new_eeprom(
         scl_bit: metadata.scl_bit,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rom_with_ram_header(ram_type: u8, start_address: u32, end_address: u32) -> Vec<u8> {
+        let mut rom = vec![0; 0x200];
+        rom[0x1B0..0x1B4].copy_from_slice(&[b'R', b'A', ram_type, 0x20]);
+        rom[0x1B4..0x1B8].copy_from_slice(&start_address.to_be_bytes());
+        rom[0x1B8..0x1BC].copy_from_slice(&end_address.to_be_bytes());
+        rom
+    }
+
+    #[test]
+    fn sixteen_bit_ram_header() {
+        let rom = rom_with_ram_header(0xE0, 0x200000, 0x203FFF);
+        let mut memory = ExternalMemory::from_rom(&rom, None).unwrap();
+
+        let ExternalMemory::Ram(ram) = &memory else { panic!("expected RAM, was {memory:?}") };
+        assert_eq!(ram.ram_type, RamType::SixteenBit);
+        assert!(ram.persistent);
+        assert_eq!(ram.ram.len(), 0x4000);
+
+        memory.write_word(0x200010, 0x1234);
+        assert_eq!(memory.read_byte(0x200010), Some(0x12));
+        assert_eq!(memory.read_byte(0x200011), Some(0x34));
+        assert_eq!(memory.read_byte(0x204000), None);
+    }
+
+    #[test]
+    fn odd_address_ram_header() {
+        let rom = rom_with_ram_header(0xF8, 0x200001, 0x203FFF);
+        let mut memory = ExternalMemory::from_rom(&rom, None).unwrap();
+
+        let ExternalMemory::Ram(ram) = &memory else { panic!("expected RAM, was {memory:?}") };
+        assert_eq!(ram.ram_type, RamType::EightBitOddAddress);
+        assert!(ram.persistent);
+        assert_eq!(ram.ram.len(), 0x2000);
+
+        memory.write_word(0x200010, 0x1234);
+        assert_eq!(memory.read_byte(0x200010), None);
+        assert_eq!(memory.read_byte(0x200011), Some(0x34));
+        assert_eq!(memory.get_memory()[0x8], 0x34);
+    }
+
+    #[test]
+    fn even_address_ram_header() {
+        let rom = rom_with_ram_header(0xB0, 0x200000, 0x203FFE);
+        let mut memory = ExternalMemory::from_rom(&rom, None).unwrap();
+
+        let ExternalMemory::Ram(ram) = &memory else { panic!("expected RAM, was {memory:?}") };
+        assert_eq!(ram.ram_type, RamType::EightBitEvenAddress);
+        assert!(!ram.persistent);
+        assert_eq!(ram.ram.len(), 0x2000);
+
+        memory.write_word(0x200010, 0x1234);
+        assert_eq!(memory.read_byte(0x200010), Some(0x12));
+        assert_eq!(memory.read_byte(0x200011), None);
+        assert_eq!(memory.get_memory()[0x8], 0x12);
+    }
+
+    #[test]
+    fn inverted_ram_header_addresses() {
+        let rom = rom_with_ram_header(0xF8, 0x203FFF, 0x200001);
+        let mut memory = ExternalMemory::from_rom(&rom, None).unwrap();
+
+        let ExternalMemory::Ram(ram) = &memory else { panic!("expected RAM, was {memory:?}") };
+        assert_eq!(ram.ram.len(), DEFAULT_RAM_LEN as usize);
+
+        memory.write_byte(0x204001, 0x56);
+        assert_eq!(memory.read_byte(0x204001), Some(0x56));
+    }
+
+    fn eeprom_rom() -> Vec<u8> {
+        // NHLPA Hockey '93 serial number; uses a 128-byte X24C01 EEPROM
+        let mut rom = vec![0; 0x200];
+        rom[0x183..0x18B].copy_from_slice(b"T-50396 ");
+        rom
+    }
+
+    #[test]
+    fn matching_save_size() {
+        let rom = rom_with_ram_header(0xF8, 0x200001, 0x203FFF);
+        let memory = ExternalMemory::from_rom(&rom, Some(vec![0x55; 0x2000])).unwrap();
+        assert_eq!(memory.get_memory(), &[0x55; 0x2000]);
+
+        let memory = ExternalMemory::from_rom(&eeprom_rom(), Some(vec![0x66; 128])).unwrap();
+        assert_eq!(memory.get_memory(), &[0x66; 128]);
+    }
+
+    #[test]
+    fn short_save_is_padded() {
+        let rom = rom_with_ram_header(0xF8, 0x200001, 0x203FFF);
+        let memory = ExternalMemory::from_rom(&rom, Some(vec![0x55; 0x1000])).unwrap();
+        assert_eq!(memory.get_memory().len(), 0x2000);
+        assert_eq!(&memory.get_memory()[..0x1000], &[0x55; 0x1000]);
+        assert_eq!(&memory.get_memory()[0x1000..], &[0; 0x1000]);
+
+        let memory = ExternalMemory::from_rom(&eeprom_rom(), Some(vec![0x66; 100])).unwrap();
+        assert_eq!(memory.get_memory().len(), 128);
+        assert_eq!(&memory.get_memory()[..100], &[0x66; 100]);
+    }
+
+    #[test]
+    fn long_save_is_truncated_only_if_excess_is_zero() {
+        let rom = rom_with_ram_header(0xF8, 0x200001, 0x203FFF);
+
+        let mut save = vec![0x55; 0x2000];
+        save.resize(0x4000, 0);
+        let memory = ExternalMemory::from_rom(&rom, Some(save)).unwrap();
+        assert_eq!(memory.get_memory(), &[0x55; 0x2000]);
+
+        assert!(matches!(
+            ExternalMemory::from_rom(&rom, Some(vec![0x55; 0x4000])),
+            Err(CartridgeRamError::SaveTooLarge { expected: 0x2000, actual: 0x4000 })
+        ));
+    }
+
+    #[test]
+    fn wrong_save_type() {
+        // EEPROM-sized save loaded into an SRAM cartridge
+        let rom = rom_with_ram_header(0xF8, 0x200001, 0x203FFF);
+        assert!(matches!(
+            ExternalMemory::from_rom(&rom, Some(vec![0x55; 256])),
+            Err(CartridgeRamError::EepromSaveForSramCartridge { expected: 0x2000, actual: 256 })
+        ));
+
+        // SRAM save loaded into an EEPROM cartridge
+        assert!(matches!(
+            ExternalMemory::from_rom(&eeprom_rom(), Some(vec![0x55; 0x2000])),
+            Err(CartridgeRamError::SramSaveForEepromCartridge { expected: 128, actual: 0x2000 })
+        ));
+    }
+
+    #[test]
+    fn missing_ram_header() {
+        let rom = vec![0; 0x200];
+        assert!(matches!(ExternalMemory::from_rom(&rom, None), Ok(ExternalMemory::None)));
+
+        let rom = vec![0; 0x100];
+        assert!(matches!(ExternalMemory::from_rom(&rom, None), Ok(ExternalMemory::None)));
+    }
+
+    // Bit-bangs the EA serial lines: SDA on bit 7 and SCL on bit 6 of $200000
+    struct EaSerialLines<'a>(&'a mut ExternalMemory);
+
+    impl EaSerialLines<'_> {
+        fn set(&mut self, sda: bool, scl: bool) {
+            self.0.write_byte(0x200000, (u8::from(sda) << 7) | (u8::from(scl) << 6));
+        }
+
+        fn sda(&self) -> bool {
+            self.0.read_byte(0x200000).unwrap().bit(7)
+        }
+
+        fn start(&mut self) {
+            self.set(true, true);
+            self.set(false, true);
+            self.set(false, false);
+        }
+
+        fn stop(&mut self) {
+            self.set(false, false);
+            self.set(false, true);
+            self.set(true, true);
+        }
+
+        fn send_byte(&mut self, byte: u8) {
+            for i in (0..8).rev() {
+                let bit = byte.bit(i);
+                self.set(bit, false);
+                self.set(bit, true);
+                self.set(bit, false);
+            }
+        }
+
+        fn receive_byte(&mut self) -> u8 {
+            (0..8).fold(0, |byte, _| {
+                self.set(true, false);
+                self.set(true, true);
+                (byte << 1) | u8::from(self.sda())
+            })
+        }
+
+        fn ack(&mut self) -> bool {
+            self.set(true, false);
+            self.set(true, true);
+            let ack = !self.sda();
+            self.set(true, false);
+            ack
+        }
+    }
+
+    #[test]
+    fn eeprom_serial_write_then_read() {
+        let mut memory = ExternalMemory::from_rom(&eeprom_rom(), None).unwrap();
+        assert!(memory.is_persistent());
+
+        // A new chip starts dirty so that the initial save file gets written
+        assert!(memory.get_and_clear_dirty_bit());
+
+        // 24C01 mode: 7-bit word address followed by the R/W bit, then data
+        let mut lines = EaSerialLines(&mut memory);
+        lines.start();
+        lines.send_byte(0x15 << 1);
+        assert!(lines.ack());
+        lines.send_byte(0x5A);
+        assert!(lines.ack());
+        lines.stop();
+
+        assert!(memory.get_and_clear_dirty_bit());
+        assert_eq!(memory.get_memory()[0x15], 0x5A);
+
+        let mut lines = EaSerialLines(&mut memory);
+        lines.start();
+        lines.send_byte((0x15 << 1) | 1);
+        assert!(lines.ack());
+        assert_eq!(lines.receive_byte(), 0x5A);
+        lines.stop();
+
+        assert!(!memory.get_and_clear_dirty_bit());
+    }
+}
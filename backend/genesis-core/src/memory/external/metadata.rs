@@ -14,6 +14,17 @@ pub enum EepromType {
     X24C16,
 }
 
+impl EepromType {
+    pub fn memory_len(self) -> usize {
+        match self {
+            Self::X24C01 => 128,
+            Self::X24C02 => 256,
+            Self::X24C08 => 1024,
+            Self::X24C16 => 2048,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct EepromMetadata {
     pub eeprom_type: EepromType,
@@ -100,7 +111,7 @@ const CODEMASTERS_24C16_METADATA: EepromMetadata = EepromMetadata {
 const CRC: Crc<u32> = Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
 
 pub fn eeprom(rom: &[u8]) -> Option<EepromMetadata> {
-    let serial_number: String = rom[0x183..0x18B].iter().map(|&b| b as char).collect();
+    let serial_number: String = rom.get(0x183..0x18B)?.iter().map(|&b| b as char).collect();
     match serial_number.as_str() {
         // NBA Jam (UE)
         // NBA Jam (J)
@@ -8,6 +8,8 @@ mod registers;
 mod render;
 mod sprites;
 
+pub use debug::{DebugPlane, SpriteDebugInfo};
+
 use crate::memory::{Memory, PhysicalMedium};
 use crate::vdp::colors::ColorModifier;
 use crate::vdp::dma::{DmaTracker, LineType};
@@ -270,6 +272,11 @@ pub struct VdpConfig {
     pub emulate_non_linear_dac: bool,
     pub render_vertical_border: bool,
     pub render_horizontal_border: bool,
+    /// Layer toggles for debugging; disabled layers are rendered as transparent
+    pub plane_a_enabled: bool,
+    pub plane_b_enabled: bool,
+    pub window_enabled: bool,
+    pub sprites_enabled: bool,
 }
 
 type Vram = [u8; VRAM_LEN];
@@ -619,8 +626,7 @@ impl Vdp {
     pub fn read_status(&mut self) -> u16 {
         log::trace!("VDP status register read");
 
-        let interlaced_odd =
-            self.registers.interlacing_mode.is_interlaced() && self.state.frame_count % 2 == 1;
+        let interlaced_odd = self.registers.interlacing_mode.is_interlaced() && self.odd_field();
 
         let scanline_mclk = self.state.scanline_mclk_cycles;
         let v_counter: u16 = self.v_counter(scanline_mclk).into();
@@ -1059,6 +1065,20 @@ impl Vdp {
         self.render_scanline(render_scanline, 0);
     }
 
+    /// Current backdrop color, as selected by VDP register #7. This is also the border color.
+    #[must_use]
+    pub fn backdrop_color(&self) -> Color {
+        let color = colors::resolve_color(
+            &self.cram,
+            self.registers.background_palette,
+            self.registers.background_color_id,
+        );
+        let r = ((color >> 1) & 0x07) as u8;
+        let g = ((color >> 5) & 0x07) as u8;
+        let b = ((color >> 9) & 0x07) as u8;
+        colors::gen_to_rgb(r, g, b, ColorModifier::None, self.config.emulate_non_linear_dac)
+    }
+
     #[must_use]
     pub fn frame_buffer(&self) -> &[Color; FRAME_BUFFER_LEN] {
         &self.frame_buffer
@@ -1078,6 +1098,19 @@ impl Vdp {
         }
     }
 
+    /// Whether the frame buffer holds both fields of an interlaced double resolution frame, with the
+    /// even field in even rows and the odd field in odd rows
+    #[must_use]
+    pub fn is_interlaced_double(&self) -> bool {
+        self.registers.interlacing_mode == InterlacingMode::InterlacedDouble
+    }
+
+    /// Whether the current frame is an odd field when interlacing is enabled
+    #[must_use]
+    pub fn odd_field(&self) -> bool {
+        self.state.frame_count % 2 == 1
+    }
+
     #[must_use]
     pub fn screen_height(&self) -> u32 {
         let screen_height: u32 = if self.config.render_vertical_border {
@@ -1154,6 +1187,7 @@ fn scanline_mclk_to_pixel_h40(scanline_mclk: u64) -> u16 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use jgenesis_common::frontend::FrameSize;
 
     fn new_vdp() -> Vdp {
         Vdp::new(
@@ -1163,6 +1197,10 @@ mod tests {
                 emulate_non_linear_dac: false,
                 render_vertical_border: false,
                 render_horizontal_border: false,
+                plane_a_enabled: true,
+                plane_b_enabled: true,
+                window_enabled: true,
+                sprites_enabled: true,
             },
         )
     }
@@ -1212,4 +1250,60 @@ mod tests {
         assert_eq!(vdp.h_counter(MCLK_CYCLES_PER_SCANLINE - 16), 0xFF);
         assert_eq!(vdp.h_counter(MCLK_CYCLES_PER_SCANLINE - 1), 0xFF);
     }
+
+    #[test]
+    fn debug_nametable() {
+        let mut vdp = new_vdp();
+        vdp.registers.scroll_a_base_nt_addr = 0xC000;
+
+        // Top-left cell: palette 2, horizontal flip, pattern 1
+        vdp.vram[0xC000..0xC002].copy_from_slice(&0x4801_u16.to_be_bytes());
+
+        // Pattern 1, row 0: color 1 then color 2
+        vdp.vram[32] = 0x12;
+
+        vdp.cram[0x21] = 0x000E;
+        vdp.cram[0x22] = 0x00E0;
+
+        let mut cram_colors = vec![Color::default(); CRAM_LEN_WORDS];
+        vdp.copy_cram(&mut cram_colors);
+
+        let mut out = vec![Color::default(); 256 * 256];
+        let frame_size = vdp.debug_nametable(DebugPlane::ScrollA, &mut out);
+        assert_eq!(frame_size, FrameSize { width: 256, height: 256 });
+
+        // Horizontally flipped, so the pattern's first two pixels end up at the right edge
+        assert_eq!(out[7], cram_colors[0x21]);
+        assert_eq!(out[6], cram_colors[0x22]);
+        assert_eq!(out[0], cram_colors[0x20]);
+    }
+
+    #[test]
+    fn debug_sprites() {
+        let mut vdp = new_vdp();
+        vdp.registers.sprite_attribute_table_base_addr = 0xF800;
+
+        // Sprite 1: Y=$105, 2x3 cells, link 2, priority, palette 2, vertical flip, pattern $123,
+        // X=$1AB
+        vdp.vram[0xF808..0xF810].copy_from_slice(&[0x01, 0x05, 0x06, 0x02, 0xD1, 0x23, 0x01, 0xAB]);
+
+        let sprites = vdp.debug_sprites();
+        assert_eq!(sprites.len(), 64);
+        assert_eq!(
+            sprites[1],
+            SpriteDebugInfo {
+                index: 1,
+                x: 0x1AB,
+                y: 0x105,
+                h_size_cells: 2,
+                v_size_cells: 3,
+                pattern_generator: 0x123,
+                palette: 2,
+                priority: true,
+                vertical_flip: true,
+                horizontal_flip: false,
+                link: 2,
+            }
+        );
+    }
 }
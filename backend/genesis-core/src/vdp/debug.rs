@@ -1,8 +1,31 @@
 use crate::vdp;
-use crate::vdp::{colors, render, ColorModifier, Vdp};
+use crate::vdp::{colors, render, CachedSpriteData, ColorModifier, SpriteData, Vdp};
 
 use crate::vdp::render::PatternGeneratorArgs;
-use jgenesis_common::frontend::Color;
+use jgenesis_common::frontend::{Color, FrameSize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugPlane {
+    ScrollA,
+    ScrollB,
+    Window,
+}
+
+/// Decoded sprite attribute table entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpriteDebugInfo {
+    pub index: u8,
+    pub x: u16,
+    pub y: u16,
+    pub h_size_cells: u8,
+    pub v_size_cells: u8,
+    pub pattern_generator: u16,
+    pub palette: u8,
+    pub priority: bool,
+    pub vertical_flip: bool,
+    pub horizontal_flip: bool,
+    pub link: u8,
+}
 
 impl Vdp {
     pub fn copy_cram(&self, out: &mut [Color]) {
@@ -36,6 +59,96 @@ impl Vdp {
             }
         }
     }
+
+    /// Render the entire nametable for the given plane, ignoring scroll values, using each cell's
+    /// palette and flip attributes. `out` is filled in row-major order and must be large enough to
+    /// hold the returned size.
+    pub fn debug_nametable(&self, plane: DebugPlane, out: &mut [Color]) -> FrameSize {
+        let (base_addr, width_cells, height_cells) = match plane {
+            DebugPlane::ScrollA => (
+                self.registers.scroll_a_base_nt_addr,
+                self.registers.horizontal_scroll_size.to_pixels() / 8,
+                self.registers.vertical_scroll_size.to_pixels() / 8,
+            ),
+            DebugPlane::ScrollB => (
+                self.registers.scroll_b_base_nt_addr,
+                self.registers.horizontal_scroll_size.to_pixels() / 8,
+                self.registers.vertical_scroll_size.to_pixels() / 8,
+            ),
+            DebugPlane::Window => (
+                self.registers.window_base_nt_addr,
+                self.registers.horizontal_display_size.window_width_cells(),
+                32,
+            ),
+        };
+        let width_pixels = 8 * usize::from(width_cells);
+
+        for row in 0..height_cells {
+            for col in 0..width_cells {
+                let nt_word =
+                    render::read_name_table_word(&self.vram, base_addr, width_cells, row, col);
+
+                for cell_row in 0..8 {
+                    for cell_col in 0..8 {
+                        let color_id = render::read_pattern_generator(
+                            &self.vram,
+                            PatternGeneratorArgs {
+                                vertical_flip: nt_word.vertical_flip,
+                                horizontal_flip: nt_word.horizontal_flip,
+                                pattern_generator: nt_word.pattern_generator,
+                                row: cell_row,
+                                col: cell_col,
+                                cell_height: 8,
+                            },
+                        );
+                        let color = colors::resolve_color(&self.cram, nt_word.palette, color_id);
+
+                        let out_idx = usize::from(8 * row + cell_row) * width_pixels
+                            + usize::from(8 * col + cell_col);
+                        out[out_idx] = parse_gen_color(color);
+                    }
+                }
+            }
+        }
+
+        FrameSize { width: width_pixels as u32, height: 8 * u32::from(height_cells) }
+    }
+
+    /// Decode every entry in the sprite attribute table, in table order. This reads all fields
+    /// from VRAM rather than from the VDP's internal sprite cache.
+    #[must_use]
+    pub fn debug_sprites(&self) -> Vec<SpriteDebugInfo> {
+        let sprite_table_addr = self.registers.masked_sprite_attribute_table_addr();
+        let sprite_table_len = self.registers.horizontal_display_size.sprite_table_len();
+
+        (0..sprite_table_len)
+            .map(|sprite_idx| {
+                let sprite_addr = sprite_table_addr.wrapping_add(8 * sprite_idx) as usize;
+                let bytes = &self.vram[sprite_addr..sprite_addr + 8];
+
+                let mut cached_data = CachedSpriteData::default();
+                cached_data.update_first_word_msb(bytes[0]);
+                cached_data.update_first_word_lsb(bytes[1]);
+                cached_data.update_second_word_msb(bytes[2]);
+                cached_data.update_second_word_lsb(bytes[3]);
+                let sprite = SpriteData::create(cached_data, &bytes[4..8]);
+
+                SpriteDebugInfo {
+                    index: sprite_idx as u8,
+                    x: sprite.h_position,
+                    y: sprite.v_position,
+                    h_size_cells: sprite.h_size_cells,
+                    v_size_cells: sprite.v_size_cells,
+                    pattern_generator: sprite.pattern_generator,
+                    palette: sprite.palette,
+                    priority: sprite.priority,
+                    vertical_flip: sprite.vertical_flip,
+                    horizontal_flip: sprite.horizontal_flip,
+                    link: sprite.link_data,
+                }
+            })
+            .collect()
+    }
 }
 
 fn parse_gen_color(gen_color: u16) -> Color {
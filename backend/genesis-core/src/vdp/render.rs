@@ -387,37 +387,42 @@ impl Vdp {
                 },
             );
 
-            let in_window = self.latched_registers.is_in_window(raster_line.line, pixel as u16);
-            let (window_priority, window_palette, window_color_id) = if in_window {
-                let window_v_cell = raster_line.line / cell_height;
-
-                let window_width_cells =
-                    self.latched_registers.horizontal_display_size.window_width_cells();
-                let window_pixel = (pixel as u16) & (window_width_cells * 8 - 1);
-                let window_h_cell = window_pixel / 8;
+            // Disabled layers are rendered as fully transparent
+            let scroll_a_color_id = if self.config.plane_a_enabled { scroll_a_color_id } else { 0 };
+            let scroll_b_color_id = if self.config.plane_b_enabled { scroll_b_color_id } else { 0 };
 
-                let window_nt_word = read_name_table_word(
-                    &self.vram,
-                    self.latched_registers.window_base_nt_addr,
-                    window_width_cells,
-                    window_v_cell,
-                    window_h_cell,
-                );
-                let window_color_id = read_pattern_generator(
-                    &self.vram,
-                    PatternGeneratorArgs {
-                        vertical_flip: window_nt_word.vertical_flip,
-                        horizontal_flip: window_nt_word.horizontal_flip,
-                        pattern_generator: window_nt_word.pattern_generator,
-                        row: raster_line.line,
-                        col: window_pixel,
-                        cell_height,
-                    },
-                );
-                (window_nt_word.priority, window_nt_word.palette, window_color_id)
-            } else {
-                (false, 0, 0)
-            };
+            let in_window = self.latched_registers.is_in_window(raster_line.line, pixel as u16);
+            let (window_priority, window_palette, window_color_id) =
+                if in_window && self.config.window_enabled {
+                    let window_v_cell = raster_line.line / cell_height;
+
+                    let window_width_cells =
+                        self.latched_registers.horizontal_display_size.window_width_cells();
+                    let window_pixel = (pixel as u16) & (window_width_cells * 8 - 1);
+                    let window_h_cell = window_pixel / 8;
+
+                    let window_nt_word = read_name_table_word(
+                        &self.vram,
+                        self.latched_registers.window_base_nt_addr,
+                        window_width_cells,
+                        window_v_cell,
+                        window_h_cell,
+                    );
+                    let window_color_id = read_pattern_generator(
+                        &self.vram,
+                        PatternGeneratorArgs {
+                            vertical_flip: window_nt_word.vertical_flip,
+                            horizontal_flip: window_nt_word.horizontal_flip,
+                            pattern_generator: window_nt_word.pattern_generator,
+                            row: raster_line.line,
+                            col: window_pixel,
+                            cell_height,
+                        },
+                    );
+                    (window_nt_word.priority, window_nt_word.palette, window_color_id)
+                } else {
+                    (false, 0, 0)
+                };
 
             let SpritePixel {
                 palette: sprite_palette,
@@ -428,6 +433,7 @@ impl Vdp {
                 .get(pixel as usize)
                 .copied()
                 .unwrap_or(SpritePixel::default());
+            let sprite_color_id = if self.config.sprites_enabled { sprite_color_id } else { 0 };
 
             let (scroll_a_priority, scroll_a_palette, scroll_a_color_id) = if in_window {
                 // Window replaces scroll A if this pixel is inside the window
@@ -848,15 +854,15 @@ fn read_h_scroll(
 }
 
 #[derive(Debug, Clone, Copy, Default)]
-struct NameTableWord {
-    priority: bool,
-    palette: u8,
-    vertical_flip: bool,
-    horizontal_flip: bool,
-    pattern_generator: u16,
+pub struct NameTableWord {
+    pub priority: bool,
+    pub palette: u8,
+    pub vertical_flip: bool,
+    pub horizontal_flip: bool,
+    pub pattern_generator: u16,
 }
 
-fn read_name_table_word(
+pub fn read_name_table_word(
     vram: &Vram,
     base_addr: u16,
     name_table_width: u16,
@@ -1056,6 +1062,76 @@ fn determine_pixel_color(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::vdp::VdpConfig;
+    use jgenesis_common::frontend::Color;
+
+    fn test_config() -> VdpConfig {
+        VdpConfig {
+            enforce_sprite_limits: true,
+            emulate_non_linear_dac: false,
+            render_vertical_border: false,
+            render_horizontal_border: false,
+            plane_a_enabled: true,
+            plane_b_enabled: true,
+            window_enabled: true,
+            sprites_enabled: true,
+        }
+    }
+
+    fn new_vdp(config: VdpConfig) -> Vdp {
+        let mut vdp = Vdp::new(TimingMode::Ntsc, config);
+        vdp.registers.display_enabled = true;
+        vdp.latched_registers.display_enabled = true;
+
+        // Backdrop is palette 0 color 0; sprite pixel at X=10 uses palette 1 color 1
+        vdp.cram[0] = 0x0000;
+        vdp.cram[17] = 0x0EEE;
+        vdp.sprite_buffers.pixels[10] = SpritePixel { palette: 1, color_id: 1, priority: false };
+
+        vdp
+    }
+
+    #[test]
+    fn sprites_disabled() {
+        let config = test_config();
+        let line = RasterLine { line: 0, in_v_border: false };
+
+        let mut vdp = new_vdp(config);
+        vdp.render_pixels_in_scanline(line, 0, 0, false);
+        assert_ne!(vdp.frame_buffer()[10], vdp.frame_buffer()[0]);
+
+        vdp.reload_config(VdpConfig { sprites_enabled: false, ..config });
+        vdp.render_pixels_in_scanline(line, 0, 0, false);
+        assert_eq!(vdp.frame_buffer()[10], vdp.frame_buffer()[0]);
+    }
+
+    #[test]
+    fn interlaced_double_resolution() {
+        let mut vdp = Vdp::new(TimingMode::Ntsc, test_config());
+        for registers in [&mut vdp.registers, &mut vdp.latched_registers] {
+            registers.display_enabled = true;
+            registers.interlacing_mode = InterlacingMode::InterlacedDouble;
+            registers.scroll_a_base_nt_addr = 0xC000;
+            registers.scroll_b_base_nt_addr = 0xE000;
+            registers.sprite_attribute_table_base_addr = 0xF000;
+            registers.h_scroll_table_base_addr = 0xFC00;
+        }
+
+        // Tiles are 8x16 in this mode; tile 0 has color 1 in even rows and color 2 in odd rows
+        for (row, pattern) in vdp.vram[..64].chunks_exact_mut(4).enumerate() {
+            pattern.fill(if row % 2 == 0 { 0x11 } else { 0x22 });
+        }
+        vdp.cram[1] = 0x000E;
+        vdp.cram[2] = 0x0E00;
+
+        // Each scanline renders one line from each field into consecutive frame buffer rows
+        vdp.render_scanline(0, 0);
+
+        let width = vdp.screen_width() as usize;
+        let (even_row, odd_row) = vdp.frame_buffer()[..2 * width].split_at(width);
+        assert!(even_row.iter().all(|&color| color == Color::rgb(255, 0, 0)));
+        assert!(odd_row.iter().all(|&color| color == Color::rgb(0, 0, 255)));
+    }
 
     #[test]
     fn test_div_floor() {
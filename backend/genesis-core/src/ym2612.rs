@@ -14,7 +14,10 @@ use crate::ym2612::phase::PhaseGenerator;
 use crate::ym2612::timer::{TimerA, TimerB, TimerTickEffect};
 use bincode::{Decode, Encode};
 use jgenesis_common::num::{GetBit, U16Ext};
+use jgenesis_proc_macros::{FakeDecode, FakeEncode};
 use std::array;
+use std::collections::VecDeque;
+use std::mem;
 use std::sync::OnceLock;
 
 const FM_CLOCK_DIVIDER: u8 = 6;
@@ -31,6 +34,10 @@ const OPERATOR_OUTPUT_MAX: i16 = 0x1FFF;
 const GROUP_1_BASE_CHANNEL: usize = 0;
 const GROUP_2_BASE_CHANNEL: usize = 3;
 
+// Maximum number of samples kept in each channel tap buffer, roughly 1 second of output; older
+// samples are discarded if the buffers are not taken often enough
+const MAX_CHANNEL_TAP_SAMPLES: usize = 53_267;
+
 fn compute_key_code(f_number: u16, block: u8) -> u8 {
     // Bits 4-2: Block
     // Bit 1: F11
@@ -177,8 +184,9 @@ impl FmChannel {
             algorithm: 0,
             am_sensitivity: 0,
             fm_sensitivity: 0,
-            l_output: false,
-            r_output: false,
+            // Both stereo outputs are enabled at power-on
+            l_output: true,
+            r_output: true,
             divider: FM_SAMPLE_DIVIDER,
             current_output: (0, 0),
         }
@@ -352,6 +360,10 @@ enum RegisterGroup {
     Two,
 }
 
+// Channel taps are debug output, not emulation state, so they are never saved in save states
+#[derive(Debug, Clone, Default, FakeEncode, FakeDecode)]
+struct ChannelTaps(Option<[VecDeque<(f64, f64)>; 6]>);
+
 #[derive(Debug, Clone, Encode, Decode)]
 pub struct Ym2612 {
     channels: [FmChannel; 6],
@@ -367,6 +379,8 @@ pub struct Ym2612 {
     timer_b: TimerB,
     csm_enabled: bool,
     quantize_output: bool,
+    muted_channels: [bool; 6],
+    channel_taps: ChannelTaps,
 }
 
 impl Ym2612 {
@@ -386,11 +400,17 @@ impl Ym2612 {
             timer_b: TimerB::new(),
             csm_enabled: false,
             quantize_output,
+            muted_channels: [false; 6],
+            channel_taps: ChannelTaps::default(),
         }
     }
 
     pub fn reset(&mut self) {
+        let muted_channels = self.muted_channels;
+        let channel_taps_enabled = self.channel_taps_enabled();
         *self = Self::new(self.quantize_output);
+        self.muted_channels = muted_channels;
+        self.set_channel_taps_enabled(channel_taps_enabled);
     }
 
     // Set the address register and set group to 1 (system registers + channels 1-3)
@@ -568,6 +588,7 @@ impl Ym2612 {
             self.sample_divider -= 1;
             if self.sample_divider == 0 {
                 self.sample_divider = FM_SAMPLE_DIVIDER;
+                self.record_channel_taps();
                 return YmTickEffect::OutputSample;
             }
         }
@@ -581,19 +602,18 @@ impl Ym2612 {
 
         let mut sum_l = 0;
         let mut sum_r = 0;
-        for channel in &self.channels[0..5] {
+        for (channel, &muted) in self.channels[0..5].iter().zip(&self.muted_channels) {
+            if muted {
+                continue;
+            }
+
             let (sample_l, sample_r) = channel.current_output;
             sum_l += i32::from(sample_l & quantization_mask);
             sum_r += i32::from(sample_r & quantization_mask);
         }
 
-        let (ch6_sample_l, ch6_sample_r) = if self.pcm_enabled {
-            // Convert unsigned 8-bit sample to a signed 14-bit sample
-            let pcm_sample = (i16::from(self.pcm_sample) - 128) << 6;
-            (pcm_sample, pcm_sample)
-        } else {
-            self.channels[5].current_output
-        };
+        let (ch6_sample_l, ch6_sample_r) =
+            if self.muted_channels[5] { (0, 0) } else { self.channel_6_output() };
         sum_l += i32::from(ch6_sample_l);
         sum_r += i32::from(ch6_sample_r);
 
@@ -601,6 +621,42 @@ impl Ym2612 {
         (f64::from(sum_l) / 49152.0, f64::from(sum_r) / 49152.0)
     }
 
+    fn channel_6_output(&self) -> (i16, i16) {
+        let channel = &self.channels[5];
+        if self.pcm_enabled {
+            // Convert unsigned 8-bit sample to a signed 14-bit sample; the DAC uses channel 6's
+            // L/R output bits
+            let pcm_sample = (i16::from(self.pcm_sample) - 128) << 6;
+            (pcm_sample * i16::from(channel.l_output), pcm_sample * i16::from(channel.r_output))
+        } else {
+            channel.current_output
+        }
+    }
+
+    fn record_channel_taps(&mut self) {
+        if self.channel_taps.0.is_none() {
+            return;
+        }
+
+        let outputs: [(i16, i16); 6] = array::from_fn(|channel_idx| {
+            if channel_idx == 5 {
+                self.channel_6_output()
+            } else {
+                self.channels[channel_idx].current_output
+            }
+        });
+
+        let Some(channel_taps) = &mut self.channel_taps.0 else { return };
+        for (tap, (sample_l, sample_r)) in channel_taps.iter_mut().zip(outputs) {
+            if tap.len() == MAX_CHANNEL_TAP_SAMPLES {
+                tap.pop_front();
+            }
+
+            // Each channel has a range of [-8192, 8191]
+            tap.push_back((f64::from(sample_l) / 8192.0, f64::from(sample_r) / 8192.0));
+        }
+    }
+
     fn quantization_mask(&self) -> i16 {
         if self.quantize_output {
             // Simulate a 9-bit DAC by masking out the lowest 5 bits of the 14-bit channel outputs
@@ -806,6 +862,52 @@ impl Ym2612 {
         }
     }
 
+    #[must_use]
+    pub fn muted_channels(&self) -> [bool; 6] {
+        self.muted_channels
+    }
+
+    /// Mute or unmute a channel (0-5). Muted channels keep running but do not contribute to the
+    /// output.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `channel` is not in the range 0-5.
+    pub fn set_channel_muted(&mut self, channel: usize, muted: bool) {
+        self.muted_channels[channel] = muted;
+    }
+
+    #[must_use]
+    pub fn channel_taps_enabled(&self) -> bool {
+        self.channel_taps.0.is_some()
+    }
+
+    /// Enable or disable per-channel output taps. While enabled, every output sample also records
+    /// each channel's individual output (before mixing and muting) into a per-channel buffer.
+    ///
+    /// Each buffer holds at most about 1 second of samples; if the buffers are not taken often
+    /// enough, the oldest samples are discarded.
+    pub fn set_channel_taps_enabled(&mut self, enabled: bool) {
+        if enabled != self.channel_taps_enabled() {
+            self.channel_taps = ChannelTaps(enabled.then(|| array::from_fn(|_| VecDeque::new())));
+        }
+    }
+
+    /// Per-channel output samples recorded since the buffers were last taken, or `None` if channel
+    /// taps are disabled.
+    #[must_use]
+    pub fn channel_taps(&self) -> Option<&[VecDeque<(f64, f64)>; 6]> {
+        self.channel_taps.0.as_ref()
+    }
+
+    /// Take the recorded per-channel output samples, leaving the tap buffers empty.
+    pub fn take_channel_taps(&mut self) -> Option<[Vec<(f64, f64)>; 6]> {
+        self.channel_taps
+            .0
+            .as_mut()
+            .map(|channel_taps| array::from_fn(|i| mem::take(&mut channel_taps[i]).into()))
+    }
+
     #[must_use]
     pub fn get_quantize_output(&self) -> bool {
         self.quantize_output
@@ -815,3 +917,185 @@ impl Ym2612 {
         self.quantize_output = quantize_output;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[track_caller]
+    fn assert_approx_eq(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "expected {expected}, was {actual}");
+    }
+
+    fn write_register(ym2612: &mut Ym2612, register: u8, value: u8) {
+        ym2612.write_address_1(register);
+        ym2612.write_data(value);
+    }
+
+    #[test]
+    fn fm_channel_stereo_output() {
+        let mut ym2612 = Ym2612::new(false);
+
+        // Channel 1: algorithm 7 (all operators output), max attack rate, no attenuation
+        write_register(&mut ym2612, 0xB0, 0x07);
+        for operator_offset in [0x00, 0x04, 0x08, 0x0C] {
+            write_register(&mut ym2612, 0x30 + operator_offset, 0x01);
+            write_register(&mut ym2612, 0x40 + operator_offset, 0x00);
+            write_register(&mut ym2612, 0x50 + operator_offset, 0x1F);
+        }
+        write_register(&mut ym2612, 0xA4, 0x22);
+        write_register(&mut ym2612, 0xA0, 0x69);
+
+        // Right output only
+        write_register(&mut ym2612, 0xB4, 0x40);
+
+        // Key on all operators
+        write_register(&mut ym2612, 0x28, 0xF0);
+
+        let mut right_nonzero = false;
+        for _ in 0..200 * u32::from(FM_CLOCK_DIVIDER) * u32::from(FM_SAMPLE_DIVIDER) {
+            if ym2612.tick() == YmTickEffect::OutputSample {
+                let (sample_l, sample_r) = ym2612.sample();
+                assert_approx_eq(sample_l, 0.0);
+                right_nonzero |= sample_r.abs() > 0.0;
+            }
+        }
+        assert!(right_nonzero);
+    }
+
+    #[test]
+    fn channel_taps() {
+        let mut ym2612 = Ym2612::new(false);
+        ym2612.set_channel_taps_enabled(true);
+
+        // Channel 1: algorithm 7 (all operators output), max attack rate, no attenuation
+        write_register(&mut ym2612, 0xB0, 0x07);
+        for operator_offset in [0x00, 0x04, 0x08, 0x0C] {
+            write_register(&mut ym2612, 0x30 + operator_offset, 0x01);
+            write_register(&mut ym2612, 0x40 + operator_offset, 0x00);
+            write_register(&mut ym2612, 0x50 + operator_offset, 0x1F);
+        }
+        write_register(&mut ym2612, 0xA4, 0x22);
+        write_register(&mut ym2612, 0xA0, 0x69);
+
+        // Key on all operators
+        write_register(&mut ym2612, 0x28, 0xF0);
+
+        let mut mixed_samples = 0;
+        for _ in 0..200 * u32::from(FM_CLOCK_DIVIDER) * u32::from(FM_SAMPLE_DIVIDER) {
+            if ym2612.tick() == YmTickEffect::OutputSample {
+                mixed_samples += 1;
+            }
+        }
+
+        let channel_taps = ym2612.take_channel_taps().unwrap();
+        for tap in &channel_taps {
+            assert_eq!(tap.len(), mixed_samples);
+        }
+
+        assert!(channel_taps[0].iter().any(|&(sample_l, _)| sample_l.abs() > 0.0));
+        for &(sample_l, sample_r) in channel_taps[1..].iter().flatten() {
+            assert_approx_eq(sample_l, 0.0);
+            assert_approx_eq(sample_r, 0.0);
+        }
+
+        // Taking the taps clears the buffers
+        assert!(ym2612.channel_taps().unwrap().iter().all(VecDeque::is_empty));
+
+        // Buffers are capped if they are never taken
+        for _ in 0..(MAX_CHANNEL_TAP_SAMPLES + 10)
+            * usize::from(FM_CLOCK_DIVIDER)
+            * usize::from(FM_SAMPLE_DIVIDER)
+        {
+            ym2612.tick();
+        }
+        assert!(
+            ym2612.channel_taps().unwrap().iter().all(|tap| tap.len() == MAX_CHANNEL_TAP_SAMPLES)
+        );
+
+        // Taps are not included in save states
+        let config = bincode::config::standard();
+        let bytes = bincode::encode_to_vec(&ym2612, config).unwrap();
+        let (loaded, _): (Ym2612, _) = bincode::decode_from_slice(&bytes, config).unwrap();
+        assert!(!loaded.channel_taps_enabled());
+
+        ym2612.set_channel_taps_enabled(false);
+        assert!(ym2612.take_channel_taps().is_none());
+    }
+
+    #[test]
+    fn timer_a_overflow() {
+        let mut ym2612 = Ym2612::new(false);
+
+        // Interval of 1020 = overflow every 4 samples (4 * 144 ticks)
+        write_register(&mut ym2612, 0x24, 0xFF);
+        write_register(&mut ym2612, 0x25, 0x00);
+
+        // Enable timer A and its overflow flag
+        write_register(&mut ym2612, 0x27, 0x05);
+
+        for _ in 0..4 * 144 - 1 {
+            ym2612.tick();
+        }
+        assert_eq!(ym2612.read_register() & 0x01, 0x00);
+
+        ym2612.tick();
+        assert_eq!(ym2612.read_register() & 0x01, 0x01);
+
+        // Reset the overflow flag while leaving the timer running
+        write_register(&mut ym2612, 0x27, 0x15);
+        assert_eq!(ym2612.read_register() & 0x01, 0x00);
+
+        for _ in 0..4 * 144 {
+            ym2612.tick();
+        }
+        assert_eq!(ym2612.read_register() & 0x01, 0x01);
+    }
+
+    #[test]
+    fn dac_pcm_ramp() {
+        let mut ym2612 = Ym2612::new(false);
+        write_register(&mut ym2612, 0x2B, 0x80);
+
+        let mut prev_sample = f64::NEG_INFINITY;
+        for pcm_sample in (0x00..=0xFF).step_by(0x10) {
+            write_register(&mut ym2612, 0x2A, pcm_sample);
+
+            let (sample_l, sample_r) = ym2612.sample();
+            assert_approx_eq(sample_l, sample_r);
+            assert!(sample_l > prev_sample);
+            prev_sample = sample_l;
+
+            // Channel 6 outputs the 8-bit PCM sample in place of FM synthesis
+            let expected = f64::from((i16::from(pcm_sample) - 128) << 6) / 49152.0;
+            assert_approx_eq(sample_l, expected);
+        }
+
+        // Disabling the DAC switches channel 6 back to FM output, which is silent here
+        write_register(&mut ym2612, 0x2B, 0x00);
+        let (sample_l, sample_r) = ym2612.sample();
+        assert_approx_eq(sample_l, 0.0);
+        assert_approx_eq(sample_r, 0.0);
+    }
+
+    #[test]
+    fn dac_stereo_output() {
+        let mut ym2612 = Ym2612::new(false);
+
+        // Enable DAC and output a max value sample
+        write_register(&mut ym2612, 0x2B, 0x80);
+        write_register(&mut ym2612, 0x2A, 0xFF);
+
+        let (sample_l, sample_r) = ym2612.sample();
+        assert!(sample_l > 0.0);
+        assert_approx_eq(sample_l, sample_r);
+
+        // Left output only on channel 6
+        ym2612.write_address_2(0xB6);
+        ym2612.write_data(0x80);
+
+        let (sample_l, sample_r) = ym2612.sample();
+        assert!(sample_l > 0.0);
+        assert_approx_eq(sample_r, 0.0);
+    }
+}
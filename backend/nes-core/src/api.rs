@@ -9,9 +9,13 @@ use crate::ppu::PpuState;
 use crate::{apu, cpu, graphics, ppu};
 use bincode::{Decode, Encode};
 use jgenesis_common::frontend::{
-    AudioOutput, Color, EmulatorTrait, FrameSize, PixelAspectRatio, Renderer, SaveWriter,
-    TickEffect, TickResult, TimingMode,
+    AudioOutput, Color, EmulatorTrait, FrameSize, MemoryRegion, PixelAspectRatio, Renderer,
+    SaveWriter, TickEffect, TickResult, TimingMode,
 };
+#[cfg(any(test, feature = "perf"))]
+use jgenesis_common::frontend::PerfCounters;
+use jgenesis_common::rng::Rng;
+use jgenesis_common::save_state::VersionedState;
 use jgenesis_proc_macros::{EnumDisplay, EnumFromStr, PartialClone};
 use std::fmt::{Debug, Display, Formatter};
 use std::mem;
@@ -21,6 +25,8 @@ pub use graphics::PatternTable;
 use mos6502_emu::bus::BusInterface;
 
 // The number of master clock ticks to run in one `Emulator::tick` call
+#[cfg(any(test, feature = "perf"))]
+const NTSC_MASTER_CLOCK_TICKS: u32 = 12;
 const PAL_MASTER_CLOCK_TICKS: u32 = 80;
 
 const PAL_CPU_DIVIDER: u32 = 16;
@@ -92,6 +98,8 @@ pub struct NesEmulatorConfig {
     /// Some games exhibit severe glitches when opposing joypad directions are pressed
     /// simultaneously, e.g. Zelda 2 and Battletoads
     pub allow_opposing_joypad_inputs: bool,
+    /// Seed for all pseudorandom power-on state; currently only the initial CPU RAM contents
+    pub rng_seed: u64,
 }
 
 #[derive(Debug, Error)]
@@ -123,6 +131,9 @@ pub struct NesEmulator {
     // Kept around to enable hard reset
     #[partial_clone(default)]
     raw_rom_bytes: Vec<u8>,
+    // Always compiled in tests so the counters are exercised without the `perf` feature
+    #[cfg(any(test, feature = "perf"))]
+    perf_counters: PerfCounters,
 }
 
 impl NesEmulator {
@@ -141,7 +152,7 @@ impl NesEmulator {
         let mapper = cartridge::from_ines_file(&rom_bytes, sav_bytes, config.forced_timing_mode)?;
         let timing_mode = mapper.timing_mode();
 
-        let mut bus = Bus::from_cartridge(mapper);
+        let mut bus = Bus::from_cartridge(mapper, &mut Rng::new(config.rng_seed));
 
         let cpu_state = CpuState::new(&mut bus.cpu());
         let ppu_state = PpuState::new(timing_mode);
@@ -158,11 +169,34 @@ impl NesEmulator {
             rgba_frame_buffer: new_rgba_frame_buffer(),
             audio_resampler: AudioResampler::new(timing_mode, config.audio_refresh_rate_adjustment),
             raw_rom_bytes: rom_bytes,
+            #[cfg(any(test, feature = "perf"))]
+            perf_counters: PerfCounters::default(),
         })
     }
 
+    /// Return the performance counters accumulated since the last call, and reset them to zero.
+    #[cfg(any(test, feature = "perf"))]
+    pub fn perf_counters(&mut self) -> PerfCounters {
+        mem::take(&mut self.perf_counters)
+    }
+
+    fn tick_cpu(&mut self) {
+        cpu::tick(
+            &mut self.cpu_state,
+            &mut self.bus.cpu(),
+            self.apu_state.is_active_cycle(),
+            |_| {
+                // Only called for instructions that actually execute, not for interrupts or DMA
+                #[cfg(any(test, feature = "perf"))]
+                {
+                    self.perf_counters.instructions += 1;
+                }
+            },
+        );
+    }
+
     fn ntsc_tick(&mut self) {
-        cpu::tick(&mut self.cpu_state, &mut self.bus.cpu(), self.apu_state.is_active_cycle());
+        self.tick_cpu();
         apu::tick(&mut self.apu_state, &mut self.bus.cpu(), self.config);
         ppu::tick(&mut self.ppu_state, &mut self.bus.ppu(), self.config);
         self.bus.tick_cpu();
@@ -181,7 +215,7 @@ impl NesEmulator {
 
     fn pal_tick(&mut self) {
         // Both CPU and PPU tick on the first master clock cycle
-        cpu::tick(&mut self.cpu_state, &mut self.bus.cpu(), self.apu_state.is_active_cycle());
+        self.tick_cpu();
         apu::tick(&mut self.apu_state, &mut self.bus.cpu(), self.config);
         ppu::tick(&mut self.ppu_state, &mut self.bus.ppu(), self.config);
         self.bus.tick_cpu();
@@ -193,11 +227,7 @@ impl NesEmulator {
 
         for i in 1..PAL_MASTER_CLOCK_TICKS {
             if i % PAL_CPU_DIVIDER == 0 {
-                cpu::tick(
-                    &mut self.cpu_state,
-                    &mut self.bus.cpu(),
-                    self.apu_state.is_active_cycle(),
-                );
+                self.tick_cpu();
                 apu::tick(&mut self.apu_state, &mut self.bus.cpu(), self.config);
                 self.bus.tick_cpu();
                 self.bus.tick();
@@ -275,6 +305,26 @@ fn new_rgba_frame_buffer() -> Vec<Color> {
     vec![Color::default(); ppu::SCREEN_WIDTH as usize * ppu::MAX_SCREEN_HEIGHT as usize]
 }
 
+impl VersionedState for NesEmulator {
+    const SYSTEM_ID: [u8; 4] = *b"NES ";
+    const STATE_VERSION: u32 = 1;
+}
+
+// Memory region IDs for EmulatorTrait::read_region, as indices into MEMORY_REGIONS
+const CPU_RAM_REGION_ID: usize = 0;
+const PRG_RAM_REGION_ID: usize = 1;
+
+// (name, base address) of each memory region, in region ID order
+const MEMORY_REGIONS: [(&str, u32); 2] = [("CPU RAM", 0x0000), ("PRG RAM", 0x6000)];
+
+fn memory_region_bytes(emulator: &NesEmulator, region_id: usize) -> Option<&[u8]> {
+    match region_id {
+        CPU_RAM_REGION_ID => Some(emulator.bus.cpu_internal_ram()),
+        PRG_RAM_REGION_ID => Some(emulator.bus.mapper().get_prg_ram()),
+        _ => None,
+    }
+}
+
 impl EmulatorTrait for NesEmulator {
     type Inputs = NesInputs;
     type Config = NesEmulatorConfig;
@@ -317,6 +367,14 @@ impl EmulatorTrait for NesEmulator {
             TimingMode::Pal => self.pal_tick(),
         }
 
+        #[cfg(any(test, feature = "perf"))]
+        {
+            self.perf_counters.master_clock_cycles += u64::from(match timing_mode {
+                TimingMode::Ntsc => NTSC_MASTER_CLOCK_TICKS,
+                TimingMode::Pal => PAL_MASTER_CLOCK_TICKS,
+            });
+        }
+
         if !prev_in_vblank && self.ppu_state.in_vblank() {
             if self.config.pal_black_border {
                 ppu::render_pal_black_border(&mut self.ppu_state);
@@ -377,6 +435,21 @@ impl EmulatorTrait for NesEmulator {
     fn timing_mode(&self) -> TimingMode {
         self.bus.mapper().timing_mode()
     }
+
+    fn memory_regions(&self) -> Vec<MemoryRegion> {
+        MEMORY_REGIONS
+            .iter()
+            .enumerate()
+            .map(|(region_id, &(name, base_address))| {
+                let len = memory_region_bytes(self, region_id).map_or(0, <[u8]>::len);
+                MemoryRegion::new(name, base_address, len)
+            })
+            .collect()
+    }
+
+    fn read_region(&self, region_id: usize, offset: usize) -> Option<u8> {
+        memory_region_bytes(self, region_id)?.get(offset).copied()
+    }
 }
 
 fn init_apu(apu_state: &mut ApuState, bus: &mut Bus, config: NesEmulatorConfig) {
@@ -390,3 +463,63 @@ fn init_apu(apu_state: &mut ApuState, bus: &mut Bus, config: NesEmulatorConfig)
         bus.tick();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jgenesis_common::testing::{NullAudioOutput, NullRenderer, NullSaveWriter};
+
+    fn new_emulator() -> NesEmulator {
+        // iNES header for NROM with one 16KB PRG ROM bank and one 8KB CHR ROM bank
+        let mut rom = b"NES\x1A\x01\x01".to_vec();
+        rom.resize(16, 0);
+
+        // JMP $8000 (infinite loop), with the RESET vector pointing at it
+        let mut prg_rom = vec![0; 16 * 1024];
+        prg_rom[0..3].copy_from_slice(&[0x4C, 0x00, 0x80]);
+        prg_rom[0x3FFC..0x3FFE].copy_from_slice(&[0x00, 0x80]);
+        rom.extend(prg_rom);
+        rom.extend(vec![0; 8 * 1024]);
+
+        let config = NesEmulatorConfig {
+            forced_timing_mode: None,
+            aspect_ratio: NesAspectRatio::Ntsc,
+            overscan: Overscan::NONE,
+            remove_sprite_limit: false,
+            pal_black_border: false,
+            silence_ultrasonic_triangle_output: false,
+            audio_refresh_rate_adjustment: false,
+            allow_opposing_joypad_inputs: false,
+            rng_seed: 0,
+        };
+        NesEmulator::create(rom, config, &mut NullSaveWriter).unwrap()
+    }
+
+    #[test]
+    fn perf_counters() {
+        let mut emulator = new_emulator();
+        emulator
+            .run_frames(
+                1,
+                &mut NullRenderer,
+                &mut NullAudioOutput,
+                &NesInputs::default(),
+                &mut NullSaveWriter,
+            )
+            .unwrap();
+
+        // Counters must not leak into save states
+        let config = bincode::config::standard();
+        let state = bincode::encode_to_vec(&emulator, config).unwrap();
+
+        let counters = emulator.perf_counters();
+        assert_eq!(state, bincode::encode_to_vec(&emulator, config).unwrap());
+
+        // JMP absolute takes 3 CPU cycles, and each CPU cycle is 12 master clock cycles
+        let cpu_cycles = counters.master_clock_cycles / u64::from(NTSC_MASTER_CLOCK_TICKS);
+        assert_ne!(cpu_cycles, 0);
+        assert!(counters.instructions.abs_diff(cpu_cycles / 3) <= 1);
+
+        assert_eq!(emulator.perf_counters(), PerfCounters::default());
+    }
+}
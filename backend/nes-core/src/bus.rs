@@ -40,6 +40,7 @@ use crate::input::{LatchedJoypadState, NesJoypadState};
 use bincode::{Decode, Encode};
 use jgenesis_common::frontend::TimingMode;
 use jgenesis_common::num::GetBit;
+use jgenesis_common::rng::Rng;
 use jgenesis_proc_macros::PartialClone;
 use mos6502_emu::bus::BusInterface;
 use std::array;
@@ -605,11 +606,11 @@ pub struct Bus {
 }
 
 impl Bus {
-    pub(crate) fn from_cartridge(mapper: Mapper) -> Self {
+    pub(crate) fn from_cartridge(mapper: Mapper, rng: &mut Rng) -> Self {
         Self {
             mapper,
             // (Somewhat) randomize initial RAM contents
-            cpu_internal_ram: array::from_fn(|_| if rand::random() { 0x00 } else { 0xFF }),
+            cpu_internal_ram: array::from_fn(|_| if rng.next_bool() { 0x00 } else { 0xFF }),
             ppu_registers: PpuRegisters::new(),
             io_registers: IoRegisters::new(),
             ppu_vram: [0; 2048],
@@ -678,6 +679,10 @@ impl Bus {
         &self.mapper
     }
 
+    pub(crate) fn cpu_internal_ram(&self) -> &[u8] {
+        &self.cpu_internal_ram
+    }
+
     pub(crate) fn mapper_mut(&mut self) -> &mut Mapper {
         &mut self.mapper
     }
@@ -976,14 +981,17 @@ fn map_palette_address(address: u16) -> usize {
 #[cfg(test)]
 mod tests {
     use crate::bus::{cartridge, Bus};
+    use jgenesis_common::rng::Rng;
 
     #[test]
     fn randomized_ram_on_startup() {
         let mapper = cartridge::new_mmc1(vec![0; 32768]);
-        let bus1 = Bus::from_cartridge(mapper.clone());
-        let bus2 = Bus::from_cartridge(mapper);
+        let bus1 = Bus::from_cartridge(mapper.clone(), &mut Rng::new(1));
+        let bus2 = Bus::from_cartridge(mapper.clone(), &mut Rng::new(2));
+        let bus3 = Bus::from_cartridge(mapper, &mut Rng::new(1));
 
         assert_ne!(bus1.cpu_internal_ram, bus2.cpu_internal_ram);
+        assert_eq!(bus1.cpu_internal_ram, bus3.cpu_internal_ram);
     }
 }
 
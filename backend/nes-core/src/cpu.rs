@@ -39,8 +39,14 @@ impl CpuState {
     }
 }
 
-/// Run the CPU for 1 CPU cycle.
-pub fn tick(state: &mut CpuState, bus: &mut CpuBus<'_>, is_apu_active_cycle: bool) {
+/// Run the CPU for 1 CPU cycle, calling `trace_hook` with the opcode's PC if this cycle starts a
+/// new instruction.
+pub fn tick<F: FnMut(u16)>(
+    state: &mut CpuState,
+    bus: &mut CpuBus<'_>,
+    is_apu_active_cycle: bool,
+    trace_hook: F,
+) {
     if state.mos6502.frozen() {
         return;
     }
@@ -64,7 +70,7 @@ pub fn tick(state: &mut CpuState, bus: &mut CpuBus<'_>, is_apu_active_cycle: boo
                     State::OamDma(oam_dma_state)
                 }
             } else {
-                state.mos6502.tick(bus);
+                state.mos6502.tick_traced(bus, trace_hook);
                 State::CpuExecuting
             }
         }
@@ -13,13 +13,19 @@ use genesis_core::input::InputState;
 use genesis_core::memory::{MainBus, MainBusSignals, MainBusWrites, Memory};
 use genesis_core::vdp::{Vdp, VdpTickEffect};
 use genesis_core::ym2612::{Ym2612, YmTickEffect};
-use genesis_core::{GenesisAspectRatio, GenesisEmulatorConfig, GenesisInputs, GenesisRegion};
+use genesis_core::{
+    GenesisAspectRatio, GenesisEmulatorConfig, GenesisInputs, GenesisRegion, RenderOptions,
+};
 use jgenesis_common::frontend::{
-    AudioOutput, Color, EmulatorTrait, PartialClone, Renderer, SaveWriter, TickEffect, TimingMode,
+    AudioOutput, Color, EmulatorTrait, MemoryRegion, PartialClone, Renderer, SaveWriter,
+    TickEffect, TimingMode,
 };
-use m68000_emu::M68000;
+use jgenesis_common::rng::Rng;
+use jgenesis_common::save_state::VersionedState;
+use m68000_emu::{BreakpointHit, Breakpoints, M68000};
 use smsgg_core::psg::{Psg, PsgTickEffect, PsgVersion};
 use std::fmt::{Debug, Display};
+use std::mem;
 use std::path::Path;
 use thiserror::Error;
 use z80_emu::Z80;
@@ -85,12 +91,16 @@ pub struct SegaCdEmulator {
     main_bus_writes: MainBusWrites,
     aspect_ratio: GenesisAspectRatio,
     adjust_aspect_ratio_in_2x_resolution: bool,
+    render_options: RenderOptions,
     disc_title: String,
     genesis_mclk_cycles: u64,
     sega_cd_mclk_cycles: u64,
     sega_cd_mclk_cycle_product: u64,
     sub_cpu_wait_cycles: u64,
+    // Sub CPU cycles left over after the sub CPU stopped at a breakpoint
+    sub_cpu_breakpoint_cycles: u64,
     load_disc_into_ram: bool,
+    rng_seed: u64,
 }
 
 // This is a macro instead of a function so that it only mutably borrows the needed fields
@@ -103,7 +113,11 @@ macro_rules! new_main_bus {
             &mut $self.ym2612,
             &mut $self.input,
             $self.timing_mode,
-            MainBusSignals { z80_busack: $self.z80.stalled(), m68k_reset: $m68k_reset },
+            MainBusSignals {
+                z80_busack: $self.z80.stalled(),
+                m68k_reset: $m68k_reset,
+                expansion_connected: true,
+            },
             std::mem::take(&mut $self.main_bus_writes),
         )
     };
@@ -180,7 +194,13 @@ impl SegaCdEmulator {
         )?;
         let disc_title = sega_cd.disc_title()?.unwrap_or("(no disc)".into());
 
-        let memory = Memory::new(sega_cd);
+        let mut memory = Memory::new(sega_cd);
+        memory.set_forced_console_region(emulator_config.genesis.console_region);
+        memory.set_tmss_enabled(emulator_config.genesis.tmss_enabled);
+        memory.init_ram(
+            emulator_config.genesis.ram_init_pattern,
+            &mut Rng::new(emulator_config.genesis.rng_seed),
+        );
         let timing_mode =
             emulator_config.genesis.forced_timing_mode.unwrap_or_else(|| {
                 match memory.hardware_region() {
@@ -201,7 +221,9 @@ impl SegaCdEmulator {
         let pcm = Rf5c164::new();
         let input = InputState::new();
 
-        let audio_resampler = AudioResampler::new(timing_mode);
+        let mut audio_resampler =
+            AudioResampler::new(timing_mode, emulator_config.genesis.dc_blocking_filter);
+        audio_resampler.set_stereo_mix(emulator_config.genesis.to_stereo_mix());
         let mut emulator = Self {
             memory,
             main_cpu,
@@ -220,12 +242,15 @@ impl SegaCdEmulator {
             adjust_aspect_ratio_in_2x_resolution: emulator_config
                 .genesis
                 .adjust_aspect_ratio_in_2x_resolution,
+            render_options: RenderOptions::new(emulator_config.genesis),
             disc_title,
             genesis_mclk_cycles: 0,
             sega_cd_mclk_cycles: 0,
             sega_cd_mclk_cycle_product: 0,
             sub_cpu_wait_cycles: 0,
+            sub_cpu_breakpoint_cycles: 0,
             load_disc_into_ram: emulator_config.load_disc_into_ram,
+            rng_seed: emulator_config.genesis.rng_seed,
         };
 
         // Reset main CPU so that execution starts from the right place
@@ -235,27 +260,63 @@ impl SegaCdEmulator {
     }
 
     #[inline]
-    fn tick_sub_cpu(&mut self, mut sub_cpu_cycles: u64) {
+    // Returns whether the sub CPU stopped at a breakpoint
+    fn tick_sub_cpu(&mut self, mut sub_cpu_cycles: u64) -> bool {
+        sub_cpu_cycles += mem::take(&mut self.sub_cpu_breakpoint_cycles);
+
         while sub_cpu_cycles >= self.sub_cpu_wait_cycles {
             let wait_cycles = self.sub_cpu_wait_cycles;
             let mut bus =
                 SubBus::new(&mut self.memory, &mut self.graphics_coprocessor, &mut self.pcm);
             self.sub_cpu_wait_cycles = self.sub_cpu.execute_instruction(&mut bus).into();
             sub_cpu_cycles -= wait_cycles;
+
+            if self.sub_cpu.breakpoint_hit().is_some() {
+                // Stop at the breakpoint and run the remaining cycles on the next tick
+                self.sub_cpu_breakpoint_cycles = sub_cpu_cycles;
+                return true;
+            }
         }
 
         self.sub_cpu_wait_cycles -= sub_cpu_cycles;
+        false
     }
 
-    fn render_frame<R: Renderer>(&self, renderer: &mut R) -> Result<(), R::Err> {
+    fn render_frame<R: Renderer>(&mut self, renderer: &mut R) -> Result<(), R::Err> {
         genesis_core::render_frame(
             &self.vdp,
             self.aspect_ratio,
             self.adjust_aspect_ratio_in_2x_resolution,
+            &mut self.render_options,
             renderer,
         )
     }
 
+    /// Main 68000 execute and data breakpoints. When either 68000 stops at a breakpoint,
+    /// [`EmulatorTrait::tick`] returns [`TickEffect::BreakpointHit`].
+    ///
+    /// Breakpoints are not included in save states; they are kept when a state is loaded.
+    pub fn main_cpu_breakpoints_mut(&mut self) -> &mut Breakpoints {
+        self.main_cpu.breakpoints_mut()
+    }
+
+    /// Sub 68000 execute and data breakpoints. See [`Self::main_cpu_breakpoints_mut`].
+    pub fn sub_cpu_breakpoints_mut(&mut self) -> &mut Breakpoints {
+        self.sub_cpu.breakpoints_mut()
+    }
+
+    /// The breakpoint hit by the main 68000's most recent instruction, if any.
+    #[must_use]
+    pub fn main_cpu_breakpoint_hit(&self) -> Option<BreakpointHit> {
+        self.main_cpu.breakpoint_hit()
+    }
+
+    /// The breakpoint hit by the sub 68000's most recent instruction, if any.
+    #[must_use]
+    pub fn sub_cpu_breakpoint_hit(&self) -> Option<BreakpointHit> {
+        self.sub_cpu.breakpoint_hit()
+    }
+
     #[must_use]
     pub fn disc_title(&self) -> &str {
         &self.disc_title
@@ -290,6 +351,38 @@ impl SegaCdEmulator {
     }
 }
 
+impl VersionedState for SegaCdEmulator {
+    const SYSTEM_ID: [u8; 4] = *b"SGCD";
+    const STATE_VERSION: u32 = 1;
+}
+
+// Memory region IDs for EmulatorTrait::read_region, as indices into MEMORY_REGIONS
+const MAIN_RAM_REGION_ID: usize = 0;
+const AUDIO_RAM_REGION_ID: usize = 1;
+const PRG_RAM_REGION_ID: usize = 2;
+const BACKUP_RAM_REGION_ID: usize = 3;
+const RAM_CARTRIDGE_REGION_ID: usize = 4;
+
+// (name, base address) of each memory region, in region ID order
+const MEMORY_REGIONS: [(&str, u32); 5] = [
+    ("Main RAM", 0xFF0000),
+    ("Audio RAM", 0xA00000),
+    ("PRG RAM", 0x000000),
+    ("Backup RAM", 0xFE0000),
+    ("RAM Cartridge", 0x600000),
+];
+
+fn memory_region_bytes(emulator: &SegaCdEmulator, region_id: usize) -> Option<&[u8]> {
+    match region_id {
+        MAIN_RAM_REGION_ID => Some(emulator.memory.main_ram()),
+        AUDIO_RAM_REGION_ID => Some(emulator.memory.audio_ram()),
+        PRG_RAM_REGION_ID => Some(emulator.memory.medium().prg_ram()),
+        BACKUP_RAM_REGION_ID => Some(emulator.memory.medium().backup_ram()),
+        RAM_CARTRIDGE_REGION_ID => Some(emulator.memory.medium().ram_cartridge()),
+        _ => None,
+    }
+}
+
 impl EmulatorTrait for SegaCdEmulator {
     type Inputs = GenesisInputs;
     type Config = SegaCdEmulatorConfig;
@@ -319,6 +412,7 @@ impl EmulatorTrait for SegaCdEmulator {
 
         // Main 68000
         let main_cpu_cycles = self.main_cpu.execute_instruction(&mut main_bus);
+        let main_cpu_breakpoint_hit = self.main_cpu.breakpoint_hit().is_some();
 
         let genesis_mclk_elapsed = u64::from(main_cpu_cycles) * MAIN_CPU_DIVIDER;
         let z80_cycles = (self.genesis_mclk_cycles + genesis_mclk_elapsed) / Z80_DIVIDER
@@ -326,11 +420,14 @@ impl EmulatorTrait for SegaCdEmulator {
         self.genesis_mclk_cycles += genesis_mclk_elapsed;
 
         // Z80
-        for _ in 0..z80_cycles {
-            self.z80.tick(&mut main_bus);
+        if !main_bus.z80_halted() {
+            for _ in 0..z80_cycles {
+                self.z80.tick(&mut main_bus);
+            }
         }
 
         self.main_bus_writes = main_bus.take_writes();
+        self.memory.tick_z80_signals(genesis_mclk_elapsed);
 
         self.sega_cd_mclk_cycle_product += genesis_mclk_elapsed * SEGA_CD_MASTER_CLOCK_RATE;
         let scd_mclk_elapsed = match self.timing_mode {
@@ -370,7 +467,7 @@ impl EmulatorTrait for SegaCdEmulator {
         );
 
         // Sub 68000
-        self.tick_sub_cpu(sub_cpu_cycles);
+        let sub_cpu_breakpoint_hit = self.tick_sub_cpu(sub_cpu_cycles);
 
         // Apply main CPU writes after ticking the sub CPU; this fixes random freezing in Silpheed
         self.main_bus_writes = new_main_bus!(self, m68k_reset: false).apply_writes();
@@ -424,6 +521,10 @@ impl EmulatorTrait for SegaCdEmulator {
             return Ok(TickEffect::FrameRendered);
         }
 
+        if main_cpu_breakpoint_hit || sub_cpu_breakpoint_hit {
+            return Ok(TickEffect::BreakpointHit);
+        }
+
         Ok(TickEffect::None)
     }
 
@@ -438,17 +539,25 @@ impl EmulatorTrait for SegaCdEmulator {
         self.aspect_ratio = config.genesis.aspect_ratio;
         self.adjust_aspect_ratio_in_2x_resolution =
             config.genesis.adjust_aspect_ratio_in_2x_resolution;
+        self.render_options.reload_config(config.genesis);
         self.vdp.reload_config(config.genesis.to_vdp_config());
         self.ym2612.set_quantize_output(config.genesis.quantize_ym2612_output);
+        self.audio_resampler.set_dc_blocking_enabled(config.genesis.dc_blocking_filter);
+        self.audio_resampler.set_stereo_mix(config.genesis.to_stereo_mix());
         self.input.reload_config(config.genesis);
 
         let sega_cd = self.memory.medium_mut();
         sega_cd.set_forced_region(config.genesis.forced_region);
         sega_cd.set_enable_ram_cartridge(config.enable_ram_cartridge);
+
+        self.memory.set_forced_console_region(config.genesis.console_region);
+        self.memory.set_tmss_enabled(config.genesis.tmss_enabled);
     }
 
     fn take_rom_from(&mut self, other: &mut Self) {
         self.memory.medium_mut().take_rom_from(other.memory.medium_mut());
+        self.main_cpu.take_breakpoints_from(&mut other.main_cpu);
+        self.sub_cpu.take_breakpoints_from(&mut other.sub_cpu);
     }
 
     fn soft_reset(&mut self) {
@@ -467,24 +576,42 @@ impl EmulatorTrait for SegaCdEmulator {
         let bios = Vec::from(sega_cd.bios());
         let disc = sega_cd.take_cdrom();
         let forced_region = sega_cd.forced_region();
+        let console_region = self.memory.forced_console_region();
+        let tmss_enabled = self.memory.tmss_enabled();
+        let ram_init_pattern = self.memory.ram_init_pattern();
         let enable_ram_cartridge = sega_cd.get_enable_ram_cartridge();
         let vdp_config = self.vdp.config();
         let (p1_controller_type, p2_controller_type) = self.input.controller_types();
+        let stereo_mix = self.audio_resampler.stereo_mix();
 
-        *self = Self::create_from_disc(
+        let mut emulator = Self::create_from_disc(
             bios,
             disc,
             SegaCdEmulatorConfig {
                 genesis: GenesisEmulatorConfig {
                     forced_timing_mode: Some(self.timing_mode),
                     forced_region,
+                    console_region,
+                    tmss_enabled,
+                    ram_init_pattern,
+                    rng_seed: self.rng_seed,
                     aspect_ratio: self.aspect_ratio,
                     adjust_aspect_ratio_in_2x_resolution: self.adjust_aspect_ratio_in_2x_resolution,
+                    deinterlacing_mode: self.render_options.deinterlacing_mode(),
                     remove_sprite_limits: !vdp_config.enforce_sprite_limits,
                     emulate_non_linear_vdp_dac: vdp_config.emulate_non_linear_dac,
                     render_vertical_border: vdp_config.render_vertical_border,
                     render_horizontal_border: vdp_config.render_horizontal_border,
+                    ntsc_composite_filter: self.render_options.ntsc_composite_filter(),
+                    overscan_border_width: self.render_options.overscan_border_width(),
+                    plane_a_enabled: vdp_config.plane_a_enabled,
+                    plane_b_enabled: vdp_config.plane_b_enabled,
+                    window_enabled: vdp_config.window_enabled,
+                    sprites_enabled: vdp_config.sprites_enabled,
                     quantize_ym2612_output: self.ym2612.get_quantize_output(),
+                    dc_blocking_filter: self.audio_resampler.dc_blocking_enabled(),
+                    psg_pan: stereo_mix.psg_pan,
+                    mono_audio: stereo_mix.mono,
                     p1_controller_type,
                     p2_controller_type,
                 },
@@ -494,9 +621,31 @@ impl EmulatorTrait for SegaCdEmulator {
             save_writer,
         )
         .expect("Hard reset should not cause an I/O error");
+
+        // Breakpoints belong to the debugger session rather than the console, so move them into
+        // the rebuilt CPUs
+        emulator.main_cpu.take_breakpoints_from(&mut self.main_cpu);
+        emulator.sub_cpu.take_breakpoints_from(&mut self.sub_cpu);
+
+        *self = emulator;
     }
 
     fn timing_mode(&self) -> TimingMode {
         self.timing_mode
     }
+
+    fn memory_regions(&self) -> Vec<MemoryRegion> {
+        MEMORY_REGIONS
+            .iter()
+            .enumerate()
+            .map(|(region_id, &(name, base_address))| {
+                let len = memory_region_bytes(self, region_id).map_or(0, <[u8]>::len);
+                MemoryRegion::new(name, base_address, len)
+            })
+            .collect()
+    }
+
+    fn read_region(&self, region_id: usize, offset: usize) -> Option<u8> {
+        memory_region_bytes(self, region_id)?.get(offset).copied()
+    }
 }
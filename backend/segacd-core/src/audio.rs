@@ -5,7 +5,7 @@
 #![allow(clippy::excessive_precision)]
 
 use bincode::{Decode, Encode};
-use genesis_core::audio::Ym2612Resampler;
+use genesis_core::audio::{DcBlockingFilter, StereoMix, Ym2612Resampler};
 use jgenesis_common::audio::SignalResampler;
 use jgenesis_common::frontend::{AudioOutput, TimingMode};
 use smsgg_core::audio::PsgResampler;
@@ -109,10 +109,13 @@ pub struct AudioResampler {
     psg_resampler: PsgResampler,
     pcm_resampler: PcmResampler,
     cd_resampler: CdResampler,
+    dc_blocking_filter: DcBlockingFilter,
+    dc_blocking_enabled: bool,
+    stereo_mix: StereoMix,
 }
 
 impl AudioResampler {
-    pub fn new(timing_mode: TimingMode) -> Self {
+    pub fn new(timing_mode: TimingMode, dc_blocking_enabled: bool) -> Self {
         let genesis_mclk_frequency = match timing_mode {
             TimingMode::Ntsc => NTSC_GENESIS_MCLK_FREQUENCY,
             TimingMode::Pal => PAL_GENESIS_MCLK_FREQUENCY,
@@ -123,7 +126,33 @@ impl AudioResampler {
         let pcm_resampler = new_pcm_resampler();
         let cd_resampler = new_cd_resampler();
 
-        Self { ym2612_resampler, psg_resampler, pcm_resampler, cd_resampler }
+        let dc_blocking_filter = DcBlockingFilter::new(ym2612_resampler.output_frequency());
+
+        Self {
+            ym2612_resampler,
+            psg_resampler,
+            pcm_resampler,
+            cd_resampler,
+            dc_blocking_filter,
+            dc_blocking_enabled,
+            stereo_mix: StereoMix::default(),
+        }
+    }
+
+    pub fn dc_blocking_enabled(&self) -> bool {
+        self.dc_blocking_enabled
+    }
+
+    pub fn set_dc_blocking_enabled(&mut self, dc_blocking_enabled: bool) {
+        self.dc_blocking_enabled = dc_blocking_enabled;
+    }
+
+    pub fn stereo_mix(&self) -> StereoMix {
+        self.stereo_mix
+    }
+
+    pub fn set_stereo_mix(&mut self, stereo_mix: StereoMix) {
+        self.stereo_mix = stereo_mix;
     }
 
     pub fn collect_ym2612_sample(&mut self, sample_l: f64, sample_r: f64) {
@@ -158,17 +187,26 @@ impl AudioResampler {
             let (psg_l, psg_r) = self.psg_resampler.output_buffer_pop_front().unwrap();
             let (pcm_l, pcm_r) = self.pcm_resampler.output_buffer_pop_front().unwrap();
             let (cd_l, cd_r) = self.cd_resampler.output_buffer_pop_front().unwrap();
-
-            let sample_l = (ym2612_l
-                + PSG_COEFFICIENT * psg_l
-                + PCM_COEFFICIENT * pcm_l
-                + CD_COEFFICIENT * cd_l)
-                .clamp(-1.0, 1.0);
-            let sample_r = (ym2612_r
-                + PSG_COEFFICIENT * psg_r
-                + PCM_COEFFICIENT * pcm_r
-                + CD_COEFFICIENT * cd_r)
-                .clamp(-1.0, 1.0);
+            let (psg_l, psg_r) = self.stereo_mix.pan_psg(psg_l, psg_r);
+
+            let (mut sample_l, mut sample_r) = self.stereo_mix.downmix(
+                ym2612_l
+                    + PSG_COEFFICIENT * psg_l
+                    + PCM_COEFFICIENT * pcm_l
+                    + CD_COEFFICIENT * cd_l,
+                ym2612_r
+                    + PSG_COEFFICIENT * psg_r
+                    + PCM_COEFFICIENT * pcm_r
+                    + CD_COEFFICIENT * cd_r,
+            );
+
+            let (filtered_l, filtered_r) = self.dc_blocking_filter.filter(sample_l, sample_r);
+            if self.dc_blocking_enabled {
+                (sample_l, sample_r) = (filtered_l, filtered_r);
+            }
+
+            let sample_l = sample_l.clamp(-1.0, 1.0);
+            let sample_r = sample_r.clamp(-1.0, 1.0);
 
             audio_output.push_sample(sample_l, sample_r)?;
         }
@@ -518,6 +518,10 @@ impl SegaCd {
         self.bios.0.as_slice()
     }
 
+    pub fn prg_ram(&self) -> &[u8] {
+        self.prg_ram.as_slice()
+    }
+
     pub fn backup_ram(&self) -> &[u8] {
         self.backup_ram.as_slice()
     }
@@ -3,22 +3,34 @@
 use crate::audio::AudioResampler;
 use crate::bus::Bus;
 use crate::input::InputState;
+use crate::link::LinkPort;
 use crate::memory::Memory;
 use crate::psg::{Psg, PsgTickEffect, PsgVersion};
-use crate::vdp::{Vdp, VdpBuffer, VdpTickEffect};
+use crate::vdp::{Vdp, VdpBuffer, VdpLayers, VdpStatusFlags, VdpTickEffect};
 use crate::ym2413::Ym2413;
 use crate::{vdp, SmsGgInputs, VdpVersion};
 use bincode::{Decode, Encode};
 use jgenesis_common::frontend::{
-    AudioOutput, Color, EmulatorTrait, FrameSize, PartialClone, PixelAspectRatio, Renderer,
-    SaveWriter, TickEffect, TimingMode,
+    frame_buffer_to_rgb8, AudioOutput, Color, EmulatorTrait, FrameSize, MemoryRegion,
+    OverscanBuffer, PartialClone, PixelAspectRatio, Renderer, SaveWriter, TickEffect, TimingMode,
 };
+#[cfg(any(test, feature = "perf"))]
+use jgenesis_common::frontend::PerfCounters;
+use jgenesis_common::movie::MoviePlayback;
+use jgenesis_common::ram::RamInitPattern;
+use jgenesis_common::rng::Rng;
+use jgenesis_common::save_state::VersionedState;
 use jgenesis_proc_macros::{EnumDisplay, EnumFromStr, FakeDecode, FakeEncode};
 use std::fmt::{Debug, Display};
+use std::mem;
 use std::ops::{Deref, DerefMut};
 use thiserror::Error;
 use z80_emu::{InterruptMode, Z80};
 
+// The Z80 is clocked at mclk/15
+#[cfg(any(test, feature = "perf"))]
+const Z80_MCLK_DIVIDER: u64 = 15;
+
 #[derive(Debug, Error)]
 pub enum SmsGgError<RErr, AErr, SErr> {
     #[error("Rendering error: {0}")]
@@ -68,19 +80,58 @@ pub enum SmsRegion {
     Domestic,
 }
 
+/// Sound chip that can be muted using [`SmsGgEmulator::set_chip_muted`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChipId {
+    Psg,
+    /// YM2413 FM sound unit
+    Fm,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct SmsGgEmulatorConfig {
     pub vdp_version: VdpVersion,
+    /// Timing mode to use instead of the one implied by the VDP version, if set
+    pub forced_timing_mode: Option<TimingMode>,
     pub psg_version: PsgVersion,
     pub pixel_aspect_ratio: Option<PixelAspectRatio>,
     pub remove_sprite_limit: bool,
+    /// Layer toggles for debugging; disabled layers are rendered as transparent
+    pub background_enabled: bool,
+    pub sprites_enabled: bool,
+    pub left_column_blanking_enabled: bool,
     pub sms_region: SmsRegion,
+    /// Contents of system RAM at power-on
+    pub ram_init_pattern: RamInitPattern,
+    /// Seed for all pseudorandom power-on state. Currently only consumed by
+    /// [`RamInitPattern::Random`]
+    pub rng_seed: u64,
     pub sms_crop_vertical_border: bool,
     pub sms_crop_left_border: bool,
+    /// Width in pixels of an overscan border filled with the backdrop color that is added to every
+    /// edge of the rendered frame, after cropping; 0 disables
+    pub overscan_border_width: u32,
     pub fm_sound_unit_enabled: bool,
+    /// Linear gain applied to PSG output; 1.0 is unchanged
+    pub psg_gain: f64,
+    /// Linear gain applied to FM sound unit output; 1.0 is unchanged
+    pub fm_gain: f64,
+    /// Audio low-pass filter cutoff frequency in Hz, or None to disable low-pass filtering
+    pub psg_lpf_cutoff: Option<u32>,
     pub overclock_z80: bool,
 }
 
+impl SmsGgEmulatorConfig {
+    #[must_use]
+    pub fn to_vdp_layers(self) -> VdpLayers {
+        VdpLayers {
+            background_enabled: self.background_enabled,
+            sprites_enabled: self.sprites_enabled,
+            left_column_blanking_enabled: self.left_column_blanking_enabled,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Encode, Decode, PartialClone)]
 pub struct SmsGgEmulator {
     #[partial_clone(partial)]
@@ -92,15 +143,26 @@ pub struct SmsGgEmulator {
     psg: Psg,
     ym2413: Option<Ym2413>,
     input: InputState,
+    link_port: LinkPort,
     audio_resampler: AudioResampler,
     frame_buffer: FrameBuffer,
     sms_crop_vertical_border: bool,
     sms_crop_left_border: bool,
+    overscan_border_width: u32,
+    overscan_buffer: OverscanBuffer,
+    psg_gain: f64,
+    fm_gain: f64,
+    psg_muted: bool,
+    fm_muted: bool,
+    rng_seed: u64,
     overclock_z80: bool,
     z80_cycles_remainder: u32,
     vdp_cycles_remainder: u32,
     frame_count: u64,
     reset_frames_remaining: u32,
+    // Always compiled in tests so the counters are exercised without the `perf` feature
+    #[cfg(any(test, feature = "perf"))]
+    perf_counters: PerfCounters,
 }
 
 impl SmsGgEmulator {
@@ -112,8 +174,11 @@ impl SmsGgEmulator {
     ) -> Self {
         let cartridge_ram = save_writer.load_bytes("sav").ok();
 
-        let memory = Memory::new(rom, cartridge_ram);
-        let vdp = Vdp::new(config.vdp_version, config.remove_sprite_limit);
+        let mut memory = Memory::new(rom, cartridge_ram);
+        memory.init_ram(config.ram_init_pattern, &mut Rng::new(config.rng_seed));
+        let mut vdp = Vdp::new(config.vdp_version, config.remove_sprite_limit);
+        vdp.set_forced_timing_mode(config.forced_timing_mode);
+        vdp.set_layers(config.to_vdp_layers());
         let psg = Psg::new(config.psg_version);
         let input = InputState::new(config.sms_region);
 
@@ -132,15 +197,25 @@ impl SmsGgEmulator {
             psg,
             ym2413,
             input,
-            audio_resampler: AudioResampler::new(timing_mode),
+            link_port: LinkPort::new(),
+            audio_resampler: AudioResampler::new(timing_mode, config.psg_lpf_cutoff),
             frame_buffer: FrameBuffer::new(),
             sms_crop_vertical_border: config.sms_crop_vertical_border,
             sms_crop_left_border: config.sms_crop_left_border,
+            overscan_border_width: config.overscan_border_width,
+            overscan_buffer: OverscanBuffer::new(),
+            psg_gain: config.psg_gain,
+            fm_gain: config.fm_gain,
+            psg_muted: false,
+            fm_muted: false,
+            rng_seed: config.rng_seed,
             overclock_z80: config.overclock_z80,
             z80_cycles_remainder: 0,
             vdp_cycles_remainder: 0,
             frame_count: 0,
             reset_frames_remaining: 0,
+            #[cfg(any(test, feature = "perf"))]
+            perf_counters: PerfCounters::default(),
         }
     }
 
@@ -155,7 +230,41 @@ impl SmsGgEmulator {
         self.memory.cartridge_has_battery()
     }
 
+    /// Mute or unmute a sound chip. Muted chips keep running but do not contribute to the audio
+    /// output.
+    pub fn set_chip_muted(&mut self, chip: ChipId, muted: bool) {
+        match chip {
+            ChipId::Psg => self.psg_muted = muted,
+            ChipId::Fm => self.fm_muted = muted,
+        }
+    }
+
     fn render_frame<R: Renderer>(&mut self, renderer: &mut R) -> Result<(), R::Err> {
+        let mut frame_buffer = mem::take(&mut self.frame_buffer.0);
+        let frame_size = self.populate_frame_buffer_into(&mut frame_buffer);
+        self.frame_buffer.0 = frame_buffer;
+
+        let backdrop_color = convert_color(self.vdp.backdrop_color(), self.vdp_version);
+        let (frame_buffer, frame_size) = self.overscan_buffer.apply(
+            &self.frame_buffer,
+            frame_size,
+            self.overscan_border_width,
+            backdrop_color,
+        );
+
+        renderer.render_frame(frame_buffer, frame_size, self.pixel_aspect_ratio)
+    }
+
+    /// Snapshot the current frame as packed RGB8 pixels, without going through a [`Renderer`].
+    #[must_use]
+    pub fn frame_rgb8(&self) -> (Vec<u8>, FrameSize) {
+        let mut frame_buffer = vec![Color::default(); vdp::FRAME_BUFFER_LEN];
+        let frame_size = self.populate_frame_buffer_into(&mut frame_buffer);
+        (frame_buffer_to_rgb8(&frame_buffer, frame_size), frame_size)
+    }
+
+    // Convert the VDP's buffer into colors, applying border cropping, and return the frame size
+    fn populate_frame_buffer_into(&self, frame_buffer: &mut [Color]) -> FrameSize {
         let crop_vertical_border =
             self.vdp_version.is_master_system() && self.sms_crop_vertical_border;
         let crop_left_border = self.vdp_version.is_master_system() && self.sms_crop_left_border;
@@ -164,7 +273,7 @@ impl SmsGgEmulator {
             self.vdp_version,
             crop_vertical_border,
             crop_left_border,
-            &mut self.frame_buffer,
+            frame_buffer,
         );
 
         let viewport = self.vdp_version.viewport_size();
@@ -179,8 +288,7 @@ impl SmsGgEmulator {
             viewport.height.into()
         };
 
-        let frame_size = FrameSize { width: frame_width, height: frame_height };
-        renderer.render_frame(&self.frame_buffer, frame_size, self.pixel_aspect_ratio)
+        FrameSize { width: frame_width, height: frame_height }
     }
 
     pub fn copy_cram(&self, out: &mut [Color]) {
@@ -190,6 +298,23 @@ impl SmsGgEmulator {
     pub fn copy_vram(&self, out: &mut [Color], palette: u8, row_len: usize) {
         self.vdp.copy_vram(out, palette, row_len);
     }
+
+    /// Render the full Mode 4 nametable, ignoring scroll values, using each tile's palette and flip
+    /// attributes. Returns the size of the rendered nametable.
+    pub fn debug_nametable(&self, out: &mut [Color]) -> FrameSize {
+        self.vdp.debug_nametable(out)
+    }
+
+    #[must_use]
+    pub fn vdp_status_flags(&self) -> VdpStatusFlags {
+        self.vdp.status_flags()
+    }
+
+    /// Return the performance counters accumulated since the last call, and reset them to zero.
+    #[cfg(any(test, feature = "perf"))]
+    pub fn perf_counters(&mut self) -> PerfCounters {
+        mem::take(&mut self.perf_counters)
+    }
 }
 
 fn init_z80(z80: &mut Z80) {
@@ -198,6 +323,37 @@ fn init_z80(z80: &mut Z80) {
     z80.set_interrupt_mode(InterruptMode::Mode1);
 }
 
+// The FM sound unit output is mono, so it's mixed equally into both channels
+fn mix_audio(
+    (psg_sample_l, psg_sample_r): (f64, f64),
+    ym_sample: f64,
+    psg_gain: f64,
+    fm_gain: f64,
+) -> (f64, f64) {
+    let ym_sample = fm_gain * ym_sample;
+    (psg_gain * psg_sample_l + ym_sample, psg_gain * psg_sample_r + ym_sample)
+}
+
+impl VersionedState for SmsGgEmulator {
+    const SYSTEM_ID: [u8; 4] = *b"SMGG";
+    const STATE_VERSION: u32 = 1;
+}
+
+// Memory region IDs for EmulatorTrait::read_region, as indices into MEMORY_REGIONS
+const SYSTEM_RAM_REGION_ID: usize = 0;
+const CARTRIDGE_RAM_REGION_ID: usize = 1;
+
+// (name, base address) of each memory region, in region ID order
+const MEMORY_REGIONS: [(&str, u32); 2] = [("System RAM", 0xC000), ("Cartridge RAM", 0x8000)];
+
+fn memory_region_bytes(emulator: &SmsGgEmulator, region_id: usize) -> Option<&[u8]> {
+    match region_id {
+        SYSTEM_RAM_REGION_ID => Some(emulator.memory.system_ram()),
+        CARTRIDGE_RAM_REGION_ID => Some(emulator.memory.cartridge_ram()),
+        _ => None,
+    }
+}
+
 impl EmulatorTrait for SmsGgEmulator {
     type Inputs = SmsGgInputs;
     type Config = SmsGgEmulatorConfig;
@@ -228,14 +384,25 @@ impl EmulatorTrait for SmsGgEmulator {
         A: AudioOutput,
         S: SaveWriter,
     {
-        let t_cycles = self.z80.execute_instruction(&mut Bus::new(
-            self.vdp_version,
-            &mut self.memory,
-            &mut self.vdp,
-            &mut self.psg,
-            self.ym2413.as_mut(),
-            &mut self.input,
-        ));
+        let t_cycles = self.z80.execute_instruction_traced(
+            &mut Bus::new(
+                self.vdp_version,
+                &mut self.memory,
+                &mut self.vdp,
+                &mut self.psg,
+                self.ym2413.as_mut(),
+                &mut self.input,
+                &mut self.link_port,
+            ),
+            |_| {
+                // Only called for instructions that actually execute, not for interrupts, HALT,
+                // or bus stalls
+                #[cfg(any(test, feature = "perf"))]
+                {
+                    self.perf_counters.instructions += 1;
+                }
+            },
+        );
         let (t_cycles, remainder) = if self.overclock_z80 {
             // Emulate a Z80 running at 2x speed by only ticking the rest of the components for
             // half as many cycles
@@ -246,21 +413,29 @@ impl EmulatorTrait for SmsGgEmulator {
         };
         self.z80_cycles_remainder = remainder;
 
+        #[cfg(any(test, feature = "perf"))]
+        {
+            self.perf_counters.master_clock_cycles += u64::from(t_cycles) * Z80_MCLK_DIVIDER;
+        }
+
         for _ in 0..t_cycles {
             if let Some(ym2413) = &mut self.ym2413 {
                 ym2413.tick();
             }
             if self.psg.tick() == PsgTickEffect::Clocked {
-                let (psg_sample_l, psg_sample_r) =
-                    if self.memory.psg_enabled() { self.psg.sample() } else { (0.0, 0.0) };
-                let ym_sample = if self.memory.fm_enabled() {
+                let (psg_sample_l, psg_sample_r) = if self.memory.psg_enabled() && !self.psg_muted {
+                    self.psg.sample()
+                } else {
+                    (0.0, 0.0)
+                };
+                let ym_sample = if self.memory.fm_enabled() && !self.fm_muted {
                     self.ym2413.as_ref().map_or(0.0, Ym2413::sample)
                 } else {
                     0.0
                 };
 
-                let sample_l = psg_sample_l + ym_sample;
-                let sample_r = psg_sample_r + ym_sample;
+                let (sample_l, sample_r) =
+                    mix_audio((psg_sample_l, psg_sample_r), ym_sample, self.psg_gain, self.fm_gain);
                 self.audio_resampler.collect_sample(sample_l, sample_r);
             }
         }
@@ -271,6 +446,11 @@ impl EmulatorTrait for SmsGgEmulator {
         let mut frame_rendered = false;
         let vdp_cycles = t_cycles_plus_leftover / 2 * 3;
         for _ in 0..vdp_cycles {
+            let (dot, scanline) = self.vdp.beam_position();
+            if self.input.update_light_phaser(dot, scanline) {
+                self.vdp.latch_h_counter();
+            }
+
             if self.vdp.tick() == VdpTickEffect::FrameComplete {
                 self.render_frame(renderer).map_err(SmsGgError::Render)?;
                 frame_rendered = true;
@@ -278,7 +458,10 @@ impl EmulatorTrait for SmsGgEmulator {
                 self.audio_resampler.output_samples(audio_output).map_err(SmsGgError::Audio)?;
 
                 self.input.set_inputs(*inputs);
-                self.input.set_reset(self.reset_frames_remaining != 0);
+                // Game Gear has no RESET button
+                self.input.set_reset(
+                    self.vdp_version.is_master_system() && self.reset_frames_remaining != 0,
+                );
                 self.reset_frames_remaining = self.reset_frames_remaining.saturating_sub(1);
 
                 self.frame_count += 1;
@@ -307,14 +490,20 @@ impl EmulatorTrait for SmsGgEmulator {
     fn reload_config(&mut self, config: &Self::Config) {
         self.vdp_version = config.vdp_version;
         self.vdp.set_version(config.vdp_version);
+        self.vdp.set_forced_timing_mode(config.forced_timing_mode);
         self.psg.set_version(config.psg_version);
         self.pixel_aspect_ratio = config.pixel_aspect_ratio;
         self.vdp.set_remove_sprite_limit(config.remove_sprite_limit);
+        self.vdp.set_layers(config.to_vdp_layers());
         self.input.set_region(config.sms_region);
         self.sms_crop_vertical_border = config.sms_crop_vertical_border;
         self.sms_crop_left_border = config.sms_crop_left_border;
+        self.overscan_border_width = config.overscan_border_width;
+        self.psg_gain = config.psg_gain;
+        self.fm_gain = config.fm_gain;
         self.overclock_z80 = config.overclock_z80;
         self.audio_resampler.update_timing_mode(self.vdp.timing_mode());
+        self.audio_resampler.update_lpf_cutoff(config.psg_lpf_cutoff);
     }
 
     fn take_rom_from(&mut self, other: &mut Self) {
@@ -333,14 +522,21 @@ impl EmulatorTrait for SmsGgEmulator {
         log::info!("Hard resetting console");
 
         let (rom, ram) = self.memory.take_cartridge_rom_and_ram();
+        let ram_init_pattern = self.memory.ram_init_pattern();
         self.memory = Memory::new(rom, Some(ram));
+        self.memory.init_ram(ram_init_pattern, &mut Rng::new(self.rng_seed));
 
         self.z80 = Z80::new();
         init_z80(&mut self.z80);
 
+        let forced_timing_mode = self.vdp.forced_timing_mode();
+        let layers = self.vdp.layers();
         self.vdp = Vdp::new(self.vdp_version, self.vdp.get_remove_sprite_limit());
+        self.vdp.set_forced_timing_mode(forced_timing_mode);
+        self.vdp.set_layers(layers);
         self.psg = Psg::new(self.psg.version());
         self.input = InputState::new(self.input.region());
+        self.link_port = LinkPort::new();
 
         self.vdp_cycles_remainder = 0;
         self.frame_count = 0;
@@ -349,6 +545,27 @@ impl EmulatorTrait for SmsGgEmulator {
     fn timing_mode(&self) -> TimingMode {
         self.vdp.timing_mode()
     }
+
+    fn memory_regions(&self) -> Vec<MemoryRegion> {
+        MEMORY_REGIONS
+            .iter()
+            .enumerate()
+            .map(|(region_id, &(name, base_address))| {
+                let len = memory_region_bytes(self, region_id).map_or(0, <[u8]>::len);
+                MemoryRegion::new(name, base_address, len)
+            })
+            .collect()
+    }
+
+    fn read_region(&self, region_id: usize, offset: usize) -> Option<u8> {
+        memory_region_bytes(self, region_id)?.get(offset).copied()
+    }
+}
+
+impl MoviePlayback<SmsGgInputs> for SmsGgEmulator {
+    fn prepare_movie_frame(&mut self, frame: &SmsGgInputs) -> SmsGgInputs {
+        *frame
+    }
 }
 
 fn populate_frame_buffer(
@@ -373,21 +590,418 @@ fn populate_frame_buffer(
 
     for (i, row) in vdp_buffer.iter().skip(row_skip).take(row_take).enumerate() {
         for (j, color) in row.iter().copied().skip(col_skip).enumerate() {
-            let (r, g, b) = if vdp_version.is_master_system() {
-                (
-                    vdp::convert_sms_color(color & 0x03),
-                    vdp::convert_sms_color((color >> 2) & 0x03),
-                    vdp::convert_sms_color((color >> 4) & 0x03),
-                )
-            } else {
-                (
-                    vdp::convert_gg_color(color & 0x0F),
-                    vdp::convert_gg_color((color >> 4) & 0x0F),
-                    vdp::convert_gg_color((color >> 8) & 0x0F),
+            frame_buffer[i * screen_width + j] = convert_color(color, vdp_version);
+        }
+    }
+}
+
+fn convert_color(color: u16, vdp_version: VdpVersion) -> Color {
+    if vdp_version.is_master_system() {
+        vdp::sms_color_to_rgb(color)
+    } else {
+        vdp::gg_color_to_rgb(color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::DEFAULT_PSG_LPF_CUTOFF;
+    use jgenesis_common::movie::{Movie, MoviePlayer};
+    use jgenesis_common::num::GetBit;
+    use jgenesis_common::testing::{
+        CapturingAudioOutput, CapturingRenderer, NullAudioOutput, NullRenderer, NullSaveWriter,
+    };
+
+    #[track_caller]
+    fn assert_approx_eq(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "expected {expected}, was {actual}");
+    }
+
+    #[track_caller]
+    fn assert_sample_approx_eq(
+        (sample_l, sample_r): (f64, f64),
+        (expected_l, expected_r): (f64, f64),
+    ) {
+        assert_approx_eq(sample_l, expected_l);
+        assert_approx_eq(sample_r, expected_r);
+    }
+
+    fn is_silent((sample_l, sample_r): (f64, f64)) -> bool {
+        sample_l.abs() < 1e-9 && sample_r.abs() < 1e-9
+    }
+
+    fn new_config(vdp_version: VdpVersion) -> SmsGgEmulatorConfig {
+        SmsGgEmulatorConfig {
+            vdp_version,
+            forced_timing_mode: None,
+            psg_version: PsgVersion::MasterSystem2,
+            pixel_aspect_ratio: None,
+            remove_sprite_limit: false,
+            background_enabled: true,
+            sprites_enabled: true,
+            left_column_blanking_enabled: true,
+            sms_region: SmsRegion::International,
+            ram_init_pattern: RamInitPattern::default(),
+            rng_seed: 0,
+            sms_crop_vertical_border: false,
+            sms_crop_left_border: false,
+            overscan_border_width: 0,
+            fm_sound_unit_enabled: false,
+            psg_gain: 1.0,
+            fm_gain: 1.0,
+            psg_lpf_cutoff: Some(DEFAULT_PSG_LPF_CUTOFF),
+            overclock_z80: false,
+        }
+    }
+
+    fn new_emulator_with_config(config: SmsGgEmulatorConfig) -> SmsGgEmulator {
+        // JR -2 (infinite loop)
+        let mut rom = vec![0; 0x8000];
+        rom[0..2].copy_from_slice(&[0x18, 0xFE]);
+
+        SmsGgEmulator::create(rom, config, &mut NullSaveWriter)
+    }
+
+    fn new_emulator(vdp_version: VdpVersion) -> SmsGgEmulator {
+        new_emulator_with_config(new_config(vdp_version))
+    }
+
+    #[test]
+    fn run_frames() {
+        let mut emulator = new_emulator(VdpVersion::NtscMasterSystem2);
+        assert_eq!(emulator.frame_count, 0);
+
+        emulator
+            .run_frames(
+                2,
+                &mut NullRenderer,
+                &mut NullAudioOutput,
+                &SmsGgInputs::default(),
+                &mut NullSaveWriter,
+            )
+            .unwrap();
+        assert_eq!(emulator.frame_count, 2);
+    }
+
+    #[test]
+    fn movie_playback() {
+        #[rustfmt::skip]
+        let program = [
+            // IN A, ($DC) (controller port A)
+            0xDB, 0xDC,
+            // LD ($C000), A
+            0x32, 0x00, 0xC0,
+            // JR -7
+            0x18, 0xF9,
+        ];
+        let mut rom = vec![0; 0x8000];
+        rom[..program.len()].copy_from_slice(&program);
+        let new_emulator = || {
+            SmsGgEmulator::create(
+                rom.clone(),
+                new_config(VdpVersion::NtscMasterSystem2),
+                &mut NullSaveWriter,
+            )
+        };
+
+        let mut emulator = new_emulator();
+        let mut movie = Movie::new(SmsGgEmulator::SYSTEM_ID, 0, None);
+        let mut recorded_port_values = Vec::new();
+        for i in 0..8 {
+            let mut inputs = SmsGgInputs::default();
+            inputs.p1.up = i % 2 == 0;
+            inputs.p1.button1 = i % 3 == 0;
+            inputs.p2.up = i >= 4;
+
+            movie.record_frame(inputs);
+            emulator
+                .run_frames(
+                    1,
+                    &mut NullRenderer,
+                    &mut NullAudioOutput,
+                    &inputs,
+                    &mut NullSaveWriter,
                 )
+                .unwrap();
+            recorded_port_values.push(emulator.memory.system_ram()[0]);
+        }
+
+        // Inputs are visible to the game
+        assert_ne!(recorded_port_values[1], recorded_port_values[2]);
+
+        let mut bytes = Vec::new();
+        movie.write(&mut bytes).unwrap();
+        let movie: Movie<SmsGgInputs> =
+            Movie::read(&mut bytes.as_slice(), SmsGgEmulator::SYSTEM_ID, 0).unwrap();
+
+        let mut emulator = new_emulator();
+        let mut player = MoviePlayer::new(movie);
+        player.start(&mut emulator, bincode::config::standard()).unwrap();
+
+        let mut replayed_port_values = Vec::new();
+        while player
+            .play_frame(&mut emulator, &mut NullRenderer, &mut NullAudioOutput, &mut NullSaveWriter)
+            .unwrap()
+        {
+            replayed_port_values.push(emulator.memory.system_ram()[0]);
+        }
+        assert_eq!(replayed_port_values, recorded_port_values);
+    }
+
+    #[test]
+    fn reset_button_port() {
+        #[rustfmt::skip]
+        let program = [
+            // IN A, ($DD) (I/O port B/misc.)
+            0xDB, 0xDD,
+            // LD ($C000), A
+            0x32, 0x00, 0xC0,
+            // JR -7
+            0x18, 0xF9,
+        ];
+        let mut rom = vec![0; 0x8000];
+        rom[..program.len()].copy_from_slice(&program);
+
+        // RESET is active low and only exists on the SMS
+        for (vdp_version, expected_reset_bit) in
+            [(VdpVersion::NtscMasterSystem2, false), (VdpVersion::GameGear, true)]
+        {
+            let mut emulator =
+                SmsGgEmulator::create(rom.clone(), new_config(vdp_version), &mut NullSaveWriter);
+            let run_frames = |emulator: &mut SmsGgEmulator, frames: u32| {
+                emulator
+                    .run_frames(
+                        frames,
+                        &mut NullRenderer,
+                        &mut NullAudioOutput,
+                        &SmsGgInputs::default(),
+                        &mut NullSaveWriter,
+                    )
+                    .unwrap();
             };
 
-            frame_buffer[i * screen_width + j] = Color::rgb(r, g, b);
+            run_frames(&mut emulator, 1);
+            assert!(emulator.memory.system_ram()[0].bit(4), "{vdp_version:?}");
+
+            // Reset is latched at the end of a frame, so it is visible starting the frame after
+            emulator.soft_reset();
+            run_frames(&mut emulator, 2);
+            assert_eq!(
+                emulator.memory.system_ram()[0].bit(4),
+                expected_reset_bit,
+                "{vdp_version:?}"
+            );
         }
     }
+
+    #[test]
+    fn forced_timing_mode() {
+        let pal_psg_frequency =
+            AudioResampler::new(TimingMode::Pal, Some(DEFAULT_PSG_LPF_CUTOFF)).psg_frequency();
+        let ntsc_psg_frequency =
+            AudioResampler::new(TimingMode::Ntsc, Some(DEFAULT_PSG_LPF_CUTOFF)).psg_frequency();
+
+        let mut config = new_config(VdpVersion::NtscMasterSystem2);
+        config.forced_timing_mode = Some(TimingMode::Pal);
+        let mut emulator = new_emulator_with_config(config);
+
+        assert_eq!(emulator.timing_mode(), TimingMode::Pal);
+        assert_approx_eq(emulator.audio_resampler.psg_frequency(), pal_psg_frequency);
+
+        config.forced_timing_mode = None;
+        emulator.reload_config(&config);
+
+        assert_eq!(emulator.timing_mode(), TimingMode::Ntsc);
+        assert_approx_eq(emulator.audio_resampler.psg_frequency(), ntsc_psg_frequency);
+    }
+
+    #[test]
+    fn capturing_sinks() {
+        let mut emulator = new_emulator(VdpVersion::GameGear);
+        let mut renderer = CapturingRenderer::new();
+        let mut audio_output = CapturingAudioOutput::new();
+
+        emulator
+            .run_frames(
+                3,
+                &mut renderer,
+                &mut audio_output,
+                &SmsGgInputs::default(),
+                &mut NullSaveWriter,
+            )
+            .unwrap();
+
+        assert_eq!(renderer.frames_rendered(), 3);
+        assert_eq!(renderer.last_frame_size(), Some(FrameSize { width: 160, height: 144 }));
+        assert_eq!(renderer.last_frame().len(), 160 * 144);
+        assert!(!audio_output.samples().is_empty());
+    }
+
+    #[test]
+    fn tick_skip_render() {
+        let mut emulator = new_emulator(VdpVersion::NtscMasterSystem2);
+
+        // PSG square wave on channel 0 at max volume
+        emulator.psg.write(0x8F);
+        emulator.psg.write(0x03);
+        emulator.psg.write(0x90);
+
+        let mut audio_output = CapturingAudioOutput::new();
+        let mut frames_skipped = 0;
+        while frames_skipped < 3 {
+            let tick_effect = emulator
+                .tick_skip_render(&mut audio_output, &SmsGgInputs::default(), &mut NullSaveWriter)
+                .unwrap();
+            assert_ne!(tick_effect, TickEffect::FrameRendered);
+            if tick_effect == TickEffect::FrameSkipped {
+                frames_skipped += 1;
+            }
+        }
+
+        assert_eq!(emulator.frame_count, 3);
+
+        // Roughly 3 frames of audio at 48000 Hz / 60 FPS
+        let samples = audio_output.samples();
+        assert!((2000..2800).contains(&samples.len()), "{}", samples.len());
+        assert!(!samples.iter().copied().all(is_silent));
+    }
+
+    #[test]
+    fn mute_psg() {
+        // The default low-pass filter adds a small constant offset, so disable it to check that
+        // muted output is exactly silent
+        let mut config = new_config(VdpVersion::NtscMasterSystem2);
+        config.psg_lpf_cutoff = None;
+        let mut emulator = new_emulator_with_config(config);
+        emulator.psg.write(0x8F);
+        emulator.psg.write(0x03);
+        emulator.psg.write(0x90);
+        emulator.set_chip_muted(ChipId::Psg, true);
+
+        let mut audio_output = CapturingAudioOutput::new();
+        emulator
+            .run_frames(
+                1,
+                &mut NullRenderer,
+                &mut audio_output,
+                &SmsGgInputs::default(),
+                &mut NullSaveWriter,
+            )
+            .unwrap();
+
+        assert!(!audio_output.samples().is_empty());
+        assert!(audio_output.samples().iter().copied().all(is_silent));
+    }
+
+    #[test]
+    fn frame_rgb8() {
+        let mut emulator = new_emulator(VdpVersion::NtscMasterSystem2);
+        let mut renderer = CapturingRenderer::new();
+
+        emulator
+            .run_frames(
+                1,
+                &mut renderer,
+                &mut NullAudioOutput,
+                &SmsGgInputs::default(),
+                &mut NullSaveWriter,
+            )
+            .unwrap();
+
+        let (rgb, frame_size) = emulator.frame_rgb8();
+        assert_eq!(renderer.last_frame_size(), Some(frame_size));
+        assert_eq!(rgb.len(), (frame_size.width * frame_size.height * 3) as usize);
+
+        let expected: Vec<u8> =
+            renderer.last_frame().iter().flat_map(|color| [color.r, color.g, color.b]).collect();
+        assert_eq!(rgb, expected);
+    }
+
+    #[test]
+    fn overscan_border() {
+        let mut config = new_config(VdpVersion::NtscMasterSystem2);
+        config.overscan_border_width = 8;
+        let mut emulator = new_emulator_with_config(config);
+
+        // CRAM $13 = blue, then backdrop color register = 3
+        for control in [0x13, 0xC0] {
+            emulator.vdp.write_control(control);
+        }
+        emulator.vdp.write_data(0x30);
+        for control in [0x03, 0x87] {
+            emulator.vdp.write_control(control);
+        }
+
+        let mut renderer = CapturingRenderer::new();
+        emulator
+            .run_frames(
+                1,
+                &mut renderer,
+                &mut NullAudioOutput,
+                &SmsGgInputs::default(),
+                &mut NullSaveWriter,
+            )
+            .unwrap();
+
+        let backdrop_color = convert_color(emulator.vdp.backdrop_color(), emulator.vdp_version);
+        assert_eq!(backdrop_color, Color::rgb(0, 0, 255));
+
+        assert_eq!(renderer.last_frame_size(), Some(FrameSize { width: 272, height: 240 }));
+        let frame = renderer.last_frame();
+        for row in 0..240 {
+            for col in (0..8).chain(264..272) {
+                assert_eq!(frame[row * 272 + col], backdrop_color, "row {row} col {col}");
+            }
+        }
+        for row in (0..8).chain(232..240) {
+            assert!(frame[row * 272..(row + 1) * 272].iter().all(|&color| color == backdrop_color));
+        }
+
+        // The original frame is inside the border, unchanged
+        let (rgb, _) = emulator.frame_rgb8();
+        let inner_rgb: Vec<u8> = (8..232)
+            .flat_map(|row| &frame[row * 272 + 8..row * 272 + 264])
+            .flat_map(|color| [color.r, color.g, color.b])
+            .collect();
+        assert_eq!(inner_rgb, rgb);
+    }
+
+    #[test]
+    fn fm_psg_mix() {
+        assert_sample_approx_eq(mix_audio((0.25, -0.125), 0.5, 1.0, 1.0), (0.75, 0.375));
+
+        // Zero FM gain removes the FM contribution
+        assert_sample_approx_eq(mix_audio((0.25, -0.125), 0.5, 1.0, 0.0), (0.25, -0.125));
+
+        assert_sample_approx_eq(mix_audio((0.25, -0.125), 0.5, 2.0, 0.5), (0.75, 0.0));
+    }
+
+    #[test]
+    fn perf_counters() {
+        let mut emulator = new_emulator(VdpVersion::NtscMasterSystem2);
+        emulator
+            .run_frames(
+                1,
+                &mut NullRenderer,
+                &mut NullAudioOutput,
+                &SmsGgInputs::default(),
+                &mut NullSaveWriter,
+            )
+            .unwrap();
+
+        // Counters must not leak into save states
+        let config = bincode::config::standard();
+        let state = bincode::encode_to_vec(&emulator, config).unwrap();
+
+        let counters = emulator.perf_counters();
+        assert_eq!(state, bincode::encode_to_vec(&emulator, config).unwrap());
+
+        // JR takes 12 Z80 cycles
+        let z80_cycles = counters.master_clock_cycles / Z80_MCLK_DIVIDER;
+        assert_ne!(z80_cycles, 0);
+        assert!(counters.instructions.abs_diff(z80_cycles / 12) <= 1);
+
+        assert_eq!(emulator.perf_counters(), PerfCounters::default());
+    }
 }
@@ -5,10 +5,16 @@
 use bincode::{Decode, Encode};
 use jgenesis_common::audio::SignalResampler;
 use jgenesis_common::frontend::{AudioOutput, TimingMode};
+use std::array;
+use std::f64::consts::PI;
 
 const NTSC_MCLK_FREQUENCY: f64 = 53_693_175.0;
 const PAL_MCLK_FREQUENCY: f64 = 53_203_424.0;
 
+// The Z80 and PSG are clocked at mclk/15, and the PSG produces a sample every 16 PSG cycles
+const CPU_CLOCK_DIVIDER: f64 = 15.0;
+const PSG_SAMPLE_DIVIDER: f64 = 16.0;
+
 trait TimingModeExt {
     fn mclk_frequency(self) -> f64;
 }
@@ -22,8 +28,13 @@ impl TimingModeExt for TimingMode {
     }
 }
 
+/// Default PSG low-pass filter cutoff frequency in Hz. The precomputed filter coefficients are
+/// used for this cutoff; other cutoffs use a windowed-sinc filter computed at runtime.
+pub const DEFAULT_PSG_LPF_CUTOFF: u32 = 24000;
+
+pub const PSG_LPF_TAPS: usize = 35;
 const PSG_LPF_COEFFICIENT_0: f64 = -0.001070923693774405;
-const PSG_LPF_COEFFICIENTS: [f64; 35] = [
+const PSG_LPF_COEFFICIENTS: [f64; PSG_LPF_TAPS] = [
     -0.001070923693774405,
     -0.001685350075225726,
     -0.001863413379276681,
@@ -63,7 +74,7 @@ const PSG_LPF_COEFFICIENTS: [f64; 35] = [
 
 const PSG_HPF_CHARGE_FACTOR: f64 = 0.999212882632514;
 
-pub type PsgResampler = SignalResampler<35, 0>;
+pub type PsgResampler = SignalResampler<PSG_LPF_TAPS, 0>;
 
 #[must_use]
 pub fn new_psg_resampler(console_mclk_frequency: f64) -> PsgResampler {
@@ -76,24 +87,98 @@ pub fn new_psg_resampler(console_mclk_frequency: f64) -> PsgResampler {
     )
 }
 
-fn compute_psg_frequency(console_mclk_frequency: f64) -> f64 {
-    console_mclk_frequency / 15.0 / 16.0
+#[must_use]
+pub fn compute_psg_frequency(console_mclk_frequency: f64) -> f64 {
+    console_mclk_frequency / CPU_CLOCK_DIVIDER / PSG_SAMPLE_DIVIDER
+}
+
+/// Low-pass filter coefficients for a [`PsgResampler`] with the given cutoff frequency in Hz, or
+/// None for no filtering.
+#[must_use]
+pub fn psg_lpf_coefficients(psg_frequency: f64, cutoff: Option<u32>) -> (f64, [f64; PSG_LPF_TAPS]) {
+    match cutoff {
+        Some(DEFAULT_PSG_LPF_CUTOFF) => (PSG_LPF_COEFFICIENT_0, PSG_LPF_COEFFICIENTS),
+        Some(cutoff) => {
+            let coefficients = windowed_sinc_lpf(psg_frequency, cutoff.into());
+            (coefficients[0], coefficients)
+        }
+        None => {
+            // Pass the most recent sample through unfiltered
+            let mut coefficients = [0.0; PSG_LPF_TAPS];
+            coefficients[PSG_LPF_TAPS - 1] = 1.0;
+            (0.0, coefficients)
+        }
+    }
+}
+
+/// Hamming-windowed sinc low-pass filter, normalized to unity gain at DC.
+#[must_use]
+pub fn windowed_sinc_lpf<const N: usize>(source_frequency: f64, cutoff: f64) -> [f64; N] {
+    let normalized_cutoff = (2.0 * cutoff / source_frequency).clamp(0.0, 1.0);
+
+    let mut coefficients: [f64; N] = array::from_fn(|n| {
+        let sinc = if 2 * n == N - 1 {
+            1.0
+        } else {
+            let x = PI * normalized_cutoff * (n as f64 - (N - 1) as f64 / 2.0);
+            x.sin() / x
+        };
+        let window = 0.54 - 0.46 * (2.0 * PI * n as f64 / (N - 1) as f64).cos();
+        normalized_cutoff * sinc * window
+    });
+
+    let sum: f64 = coefficients.iter().sum();
+    for coefficient in &mut coefficients {
+        *coefficient /= sum;
+    }
+
+    coefficients
 }
 
 #[derive(Debug, Clone, Encode, Decode)]
 pub(crate) struct AudioResampler {
     psg_resampler: PsgResampler,
+    psg_frequency: f64,
+    lpf_cutoff: Option<u32>,
 }
 
 impl AudioResampler {
-    pub fn new(timing_mode: TimingMode) -> Self {
+    pub fn new(timing_mode: TimingMode, lpf_cutoff: Option<u32>) -> Self {
         let psg_resampler = new_psg_resampler(timing_mode.mclk_frequency());
-        Self { psg_resampler }
+        let psg_frequency = compute_psg_frequency(timing_mode.mclk_frequency());
+
+        let mut resampler = Self { psg_resampler, psg_frequency, lpf_cutoff: None };
+        resampler.update_lpf_cutoff(lpf_cutoff);
+        resampler
     }
 
     pub fn update_timing_mode(&mut self, timing_mode: TimingMode) {
-        let psg_frequency = compute_psg_frequency(timing_mode.mclk_frequency());
-        self.psg_resampler.update_source_frequency(psg_frequency);
+        self.psg_frequency = compute_psg_frequency(timing_mode.mclk_frequency());
+        self.psg_resampler.update_source_frequency(self.psg_frequency);
+        self.update_lpf_coefficients();
+    }
+
+    #[cfg(test)]
+    pub fn psg_frequency(&self) -> f64 {
+        self.psg_frequency
+    }
+
+    /// Number of PSG samples per output sample.
+    #[cfg(test)]
+    pub fn downsampling_ratio(&self) -> f64 {
+        self.psg_frequency / self.psg_resampler.output_frequency() as f64
+    }
+
+    /// Set the low-pass filter cutoff frequency in Hz, or None to disable low-pass filtering.
+    pub fn update_lpf_cutoff(&mut self, lpf_cutoff: Option<u32>) {
+        self.lpf_cutoff = lpf_cutoff;
+        self.update_lpf_coefficients();
+    }
+
+    fn update_lpf_coefficients(&mut self) {
+        let (coefficient_0, coefficients) =
+            psg_lpf_coefficients(self.psg_frequency, self.lpf_cutoff);
+        self.psg_resampler.update_lpf_coefficients(coefficient_0, coefficients);
     }
 
     pub fn collect_sample(&mut self, sample_l: f64, sample_r: f64) {
@@ -108,3 +193,61 @@ impl AudioResampler {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jgenesis_common::testing::CapturingAudioOutput;
+
+    // Resample 100ms of a sine wave at the given frequency and return the mean output power
+    fn output_power(lpf_cutoff: Option<u32>, signal_frequency: f64) -> f64 {
+        let mut resampler = AudioResampler::new(TimingMode::Ntsc, lpf_cutoff);
+        let psg_frequency = compute_psg_frequency(NTSC_MCLK_FREQUENCY);
+
+        for i in 0..(psg_frequency / 10.0) as u64 {
+            let sample = 0.5 * (2.0 * PI * signal_frequency * i as f64 / psg_frequency).sin();
+            resampler.collect_sample(sample, sample);
+        }
+
+        let mut audio_output = CapturingAudioOutput::new();
+        resampler.output_samples(&mut audio_output).unwrap();
+
+        let samples = audio_output.samples();
+        samples.iter().map(|&(l, _)| l * l).sum::<f64>() / samples.len() as f64
+    }
+
+    #[test]
+    fn downsampling_ratios() {
+        // 3.579545 MHz (NTSC) and 3.546895 MHz (PAL) PSG clocks / 16 / 48000 Hz
+        let ntsc_ratio = AudioResampler::new(TimingMode::Ntsc, None).downsampling_ratio();
+        assert!((ntsc_ratio - 4.660865885416667).abs() < 1e-9, "NTSC ratio {ntsc_ratio}");
+
+        let pal_ratio = AudioResampler::new(TimingMode::Pal, None).downsampling_ratio();
+        assert!((pal_ratio - 4.618352777777778).abs() < 1e-9, "PAL ratio {pal_ratio}");
+    }
+
+    #[test]
+    fn lpf_cutoff() {
+        // A 10 kHz tone should be heavily attenuated by a 2 kHz cutoff but not by a 20 kHz cutoff
+        let low_cutoff_power = output_power(Some(2000), 10000.0);
+        let high_cutoff_power = output_power(Some(20000), 10000.0);
+        assert!(
+            low_cutoff_power < 0.1 * high_cutoff_power,
+            "2 kHz power {low_cutoff_power}, 20 kHz power {high_cutoff_power}"
+        );
+
+        // Unattenuated 0.5 amplitude sine wave has mean power 0.125
+        assert!((high_cutoff_power - 0.125).abs() < 0.01, "20 kHz power {high_cutoff_power}");
+    }
+
+    #[test]
+    fn lpf_disabled() {
+        // With filtering disabled, a tone well above the default cutoff passes through
+        let default_power = output_power(Some(DEFAULT_PSG_LPF_CUTOFF), 30000.0);
+        let unfiltered_power = output_power(None, 30000.0);
+        assert!(
+            unfiltered_power > 2.0 * default_power,
+            "default power {default_power}, unfiltered power {unfiltered_power}"
+        );
+    }
+}
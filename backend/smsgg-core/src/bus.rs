@@ -1,6 +1,7 @@
 //! Implementation of the Z80's bus interface, which connects it to all other components
 
 use crate::input::InputState;
+use crate::link::LinkPort;
 use crate::memory::Memory;
 use crate::psg::Psg;
 use crate::vdp::Vdp;
@@ -16,6 +17,7 @@ pub struct Bus<'a> {
     psg: &'a mut Psg,
     ym2413: Option<&'a mut Ym2413>,
     input: &'a mut InputState,
+    link_port: &'a mut LinkPort,
 }
 
 impl<'a> Bus<'a> {
@@ -26,8 +28,9 @@ impl<'a> Bus<'a> {
         psg: &'a mut Psg,
         ym2413: Option<&'a mut Ym2413>,
         input: &'a mut InputState,
+        link_port: &'a mut LinkPort,
     ) -> Self {
-        Self { version, memory, vdp, psg, ym2413, input }
+        Self { version, memory, vdp, psg, ym2413, input, link_port }
     }
 }
 
@@ -43,12 +46,10 @@ impl<'a> BusInterface for Bus<'a> {
     fn read_io(&mut self, address: u16) -> u8 {
         let address = address & 0xFF;
         if self.version == VdpVersion::GameGear && address <= 0x06 {
-            // TODO Game Gear registers
             return match address {
                 0x00 => (u8::from(!self.input.pause_pressed()) << 7) | 0x40,
-                0x01 => 0x7F,
-                0x02 | 0x04 | 0x06 => 0xFF,
-                0x03 | 0x05 => 0x00,
+                0x01..=0x05 => self.link_port.read(address as u8),
+                0x06 => 0xFF,
                 _ => unreachable!("value is <= 0x06"),
             };
         }
@@ -67,9 +68,8 @@ impl<'a> BusInterface for Bus<'a> {
                 self.vdp.v_counter()
             }
             (false, true, true) => {
-                // TODO H counter
                 log::trace!("H counter read");
-                0x00
+                self.vdp.h_counter()
             }
             (true, false, false) => {
                 log::trace!("VDP data read");
@@ -93,8 +93,10 @@ impl<'a> BusInterface for Bus<'a> {
     fn write_io(&mut self, address: u16, value: u8) {
         let address = address & 0xFF;
         if self.version == VdpVersion::GameGear && address <= 0x06 {
-            if address == 0x06 {
-                self.psg.write_stereo_control(value);
+            match address {
+                0x01..=0x05 => self.link_port.write(address as u8, value),
+                0x06 => self.psg.write_stereo_control(value),
+                _ => {}
             }
             return;
         }
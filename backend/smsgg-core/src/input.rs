@@ -25,9 +25,16 @@ define_controller_inputs! {
         p1: Player::One,
         p2: Player::Two,
         pause: Button::Pause,
+        // Light Phaser aim position in port A, as (x, y) pixels relative to the top-left corner of
+        // the active display, or None if not aimed at the screen. The trigger is P1 Button 1
+        light_phaser: Option<(u16, u16)>,
     }
 }
 
+// Approximate the area around the aim position that the Light Phaser sensor can see
+const LIGHT_PHASER_DOTS: u16 = 16;
+const LIGHT_PHASER_LINES: u16 = 4;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
 enum PinDirection {
     Input,
@@ -52,6 +59,7 @@ pub struct InputState {
     port_b_th: PinDirection,
     region: SmsRegion,
     reset: bool,
+    light_phaser_detecting: bool,
 }
 
 impl InputState {
@@ -64,6 +72,7 @@ impl InputState {
             port_b_th: PinDirection::Input,
             region,
             reset: false,
+            light_phaser_detecting: false,
         }
     }
 
@@ -87,6 +96,22 @@ impl InputState {
         self.reset = reset;
     }
 
+    // Update the Light Phaser sensor for the current beam position. The sensor pulls port A TH low
+    // while it detects light, and returns true if TH transitioned from high to low, which latches
+    // the VDP H counter
+    pub fn update_light_phaser(&mut self, dot: u16, scanline: u16) -> bool {
+        let detecting = self.inputs.light_phaser.is_some_and(|(x, y)| {
+            (x..x + LIGHT_PHASER_DOTS).contains(&dot)
+                && (y..y + LIGHT_PHASER_LINES).contains(&scanline)
+        });
+
+        let th_falling_edge =
+            detecting && !self.light_phaser_detecting && self.port_a_th == PinDirection::Input;
+        self.light_phaser_detecting = detecting;
+
+        th_falling_edge
+    }
+
     pub fn write_control(&mut self, value: u8) {
         self.port_b_th =
             if value.bit(3) { PinDirection::Input } else { PinDirection::Output(value.bit(7)) };
@@ -114,8 +139,10 @@ impl InputState {
     pub fn port_dd(&self) -> u8 {
         let port_b_th_bit =
             u8::from(self.region == SmsRegion::International && self.port_b_th.bit(true)) << 7;
-        let port_a_th_bit =
-            u8::from(self.region == SmsRegion::International && self.port_a_th.bit(true)) << 6;
+        let port_a_th_bit = u8::from(
+            self.region == SmsRegion::International
+                && self.port_a_th.bit(!self.light_phaser_detecting),
+        ) << 6;
         let port_b_tr_bit = u8::from(self.port_b_tr.bit(!self.inputs.p2.button2)) << 3;
 
         port_b_th_bit
@@ -128,3 +155,90 @@ impl InputState {
             | u8::from(!self.inputs.p2.left)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reset_button() {
+        let mut input = InputState::new(SmsRegion::International);
+        assert!(input.port_dd().bit(4));
+
+        // RESET is active low
+        input.set_reset(true);
+        assert!(!input.port_dd().bit(4));
+
+        input.set_reset(false);
+        assert!(input.port_dd().bit(4));
+    }
+
+    #[test]
+    fn th_bits_japanese_sms() {
+        // TH pins configured as inputs
+        let mut input = InputState::new(SmsRegion::International);
+        input.write_control(0xFF);
+        assert_eq!(input.port_dd() & 0xC0, 0xC0);
+
+        input.set_region(SmsRegion::Domestic);
+        assert_eq!(input.port_dd() & 0xC0, 0x00);
+
+        // TH pins configured as outputs set high
+        input.write_control(0xF5);
+        assert_eq!(input.port_dd() & 0xC0, 0x00);
+
+        input.set_region(SmsRegion::International);
+        assert_eq!(input.port_dd() & 0xC0, 0xC0);
+    }
+
+    #[test]
+    fn player_2_inputs() {
+        let mut input = InputState::new(SmsRegion::International);
+        assert_eq!(input.port_dc(), 0xFF);
+        assert_eq!(input.port_dd() & 0x0F, 0x0F);
+
+        let mut inputs = SmsGgInputs::default();
+        inputs.p2.up = true;
+        inputs.p2.left = true;
+        inputs.p2.button1 = true;
+        input.set_inputs(inputs);
+
+        assert_eq!(input.port_dc(), 0xBF);
+        assert_eq!(input.port_dd() & 0x0F, 0x0A);
+
+        let mut inputs = SmsGgInputs::default();
+        inputs.p2.down = true;
+        inputs.p2.right = true;
+        inputs.p2.button2 = true;
+        input.set_inputs(inputs);
+
+        assert_eq!(input.port_dc(), 0x7F);
+        assert_eq!(input.port_dd() & 0x0F, 0x05);
+    }
+
+    #[test]
+    fn light_phaser_th() {
+        let mut input = InputState::new(SmsRegion::International);
+        input.write_control(0xFF);
+        input.set_inputs(SmsGgInputs { light_phaser: Some((100, 50)), ..SmsGgInputs::default() });
+
+        assert!(!input.update_light_phaser(100, 49));
+        assert!(!input.update_light_phaser(99, 50));
+        assert!(input.port_dd().bit(6));
+
+        // TH goes low when the beam reaches the aim position
+        assert!(input.update_light_phaser(100, 50));
+        assert!(!input.port_dd().bit(6));
+
+        // No new edge while TH stays low
+        assert!(!input.update_light_phaser(101, 50));
+        assert!(!input.port_dd().bit(6));
+
+        assert!(!input.update_light_phaser(100 + LIGHT_PHASER_DOTS, 50));
+        assert!(input.port_dd().bit(6));
+
+        // No edge if TH is configured as an output
+        input.write_control(0xFD);
+        assert!(!input.update_light_phaser(100, 51));
+    }
+}
@@ -2,14 +2,15 @@ mod api;
 pub mod audio;
 mod bus;
 mod input;
+mod link;
 mod memory;
 pub mod psg;
 mod vdp;
 mod ym2413;
 
-pub use api::{SmsGgEmulator, SmsGgEmulatorConfig, SmsGgError, SmsGgResult, SmsRegion};
+pub use api::{ChipId, SmsGgEmulator, SmsGgEmulatorConfig, SmsGgError, SmsGgResult, SmsRegion};
 pub use input::{SmsGgButton, SmsGgInputs, SmsGgJoypadState};
-pub use vdp::{gg_color_to_rgb, sms_color_to_rgb, VdpVersion};
+pub use vdp::{gg_color_to_rgb, sms_color_to_rgb, VdpLayers, VdpStatusFlags, VdpVersion};
 
 // 8:7
 pub const SMS_NTSC_ASPECT_RATIO: f64 = 1.1428571428571428;
@@ -0,0 +1,116 @@
+//! Game Gear EXT port / link cable registers
+//!
+//! Link cable peers are not emulated; the port behaves as if nothing is connected. Some games probe
+//! these registers at boot and depend on them responding correctly.
+
+use bincode::{Decode, Encode};
+use jgenesis_common::num::GetBit;
+
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct LinkPort {
+    parallel_data: u8,
+    parallel_control: u8,
+    transmit_data: u8,
+    receive_data: u8,
+    serial_control: u8,
+}
+
+impl LinkPort {
+    pub fn new() -> Self {
+        Self {
+            parallel_data: 0x7F,
+            parallel_control: 0xFF,
+            transmit_data: 0x00,
+            receive_data: 0xFF,
+            serial_control: 0x00,
+        }
+    }
+
+    pub fn read(&self, address: u8) -> u8 {
+        match address {
+            // $01: Parallel data; pins configured as inputs are pulled high with no peer connected
+            0x01 => {
+                let input_mask = self.parallel_control & 0x7F;
+                (self.parallel_data & !input_mask & 0x7F) | input_mask
+            }
+            // $02: Parallel data direction and NMI enable
+            0x02 => self.parallel_control,
+            // $03: Serial transmit data
+            0x03 => self.transmit_data,
+            // $04: Serial receive data
+            0x04 => self.receive_data,
+            // $05: Serial control / status; the status bits (0-2) always read 0 because transfers
+            // complete immediately and nothing is ever received
+            0x05 => self.serial_control & 0xF8,
+            _ => 0xFF,
+        }
+    }
+
+    pub fn write(&mut self, address: u8, value: u8) {
+        log::trace!("Link port write: {address:02X} {value:02X}");
+
+        match address {
+            0x01 => self.parallel_data = value & 0x7F,
+            0x02 => self.parallel_control = value,
+            0x03 => {
+                self.transmit_data = value;
+
+                if self.serial_control.bit(4) {
+                    // Transmit enabled; with no peer, the transfer completes immediately and the
+                    // receive line stays high
+                    self.receive_data = 0xFF;
+                    log::trace!("Serial transfer of {value:02X} completed with no peer");
+                }
+            }
+            0x05 => self.serial_control = value & 0xF8,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn power_on_values() {
+        let link_port = LinkPort::new();
+        assert_eq!(link_port.read(0x01), 0x7F);
+        assert_eq!(link_port.read(0x02), 0xFF);
+        assert_eq!(link_port.read(0x03), 0x00);
+        assert_eq!(link_port.read(0x04), 0xFF);
+        assert_eq!(link_port.read(0x05), 0x00);
+    }
+
+    #[test]
+    fn serial_transfer_without_peer() {
+        let mut link_port = LinkPort::new();
+
+        // Enable transmit and receive
+        link_port.write(0x05, 0x30);
+        assert_eq!(link_port.read(0x05), 0x30);
+
+        link_port.write(0x03, 0x5A);
+        assert_eq!(link_port.read(0x03), 0x5A);
+
+        // Transmit buffer is immediately empty and nothing is received
+        assert_eq!(link_port.read(0x05) & 0x07, 0x00);
+        assert_eq!(link_port.read(0x04), 0xFF);
+    }
+
+    #[test]
+    fn parallel_data_direction() {
+        let mut link_port = LinkPort::new();
+        link_port.write(0x01, 0x00);
+
+        // All pins are inputs by default
+        assert_eq!(link_port.read(0x01), 0x7F);
+
+        // Pins 0-3 output, pins 4-6 input
+        link_port.write(0x02, 0x70);
+        assert_eq!(link_port.read(0x01), 0x70);
+
+        link_port.write(0x01, 0x05);
+        assert_eq!(link_port.read(0x01), 0x75);
+    }
+}
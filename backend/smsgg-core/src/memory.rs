@@ -5,6 +5,8 @@ mod metadata;
 use bincode::{Decode, Encode};
 use crc::Crc;
 use jgenesis_common::num::GetBit;
+use jgenesis_common::ram::RamInitPattern;
+use jgenesis_common::rng::Rng;
 use jgenesis_proc_macros::{FakeDecode, FakeEncode, PartialClone};
 use std::mem;
 use std::ops::{Index, RangeInclusive};
@@ -198,6 +200,7 @@ pub struct Memory {
     cartridge: Cartridge,
     ram: [u8; SYSTEM_RAM_SIZE],
     audio_control: AudioControl,
+    ram_init_pattern: RamInitPattern,
 }
 
 impl Memory {
@@ -206,9 +209,20 @@ impl Memory {
             cartridge: Cartridge::new(rom, initial_cartridge_ram),
             ram: [0; SYSTEM_RAM_SIZE],
             audio_control: AudioControl::default(),
+            ram_init_pattern: RamInitPattern::default(),
         }
     }
 
+    // Fill system RAM with the given power-on pattern
+    pub fn init_ram(&mut self, ram_init_pattern: RamInitPattern, rng: &mut Rng) {
+        ram_init_pattern.fill(&mut self.ram, rng);
+        self.ram_init_pattern = ram_init_pattern;
+    }
+
+    pub fn ram_init_pattern(&self) -> RamInitPattern {
+        self.ram_init_pattern
+    }
+
     pub fn read(&self, address: u16) -> u8 {
         match address {
             0x0000..=0xBFFF => self.cartridge.read(address),
@@ -262,6 +276,10 @@ impl Memory {
         }
     }
 
+    pub fn system_ram(&self) -> &[u8] {
+        &self.ram
+    }
+
     pub fn cartridge_ram(&self) -> &[u8] {
         &self.cartridge.ram
     }
@@ -311,3 +329,34 @@ impl Memory {
         self.audio_control.psg_enabled = control_bits == 0 || control_bits == 3;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audio_control_states() {
+        let mut memory = Memory::new(vec![0; 0x8000], None);
+        assert!(memory.psg_enabled() && !memory.fm_enabled());
+
+        // $00: PSG only
+        memory.write_audio_control(0x00);
+        assert!(memory.psg_enabled() && !memory.fm_enabled());
+        assert_eq!(memory.read_audio_control(), 0x00);
+
+        // $01: FM only
+        memory.write_audio_control(0x01);
+        assert!(!memory.psg_enabled() && memory.fm_enabled());
+        assert_eq!(memory.read_audio_control(), 0x01);
+
+        // $02: Both muted
+        memory.write_audio_control(0x02);
+        assert!(!memory.psg_enabled() && !memory.fm_enabled());
+        assert_eq!(memory.read_audio_control(), 0x02);
+
+        // $03: PSG and FM both audible
+        memory.write_audio_control(0xFF);
+        assert!(memory.psg_enabled() && memory.fm_enabled());
+        assert_eq!(memory.read_audio_control(), 0x03);
+    }
+}
@@ -679,6 +679,21 @@ impl<'a> IntoIterator for &'a VdpBuffer {
     }
 }
 
+/// Layer toggles for debugging; disabled layers are rendered as transparent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub struct VdpLayers {
+    pub background_enabled: bool,
+    pub sprites_enabled: bool,
+    /// If false, ignore the register bit that blanks the leftmost 8 pixels
+    pub left_column_blanking_enabled: bool,
+}
+
+impl Default for VdpLayers {
+    fn default() -> Self {
+        Self { background_enabled: true, sprites_enabled: true, left_column_blanking_enabled: true }
+    }
+}
+
 #[derive(Debug, Clone, Encode, Decode)]
 pub struct Vdp {
     frame_buffer: VdpBuffer,
@@ -690,12 +705,22 @@ pub struct Vdp {
     sprite_buffer: SpriteBuffer,
     remove_sprite_limit: bool,
     line_counter: u8,
+    h_counter_latch: u8,
+    forced_timing_mode: Option<TimingMode>,
+    layers: VdpLayers,
 }
 
 const DOTS_PER_SCANLINE: u16 = 342;
 const NTSC_SCANLINES_PER_FRAME: u16 = 262;
 const PAL_SCANLINES_PER_FRAME: u16 = 313;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct VdpStatusFlags {
+    pub frame_interrupt_pending: bool,
+    pub sprite_overflow: bool,
+    pub sprite_collision: bool,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VdpTickEffect {
     None,
@@ -714,6 +739,9 @@ impl Vdp {
             sprite_buffer: SpriteBuffer::new(),
             remove_sprite_limit,
             line_counter: 0xFF,
+            h_counter_latch: 0,
+            forced_timing_mode: None,
+            layers: VdpLayers::default(),
         }
     }
 
@@ -830,7 +858,10 @@ impl Vdp {
                     break;
                 }
 
-                if self.registers.hide_left_column && dot < 8 {
+                if self.registers.hide_left_column
+                    && self.layers.left_column_blanking_enabled
+                    && dot < 8
+                {
                     self.frame_buffer.set(frame_buffer_row, dot, backdrop_color);
                     continue;
                 }
@@ -876,14 +907,22 @@ impl Vdp {
                     }
                 }
 
-                let sprite_color_id = found_sprite_color_id.unwrap_or(0);
-                let pixel_color =
-                    if sprite_color_id != 0 && (bg_color_id == 0 || !bg_tile_data.priority) {
-                        // Sprites can only use palette 1
-                        self.read_color_ram_word(0x10 | sprite_color_id)
-                    } else {
-                        self.read_color_ram_word(bg_base_cram_addr | bg_color_id)
-                    };
+                let sprite_color_id = if self.layers.sprites_enabled {
+                    found_sprite_color_id.unwrap_or(0)
+                } else {
+                    0
+                };
+                let bg_visible = self.layers.background_enabled;
+                let pixel_color = if sprite_color_id != 0
+                    && (!bg_visible || bg_color_id == 0 || !bg_tile_data.priority)
+                {
+                    // Sprites can only use palette 1
+                    self.read_color_ram_word(0x10 | sprite_color_id)
+                } else if bg_visible {
+                    self.read_color_ram_word(bg_base_cram_addr | bg_color_id)
+                } else {
+                    backdrop_color
+                };
                 self.frame_buffer.set(frame_buffer_row, dot, pixel_color);
             }
         }
@@ -965,11 +1004,12 @@ impl Vdp {
             self.scanline += 1;
             self.dot = 0;
 
-            let scanlines_per_frame = match self.registers.version.timing_mode() {
+            let scanlines_per_frame = match self.timing_mode() {
                 TimingMode::Ntsc => NTSC_SCANLINES_PER_FRAME,
                 TimingMode::Pal => PAL_SCANLINES_PER_FRAME,
             };
-            if self.scanline == scanlines_per_frame {
+            // >= instead of == in case the timing mode changed from PAL to NTSC mid-frame
+            if self.scanline >= scanlines_per_frame {
                 self.scanline = 0;
             }
         }
@@ -978,14 +1018,7 @@ impl Vdp {
     }
 
     fn fill_vertical_border(&mut self) {
-        let backdrop_color = match self.registers.mode {
-            Mode::Four | Mode::Four224Line => {
-                self.read_color_ram_word(0x10 | self.registers.backdrop_color)
-            }
-            Mode::GraphicsII => {
-                tms9918::TMS9918_COLOR_TO_SMS_COLOR[self.registers.backdrop_color as usize].into()
-            }
-        };
+        let backdrop_color = self.backdrop_color();
 
         let ViewportSize { top_border_height, height, bottom_border_height, .. } =
             self.frame_buffer.viewport;
@@ -1007,6 +1040,20 @@ impl Vdp {
         }
     }
 
+    /// Current backdrop color as selected by VDP register #7, in the same format as the frame
+    /// buffer. This is also the border color.
+    pub fn backdrop_color(&self) -> u16 {
+        match self.registers.mode {
+            Mode::Four | Mode::Four224Line => {
+                // Backdrop color always reads from the second half of CRAM
+                self.read_color_ram_word(0x10 | self.registers.backdrop_color)
+            }
+            Mode::GraphicsII => {
+                tms9918::TMS9918_COLOR_TO_SMS_COLOR[self.registers.backdrop_color as usize].into()
+            }
+        }
+    }
+
     pub fn frame_buffer(&self) -> &VdpBuffer {
         &self.frame_buffer
     }
@@ -1028,7 +1075,7 @@ impl Vdp {
     }
 
     pub fn v_counter(&self) -> u8 {
-        match (self.registers.version.timing_mode(), self.registers.mode) {
+        match (self.timing_mode(), self.registers.mode) {
             (TimingMode::Ntsc, Mode::Four | Mode::GraphicsII) => {
                 if self.scanline <= 0xDA {
                     self.scanline as u8
@@ -1062,6 +1109,34 @@ impl Vdp {
         }
     }
 
+    // Read the status flags without clearing them, unlike a control port read
+    pub fn status_flags(&self) -> VdpStatusFlags {
+        VdpStatusFlags {
+            frame_interrupt_pending: self.registers.frame_interrupt_pending,
+            sprite_overflow: self.registers.sprite_overflow,
+            sprite_collision: self.registers.sprite_collision,
+        }
+    }
+
+    pub fn h_counter(&self) -> u8 {
+        // H counter reads always return the latched value
+        self.h_counter_latch
+    }
+
+    pub fn latch_h_counter(&mut self) {
+        // The internal 9-bit H counter counts from $000 to $127 and then jumps to $1D2 through $1FF,
+        // for 342 dots total. The H counter port returns the highest 8 bits
+        let h_counter = if self.dot <= 0x127 { self.dot } else { self.dot - 0x128 + 0x1D2 };
+        self.h_counter_latch = (h_counter >> 1) as u8;
+
+        log::trace!("H counter latched at dot {}: {:02X}", self.dot, self.h_counter_latch);
+    }
+
+    // Returns (dot, scanline), where (0, 0) is the top-left corner of the active display
+    pub fn beam_position(&self) -> (u16, u16) {
+        (self.dot, self.scanline)
+    }
+
     pub fn interrupt_line(&self) -> InterruptLine {
         if (self.registers.frame_interrupt_enabled && self.registers.frame_interrupt_pending)
             || (self.registers.line_interrupt_enabled && self.registers.line_interrupt_pending)
@@ -1073,7 +1148,25 @@ impl Vdp {
     }
 
     pub fn timing_mode(&self) -> TimingMode {
-        self.registers.version.timing_mode()
+        self.forced_timing_mode.unwrap_or_else(|| self.registers.version.timing_mode())
+    }
+
+    pub fn forced_timing_mode(&self) -> Option<TimingMode> {
+        self.forced_timing_mode
+    }
+
+    /// Override the timing mode implied by the VDP version, or pass None to use the VDP version's
+    /// timing mode.
+    pub fn set_forced_timing_mode(&mut self, forced_timing_mode: Option<TimingMode>) {
+        self.forced_timing_mode = forced_timing_mode;
+    }
+
+    pub fn layers(&self) -> VdpLayers {
+        self.layers
+    }
+
+    pub fn set_layers(&mut self, layers: VdpLayers) {
+        self.layers = layers;
     }
 
     pub fn set_version(&mut self, version: VdpVersion) {
@@ -1114,3 +1207,149 @@ pub fn gg_color_to_rgb(color: u16) -> Color {
     let b = convert_gg_color((color >> 8) & 0x0F);
     Color::rgb(r, g, b)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jgenesis_common::frontend::FrameSize;
+
+    fn latch_at_dot(vdp: &mut Vdp, dot: u16) -> u8 {
+        while vdp.beam_position().0 != dot {
+            let _ = vdp.tick();
+        }
+
+        vdp.latch_h_counter();
+        vdp.h_counter()
+    }
+
+    #[test]
+    fn h_counter_latch() {
+        let mut vdp = Vdp::new(VdpVersion::NtscMasterSystem2, false);
+        assert_eq!(vdp.h_counter(), 0x00);
+
+        assert_eq!(latch_at_dot(&mut vdp, 100), 0x32);
+
+        // Latched value does not change until the next latch
+        let _ = vdp.tick();
+        assert_eq!(vdp.h_counter(), 0x32);
+
+        assert_eq!(latch_at_dot(&mut vdp, 0x127), 0x93);
+        assert_eq!(latch_at_dot(&mut vdp, 0x128), 0xE9);
+        assert_eq!(latch_at_dot(&mut vdp, DOTS_PER_SCANLINE - 1), 0xFF);
+    }
+
+    fn tick_to_scanline(vdp: &mut Vdp, scanline: u16) {
+        while vdp.beam_position() != (1, scanline) {
+            let _ = vdp.tick();
+        }
+    }
+
+    #[test]
+    fn sprite_overflow_flag() {
+        let mut vdp = Vdp::new(VdpVersion::NtscMasterSystem2, false);
+
+        // Register #1: Display enabled
+        vdp.write_control(0x40);
+        vdp.write_control(0x81);
+
+        // Sprite attribute table is at $3F00 by default. Place 9 sprites covering lines 10-17,
+        // followed by the $D0 end marker
+        vdp.write_control(0x00);
+        vdp.write_control(0x7F);
+        for _ in 0..9 {
+            vdp.write_data(9);
+        }
+        vdp.write_data(0xD0);
+
+        tick_to_scanline(&mut vdp, 9);
+        assert_eq!(vdp.status_flags(), VdpStatusFlags::default());
+
+        tick_to_scanline(&mut vdp, 10);
+        let expected = VdpStatusFlags { sprite_overflow: true, ..VdpStatusFlags::default() };
+        assert_eq!(vdp.status_flags(), expected);
+
+        // Reading status flags through this method should not clear them
+        assert_eq!(vdp.status_flags(), expected);
+
+        // Control port reads clear the flags
+        assert_eq!(vdp.read_control() & 0x40, 0x40);
+        assert_eq!(vdp.status_flags(), VdpStatusFlags::default());
+    }
+
+    #[test]
+    fn background_disabled() {
+        const BG_COLOR: u16 = 0x03;
+        const SPRITE_COLOR: u16 = 0x0C;
+
+        let mut vdp = Vdp::new(VdpVersion::NtscMasterSystem2, false);
+        vdp.set_layers(VdpLayers { background_enabled: false, ..VdpLayers::default() });
+
+        // Register #1: Display enabled
+        vdp.write_control(0x40);
+        vdp.write_control(0x81);
+
+        // Fill BG tile 0 (at $0000) and sprite tile 0 (at $2000) with color 15
+        for address in [0x0000_u16, 0x2000] {
+            vdp.write_control(address as u8);
+            vdp.write_control(0x40 | (address >> 8) as u8);
+            for _ in 0..32 {
+                vdp.write_data(0xFF);
+            }
+        }
+
+        // One sprite covering lines 10-17 at X=100, followed by the $D0 end marker
+        vdp.write_control(0x00);
+        vdp.write_control(0x7F);
+        vdp.write_data(9);
+        vdp.write_data(0xD0);
+        vdp.write_control(0x80);
+        vdp.write_control(0x7F);
+        vdp.write_data(100);
+        vdp.write_data(0);
+
+        // BG palette color 15, backdrop (sprite palette color 0), sprite palette color 15
+        vdp.write_control(0x0F);
+        vdp.write_control(0xC0);
+        vdp.write_data(BG_COLOR as u8);
+        for _ in 0x10..0x1F {
+            vdp.write_data(0);
+        }
+        vdp.write_data(SPRITE_COLOR as u8);
+
+        while vdp.tick() != VdpTickEffect::FrameComplete {}
+
+        let pixels: Vec<u16> = vdp.frame_buffer().iter().flatten().copied().collect();
+        assert!(pixels.iter().all(|&color| color == 0 || color == SPRITE_COLOR));
+        assert!(pixels.contains(&SPRITE_COLOR));
+
+        vdp.set_layers(VdpLayers::default());
+        while vdp.tick() != VdpTickEffect::FrameComplete {}
+
+        assert!(vdp.frame_buffer().iter().flatten().any(|&color| color == BG_COLOR));
+    }
+
+    #[test]
+    fn debug_nametable() {
+        let mut vdp = Vdp::new(VdpVersion::NtscMasterSystem2, false);
+
+        // Top-left nametable entry at $3800: tile 1, palette 1, horizontal flip
+        vdp.vram[0x3800] = 0x01;
+        vdp.vram[0x3801] = 0x0A;
+
+        // Tile 1, row 0: leftmost pixel is color 1
+        vdp.vram[32] = 0x80;
+
+        vdp.color_ram[0x11] = 0x03;
+
+        let mut cram_colors = [Color::default(); 32];
+        vdp.copy_cram(&mut cram_colors);
+
+        let mut out = vec![Color::default(); 256 * 224];
+        let frame_size = vdp.debug_nametable(&mut out);
+        assert_eq!(frame_size, FrameSize { width: 256, height: 224 });
+
+        // Horizontally flipped, so the pixel ends up at the right edge of the tile
+        assert_eq!(out[7], cram_colors[0x11]);
+        assert_eq!(out[0], cram_colors[0x10]);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::vdp::{convert_gg_color, convert_sms_color, get_color_id, Vdp, VRAM_SIZE};
 
-use jgenesis_common::frontend::Color;
+use jgenesis_common::frontend::{Color, FrameSize};
 
 impl Vdp {
     pub fn copy_cram(&self, out: &mut [Color]) {
@@ -33,15 +33,50 @@ impl Vdp {
 
                     let color_id = get_color_id(tile, row as u16, col as u16, false);
                     let color = self.read_color_ram_word((palette << 4) | color_id);
+                    out[out_idx] = self.cram_color_to_rgb(color);
+                }
+            }
+        }
+    }
+
+    /// Render the entire Mode 4 nametable, ignoring scroll values, using each tile's palette and
+    /// flip attributes. `out` is filled in row-major order and must be large enough to hold the
+    /// returned size.
+    pub fn debug_nametable(&self, out: &mut [Color]) -> FrameSize {
+        let rows = self.registers.mode.name_table_rows();
+
+        for row in 0..rows {
+            for col in 0..32 {
+                let tile_data = self.read_name_table_word(row, col);
+                let tile_addr = 32 * tile_data.tile_index as usize;
+                let tile = &self.vram[tile_addr..tile_addr + 32];
+
+                for tile_row in 0..8 {
+                    let pattern_row = if tile_data.vertical_flip { 7 - tile_row } else { tile_row };
 
-                    out[out_idx] = if self.registers.version.is_master_system() {
-                        sms_color_to_rgb(color as u8)
-                    } else {
-                        gg_color_to_rgb(color)
-                    };
+                    for tile_col in 0..8 {
+                        let color_id =
+                            get_color_id(tile, pattern_row, tile_col, tile_data.horizontal_flip);
+                        let color =
+                            self.read_color_ram_word(tile_data.palette.base_cram_addr() | color_id);
+
+                        let out_idx =
+                            usize::from(8 * row + tile_row) * 256 + usize::from(8 * col + tile_col);
+                        out[out_idx] = self.cram_color_to_rgb(color);
+                    }
                 }
             }
         }
+
+        FrameSize { width: 256, height: 8 * u32::from(rows) }
+    }
+
+    fn cram_color_to_rgb(&self, color: u16) -> Color {
+        if self.registers.version.is_master_system() {
+            sms_color_to_rgb(color as u8)
+        } else {
+            gg_color_to_rgb(color)
+        }
     }
 }
 
@@ -94,9 +94,9 @@ impl Vdp {
                 let bg_color =
                     if pattern_generator_entry.bit(7 - tile_col) { bg_color_1 } else { bg_color_0 };
 
-                let pixel_color = if sprite_color != 0 {
+                let pixel_color = if sprite_color != 0 && self.layers.sprites_enabled {
                     TMS9918_COLOR_TO_SMS_COLOR[sprite_color as usize]
-                } else if bg_color != 0 {
+                } else if bg_color != 0 && self.layers.background_enabled {
                     TMS9918_COLOR_TO_SMS_COLOR[bg_color as usize]
                 } else {
                     backdrop_color
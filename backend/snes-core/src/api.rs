@@ -3,7 +3,9 @@
 use crate::apu::{Apu, ApuTickEffect};
 use crate::audio::AudioResampler;
 use crate::bus::Bus;
+use crate::cheats::CheatList;
 use crate::input::SnesInputs;
+use crate::memory::cartridge;
 use crate::memory::dma::{DmaStatus, DmaUnit};
 use crate::memory::{CpuInternalRegisters, Memory};
 use crate::ppu::{Ppu, PpuTickEffect};
@@ -11,17 +13,25 @@ use bincode::error::EncodeError;
 use bincode::{Decode, Encode};
 use crc::Crc;
 use jgenesis_common::frontend::{
-    AudioOutput, Color, EmulatorTrait, FrameSize, PartialClone, PixelAspectRatio, Renderer,
-    SaveWriter, TickEffect, TimingMode,
+    AudioOutput, Color, EmulatorTrait, FrameSize, MemoryRegion, PartialClone, PixelAspectRatio,
+    Renderer, SaveWriter, TickEffect, TimingMode,
 };
+#[cfg(any(test, feature = "perf"))]
+use jgenesis_common::frontend::PerfCounters;
+use jgenesis_common::ram::RamInitPattern;
+use jgenesis_common::rng::Rng;
+use jgenesis_common::save_state::VersionedState;
 use jgenesis_proc_macros::{EnumDisplay, EnumFromStr, FakeDecode, FakeEncode};
 use std::fmt::{Debug, Display};
 use std::num::NonZeroU64;
+use std::ops::RangeInclusive;
 use std::{io, mem};
 use thiserror::Error;
 use wdc65816_emu::core::Wdc65816;
 use wdc65816_emu::traits::BusInterface;
 
+pub use crate::memory::MemoryWatchFn;
+
 const MEMORY_REFRESH_MCLK: u64 = 536;
 const MEMORY_REFRESH_CYCLES: u64 = 40;
 
@@ -63,6 +73,40 @@ pub struct SnesEmulatorConfig {
     pub aspect_ratio: SnesAspectRatio,
     pub audio_60hz_hack: bool,
     pub gsu_overclock_factor: NonZeroU64,
+    /// Contents of WRAM at power-on
+    pub ram_init_pattern: RamInitPattern,
+    /// Seed for all pseudorandom power-on state. Currently only consumed by
+    /// [`RamInitPattern::Random`]
+    pub rng_seed: u64,
+}
+
+/// CRC32 of the ROM image, excluding the 512-byte copier header if present. This is the checksum
+/// used by ROM databases such as No-Intro.
+#[must_use]
+pub fn rom_crc32(rom: &[u8]) -> u32 {
+    CRC.checksum(cartridge::strip_copier_header(rom))
+}
+
+/// 4-character game code from the internal header's expanded area ($FFB2-$FFB5), e.g. "ATVE".
+///
+/// Returns an empty string if the header has no expanded area, which is the case for most
+/// cartridges released before 1993, or if the ROM is too small to contain a header.
+#[must_use]
+pub fn rom_serial(rom: &[u8]) -> String {
+    let rom = cartridge::strip_copier_header(rom);
+    let Some(header_addr) = cartridge::guess_header_addr(rom) else { return String::new() };
+
+    // $FFDA = $33 indicates that the expanded header is present
+    if rom[header_addr + 0x1A] != 0x33 {
+        return String::new();
+    }
+
+    rom[header_addr - 0x0E..header_addr - 0x0A]
+        .iter()
+        .map(|&b| b as char)
+        .collect::<String>()
+        .trim()
+        .into()
 }
 
 pub type CoprocessorRomFn = dyn Fn() -> Result<Vec<u8>, (io::Error, String)>;
@@ -156,10 +200,14 @@ pub struct SnesEmulator {
     aspect_ratio: SnesAspectRatio,
     frame_count: u64,
     last_sram_checksum: u32,
+    cheats: CheatList,
     // Following fields only stored here to enable hard reset
     #[partial_clone(default)]
     coprocessor_roms: CoprocessorRoms,
     emulator_config: SnesEmulatorConfig,
+    // Always compiled in tests so the counters are exercised without the `perf` feature
+    #[cfg(any(test, feature = "perf"))]
+    perf_counters: PerfCounters,
 }
 
 impl SnesEmulator {
@@ -186,6 +234,7 @@ impl SnesEmulator {
             config.gsu_overclock_factor,
             save_writer,
         )?;
+        memory.init_main_ram(config.ram_init_pattern, &mut Rng::new(config.rng_seed));
 
         let timing_mode =
             config.forced_timing_mode.unwrap_or_else(|| memory.cartridge_timing_mode());
@@ -209,8 +258,11 @@ impl SnesEmulator {
             aspect_ratio: config.aspect_ratio,
             frame_count: 0,
             last_sram_checksum: sram_checksum,
+            cheats: CheatList::new(),
             coprocessor_roms,
             emulator_config: config,
+            #[cfg(any(test, feature = "perf"))]
+            perf_counters: PerfCounters::default(),
         };
 
         // Reset CPU so that execution starts from the right place
@@ -230,6 +282,68 @@ impl SnesEmulator {
         self.memory.has_battery_backed_sram()
     }
 
+    /// Read a byte from the CPU address space without side effects, e.g. without incrementing the
+    /// WRAM port address. I/O registers and coprocessor-handled addresses return CPU open bus.
+    #[must_use]
+    pub fn debug_read(&self, address: u32) -> u8 {
+        self.memory.debug_read(address)
+    }
+
+    /// Fill `out` with consecutive bytes read by [`Self::debug_read`], starting at `address`.
+    pub fn debug_read_range(&self, address: u32, out: &mut [u8]) {
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = self.memory.debug_read(address.wrapping_add(i as u32) & 0xFFFFFF);
+        }
+    }
+
+    /// Write a byte to WRAM or cartridge SRAM without side effects, for debuggers and cheats.
+    ///
+    /// Returns `false` if the write was ignored because the address does not map to RAM, e.g. a
+    /// ROM address.
+    pub fn debug_write(&mut self, address: u32, value: u8) -> bool {
+        let written = self.memory.debug_write(address, value);
+        if !written {
+            log::warn!("Ignoring debug write to non-RAM address {address:06X}");
+        }
+        written
+    }
+
+    #[must_use]
+    pub fn cheats(&self) -> &CheatList {
+        &self.cheats
+    }
+
+    /// Call `callback` with `(address, value, is_write)` on every CPU read or write of WRAM or the
+    /// cartridge within `range`, replacing any existing watch. WRAM is reported at $7E0000-$7FFFFF
+    /// regardless of which mirror or port the CPU used. Debug reads and writes do not trigger the
+    /// watch, and the watch is not included in save states.
+    pub fn set_memory_watch(&mut self, range: RangeInclusive<u32>, callback: Box<MemoryWatchFn>) {
+        self.memory.set_watch(range, callback);
+    }
+
+    pub fn clear_memory_watch(&mut self) {
+        self.memory.clear_watch();
+    }
+
+    /// Replace the active cheat codes. Codes are applied immediately and then at the start of
+    /// every frame. Codes that do not point to WRAM or cartridge SRAM are ignored with a warning.
+    pub fn set_cheats(&mut self, mut cheats: CheatList) {
+        cheats.retain(|code| {
+            let applied = self.memory.debug_write(code.address, code.value);
+            if !applied {
+                log::warn!("Ignoring cheat code for non-RAM address {:06X}", code.address);
+            }
+            applied
+        });
+        self.cheats = cheats;
+    }
+
+    fn apply_cheats(&mut self) {
+        for code in self.cheats.codes() {
+            self.memory.debug_write(code.address, code.value);
+        }
+    }
+
     pub fn copy_cgram(&self, out: &mut [Color]) {
         self.ppu.copy_cgram(out);
     }
@@ -249,6 +363,32 @@ impl SnesEmulator {
     pub fn copy_vram_mode7(&self, out: &mut [Color], row_len: usize) {
         self.ppu.copy_vram_mode7(out, row_len);
     }
+
+    /// Return the performance counters accumulated since the last call, and reset them to zero.
+    #[cfg(any(test, feature = "perf"))]
+    pub fn perf_counters(&mut self) -> PerfCounters {
+        mem::take(&mut self.perf_counters)
+    }
+}
+
+impl VersionedState for SnesEmulator {
+    const SYSTEM_ID: [u8; 4] = *b"SNES";
+    const STATE_VERSION: u32 = 1;
+}
+
+// Memory region IDs for EmulatorTrait::read_region, as indices into MEMORY_REGIONS
+const WRAM_REGION_ID: usize = 0;
+const SRAM_REGION_ID: usize = 1;
+
+// (name, base address) of each memory region, in region ID order
+const MEMORY_REGIONS: [(&str, u32); 2] = [("WRAM", 0x7E0000), ("SRAM", 0x700000)];
+
+fn memory_region_bytes(emulator: &SnesEmulator, region_id: usize) -> Option<&[u8]> {
+    match region_id {
+        WRAM_REGION_ID => Some(emulator.memory.main_ram()),
+        SRAM_REGION_ID => emulator.memory.sram(),
+        _ => None,
+    }
 }
 
 impl EmulatorTrait for SnesEmulator {
@@ -287,7 +427,14 @@ impl EmulatorTrait for SnesEmulator {
             match self.dma_unit.tick(&mut bus, self.total_master_cycles) {
                 DmaStatus::None => {
                     // DMA not in progress, tick CPU
-                    self.main_cpu.tick(&mut bus);
+                    self.main_cpu.tick_traced(&mut bus, |_| {
+                        // Only called when an instruction starts, not for interrupts, WAI/STP,
+                        // or mid-instruction cycles
+                        #[cfg(any(test, feature = "perf"))]
+                        {
+                            self.perf_counters.instructions += 1;
+                        }
+                    });
                     self.latched_interrupts = None;
 
                     bus.access_master_cycles
@@ -345,6 +492,9 @@ impl EmulatorTrait for SnesEmulator {
                 }
             }
 
+            // Apply cheats before the CPU starts executing the next frame
+            self.apply_cheats();
+
             self.frame_count += 1;
             tick_effect = TickEffect::FrameRendered;
         }
@@ -360,6 +510,12 @@ impl EmulatorTrait for SnesEmulator {
         self.memory.tick(master_cycles_elapsed);
 
         self.total_master_cycles += master_cycles_elapsed;
+
+        #[cfg(any(test, feature = "perf"))]
+        {
+            self.perf_counters.master_clock_cycles += master_cycles_elapsed;
+        }
+
         if prev_scanline_mclk < MEMORY_REFRESH_MCLK
             && self.ppu.scanline_master_cycles() >= MEMORY_REFRESH_MCLK
         {
@@ -410,11 +566,210 @@ impl EmulatorTrait for SnesEmulator {
         let rom = self.memory.take_rom();
 
         let coprocessor_roms = mem::take(&mut self.coprocessor_roms);
-        *self = Self::create(rom, self.emulator_config, coprocessor_roms, save_writer)
+        let cheats = mem::take(&mut self.cheats);
+        let mut emulator = Self::create(rom, self.emulator_config, coprocessor_roms, save_writer)
             .expect("Hard resetting should never fail to load");
+        emulator.memory.take_watch_from(&mut self.memory);
+        *self = emulator;
+        self.set_cheats(cheats);
     }
 
     fn timing_mode(&self) -> TimingMode {
         self.timing_mode
     }
+
+    fn memory_regions(&self) -> Vec<MemoryRegion> {
+        MEMORY_REGIONS
+            .iter()
+            .enumerate()
+            .map(|(region_id, &(name, base_address))| {
+                let len = memory_region_bytes(self, region_id).map_or(0, <[u8]>::len);
+                MemoryRegion::new(name, base_address, len)
+            })
+            .collect()
+    }
+
+    fn read_region(&self, region_id: usize, offset: usize) -> Option<u8> {
+        memory_region_bytes(self, region_id)?.get(offset).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cheats::ProActionReplayCode;
+    use jgenesis_common::testing::{NullAudioOutput, NullRenderer, NullSaveWriter};
+
+    #[test]
+    fn rom_identification() {
+        assert_eq!(rom_crc32(b"123456789"), 0xCBF43926);
+
+        // LoROM with an expanded header
+        let mut rom = vec![0; 0x8000];
+        rom[0x7FB2..0x7FB6].copy_from_slice(b"ATVE");
+        rom[0x7FD5] = 0x20;
+        rom[0x7FDA] = 0x33;
+        assert_eq!(rom_serial(&rom), "ATVE");
+
+        // Copier header does not affect either value
+        let mut rom_with_header = vec![0xFF; 0x200];
+        rom_with_header.extend_from_slice(&rom);
+        assert_eq!(rom_crc32(&rom_with_header), rom_crc32(&rom));
+        assert_eq!(rom_serial(&rom_with_header), "ATVE");
+
+        // No expanded header
+        rom[0x7FDA] = 0x01;
+        assert_eq!(rom_serial(&rom), "");
+        assert_eq!(rom_serial(&[0; 0x100]), "");
+    }
+
+    fn new_emulator() -> SnesEmulator {
+        // LoROM with reset vector = $8000
+        let mut rom = vec![0; 0x8000];
+        rom[0x7FFC..0x7FFE].copy_from_slice(&[0x00, 0x80]);
+        // LDA #$42
+        // STA $1000
+        // BRA -2 (infinite loop)
+        rom[0x0000..0x0007].copy_from_slice(&[0xA9, 0x42, 0x8D, 0x00, 0x10, 0x80, 0xFE]);
+
+        create_emulator(rom)
+    }
+
+    fn create_emulator(rom: Vec<u8>) -> SnesEmulator {
+        let config = SnesEmulatorConfig {
+            forced_timing_mode: Some(TimingMode::Ntsc),
+            aspect_ratio: SnesAspectRatio::Ntsc,
+            audio_60hz_hack: false,
+            gsu_overclock_factor: NonZeroU64::new(1).unwrap(),
+            ram_init_pattern: RamInitPattern::default(),
+            rng_seed: 0,
+        };
+        SnesEmulator::create(rom, config, CoprocessorRoms::none(), &mut NullSaveWriter).unwrap()
+    }
+
+    fn run_frame(emulator: &mut SnesEmulator) {
+        emulator
+            .run_frames(
+                1,
+                &mut NullRenderer,
+                &mut NullAudioOutput,
+                &SnesInputs::default(),
+                &mut NullSaveWriter,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn reset_reads_reset_vector() {
+        // LoROM with reset vector = $8123
+        let mut rom = vec![0; 0x8000];
+        rom[0x7FFC..0x7FFE].copy_from_slice(&[0x23, 0x81]);
+        // LDA #$12
+        // PHA
+        // PLB
+        // TCD
+        // BRA -2 (infinite loop)
+        rom[0x0123..0x012A].copy_from_slice(&[0xA9, 0x12, 0x48, 0xAB, 0x5B, 0x80, 0xFE]);
+
+        let assert_power_on_state = |emulator: &SnesEmulator| {
+            let registers = emulator.main_cpu.registers();
+            assert_eq!(registers.pbr, 0x00);
+            assert_eq!(registers.pc, 0x8123);
+            assert_eq!(registers.dbr, 0x00);
+            assert_eq!(registers.d, 0x0000);
+            assert_eq!(registers.s & 0xFF00, 0x0100);
+            assert!(registers.emulation_mode);
+            // m, x, and i set; d clear
+            assert_eq!(u8::from(registers.p) & 0x3C, 0x34);
+        };
+
+        let mut emulator = create_emulator(rom);
+        assert_power_on_state(&emulator);
+
+        run_frame(&mut emulator);
+        assert_eq!(emulator.main_cpu.registers().dbr, 0x12);
+        assert_eq!(emulator.main_cpu.registers().d, 0x0012);
+
+        emulator.soft_reset();
+        assert_power_on_state(&emulator);
+    }
+
+    #[test]
+    fn memsel_access_cycles() {
+        let mut emulator = new_emulator();
+
+        let access_cycles = |emulator: &mut SnesEmulator, address: u32| {
+            let mut bus = new_bus!(emulator);
+            bus.read(address);
+            bus.access_master_cycles
+        };
+
+        // Memory-2 is slow until MEMSEL bit 0 is set
+        assert_eq!(access_cycles(&mut emulator, 0x008000), 8);
+        assert_eq!(access_cycles(&mut emulator, 0x808000), 8);
+        assert_eq!(access_cycles(&mut emulator, 0xC00000), 8);
+
+        new_bus!(emulator).write(0x00420D, 0x01);
+        assert_eq!(access_cycles(&mut emulator, 0x808000), 6);
+        assert_eq!(access_cycles(&mut emulator, 0xC00000), 6);
+
+        // Memory-1 and WRAM are always slow
+        assert_eq!(access_cycles(&mut emulator, 0x008000), 8);
+        assert_eq!(access_cycles(&mut emulator, 0x7E0000), 8);
+
+        new_bus!(emulator).write(0x00420D, 0x00);
+        assert_eq!(access_cycles(&mut emulator, 0x808000), 8);
+    }
+
+    #[test]
+    fn cheats_pin_wram() {
+        let mut emulator = new_emulator();
+
+        let mut cheats = CheatList::new();
+        cheats.add("7E1000:99".parse().unwrap());
+        cheats.add("7E1234:56".parse().unwrap());
+        emulator.set_cheats(cheats);
+
+        for _ in 0..3 {
+            run_frame(&mut emulator);
+            assert_eq!(emulator.debug_read(0x7E1000), 0x99);
+            assert_eq!(emulator.debug_read(0x7E1234), 0x56);
+        }
+    }
+
+    #[test]
+    fn rom_cheats_are_ignored() {
+        let mut emulator = new_emulator();
+
+        let mut cheats = CheatList::new();
+        cheats.add("008000:EA".parse().unwrap());
+        cheats.add("7E1234:56".parse().unwrap());
+        emulator.set_cheats(cheats);
+
+        assert_eq!(
+            emulator.cheats().codes(),
+            &[ProActionReplayCode { address: 0x7E1234, value: 0x56 }]
+        );
+        assert_eq!(emulator.debug_read(0x008000), 0xA9);
+    }
+
+    #[test]
+    fn perf_counters() {
+        let mut emulator = new_emulator();
+        run_frame(&mut emulator);
+
+        // Counters must not leak into save states
+        let config = bincode::config::standard();
+        let state = bincode::encode_to_vec(&emulator, config).unwrap();
+
+        let counters = emulator.perf_counters();
+        assert_eq!(state, bincode::encode_to_vec(&emulator, config).unwrap());
+        assert_ne!(counters.master_clock_cycles, 0);
+        assert_ne!(counters.instructions, 0);
+
+        // Every instruction takes at least 2 CPU cycles of at least 6 master clock cycles each
+        assert!(counters.instructions * 12 <= counters.master_clock_cycles);
+
+        assert_eq!(emulator.perf_counters(), PerfCounters::default());
+    }
 }
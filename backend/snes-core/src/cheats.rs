@@ -0,0 +1,84 @@
+//! Pro Action Replay cheat codes
+
+use bincode::{Decode, Encode};
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CheatCodeError {
+    #[error("Invalid Pro Action Replay code, expected XXXXXX:YY: '{0}'")]
+    InvalidFormat(String),
+}
+
+/// A Pro Action Replay code, which writes a byte to the given address once per frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub struct ProActionReplayCode {
+    pub address: u32,
+    pub value: u8,
+}
+
+impl FromStr for ProActionReplayCode {
+    type Err = CheatCodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.trim();
+        let invalid = || CheatCodeError::InvalidFormat(code.into());
+
+        let (address, value) = code.split_once(':').ok_or_else(invalid)?;
+        if address.len() != 6 || value.len() != 2 {
+            return Err(invalid());
+        }
+
+        let address = u32::from_str_radix(address, 16).map_err(|_| invalid())?;
+        let value = u8::from_str_radix(value, 16).map_err(|_| invalid())?;
+
+        Ok(Self { address, value })
+    }
+}
+
+#[derive(Debug, Clone, Default, Encode, Decode)]
+pub struct CheatList {
+    codes: Vec<ProActionReplayCode>,
+}
+
+impl CheatList {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, code: ProActionReplayCode) {
+        self.codes.push(code);
+    }
+
+    pub fn clear(&mut self) {
+        self.codes.clear();
+    }
+
+    #[must_use]
+    pub fn codes(&self) -> &[ProActionReplayCode] {
+        &self.codes
+    }
+
+    pub(crate) fn retain(&mut self, f: impl FnMut(&ProActionReplayCode) -> bool) {
+        self.codes.retain(f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!("7E0DBE:63".parse(), Ok(ProActionReplayCode { address: 0x7E0DBE, value: 0x63 }));
+        assert_eq!(
+            " 7e0dbe:ff ".parse(),
+            Ok(ProActionReplayCode { address: 0x7E0DBE, value: 0xFF })
+        );
+
+        assert!("7E0DBE63".parse::<ProActionReplayCode>().is_err());
+        assert!("7E0DBE:063".parse::<ProActionReplayCode>().is_err());
+        assert!("7E0DBG:63".parse::<ProActionReplayCode>().is_err());
+    }
+}
@@ -2,6 +2,7 @@ pub mod api;
 mod apu;
 mod audio;
 mod bus;
+pub mod cheats;
 pub(crate) mod constants;
 pub mod input;
 mod memory;
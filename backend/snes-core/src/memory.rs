@@ -12,12 +12,19 @@ use crate::ppu::Ppu;
 use bincode::{Decode, Encode};
 use jgenesis_common::frontend::{SaveWriter, TimingMode};
 use jgenesis_common::num::{GetBit, U16Ext, U24Ext};
-use jgenesis_proc_macros::PartialClone;
-use std::array;
+use jgenesis_common::ram::RamInitPattern;
+use jgenesis_common::rng::Rng;
+use jgenesis_proc_macros::{FakeDecode, FakeEncode, PartialClone};
 use std::num::NonZeroU64;
+use std::ops::RangeInclusive;
+use std::{array, fmt, mem};
 
 const MAIN_RAM_LEN: usize = 128 * 1024;
 
+// Memory watches report WRAM accesses at their bank $7E-$7F addresses regardless of which mirror
+// or port was used
+const WRAM_BASE_ADDRESS: u32 = 0x7E0000;
+
 // H=32.5
 const AUTO_JOYPAD_START_MCLK: u64 = 130;
 
@@ -39,13 +46,48 @@ impl Memory2Speed {
     }
 }
 
-#[derive(Debug, Clone, Encode, Decode, PartialClone)]
+/// Memory watch callback. Called with the 24-bit CPU address, the byte value, and whether the
+/// access was a write.
+pub type MemoryWatchFn = dyn FnMut(u32, u8, bool);
+
+// Not saved in save states; the callback belongs to the frontend
+#[derive(Default, FakeEncode, FakeDecode)]
+struct MemoryWatch {
+    start: u32,
+    end: u32,
+    callback: Option<Box<MemoryWatchFn>>,
+}
+
+impl MemoryWatch {
+    #[inline]
+    fn notify(&mut self, address: u32, value: u8, is_write: bool) {
+        if let Some(callback) = &mut self.callback {
+            if (self.start..=self.end).contains(&address) {
+                callback(address, value, is_write);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for MemoryWatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryWatch")
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .field("callback", &self.callback.as_ref().map(|_| "<callback>"))
+            .finish()
+    }
+}
+
+#[derive(Debug, Encode, Decode, PartialClone)]
 pub struct Memory {
     #[partial_clone(partial)]
     cartridge: Cartridge,
     main_ram: Box<MainRam>,
     wram_port_address: u32,
     cpu_open_bus: u8,
+    #[partial_clone(default)]
+    watch: MemoryWatch,
 }
 
 impl Memory {
@@ -73,13 +115,19 @@ impl Memory {
             main_ram: vec![0; MAIN_RAM_LEN].into_boxed_slice().try_into().unwrap(),
             wram_port_address: 0,
             cpu_open_bus: 0,
+            watch: MemoryWatch::default(),
         })
     }
 
+    pub fn init_main_ram(&mut self, ram_init_pattern: RamInitPattern, rng: &mut Rng) {
+        ram_init_pattern.fill(self.main_ram.as_mut_slice(), rng);
+    }
+
     pub fn read_cartridge(&mut self, address: u32) -> Option<u8> {
         match self.cartridge.read(address) {
             Some(value) => {
                 self.cpu_open_bus = value;
+                self.watch.notify(address, value, false);
                 Some(value)
             }
             None => None,
@@ -87,9 +135,23 @@ impl Memory {
     }
 
     pub fn write_cartridge(&mut self, address: u32, value: u8) {
+        self.watch.notify(address, value, true);
         self.cartridge.write(address, value);
     }
 
+    /// Call `callback` on every CPU read or write of WRAM or the cartridge that falls within
+    /// `range`, replacing any existing watch. WRAM accesses are reported at their $7E0000-$7FFFFF
+    /// addresses, including accesses through the system area mirror and the WMDATA port. Debug
+    /// reads and writes do not trigger the watch.
+    pub fn set_watch(&mut self, range: RangeInclusive<u32>, callback: Box<MemoryWatchFn>) {
+        self.watch =
+            MemoryWatch { start: *range.start(), end: *range.end(), callback: Some(callback) };
+    }
+
+    pub fn clear_watch(&mut self) {
+        self.watch = MemoryWatch::default();
+    }
+
     pub fn cartridge_irq(&self) -> bool {
         self.cartridge.irq()
     }
@@ -118,21 +180,72 @@ impl Memory {
         cartridge::region_to_timing_mode(region_byte)
     }
 
-    pub fn read_wram(&self, address: u32) -> u8 {
-        self.main_ram[(address as usize) & (MAIN_RAM_LEN - 1)]
+    /// Read a byte from the CPU address space without side effects, for debuggers.
+    ///
+    /// WRAM, the WMDATA port, and cartridge ROM/SRAM are readable. Reading WMDATA does not
+    /// increment the WRAM port address. PPU, APU, and CPU I/O registers are never read and return
+    /// CPU open bus, as do coprocessor-handled cartridge addresses.
+    pub fn debug_read(&self, address: u32) -> u8 {
+        let bank = (address >> 16) as u8;
+        let offset = address as u16;
+        match (bank, offset) {
+            (0x00..=0x3F | 0x80..=0xBF, 0x0000..=0x1FFF) => {
+                self.main_ram[wram_index(offset.into())]
+            }
+            (0x00..=0x3F | 0x80..=0xBF, 0x2180) => self.main_ram[self.wram_port_address as usize],
+            (0x00..=0x3F | 0x80..=0xBF, 0x2100..=0x217F | 0x4000..=0x5FFF) => self.cpu_open_bus,
+            (0x7E..=0x7F, _) => self.main_ram[wram_index(address)],
+            _ => self.cartridge.debug_read(address).unwrap_or(self.cpu_open_bus),
+        }
+    }
+
+    /// Write a byte to the CPU address space without side effects, for debuggers and cheats.
+    ///
+    /// Only WRAM, the WMDATA port, and cartridge SRAM are writable. Returns `false` if the write
+    /// was ignored, e.g. because the address maps to ROM or to an I/O register.
+    pub fn debug_write(&mut self, address: u32, value: u8) -> bool {
+        let bank = (address >> 16) as u8;
+        let offset = address as u16;
+        match (bank, offset) {
+            (0x00..=0x3F | 0x80..=0xBF, 0x0000..=0x1FFF) => {
+                self.main_ram[wram_index(offset.into())] = value;
+                true
+            }
+            (0x00..=0x3F | 0x80..=0xBF, 0x2180) => {
+                self.main_ram[self.wram_port_address as usize] = value;
+                true
+            }
+            (0x00..=0x3F | 0x80..=0xBF, 0x2100..=0x217F | 0x4000..=0x5FFF) => false,
+            (0x7E..=0x7F, _) => {
+                self.main_ram[wram_index(address)] = value;
+                true
+            }
+            _ => self.cartridge.debug_write(address, value),
+        }
+    }
+
+    pub fn read_wram(&mut self, address: u32) -> u8 {
+        let index = wram_index(address);
+        let value = self.main_ram[index];
+        self.watch.notify(WRAM_BASE_ADDRESS | index as u32, value, false);
+        value
     }
 
     pub fn write_wram(&mut self, address: u32, value: u8) {
-        self.main_ram[(address as usize) & (MAIN_RAM_LEN - 1)] = value;
+        let index = wram_index(address);
+        self.watch.notify(WRAM_BASE_ADDRESS | index as u32, value, true);
+        self.main_ram[index] = value;
     }
 
     pub fn read_wram_port(&mut self) -> u8 {
         let value = self.main_ram[self.wram_port_address as usize];
+        self.watch.notify(WRAM_BASE_ADDRESS | self.wram_port_address, value, false);
         self.increment_wram_port_address();
         value
     }
 
     pub fn write_wram_port(&mut self, value: u8) {
+        self.watch.notify(WRAM_BASE_ADDRESS | self.wram_port_address, value, true);
         self.main_ram[self.wram_port_address as usize] = value;
         self.increment_wram_port_address();
     }
@@ -163,6 +276,15 @@ impl Memory {
 
     pub fn take_rom_from(&mut self, other: &mut Self) {
         self.cartridge.take_rom_from(&mut other.cartridge);
+        self.take_watch_from(other);
+    }
+
+    pub fn take_watch_from(&mut self, other: &mut Self) {
+        self.watch = mem::take(&mut other.watch);
+    }
+
+    pub fn main_ram(&self) -> &[u8] {
+        self.main_ram.as_slice()
     }
 
     pub fn sram(&self) -> Option<&[u8]> {
@@ -313,6 +435,9 @@ pub struct CpuInternalRegisters {
     division_dividend: u16,
     division_divisor: u8,
     division_quotient: u16,
+    multiply_steps_remaining: u8,
+    divide_steps_remaining: u8,
+    alu_shift: u32,
     memory_2_speed: Memory2Speed,
     active_gpdma_channels: [bool; 8],
     active_hdma_channels: [bool; 8],
@@ -354,6 +479,9 @@ impl CpuInternalRegisters {
             division_dividend: 0xFFFF,
             division_divisor: 0xFF,
             division_quotient: 0,
+            multiply_steps_remaining: 0,
+            divide_steps_remaining: 0,
+            alu_shift: 0,
             memory_2_speed: Memory2Speed::default(),
             active_gpdma_channels: [false; 8],
             active_hdma_channels: [false; 8],
@@ -517,16 +645,23 @@ impl CpuInternalRegisters {
             }
             0x4203 => {
                 // WRMPYB: Multiplication 8-bit operand B + start multiplication
-                self.multiply_operand_r = value;
+                // The product register is always cleared, even if the ALU is busy
+                self.multiply_product = 0;
+                if self.alu_busy() {
+                    log::trace!("  Ignoring WRMPYB write while ALU is busy");
+                    return;
+                }
 
-                // TODO delay setting the result? takes 8 CPU cycles on real hardware
-                self.multiply_product = u16::from(self.multiply_operand_l) * u16::from(value);
+                self.multiply_operand_r = value;
 
-                // Multiplication always writes operand B to the division quotient register
-                self.division_quotient = value.into();
+                // Multiplication takes 8 CPU cycles, one per bit of operand A. The quotient register
+                // is used as a shift register holding both operands, which leaves operand B in the
+                // quotient register after the multiplication completes
+                self.division_quotient = u16::from_le_bytes([self.multiply_operand_l, value]);
+                self.alu_shift = value.into();
+                self.multiply_steps_remaining = 8;
 
                 log::trace!("  Unsigned multiply operand B: {value:02X}");
-                log::trace!("  Unsigned multiply product: {:04X}", self.multiply_product);
             }
             0x4204 => {
                 // WRDIVL: Division 16-bit dividend, low byte
@@ -542,23 +677,23 @@ impl CpuInternalRegisters {
             }
             0x4206 => {
                 // WRDIVB: Division 8-bit divisor + start division
-                self.division_divisor = value;
+                // Division writes remainder to the multiply product register, which starts out as
+                // the dividend. This happens even if the ALU is busy
+                self.multiply_product = self.division_dividend;
+                if self.alu_busy() {
+                    log::trace!("  Ignoring WRDIVB write while ALU is busy");
+                    return;
+                }
 
-                // TODO delay setting the result? takes 16 CPU cycles on real hardware
-                if value != 0 {
-                    self.division_quotient = self.division_dividend / u16::from(value);
+                self.division_divisor = value;
 
-                    // Division writes remainder to the multiply product register
-                    self.multiply_product = self.division_dividend % u16::from(value);
-                } else {
-                    // Divide by 0 always sets quotient to $FFFF and remainder to dividend
-                    self.division_quotient = 0xFFFF;
-                    self.multiply_product = self.division_dividend;
-                }
+                // Division takes 16 CPU cycles, one per bit of the quotient.
+                // Dividing by 0 naturally produces a quotient of $FFFF and a remainder equal to
+                // the dividend
+                self.alu_shift = u32::from(value) << 16;
+                self.divide_steps_remaining = 16;
 
                 log::trace!("  Unsigned divide divisor: {value:02X}");
-                log::trace!("  Unsigned divide quotient: {:04X}", self.division_quotient);
-                log::trace!("  Unsigned divide remainder: {:04X}", self.multiply_product);
             }
             0x4207 => {
                 // HTIMEL: H-count timer setting, low byte
@@ -805,6 +940,9 @@ impl CpuInternalRegisters {
         prev_scanline_mclk: u64,
         inputs: &SnesInputs,
     ) {
+        // Progress multiplication/division if in progress
+        self.step_alu();
+
         // Progress auto joypad read if it's running
         self.input_state.tick(master_cycles_elapsed, *inputs);
 
@@ -824,6 +962,45 @@ impl CpuInternalRegisters {
         }
     }
 
+    fn alu_busy(&self) -> bool {
+        self.multiply_steps_remaining != 0 || self.divide_steps_remaining != 0
+    }
+
+    // Multiplication and division are performed one bit per CPU cycle using shift-and-add and
+    // shift-and-subtract, and reading the result registers mid-operation returns the partial
+    // result
+    fn step_alu(&mut self) {
+        if self.multiply_steps_remaining != 0 {
+            self.multiply_steps_remaining -= 1;
+
+            if self.division_quotient.bit(0) {
+                self.multiply_product = self.multiply_product.wrapping_add(self.alu_shift as u16);
+            }
+            self.division_quotient >>= 1;
+            self.alu_shift <<= 1;
+
+            if self.multiply_steps_remaining == 0 {
+                log::trace!("Unsigned multiply product: {:04X}", self.multiply_product);
+            }
+        }
+
+        if self.divide_steps_remaining != 0 {
+            self.divide_steps_remaining -= 1;
+
+            self.division_quotient <<= 1;
+            self.alu_shift >>= 1;
+            if u32::from(self.multiply_product) >= self.alu_shift {
+                self.multiply_product -= self.alu_shift as u16;
+                self.division_quotient |= 1;
+            }
+
+            if self.divide_steps_remaining == 0 {
+                log::trace!("Unsigned divide quotient: {:04X}", self.division_quotient);
+                log::trace!("Unsigned divide remainder: {:04X}", self.multiply_product);
+            }
+        }
+    }
+
     fn update_hv_blank_flags(&mut self, ppu: &Ppu) {
         let vblank_flag = ppu.vblank_flag();
         if !self.vblank_flag && vblank_flag {
@@ -921,6 +1098,10 @@ impl CpuInternalRegisters {
     }
 }
 
+fn wram_index(address: u32) -> usize {
+    (address as usize) & (MAIN_RAM_LEN - 1)
+}
+
 fn check_v_irq(scanline_mclk: u64, master_cycles_elapsed: u64) -> bool {
     scanline_mclk >= V_IRQ_H_MCLK
         && scanline_mclk.saturating_sub(master_cycles_elapsed) < V_IRQ_H_MCLK
@@ -933,3 +1114,359 @@ fn check_htime_passed(prev_scanline_mclk: u64, scanline_mclk: u64, htime: u16) -
     scanline_mclk >= htime_mclk
         && (prev_scanline_mclk < htime_mclk || scanline_mclk < prev_scanline_mclk)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{SnesInputDevice, SnesJoypadState};
+    use jgenesis_common::testing::NullSaveWriter;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn new_memory() -> Memory {
+        let mut rom = vec![0; 0x8000];
+        rom[0x7FC0..0x7FC5].copy_from_slice(b"TITLE");
+
+        create_memory(rom, None)
+    }
+
+    fn create_memory(rom: Vec<u8>, initial_sram: Option<Vec<u8>>) -> Memory {
+        Memory::create(
+            rom,
+            initial_sram,
+            &CoprocessorRoms::none(),
+            None,
+            NonZeroU64::new(1).unwrap(),
+            &mut NullSaveWriter,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn lorom_sram() {
+        // LoROM with 2KB of SRAM
+        let mut rom = vec![0; 0x8000];
+        rom[0x7FD8] = 0x01;
+        let mut memory = create_memory(rom, None);
+        assert_eq!(memory.sram().map(<[u8]>::len), Some(0x800));
+
+        memory.write_cartridge(0x700123, 0xAB);
+        assert_eq!(memory.read_cartridge(0x700123), Some(0xAB));
+
+        // SRAM is mirrored every 2KB throughout banks $70-$7D and $F0-$FF
+        assert_eq!(memory.read_cartridge(0x700923), Some(0xAB));
+        assert_eq!(memory.read_cartridge(0x7D0123), Some(0xAB));
+        assert_eq!(memory.read_cartridge(0xF07923), Some(0xAB));
+        assert_eq!(memory.sram().unwrap()[0x123], 0xAB);
+
+        // ROM is still read-only
+        memory.write_cartridge(0x008000, 0xCD);
+        assert_eq!(memory.read_cartridge(0x008000), Some(0x00));
+    }
+
+    #[test]
+    fn hirom_sram() {
+        // HiROM with 8KB of SRAM and a valid RESET vector at $8000
+        let mut rom = vec![0; 0x10000];
+        rom[0xFFD5] = 0x21;
+        rom[0xFFD8] = 0x03;
+        rom[0xFFFC..0xFFFE].copy_from_slice(&[0x00, 0x80]);
+        rom[0x8000] = 0x78;
+        let mut memory = create_memory(rom, None);
+        assert_eq!(memory.sram().map(<[u8]>::len), Some(0x2000));
+
+        memory.write_cartridge(0x306123, 0xAB);
+        assert_eq!(memory.read_cartridge(0x306123), Some(0xAB));
+        assert_eq!(memory.read_cartridge(0xB06123), Some(0xAB));
+        assert_eq!(memory.sram().unwrap()[0x0123], 0xAB);
+
+        // SRAM is not mapped in banks $00-$1F
+        assert_eq!(memory.read_cartridge(0x006123), None);
+    }
+
+    #[test]
+    fn initial_sram_is_loaded() {
+        let mut rom = vec![0; 0x8000];
+        rom[0x7FD8] = 0x01;
+
+        let mut sram = vec![0; 0x800];
+        sram[0x456] = 0x78;
+        let mut memory = create_memory(rom.clone(), Some(sram));
+        assert_eq!(memory.read_cartridge(0x700456), Some(0x78));
+
+        // Save files of the wrong size are ignored
+        let mut memory = create_memory(rom, Some(vec![0xFF; 0x400]));
+        assert_eq!(memory.read_cartridge(0x700456), Some(0x00));
+    }
+
+    #[test]
+    fn debug_read() {
+        let mut memory = new_memory();
+        memory.write_wram(0x000123, 0xAB);
+        memory.write_wram(0x010123, 0xCD);
+
+        assert_eq!(memory.debug_read(0x7E0123), 0xAB);
+        assert_eq!(memory.debug_read(0x7F0123), 0xCD);
+
+        // System area mirrors of the first 8KB of WRAM
+        assert_eq!(memory.debug_read(0x000123), 0xAB);
+        assert_eq!(memory.debug_read(0xBF0123), 0xAB);
+
+        // Cartridge ROM
+        assert_eq!(memory.debug_read(0x00FFC0), b'T');
+    }
+
+    #[test]
+    fn debug_write() {
+        let mut memory = new_memory();
+
+        assert!(memory.debug_write(0x7E0123, 0xAB));
+        assert_eq!(memory.read_wram(0x000123), 0xAB);
+
+        assert!(memory.debug_write(0x000456, 0xCD));
+        assert_eq!(memory.debug_read(0x7E0456), 0xCD);
+
+        // ROM writes are ignored
+        assert!(!memory.debug_write(0x00FFC0, 0x00));
+        assert_eq!(memory.debug_read(0x00FFC0), b'T');
+
+        // I/O registers are never written
+        assert!(!memory.debug_write(0x002100, 0x80));
+    }
+
+    #[test]
+    fn debug_read_wram_port() {
+        let mut memory = new_memory();
+        memory.write_wram(0x000123, 0xAB);
+
+        memory.write_wram_port_address_low(0x23);
+        memory.write_wram_port_address_mid(0x01);
+        memory.write_wram_port_address_high(0x00);
+
+        assert_eq!(memory.debug_read(0x002180), 0xAB);
+        assert_eq!(memory.debug_read(0x002180), 0xAB);
+        assert_eq!(memory.wram_port_address, 0x000123);
+
+        assert_eq!(memory.read_wram_port(), 0xAB);
+        assert_eq!(memory.wram_port_address, 0x000124);
+    }
+
+    #[test]
+    fn wram_write_watch() {
+        let mut memory = new_memory();
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let callback_events = Rc::clone(&events);
+        memory.set_watch(
+            0x7E0123..=0x7E0123,
+            Box::new(move |address, value, is_write| {
+                callback_events.borrow_mut().push((address, value, is_write));
+            }),
+        );
+
+        // System area mirror is reported at the bank $7E address
+        memory.write_wram(0x000123, 0xAB);
+        memory.write_wram(0x000124, 0xCD);
+        assert_eq!(memory.read_wram(0x7E0123), 0xAB);
+
+        // Debug accesses do not trigger the watch
+        memory.debug_write(0x7E0123, 0xEF);
+
+        memory.write_wram_port_address_low(0x23);
+        memory.write_wram_port_address_mid(0x01);
+        memory.write_wram_port_address_high(0x00);
+        memory.write_wram_port(0x12);
+
+        assert_eq!(
+            *events.borrow(),
+            vec![(0x7E0123, 0xAB, true), (0x7E0123, 0xAB, false), (0x7E0123, 0x12, true)]
+        );
+
+        memory.clear_watch();
+        memory.write_wram(0x000123, 0x34);
+        assert_eq!(events.borrow().len(), 3);
+    }
+
+    fn read_u16(registers: &mut CpuInternalRegisters, address: u32) -> u16 {
+        let lsb = registers.read_register(address, 0).unwrap();
+        let msb = registers.read_register(address + 1, 0).unwrap();
+        u16::from_le_bytes([lsb, msb])
+    }
+
+    fn step_alu(registers: &mut CpuInternalRegisters, steps: u32) {
+        for _ in 0..steps {
+            registers.step_alu();
+        }
+    }
+
+    #[test]
+    fn multiply_latency() {
+        let mut registers = CpuInternalRegisters::new();
+
+        // $FF * $0F
+        registers.write_register(0x4202, 0xFF);
+        registers.write_register(0x4203, 0x0F);
+        assert_eq!(read_u16(&mut registers, 0x4216), 0x0000);
+
+        step_alu(&mut registers, 1);
+        assert_eq!(read_u16(&mut registers, 0x4216), 0x000F);
+
+        step_alu(&mut registers, 3);
+        assert_eq!(read_u16(&mut registers, 0x4216), 0x00E1);
+
+        step_alu(&mut registers, 4);
+        assert_eq!(read_u16(&mut registers, 0x4216), 0x0EF1);
+        assert_eq!(read_u16(&mut registers, 0x4214), 0x000F);
+
+        // Result should not change once the multiplication has completed
+        step_alu(&mut registers, 8);
+        assert_eq!(read_u16(&mut registers, 0x4216), 0x0EF1);
+    }
+
+    #[test]
+    fn divide_latency() {
+        let mut registers = CpuInternalRegisters::new();
+
+        // $1234 / $56 = $36 remainder $10
+        registers.write_register(0x4204, 0x34);
+        registers.write_register(0x4205, 0x12);
+        registers.write_register(0x4206, 0x56);
+        assert_eq!(read_u16(&mut registers, 0x4216), 0x1234);
+
+        step_alu(&mut registers, 8);
+        assert_eq!(read_u16(&mut registers, 0x4214), 0x0000);
+        assert_eq!(read_u16(&mut registers, 0x4216), 0x1234);
+
+        step_alu(&mut registers, 4);
+        assert_eq!(read_u16(&mut registers, 0x4214), 0x0003);
+        assert_eq!(read_u16(&mut registers, 0x4216), 0x0214);
+
+        step_alu(&mut registers, 4);
+        assert_eq!(read_u16(&mut registers, 0x4214), 0x0036);
+        assert_eq!(read_u16(&mut registers, 0x4216), 0x0010);
+    }
+
+    #[test]
+    fn divide_by_zero() {
+        let mut registers = CpuInternalRegisters::new();
+
+        registers.write_register(0x4204, 0xCD);
+        registers.write_register(0x4205, 0xAB);
+        registers.write_register(0x4206, 0x00);
+        step_alu(&mut registers, 16);
+
+        assert_eq!(read_u16(&mut registers, 0x4214), 0xFFFF);
+        assert_eq!(read_u16(&mut registers, 0x4216), 0xABCD);
+    }
+
+    fn test_inputs() -> SnesInputs {
+        let p1 = SnesJoypadState {
+            b: true,
+            start: true,
+            a: true,
+            r: true,
+            ..SnesJoypadState::default()
+        };
+        let p2 = SnesJoypadState { left: true, x: true, ..SnesJoypadState::default() };
+        SnesInputs { p1, p2: SnesInputDevice::Controller(p2) }
+    }
+
+    #[test]
+    fn auto_joypad_read() {
+        let mut registers = CpuInternalRegisters::new();
+        let inputs = test_inputs();
+
+        registers.write_register(0x4200, 0x01);
+        assert!(registers.auto_joypad_read_enabled);
+
+        registers.input_state.tick(8, inputs);
+        registers.input_state.start_auto_joypad_read();
+
+        // HVBJOY bit 0 is set while the read is in progress
+        registers.input_state.tick(4000, inputs);
+        assert_eq!(registers.read_register(0x4212, 0).map(|hvbjoy| hvbjoy & 0x01), Some(0x01));
+        assert_eq!(read_u16(&mut registers, 0x4218), 0x0000);
+
+        registers.input_state.tick(400, inputs);
+        assert_eq!(registers.read_register(0x4212, 0).map(|hvbjoy| hvbjoy & 0x01), Some(0x00));
+
+        // Bit order from MSB to LSB: B, Y, Select, Start, Up, Down, Left, Right, A, X, L, R
+        assert_eq!(read_u16(&mut registers, 0x4218), 0x9090);
+        assert_eq!(read_u16(&mut registers, 0x421A), 0x0240);
+
+        // Manual joypad reads return 1s after the auto read drained them
+        assert_eq!(registers.read_register(0x4016, 0), Some(0x01));
+    }
+
+    #[test]
+    fn manual_joypad_read() {
+        let mut registers = CpuInternalRegisters::new();
+        registers.input_state.tick(8, test_inputs());
+
+        // Strobe JOYWR to latch the current inputs, then shift them out MSB-first
+        registers.write_register(0x4016, 0x01);
+        registers.write_register(0x4016, 0x00);
+
+        let p1_word = (0..16).fold(0_u16, |word, _| {
+            (word << 1) | u16::from(registers.read_register(0x4016, 0).unwrap() & 0x01)
+        });
+        assert_eq!(p1_word, 0x9090);
+
+        let p2_word = (0..16).fold(0_u16, |word, _| {
+            (word << 1) | u16::from(registers.read_register(0x4017, 0).unwrap() & 0x01)
+        });
+        assert_eq!(p2_word, 0x0240);
+
+        // Reads past the 16th bit return 1
+        assert_eq!(registers.read_register(0x4016, 0), Some(0x01));
+        assert_eq!(registers.read_register(0x4017, 0), Some(0x1D));
+    }
+
+    #[test]
+    fn rdnmi_read_clears_nmi_flag() {
+        let mut registers = CpuInternalRegisters::new();
+        registers.vblank_nmi_flag = true;
+
+        // Bit 7 is the NMI flag, bits 6-4 are open bus, and bits 3-0 are the CPU version
+        assert_eq!(registers.read_register(0x4210, 0xFF), Some(0xF2));
+        assert_eq!(registers.read_register(0x4210, 0xFF), Some(0x72));
+        assert_eq!(registers.read_register(0x4210, 0x00), Some(0x02));
+    }
+
+    #[test]
+    fn timeup_read_clears_irq_flag() {
+        let mut registers = CpuInternalRegisters::new();
+        registers.irq_pending = true;
+
+        assert_eq!(registers.read_register(0x4211, 0x00), Some(0x80));
+        assert!(!registers.irq_pending());
+        assert_eq!(registers.read_register(0x4211, 0x00), Some(0x00));
+
+        // Bits 6-0 are open bus
+        assert_eq!(registers.read_register(0x4211, 0xFF), Some(0x7F));
+
+        // Disabling IRQs through NMITIMEN also acknowledges a pending IRQ
+        registers.irq_pending = true;
+        registers.write_register(0x4200, 0x00);
+        assert_eq!(registers.read_register(0x4211, 0x00), Some(0x00));
+    }
+
+    #[test]
+    fn hvbjoy_read() {
+        let mut registers = CpuInternalRegisters::new();
+        assert_eq!(registers.read_register(0x4212, 0x00), Some(0x00));
+
+        registers.vblank_flag = true;
+        assert_eq!(registers.read_register(0x4212, 0x00), Some(0x80));
+
+        registers.hblank_flag = true;
+        assert_eq!(registers.read_register(0x4212, 0x00), Some(0xC0));
+
+        // Reading HVBJOY does not clear the flags; bits 5-1 are open bus
+        assert_eq!(registers.read_register(0x4212, 0xFF), Some(0xFE));
+
+        registers.vblank_flag = false;
+        registers.input_state.start_auto_joypad_read();
+        assert_eq!(registers.read_register(0x4212, 0x00), Some(0x41));
+    }
+}
@@ -66,6 +66,18 @@ impl Display for CartridgeType {
     }
 }
 
+impl CartridgeType {
+    fn header_addr(self) -> usize {
+        match self {
+            Self::LoRom | Self::Cx4 | Self::Obc1 | Self::Sa1 | Self::Sdd1 | Self::SuperFx => {
+                LOROM_HEADER_ADDR
+            }
+            Self::HiRom | Self::Spc7110 => HIROM_HEADER_ADDR,
+            Self::ExHiRom => EXHIROM_HEADER_ADDR,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum DspVariant {
     Dsp1,
@@ -211,7 +223,7 @@ impl Cartridge {
         save_writer: &mut S,
     ) -> SnesLoadResult<Self> {
         // Older SNES ROM images have an extra 512-byte header; check for that and strip it off
-        if rom.len() & 0x7FFF == 0x0200 {
+        if has_copier_header(&rom) {
             let stripped_rom = rom[0x200..].to_vec().into_boxed_slice();
             return Self::create(
                 stripped_rom,
@@ -228,16 +240,7 @@ impl Cartridge {
             CartridgeType::LoRom
         });
 
-        let rom_header_addr = match cartridge_type {
-            CartridgeType::LoRom
-            | CartridgeType::Cx4
-            | CartridgeType::Obc1
-            | CartridgeType::Sa1
-            | CartridgeType::Sdd1
-            | CartridgeType::SuperFx => LOROM_HEADER_ADDR,
-            CartridgeType::HiRom | CartridgeType::Spc7110 => HIROM_HEADER_ADDR,
-            CartridgeType::ExHiRom => EXHIROM_HEADER_ADDR,
-        };
+        let rom_header_addr = cartridge_type.header_addr();
 
         // Determine NTSC/PAL
         let region_byte = rom[rom_header_addr + 0x19];
@@ -402,6 +405,66 @@ impl Cartridge {
         }
     }
 
+    /// Read from cartridge ROM or SRAM without side effects. Returns `None` for unmapped addresses
+    /// and for any cartridge or address range that is handled by a coprocessor.
+    pub fn debug_read(&self, address: u32) -> Option<u8> {
+        let (Self::LoRom { rom, sram, .. }
+        | Self::HiRom { rom, sram, .. }
+        | Self::ExHiRom { rom, sram, .. }
+        | Self::DspLoRom { rom, sram, .. }
+        | Self::DspHiRom { rom, sram, .. }) = self
+        else {
+            return None;
+        };
+
+        match self.debug_map_address(address) {
+            CartridgeAddress::None => None,
+            CartridgeAddress::Rom(rom_addr) => Some(rom[rom_addr as usize]),
+            CartridgeAddress::Sram(sram_addr) => Some(sram[sram_addr as usize]),
+        }
+    }
+
+    /// Write to cartridge SRAM without side effects. Returns `false` and does nothing if the
+    /// address is not mapped to SRAM, including ROM addresses and coprocessor-handled addresses.
+    pub fn debug_write(&mut self, address: u32, value: u8) -> bool {
+        match self.debug_map_address(address) {
+            CartridgeAddress::Sram(_) => {
+                self.write(address, value);
+                true
+            }
+            CartridgeAddress::Rom(_) | CartridgeAddress::None => false,
+        }
+    }
+
+    // Map an address to ROM or SRAM, ignoring any address range that is handled by a coprocessor
+    fn debug_map_address(&self, address: u32) -> CartridgeAddress {
+        let bank = (address >> 16) & 0xFF;
+        let offset = address & 0xFFFF;
+        match self {
+            Self::LoRom { sram, mask, .. } => lorom_map_address(address, *mask, sram.len() as u32),
+            Self::DspLoRom { sram, mask, .. } => match (bank, offset) {
+                (0x30..=0x3F | 0xC0..=0xCF, 0x8000..=0xFFFF) => CartridgeAddress::None,
+                _ => lorom_map_address(address, *mask, sram.len() as u32),
+            },
+            Self::HiRom { sram, mask, .. } => hirom_map_address(address, *mask, sram.len() as u32),
+            Self::DspHiRom { sram, mask, .. } => match (bank, offset) {
+                (0x00..=0x0F | 0x80..=0x8F, 0x6000..=0x7FFF) => CartridgeAddress::None,
+                _ => hirom_map_address(address, *mask, sram.len() as u32),
+            },
+            Self::ExHiRom { rom, sram, srtc } => match (bank, offset, srtc) {
+                (0x00..=0x3F | 0x80..=0xBF, 0x2800..=0x2801, Some(_)) => CartridgeAddress::None,
+                _ => exhirom_map_address(address, rom.len() as u32, sram.len() as u32),
+            },
+            Self::Cx4(..)
+            | Self::Obc1(..)
+            | Self::Sa1(..)
+            | Self::Sdd1(..)
+            | Self::Spc7110(..)
+            | Self::SuperFx(..)
+            | Self::St01x { .. } => CartridgeAddress::None,
+        }
+    }
+
     pub fn write(&mut self, address: u32, value: u8) {
         let bank = (address >> 16) & 0xFF;
         let offset = address & 0xFFFF;
@@ -696,6 +759,26 @@ pub fn region_to_timing_mode(region_byte: u8) -> TimingMode {
     }
 }
 
+fn has_copier_header(rom: &[u8]) -> bool {
+    rom.len() & 0x7FFF == 0x0200
+}
+
+/// Strip the 512-byte copier header from the ROM image if it has one.
+pub(crate) fn strip_copier_header(rom: &[u8]) -> &[u8] {
+    if has_copier_header(rom) { &rom[0x200..] } else { rom }
+}
+
+/// Address of the internal header within the ROM image, which must not have a copier header.
+/// Returns `None` if the ROM is too small to contain a header.
+pub(crate) fn guess_header_addr(rom: &[u8]) -> Option<usize> {
+    if rom.len() < 0x8000 {
+        return None;
+    }
+
+    let cartridge_type = guess_cartridge_type(rom).unwrap_or(CartridgeType::LoRom);
+    Some(cartridge_type.header_addr())
+}
+
 fn guess_cartridge_type(rom: &[u8]) -> Option<CartridgeType> {
     if rom.len() < 0x8000 {
         log::error!("ROM is too small; all ROMs should be at least 32KB, was {} bytes", rom.len());
@@ -947,3 +1030,118 @@ fn exhirom_map_rom_address(address: u32, rom_len: u32) -> u32 {
         rom_addr
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jgenesis_common::testing::NullSaveWriter;
+
+    // 128KB ROM with a header at `header_addr` and a RESET vector pointing to a SEI instruction.
+    // Each 32KB bank is filled with its bank number so that mapped addresses can be checked
+    fn synthetic_rom(header_addr: usize, map_byte: u8, reset_vector: u16) -> Vec<u8> {
+        let mut rom: Vec<u8> = (0..0x20000).map(|i| (i >> 15) as u8).collect();
+        rom[header_addr + HEADER_MAP_OFFSET] = map_byte;
+        rom[header_addr + 0x3C..header_addr + 0x3E].copy_from_slice(&reset_vector.to_le_bytes());
+        rom
+    }
+
+    fn create_cartridge(rom: Vec<u8>) -> Cartridge {
+        Cartridge::create(
+            rom.into_boxed_slice(),
+            None,
+            &CoprocessorRoms::none(),
+            None,
+            NonZeroU64::new(1).unwrap(),
+            &mut NullSaveWriter,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn guess_lorom() {
+        let mut rom = synthetic_rom(LOROM_HEADER_ADDR, 0x20, 0x8000);
+        rom[0x0000] = SEI_OPCODE;
+        assert_eq!(guess_cartridge_type(&rom), Some(CartridgeType::LoRom));
+
+        let mut cartridge = create_cartridge(rom);
+        assert!(matches!(cartridge, Cartridge::LoRom { .. }));
+
+        // $008000 is ROM $000000 and $018000 is ROM $008000
+        assert_eq!(cartridge.read(0x008000), Some(SEI_OPCODE));
+        assert_eq!(cartridge.read(0x018000), Some(0x01));
+        assert_eq!(cartridge.read(0x838000), Some(0x03));
+
+        // $0000-$7FFF in banks $00-$3F is not cartridge space
+        assert_eq!(cartridge.read(0x010000), None);
+    }
+
+    #[test]
+    fn guess_hirom() {
+        let mut rom = synthetic_rom(HIROM_HEADER_ADDR, 0x21, 0x8000);
+        rom[0x8000] = SEI_OPCODE;
+        assert_eq!(guess_cartridge_type(&rom), Some(CartridgeType::HiRom));
+
+        let mut cartridge = create_cartridge(rom);
+        assert!(matches!(cartridge, Cartridge::HiRom { .. }));
+
+        // $008000 is ROM $008000 and $C10000 is ROM $010000
+        assert_eq!(cartridge.read(0x008000), Some(SEI_OPCODE));
+        assert_eq!(cartridge.read(0xC10000), Some(0x02));
+        assert_eq!(cartridge.read(0xC18000), Some(0x03));
+        assert_eq!(cartridge.read(0x418000), Some(0x03));
+
+        // Banks $00-$3F mirror the upper half of each 64KB bank
+        assert_eq!(cartridge.read(0x018000), Some(0x03));
+        assert_eq!(cartridge.read(0x010000), None);
+    }
+
+    #[test]
+    fn guess_ambiguous_defaults_to_lorom() {
+        // No map byte and no valid RESET vector in either header location
+        let rom = vec![0; 0x20000];
+        assert_eq!(guess_cartridge_type(&rom), None);
+        assert!(matches!(create_cartridge(rom), Cartridge::LoRom { .. }));
+    }
+
+    #[test]
+    fn rom_mirroring_1_5mb() {
+        let mask = RomAddressMask::from_rom_len(0x180000);
+
+        assert_eq!(mask.mask(0x000000), 0x000000);
+        assert_eq!(mask.mask(0x0FFFFF), 0x0FFFFF);
+        assert_eq!(mask.mask(0x100000), 0x100000);
+        assert_eq!(mask.mask(0x17FFFF), 0x17FFFF);
+
+        // $180000-$1FFFFF mirrors the last 512KB
+        assert_eq!(mask.mask(0x180000), 0x100000);
+        assert_eq!(mask.mask(0x1FFFFF), 0x17FFFF);
+
+        // $200000-$3FFFFF mirrors $000000-$1FFFFF
+        assert_eq!(mask.mask(0x212345), 0x012345);
+        assert_eq!(mask.mask(0x312345), 0x112345);
+        assert_eq!(mask.mask(0x392345), 0x112345);
+    }
+
+    #[test]
+    fn rom_mirroring_3mb() {
+        let mask = RomAddressMask::from_rom_len(0x300000);
+
+        assert_eq!(mask.mask(0x000000), 0x000000);
+        assert_eq!(mask.mask(0x1FFFFF), 0x1FFFFF);
+        assert_eq!(mask.mask(0x200000), 0x200000);
+        assert_eq!(mask.mask(0x2FFFFF), 0x2FFFFF);
+
+        // $300000-$3FFFFF mirrors the last 1MB
+        assert_eq!(mask.mask(0x300000), 0x200000);
+        assert_eq!(mask.mask(0x3ABCDE), 0x2ABCDE);
+    }
+
+    #[test]
+    fn rom_mirroring_power_of_two() {
+        let mask = RomAddressMask::from_rom_len(0x100000);
+
+        assert_eq!(mask.mask(0x0ABCDE), 0x0ABCDE);
+        assert_eq!(mask.mask(0x1ABCDE), 0x0ABCDE);
+        assert_eq!(mask.mask(0x3ABCDE), 0x0ABCDE);
+    }
+}
@@ -594,3 +594,129 @@ fn gpdma_start_log(bus: &Bus<'_>) {
         log::trace!("  Channel {i} increment mode: {:?}", bus.cpu_registers.dma_increment_mode[i]);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::CoprocessorRoms;
+    use crate::apu::Apu;
+    use crate::memory::{CpuInternalRegisters, Memory};
+    use crate::ppu::Ppu;
+    use jgenesis_common::frontend::TimingMode;
+    use jgenesis_common::num::U16Ext;
+    use jgenesis_common::testing::NullSaveWriter;
+    use std::num::NonZeroU64;
+
+    const TABLE_ADDR: u16 = 0x0100;
+
+    struct TestSystem {
+        memory: Memory,
+        cpu_registers: CpuInternalRegisters,
+        ppu: Ppu,
+        apu: Apu,
+    }
+
+    impl TestSystem {
+        fn new() -> Self {
+            let memory = Memory::create(
+                vec![0; 0x8000],
+                None,
+                &CoprocessorRoms::none(),
+                None,
+                NonZeroU64::new(1).unwrap(),
+                &mut NullSaveWriter,
+            )
+            .unwrap();
+
+            Self {
+                memory,
+                cpu_registers: CpuInternalRegisters::new(),
+                ppu: Ppu::new(TimingMode::Ntsc),
+                apu: Apu::new(TimingMode::Ntsc, false),
+            }
+        }
+
+        fn bus(&mut self) -> Bus<'_> {
+            Bus {
+                memory: &mut self.memory,
+                cpu_registers: &mut self.cpu_registers,
+                ppu: &mut self.ppu,
+                apu: &mut self.apu,
+                latched_interrupts: None,
+                access_master_cycles: 0,
+            }
+        }
+    }
+
+    // Set up HDMA channel 0 to write 1 byte per line to WMADDL ($2181) from a table in WRAM at
+    // $7E0100; the low byte of the WRAM port address records the value written on each line
+    fn setup_channel_0(system: &mut TestSystem, dmap: u8, table: &[u8]) {
+        for (i, &byte) in table.iter().enumerate() {
+            system.memory.write_wram(u32::from(TABLE_ADDR) + i as u32, byte);
+        }
+
+        let mut bus = system.bus();
+        bus.write(0x004300, dmap);
+        bus.write(0x004301, 0x81);
+        bus.write(0x004302, TABLE_ADDR.lsb());
+        bus.write(0x004303, TABLE_ADDR.msb());
+        bus.write(0x004304, 0x7E);
+        bus.write(0x004307, 0x7E);
+        bus.write(0x00420C, 0x01);
+    }
+
+    // Run HDMA for `lines` scanlines, returning the byte written to WMADDL on each line (if any)
+    fn run_lines(system: &mut TestSystem, dma_unit: &mut DmaUnit, lines: usize) -> Vec<Option<u8>> {
+        let mut bus = system.bus();
+        dma_unit.hdma_reload(&mut bus);
+
+        (0..lines)
+            .map(|_| {
+                bus.memory.wram_port_address = 0x100;
+                if bus.cpu_registers.hdma_line_counter[0] != 0 {
+                    dma_unit.hdma_process_channel(&mut bus, 0);
+                }
+                (bus.memory.wram_port_address != 0x100)
+                    .then_some(bus.memory.wram_port_address as u8)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn hdma_direct_table() {
+        let mut system = TestSystem::new();
+        // 2 lines with a single write, 3 lines with repeated writes, then end of table
+        setup_channel_0(&mut system, 0x00, &[0x02, 0x11, 0x83, 0x21, 0x22, 0x23, 0x00]);
+
+        let mut dma_unit = DmaUnit::new();
+        let writes = run_lines(&mut system, &mut dma_unit, 7);
+        assert_eq!(writes, vec![Some(0x11), None, Some(0x21), Some(0x22), Some(0x23), None, None]);
+
+        // Table pointer stops after the terminating $00 byte
+        assert_eq!(system.cpu_registers.hdma_table_current_address[0], TABLE_ADDR + 7);
+        assert_eq!(system.cpu_registers.hdma_line_counter[0], 0);
+
+        // Reloading at the start of the next frame restarts from the beginning of the table
+        let writes = run_lines(&mut system, &mut dma_unit, 3);
+        assert_eq!(writes, vec![Some(0x11), None, Some(0x21)]);
+    }
+
+    #[test]
+    fn hdma_indirect_table() {
+        let mut system = TestSystem::new();
+        // 2 lines with repeated writes from $7E1000, 1 line with a single write from $7E1010
+        setup_channel_0(&mut system, 0x40, &[0x82, 0x00, 0x10, 0x01, 0x10, 0x10, 0x00, 0x00, 0x00]);
+        system.memory.write_wram(0x1000, 0x31);
+        system.memory.write_wram(0x1001, 0x32);
+        system.memory.write_wram(0x1010, 0x41);
+
+        let mut dma_unit = DmaUnit::new();
+        let writes = run_lines(&mut system, &mut dma_unit, 5);
+        assert_eq!(writes, vec![Some(0x31), Some(0x32), Some(0x41), None, None]);
+
+        // The indirect address advances past each byte read, and the table pointer skips over
+        // every 3-byte entry
+        assert_eq!(system.cpu_registers.gpdma_byte_counter[0], 0x0000);
+        assert_eq!(system.cpu_registers.hdma_table_current_address[0], TABLE_ADDR + 9);
+    }
+}
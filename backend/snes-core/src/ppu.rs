@@ -1943,4 +1943,38 @@ mod tests {
 
         assert_eq!(0b11100_11110_11110, resolve_direct_color(0b111, 0b11_111_111));
     }
+
+    #[test]
+    fn wrio_latches_hv_counters() {
+        let mut ppu = Ppu::new(TimingMode::Ntsc);
+        ppu.state.scanline = 50;
+        ppu.state.scanline_master_cycles = 400;
+
+        // Writing bit 7 as 1 should not latch
+        ppu.update_wrio(0xFF);
+        assert_eq!(ppu.read_port(0x213F).map(|stat78| stat78 & 0x40), Some(0x00));
+
+        // Changing bit 7 from 1 to 0 should latch
+        ppu.update_wrio(0x7F);
+        ppu.state.scanline = 60;
+        ppu.state.scanline_master_cycles = 800;
+
+        assert_eq!(ppu.read_port(0x213F).map(|stat78| stat78 & 0x40), Some(0x40));
+        assert_eq!(ppu.read_port(0x213C), Some(100));
+        assert_eq!(ppu.read_port(0x213C).map(|msb| msb & 0x01), Some(0));
+        assert_eq!(ppu.read_port(0x213D), Some(50));
+        assert_eq!(ppu.read_port(0x213D).map(|msb| msb & 0x01), Some(0));
+
+        // Reading STAT78 clears the latch flag, and leaving bit 7 at 0 should not re-latch
+        assert_eq!(ppu.read_port(0x213F).map(|stat78| stat78 & 0x40), Some(0x00));
+        ppu.update_wrio(0x00);
+        assert_eq!(ppu.read_port(0x213C), Some(100));
+
+        // Setting bit 7 back to 1 should not latch, but a subsequent 1 -> 0 transition should
+        ppu.update_wrio(0x80);
+        ppu.update_wrio(0x00);
+        assert_eq!(ppu.read_port(0x213F).map(|stat78| stat78 & 0x40), Some(0x40));
+        assert_eq!(ppu.read_port(0x213C), Some(200));
+        assert_eq!(ppu.read_port(0x213D), Some(60));
+    }
 }
@@ -1176,9 +1176,11 @@ impl Registers {
     }
 
     pub fn update_wrio(&mut self, wrio: u8, h_counter: u16, v_counter: u16) {
-        if self.programmable_joypad_port.bit(7) & !wrio.bit(7) {
+        // Changing WRIO bit 7 from 1 to 0 latches the H/V counters
+        if self.programmable_joypad_port.bit(7) && !wrio.bit(7) {
             self.latched_h_counter = h_counter;
             self.latched_v_counter = v_counter;
+            self.new_hv_latched = true;
         }
         self.programmable_joypad_port = wrio;
     }
@@ -1,10 +1,16 @@
+mod breakpoints;
 mod instructions;
 
-use crate::core::instructions::Instruction;
+use crate::core::breakpoints::{BreakpointState, DataBreakpointBus};
+pub use crate::core::breakpoints::{BreakpointHit, Breakpoints};
+pub use crate::core::instructions::{
+    disassemble, BranchCondition, Direction, Instruction, ShiftCount, ShiftDirection, UspDirection,
+};
 use crate::traits::BusInterface;
 use jgenesis_common::num::GetBit;
 use jgenesis_proc_macros::EnumAll;
-use std::fmt::{Display, Formatter};
+use std::fmt::{Debug, Display, Formatter};
+use std::mem;
 
 #[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
@@ -111,6 +117,12 @@ pub struct DataRegister(u8);
 impl DataRegister {
     const ALL: [Self; 8] = [Self(0), Self(1), Self(2), Self(3), Self(4), Self(5), Self(6), Self(7)];
 
+    /// Register number, from 0 (D0) to 7 (D7).
+    #[must_use]
+    pub fn number(self) -> u8 {
+        self.0
+    }
+
     fn read_from(self, registers: &Registers) -> u32 {
         registers.data[self.0 as usize]
     }
@@ -142,6 +154,12 @@ pub struct AddressRegister(u8);
 impl AddressRegister {
     const ALL: [Self; 8] = [Self(0), Self(1), Self(2), Self(3), Self(4), Self(5), Self(6), Self(7)];
 
+    /// Register number, from 0 (A0) to 7 (A7).
+    #[must_use]
+    pub fn number(self) -> u8 {
+        self.0
+    }
+
     fn is_stack_pointer(self) -> bool {
         self.0 == 7
     }
@@ -272,6 +290,8 @@ enum Exception {
     DivisionByZero { cycles: u32 },
     Trap(u32),
     CheckRegister { cycles: u32 },
+    // Not a real 68000 exception; the next instruction has an execute breakpoint
+    Breakpoint,
 }
 
 type ExecuteResult<T> = Result<T, Exception>;
@@ -398,7 +418,17 @@ impl Display for ResolvedAddress {
     }
 }
 
-#[derive(Debug)]
+/// CPU state passed to a trace hook after an instruction is decoded but before it executes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceState {
+    /// Address of the instruction's opcode
+    pub pc: u32,
+    pub status_register: u16,
+    pub instruction: Instruction,
+}
+
+type TraceHook<'a> = &'a mut dyn FnMut(&TraceState);
+
 struct InstructionExecutor<'registers, 'bus, B> {
     registers: &'registers mut Registers,
     bus: &'bus mut B,
@@ -406,12 +436,28 @@ struct InstructionExecutor<'registers, 'bus, B> {
     opcode: u16,
     instruction: Option<Instruction>,
     name: &'registers str,
+    breakpoints: &'registers Breakpoints,
+    breakpoint_hit: &'registers mut Option<BreakpointHit>,
+    trace_hook: Option<TraceHook<'bus>>,
+}
+
+// Manual impl because the bus and hook references are not Debug
+impl<B> Debug for InstructionExecutor<'_, '_, B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InstructionExecutor")
+            .field("name", &self.name)
+            .field("opcode", &self.opcode)
+            .field("instruction", &self.instruction)
+            .field("allow_tas_writes", &self.allow_tas_writes)
+            .finish_non_exhaustive()
+    }
 }
 
 const ADDRESS_ERROR_VECTOR: u32 = 3;
 const ILLEGAL_OPCODE_VECTOR: u32 = 4;
 const DIVIDE_BY_ZERO_VECTOR: u32 = 5;
 const CHECK_REGISTER_VECTOR: u32 = 6;
+const PRIVILEGE_VIOLATION_VECTOR: u32 = 8;
 const AUTO_VECTORED_INTERRUPT_BASE_ADDRESS: u32 = 0x60;
 
 impl<'registers, 'bus, B: BusInterface> InstructionExecutor<'registers, 'bus, B> {
@@ -420,8 +466,21 @@ impl<'registers, 'bus, B: BusInterface> InstructionExecutor<'registers, 'bus, B>
         bus: &'bus mut B,
         allow_tas_writes: bool,
         name: &'registers str,
+        breakpoints: &'registers Breakpoints,
+        breakpoint_hit: &'registers mut Option<BreakpointHit>,
+        trace_hook: Option<TraceHook<'bus>>,
     ) -> Self {
-        Self { registers, bus, allow_tas_writes, opcode: 0, instruction: None, name }
+        Self {
+            registers,
+            bus,
+            allow_tas_writes,
+            opcode: 0,
+            instruction: None,
+            name,
+            breakpoints,
+            breakpoint_hit,
+            trace_hook,
+        }
     }
 
     // Read a word from the bus; returns an address error if address is odd
@@ -899,7 +958,23 @@ impl<'registers, 'bus, B: BusInterface> InstructionExecutor<'registers, 'bus, B>
                 // Not completely accurate but close enough; this shouldn't occur in real software
                 50
             }
-            Err(Exception::PrivilegeViolation) => todo!("privilege violation"),
+            Err(Exception::PrivilegeViolation) => {
+                log::error!(
+                    "[{}] Privileged instruction executed in user mode: {:04X}",
+                    self.name,
+                    self.opcode
+                );
+
+                // All privileged instructions check the S bit before fetching any extension words
+                if self
+                    .handle_trap(PRIVILEGE_VIOLATION_VECTOR, self.registers.pc.wrapping_sub(2))
+                    .is_err()
+                {
+                    todo!("???")
+                }
+
+                34
+            }
             Err(Exception::IllegalInstruction(opcode)) => {
                 log::error!(
                     "[{}] Illegal opcode executed: {opcode:04X} / {opcode:016b}",
@@ -942,6 +1017,12 @@ impl<'registers, 'bus, B: BusInterface> InstructionExecutor<'registers, 'bus, B>
 
                 30 + cycles
             }
+            Err(Exception::Breakpoint) => {
+                log::debug!("[{}] Stopped at breakpoint; PC={:06X}", self.name, self.registers.pc);
+
+                *self.breakpoint_hit = Some(BreakpointHit::Execute(self.registers.pc));
+                0
+            }
         }
     }
 }
@@ -980,6 +1061,7 @@ impl M68000Builder {
     pub fn build(self) -> M68000 {
         M68000 {
             registers: Registers::new(),
+            breakpoints: BreakpointState::default(),
             halted: false,
             allow_tas_writes: self.allow_tas_writes,
             name: self.name.unwrap_or_default(),
@@ -993,6 +1075,7 @@ const RESET_CYCLES: u32 = 132;
 #[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub struct M68000 {
     registers: Registers,
+    breakpoints: BreakpointState,
     halted: bool,
     allow_tas_writes: bool,
     // Used only for trace logging
@@ -1058,11 +1141,66 @@ impl M68000 {
         self.registers.ssp = ssp;
     }
 
+    /// Read data register D`n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than 7.
+    #[must_use]
+    pub fn data_register(&self, n: u8) -> u32 {
+        assert!(n < 8, "invalid data register: D{n}");
+        DataRegister(n).read_from(&self.registers)
+    }
+
+    /// Write data register D`n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than 7.
+    pub fn set_data_register(&mut self, n: u8, value: u32) {
+        assert!(n < 8, "invalid data register: D{n}");
+        DataRegister(n).write_long_word_to(&mut self.registers, value);
+    }
+
+    /// Read address register A`n`. A7 is the active stack pointer, which is SSP in supervisor
+    /// mode and USP in user mode.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than 7.
+    #[must_use]
+    pub fn address_register(&self, n: u8) -> u32 {
+        assert!(n < 8, "invalid address register: A{n}");
+        AddressRegister(n).read_from(&self.registers)
+    }
+
+    /// Write address register A`n`. Writing A7 writes the active stack pointer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than 7.
+    pub fn set_address_register(&mut self, n: u8, value: u32) {
+        assert!(n < 8, "invalid address register: A{n}");
+        AddressRegister(n).write_long_word_to(&mut self.registers, value);
+    }
+
+    /// The active stack pointer (A7).
+    #[must_use]
+    pub fn stack_pointer(&self) -> u32 {
+        self.registers.sp()
+    }
+
+    pub fn set_user_stack_pointer(&mut self, usp: u32) {
+        self.registers.usp = usp;
+    }
+
     #[must_use]
     pub fn status_register(&self) -> u16 {
         self.registers.status_register()
     }
 
+    /// Set the full status register. Changing the supervisor bit switches the active stack pointer
+    /// between SSP and USP; neither stack pointer value is modified.
     pub fn set_status_register(&mut self, status_register: u16) {
         self.registers.set_status_register(status_register);
     }
@@ -1081,8 +1219,56 @@ impl M68000 {
         self.registers.address_error
     }
 
+    #[must_use]
+    pub fn breakpoints(&self) -> &Breakpoints {
+        &self.breakpoints.breakpoints
+    }
+
+    pub fn breakpoints_mut(&mut self) -> &mut Breakpoints {
+        &mut self.breakpoints.breakpoints
+    }
+
+    /// Move `other`'s breakpoints into this CPU. Breakpoints are not included in save states, so
+    /// this should be called after loading a state to keep the breakpoints that were set.
+    pub fn take_breakpoints_from(&mut self, other: &mut Self) {
+        self.breakpoints.breakpoints = mem::take(&mut other.breakpoints.breakpoints);
+    }
+
+    /// The breakpoint hit by the most recent call to [`Self::execute_instruction`], if any.
+    ///
+    /// On an execute breakpoint, the instruction is not executed and `execute_instruction` returns
+    /// 0 cycles; the next call executes the instruction normally. On a read or write breakpoint,
+    /// the instruction that made the access has fully executed.
+    #[must_use]
+    pub fn breakpoint_hit(&self) -> Option<BreakpointHit> {
+        self.breakpoints.hit
+    }
+
     #[inline]
     pub fn execute_instruction<B: BusInterface>(&mut self, bus: &mut B) -> u32 {
+        self.execute_instruction_with_hook(bus, None)
+    }
+
+    /// Same as [`Self::execute_instruction`], but calls `trace_hook` after the next instruction
+    /// is decoded and before it is executed. The hook is not called when the CPU is handling an
+    /// interrupt, is stopped, is halted/reset by the bus, or stops at an execute breakpoint.
+    #[inline]
+    pub fn execute_instruction_traced<B: BusInterface, F: FnMut(&TraceState)>(
+        &mut self,
+        bus: &mut B,
+        mut trace_hook: F,
+    ) -> u32 {
+        self.execute_instruction_with_hook(bus, Some(&mut trace_hook))
+    }
+
+    #[inline]
+    fn execute_instruction_with_hook<'bus, B: BusInterface>(
+        &mut self,
+        bus: &'bus mut B,
+        trace_hook: Option<TraceHook<'bus>>,
+    ) -> u32 {
+        self.breakpoints.hit = None;
+
         if bus.reset() {
             self.reset(bus);
             return RESET_CYCLES;
@@ -1092,7 +1278,384 @@ impl M68000 {
             return 1;
         }
 
-        InstructionExecutor::new(&mut self.registers, bus, self.allow_tas_writes, &self.name)
+        let cycles = if self.breakpoints.breakpoints.has_data_breakpoints() {
+            let mut bus = DataBreakpointBus::new(bus, &self.breakpoints.breakpoints);
+            let trace_hook = trace_hook.map(|hook| hook as &mut dyn FnMut(&TraceState));
+            let cycles = InstructionExecutor::new(
+                &mut self.registers,
+                &mut bus,
+                self.allow_tas_writes,
+                &self.name,
+                &self.breakpoints.breakpoints,
+                &mut self.breakpoints.hit,
+                trace_hook,
+            )
+            .execute();
+
+            if let Some(hit) = bus.hit() {
+                self.breakpoints.hit.get_or_insert(hit);
+            }
+
+            cycles
+        } else {
+            InstructionExecutor::new(
+                &mut self.registers,
+                bus,
+                self.allow_tas_writes,
+                &self.name,
+                &self.breakpoints.breakpoints,
+                &mut self.breakpoints.hit,
+                trace_hook,
+            )
             .execute()
+        };
+
+        self.breakpoints.breakpoints.resume_pc = match self.breakpoints.hit {
+            Some(BreakpointHit::Execute(pc)) => Some(pc),
+            _ => None,
+        };
+
+        cycles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::InMemoryBus;
+
+    #[test]
+    fn trace_hook_sees_each_instruction() {
+        let mut bus = InMemoryBus::new();
+
+        // MOVEQ #1, D0; BRA.s *+4; NOP; ADDQ.l #1, D0
+        for (i, word) in [0x7001_u16, 0x6002, 0x4E71, 0x5280].into_iter().enumerate() {
+            bus.write_word(0x400 + 2 * i as u32, word);
+        }
+
+        let mut cpu = M68000::default();
+        cpu.set_pc(0x400);
+
+        let mut trace = Vec::new();
+        for _ in 0..3 {
+            cpu.execute_instruction_traced(&mut bus, |state| {
+                trace.push((state.pc, state.instruction));
+            });
+        }
+
+        assert_eq!(trace.iter().map(|&(pc, _)| pc).collect::<Vec<_>>(), vec![0x400, 0x402, 0x406]);
+        assert_eq!(trace[0].1, Instruction::MoveQuick(1, DataRegister(0)));
+        assert_eq!(cpu.data_registers()[0], 2);
+    }
+
+    #[test]
+    fn breakpoints() {
+        let mut bus = InMemoryBus::new();
+
+        // MOVEQ #1, D0; MOVEQ #2, D1; MOVE.w D0, ($1000).w; NOP
+        for (i, word) in [0x7001_u16, 0x7202, 0x31C0, 0x1000, 0x4E71].into_iter().enumerate() {
+            bus.write_word(0x400 + 2 * i as u32, word);
+        }
+
+        let mut cpu = M68000::default();
+        cpu.set_pc(0x400);
+        assert!(cpu.breakpoints_mut().add_execute(0x402));
+        assert!(!cpu.breakpoints_mut().add_execute(0x402));
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.breakpoint_hit(), None);
+        assert_eq!(cpu.data_register(0), 1);
+
+        // Stops before executing MOVEQ #2, D1
+        assert_eq!(cpu.execute_instruction(&mut bus), 0);
+        assert_eq!(cpu.breakpoint_hit(), Some(BreakpointHit::Execute(0x402)));
+        assert_eq!(cpu.pc(), 0x402);
+        assert_eq!(cpu.data_register(0), 1);
+        assert_eq!(cpu.data_register(1), 0);
+
+        // Resuming executes the instruction at the breakpoint
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.breakpoint_hit(), None);
+        assert_eq!(cpu.pc(), 0x404);
+        assert_eq!(cpu.data_register(1), 2);
+
+        // Word write to $1000 covers $1001; the write still happens
+        cpu.breakpoints_mut().add_write(0x1001);
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.breakpoint_hit(), Some(BreakpointHit::Write(0x1001)));
+        assert_eq!(bus.read_word(0x1000), 1);
+        assert_eq!(cpu.pc(), 0x408);
+
+        // Opcode fetches count as reads
+        cpu.breakpoints_mut().add_read(0x408);
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.breakpoint_hit(), Some(BreakpointHit::Read(0x408)));
+        assert_eq!(cpu.pc(), 0x40A);
+
+        cpu.breakpoints_mut().clear();
+        cpu.set_pc(0x400);
+        cpu.execute_instruction(&mut bus);
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.breakpoint_hit(), None);
+        assert_eq!(cpu.pc(), 0x404);
+    }
+
+    #[test]
+    fn register_accessors() {
+        let mut cpu = M68000::default();
+
+        for n in 0..8 {
+            cpu.set_data_register(n, 0x1000_0000 | u32::from(n));
+            cpu.set_address_register(n, 0x2000_0000 | u32::from(n));
+        }
+        for n in 0..8 {
+            assert_eq!(cpu.data_register(n), 0x1000_0000 | u32::from(n));
+            assert_eq!(cpu.address_register(n), 0x2000_0000 | u32::from(n));
+        }
+        assert_eq!(cpu.data_registers()[3], 0x1000_0003);
+        assert_eq!(cpu.address_registers()[6], 0x2000_0006);
+
+        // CPU starts in supervisor mode, so A7 is SSP
+        assert_eq!(cpu.supervisor_stack_pointer(), 0x2000_0007);
+
+        cpu.set_pc(0x00AB_CDEF);
+        assert_eq!(cpu.pc(), 0x00AB_CDEF);
+
+        // Supervisor mode, interrupt mask 5, X/N/Z/V/C set
+        cpu.set_status_register(0x251F);
+        assert_eq!(cpu.status_register(), 0x251F);
+    }
+
+    #[test]
+    fn instruction_cycle_counts() {
+        // Expected counts from the 68000 User's Manual instruction timing tables
+        let instructions: [(&str, &[u16], u32); 40] = [
+            ("NOP", &[0x4E71], 4),
+            ("MOVEQ #1, D0", &[0x7001], 4),
+            ("MOVE.w D0, D1", &[0x3200], 4),
+            ("MOVE.w (A0), D1", &[0x3210], 8),
+            ("MOVE.l #imm, D0", &[0x203C, 0x1234, 0x5678], 12),
+            ("MOVE.w D0, (A1)+", &[0x32C0], 8),
+            ("MOVE.l -(A0), D0", &[0x2020], 14),
+            ("MOVE.l (d16,A0), (d16,A1)", &[0x2368, 0x0010, 0x0020], 28),
+            ("MOVE.w (d8,A0,D0), D1", &[0x3230, 0x0004], 14),
+            ("MOVE.l (xxx).l, (xxx).w", &[0x21F9, 0x0000, 0x1000, 0x2000], 32),
+            ("MOVEA.l D0, A1", &[0x2240], 4),
+            ("MOVEM.l D0-D1, -(A7)", &[0x48E7, 0xC000], 24),
+            ("MOVEM.w (A0)+, D0-D3", &[0x4C98, 0x000F], 28),
+            ("LEA (d16,A0), A1", &[0x43E8, 0x0010], 8),
+            ("PEA (A0)", &[0x4850], 12),
+            ("ADD.w D0, D1", &[0xD240], 4),
+            ("ADD.l D0, D1", &[0xD280], 8),
+            ("ADD.w (A0), D1", &[0xD250], 8),
+            ("ADD.w D1, (A0)", &[0xD350], 12),
+            ("ADD.l D1, (A0)", &[0xD390], 20),
+            ("ADDA.w D0, A0", &[0xD0C0], 8),
+            ("ADDQ.w #1, D0", &[0x5240], 4),
+            ("ADDQ.l #1, D0", &[0x5280], 8),
+            ("ADDI.l #imm, D0", &[0x0680, 0x0001, 0x0000], 16),
+            ("CMP.l D0, D1", &[0xB280], 6),
+            ("CMPI.w #imm, D0", &[0x0C40, 0x0001], 8),
+            ("CLR.w D0", &[0x4240], 4),
+            ("CLR.l D0", &[0x4280], 6),
+            ("CLR.w (A0)", &[0x4250], 12),
+            ("NEG.l D0", &[0x4480], 6),
+            ("TST.w (A0)", &[0x4A50], 8),
+            ("EXG D0, D1", &[0xC141], 6),
+            ("SWAP D0", &[0x4840], 4),
+            ("MULU.w D2, D1", &[0xC2C2], 38),
+            ("LSL.w #3, D0", &[0xE748], 12),
+            ("LSL.l #1, D0", &[0xE388], 10),
+            ("LSL.w (A0)", &[0xE3D0], 12),
+            ("ASR.w (d16,A0)", &[0xE0E8, 0x0010], 16),
+            ("BTST #1, D0", &[0x0800, 0x0001], 10),
+            ("ST D0", &[0x50C0], 6),
+        ];
+
+        for (name, words, expected_cycles) in instructions {
+            let mut bus = InMemoryBus::new();
+            for (i, &word) in words.iter().enumerate() {
+                bus.write_word(0x400 + 2 * i as u32, word);
+            }
+
+            let mut cpu = M68000::default();
+            cpu.set_data_registers([0; 8]);
+            cpu.set_address_registers([0x1000, 0x2000, 0, 0, 0, 0, 0], 0, 0x8000);
+            cpu.set_pc(0x400);
+
+            assert_eq!(cpu.execute_instruction(&mut bus), expected_cycles, "{name}");
+        }
+    }
+
+    #[test]
+    fn branch_cycle_counts() {
+        // (name, words, D0, SR, expected cycles)
+        let instructions: [(&str, &[u16], u32, u16, u32); 10] = [
+            ("BRA.s taken", &[0x6002], 0, 0x2700, 10),
+            ("BRA.w taken", &[0x6000, 0x0010], 0, 0x2700, 10),
+            ("BEQ.s not taken", &[0x6702], 0, 0x2700, 8),
+            ("BEQ.w not taken", &[0x6700, 0x0010], 0, 0x2700, 12),
+            ("BSR.s", &[0x6102], 0, 0x2700, 18),
+            ("DBF D0 taken", &[0x51C8, 0x0010], 1, 0x2700, 10),
+            ("DBF D0 expired", &[0x51C8, 0x0010], 0, 0x2700, 14),
+            ("DBEQ D0 condition true", &[0x57C8, 0x0010], 1, 0x2704, 12),
+            ("JMP (A0)", &[0x4ED0], 0, 0x2700, 8),
+            ("JSR (A0)", &[0x4E90], 0, 0x2700, 16),
+        ];
+
+        for (name, words, d0, sr, expected_cycles) in instructions {
+            let mut bus = InMemoryBus::new();
+            for (i, &word) in words.iter().enumerate() {
+                bus.write_word(0x400 + 2 * i as u32, word);
+            }
+
+            let mut cpu = M68000::default();
+            cpu.set_status_register(sr);
+            cpu.set_data_register(0, d0);
+            cpu.set_address_registers([0x1000, 0, 0, 0, 0, 0, 0], 0, 0x8000);
+            cpu.set_pc(0x400);
+
+            assert_eq!(cpu.execute_instruction(&mut bus), expected_cycles, "{name}");
+        }
+    }
+
+    #[test]
+    fn condition_codes() {
+        type Condition = fn(bool, bool, bool, bool) -> bool;
+
+        // Conditions as listed in the Programmer's Reference Manual, indexed by the cc field
+        let conditions: [(&str, Condition); 16] = [
+            ("T", |_, _, _, _| true),
+            ("F", |_, _, _, _| false),
+            ("HI", |_, z, _, c| !c && !z),
+            ("LS", |_, z, _, c| c || z),
+            ("CC", |_, _, _, c| !c),
+            ("CS", |_, _, _, c| c),
+            ("NE", |_, z, _, _| !z),
+            ("EQ", |_, z, _, _| z),
+            ("VC", |_, _, v, _| !v),
+            ("VS", |_, _, v, _| v),
+            ("PL", |n, _, _, _| !n),
+            ("MI", |n, _, _, _| n),
+            ("GE", |n, _, v, _| n && v || !n && !v),
+            ("LT", |n, _, v, _| n && !v || !n && v),
+            ("GT", |n, z, v, _| !z && (n && v || !n && !v)),
+            ("LE", |n, z, v, _| z || n && !v || !n && v),
+        ];
+
+        let run = |words: &[u16], nzvc: u16| {
+            let mut bus = InMemoryBus::new();
+            for (i, &word) in words.iter().enumerate() {
+                bus.write_word(0x400 + 2 * i as u32, word);
+            }
+
+            let mut cpu = M68000::default();
+            cpu.set_status_register(0x2700 | nzvc);
+            cpu.set_data_register(0, 5);
+            cpu.set_pc(0x400);
+            cpu.execute_instruction(&mut bus);
+            cpu
+        };
+
+        for (cc, (name, condition)) in conditions.into_iter().enumerate() {
+            let cc = cc as u16;
+            for nzvc in 0..16 {
+                let expected =
+                    condition(nzvc & 8 != 0, nzvc & 4 != 0, nzvc & 2 != 0, nzvc & 1 != 0);
+                let context = format!("{name} with NZVC={nzvc:04b}");
+
+                // Scc D0
+                let cpu = run(&[0x50C0 | (cc << 8)], nzvc);
+                let expected_d0 = if expected { 0xFF } else { 0x00 };
+                assert_eq!(cpu.data_register(0), expected_d0, "S{context}");
+
+                // DBcc D0, *+$12; the loop exits without decrementing if the condition is true
+                let cpu = run(&[0x50C8 | (cc << 8), 0x0010], nzvc);
+                let (expected_pc, expected_d0) = if expected { (0x404, 5) } else { (0x412, 4) };
+                assert_eq!(cpu.pc(), expected_pc, "DB{context}");
+                assert_eq!(cpu.data_register(0), expected_d0, "DB{context}");
+
+                // Bcc.s *+$12; cc=0 and cc=1 encode BRA and BSR
+                if cc >= 2 {
+                    let cpu = run(&[0x6010 | (cc << 8)], nzvc);
+                    let expected_pc = if expected { 0x412 } else { 0x402 };
+                    assert_eq!(cpu.pc(), expected_pc, "B{context}");
+                }
+
+                // No condition test modifies the flags
+                assert_eq!(cpu.status_register(), 0x2700 | nzvc, "{context}");
+            }
+        }
+    }
+
+    #[test]
+    fn privileged_instructions_trap_in_user_mode() {
+        const HANDLER: u32 = 0x1000;
+
+        let instructions: [(&str, &[u16]); 9] = [
+            ("ORI #, SR", &[0x007C, 0x0700]),
+            ("ANDI #, SR", &[0x027C, 0x0000]),
+            ("EORI #, SR", &[0x0A7C, 0x2000]),
+            ("MOVE D0, SR", &[0x46C0]),
+            ("MOVE A0, USP", &[0x4E60]),
+            ("MOVE USP, A0", &[0x4E68]),
+            ("RESET", &[0x4E70]),
+            ("STOP #", &[0x4E72, 0x2700]),
+            ("RTE", &[0x4E73]),
+        ];
+
+        for (name, words) in instructions {
+            let mut bus = InMemoryBus::new();
+            bus.write_long_word(PRIVILEGE_VIOLATION_VECTOR * 4, HANDLER);
+            for (i, &word) in words.iter().enumerate() {
+                bus.write_word(0x400 + 2 * i as u32, word);
+            }
+
+            let mut cpu = M68000::default();
+            cpu.set_supervisor_stack_pointer(0x2000);
+            cpu.set_user_stack_pointer(0x3000);
+            // User mode, X/N set
+            cpu.set_status_register(0x0018);
+            cpu.set_data_register(0, 0x2700);
+            cpu.set_address_register(0, 0x4000);
+            cpu.set_pc(0x400);
+
+            assert_eq!(cpu.execute_instruction(&mut bus), 34, "{name}");
+            assert_eq!(cpu.pc(), HANDLER, "{name}");
+
+            // Only the S bit changes; the instruction had no effect on SR or USP
+            assert_eq!(cpu.status_register(), 0x2018, "{name}");
+            assert_eq!(cpu.user_stack_pointer(), 0x3000, "{name}");
+            assert_eq!(cpu.address_register(0), 0x4000, "{name}");
+
+            // Stacked PC points at the faulting instruction
+            assert_eq!(cpu.supervisor_stack_pointer(), 0x2000 - 6, "{name}");
+            assert_eq!(bus.read_word(0x2000 - 6), 0x0018, "{name}");
+            assert_eq!(bus.read_long_word(0x2000 - 4), 0x400, "{name}");
+        }
+    }
+
+    #[test]
+    fn supervisor_bit_swaps_stack_pointer() {
+        let mut cpu = M68000::default();
+        cpu.set_supervisor_stack_pointer(0x00FF_0000);
+        cpu.set_user_stack_pointer(0x00FF_8000);
+
+        assert_eq!(cpu.stack_pointer(), 0x00FF_0000);
+        assert_eq!(cpu.address_register(7), 0x00FF_0000);
+
+        // Clear S bit: A7 is now USP
+        cpu.set_status_register(0x0700);
+        assert_eq!(cpu.stack_pointer(), 0x00FF_8000);
+        assert_eq!(cpu.address_register(7), 0x00FF_8000);
+
+        cpu.set_address_register(7, 0x00FF_9000);
+        assert_eq!(cpu.user_stack_pointer(), 0x00FF_9000);
+        assert_eq!(cpu.supervisor_stack_pointer(), 0x00FF_0000);
+
+        // Set S bit: A7 is SSP again and USP is retained
+        cpu.set_status_register(0x2700);
+        assert_eq!(cpu.stack_pointer(), 0x00FF_0000);
+        assert_eq!(cpu.user_stack_pointer(), 0x00FF_9000);
     }
 }
@@ -0,0 +1,183 @@
+use crate::traits::BusInterface;
+#[cfg(feature = "bincode")]
+use jgenesis_proc_macros::{FakeDecode, FakeEncode};
+use std::collections::HashSet;
+
+/// Reason that the CPU stopped at a breakpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub enum BreakpointHit {
+    /// The CPU stopped before executing the instruction at this address
+    Execute(u32),
+    /// The last instruction read from this address
+    Read(u32),
+    /// The last instruction wrote to this address
+    Write(u32),
+}
+
+/// Execute and data breakpoints for an [`M68000`](crate::M68000).
+#[derive(Debug, Clone, Default)]
+pub struct Breakpoints {
+    execute: HashSet<u32>,
+    read: HashSet<u32>,
+    write: HashSet<u32>,
+    // Set after stopping at an execute breakpoint so that the next instruction at that address
+    // executes instead of immediately breaking again
+    pub(super) resume_pc: Option<u32>,
+}
+
+impl Breakpoints {
+    /// Stop before executing the instruction at `pc`. Returns `false` if the breakpoint was
+    /// already set.
+    pub fn add_execute(&mut self, pc: u32) -> bool {
+        self.execute.insert(pc)
+    }
+
+    /// Returns `false` if no breakpoint was set at `pc`.
+    pub fn remove_execute(&mut self, pc: u32) -> bool {
+        self.execute.remove(&pc)
+    }
+
+    /// Stop after any instruction that reads from `address`, including opcode and operand fetches.
+    /// Addresses are compared after applying [`BusInterface::ADDRESS_MASK`].
+    pub fn add_read(&mut self, address: u32) -> bool {
+        self.read.insert(address)
+    }
+
+    pub fn remove_read(&mut self, address: u32) -> bool {
+        self.read.remove(&address)
+    }
+
+    /// Stop after any instruction that writes to `address`. Addresses are compared after applying
+    /// [`BusInterface::ADDRESS_MASK`].
+    pub fn add_write(&mut self, address: u32) -> bool {
+        self.write.insert(address)
+    }
+
+    pub fn remove_write(&mut self, address: u32) -> bool {
+        self.write.remove(&address)
+    }
+
+    /// Remove all execute, read, and write breakpoints.
+    pub fn clear(&mut self) {
+        self.execute.clear();
+        self.read.clear();
+        self.write.clear();
+        self.resume_pc = None;
+    }
+
+    pub(super) fn should_break_at(&self, pc: u32) -> bool {
+        self.resume_pc != Some(pc) && self.execute.contains(&pc)
+    }
+
+    pub(super) fn has_data_breakpoints(&self) -> bool {
+        !self.read.is_empty() || !self.write.is_empty()
+    }
+}
+
+// Breakpoints are debugger configuration rather than CPU state, so they are never saved in save
+// states; emulators should carry them over with `M68000::take_breakpoints_from` when loading a state
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "bincode", derive(FakeEncode, FakeDecode))]
+pub(super) struct BreakpointState {
+    pub(super) breakpoints: Breakpoints,
+    pub(super) hit: Option<BreakpointHit>,
+}
+
+// Bus wrapper that records the first access to an address with a data breakpoint. Only used while
+// at least one data breakpoint is set, so that the common case pays no per-access cost
+pub(super) struct DataBreakpointBus<'a, B> {
+    bus: &'a mut B,
+    breakpoints: &'a Breakpoints,
+    hit: Option<BreakpointHit>,
+}
+
+impl<'a, B: BusInterface> DataBreakpointBus<'a, B> {
+    pub(super) fn new(bus: &'a mut B, breakpoints: &'a Breakpoints) -> Self {
+        Self { bus, breakpoints, hit: None }
+    }
+
+    pub(super) fn hit(&self) -> Option<BreakpointHit> {
+        self.hit
+    }
+
+    fn check_read(&mut self, address: u32, len: u32) {
+        if self.hit.is_none() {
+            self.hit =
+                find_breakpoint::<B>(&self.breakpoints.read, address, len).map(BreakpointHit::Read);
+        }
+    }
+
+    fn check_write(&mut self, address: u32, len: u32) {
+        if self.hit.is_none() {
+            self.hit = find_breakpoint::<B>(&self.breakpoints.write, address, len)
+                .map(BreakpointHit::Write);
+        }
+    }
+}
+
+fn find_breakpoint<B: BusInterface>(set: &HashSet<u32>, address: u32, len: u32) -> Option<u32> {
+    (0..len)
+        .map(|i| address.wrapping_add(i) & B::ADDRESS_MASK)
+        .find(|address| set.contains(address))
+}
+
+impl<B: BusInterface> BusInterface for DataBreakpointBus<'_, B> {
+    const ADDRESS_MASK: u32 = B::ADDRESS_MASK;
+
+    #[inline]
+    fn read_byte(&mut self, address: u32) -> u8 {
+        self.check_read(address, 1);
+        self.bus.read_byte(address)
+    }
+
+    #[inline]
+    fn read_word(&mut self, address: u32) -> u16 {
+        self.check_read(address, 2);
+        self.bus.read_word(address)
+    }
+
+    #[inline]
+    fn write_byte(&mut self, address: u32, value: u8) {
+        self.check_write(address, 1);
+        self.bus.write_byte(address, value);
+    }
+
+    #[inline]
+    fn write_word(&mut self, address: u32, value: u16) {
+        self.check_write(address, 2);
+        self.bus.write_word(address, value);
+    }
+
+    #[inline]
+    fn read_long_word(&mut self, address: u32) -> u32 {
+        self.check_read(address, 4);
+        self.bus.read_long_word(address)
+    }
+
+    #[inline]
+    fn write_long_word(&mut self, address: u32, value: u32) {
+        self.check_write(address, 4);
+        self.bus.write_long_word(address, value);
+    }
+
+    #[inline]
+    fn interrupt_level(&self) -> u8 {
+        self.bus.interrupt_level()
+    }
+
+    #[inline]
+    fn acknowledge_interrupt(&mut self) {
+        self.bus.acknowledge_interrupt();
+    }
+
+    #[inline]
+    fn halt(&self) -> bool {
+        self.bus.halt()
+    }
+
+    #[inline]
+    fn reset(&self) -> bool {
+        self.bus.reset()
+    }
+}
@@ -1,17 +1,20 @@
 mod arithmetic;
 mod bits;
 mod controlflow;
+mod disassemble;
 mod load;
 mod table;
 
 use crate::core::{
     AddressRegister, AddressingMode, ConditionCodes, DataRegister, Exception, ExecuteResult,
-    InstructionExecutor, OpSize, Registers,
+    InstructionExecutor, OpSize, Registers, TraceState,
 };
 use crate::traits::BusInterface;
 use jgenesis_proc_macros::EnumAll;
 use std::fmt::{Display, Formatter};
 
+pub use disassemble::disassemble;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
     RegisterToMemory,
@@ -170,6 +173,7 @@ pub enum Instruction {
 }
 
 impl Instruction {
+    #[must_use]
     pub fn source_addressing_mode(self) -> Option<AddressingMode> {
         match self {
             Self::Add { source, .. }
@@ -243,6 +247,7 @@ impl Instruction {
         }
     }
 
+    #[must_use]
     pub fn dest_addressing_mode(self) -> Option<AddressingMode> {
         match self {
             Self::Add { dest, .. }
@@ -322,6 +327,9 @@ impl<'registers, 'bus, B: BusInterface> InstructionExecutor<'registers, 'bus, B>
         use Instruction::*;
 
         let initial_pc = self.registers.pc;
+        if self.breakpoints.should_break_at(initial_pc) {
+            return Err(Exception::Breakpoint);
+        }
 
         let opcode = self.fetch_operand()?;
         self.opcode = opcode;
@@ -333,6 +341,14 @@ impl<'registers, 'bus, B: BusInterface> InstructionExecutor<'registers, 'bus, B>
             self.name
         );
 
+        if let Some(trace_hook) = self.trace_hook.as_deref_mut() {
+            trace_hook(&TraceState {
+                pc: initial_pc,
+                status_register: self.registers.status_register(),
+                instruction,
+            });
+        }
+
         match instruction {
             Add { size: OpSize::Byte, source, dest, with_extend } => {
                 self.add_byte(source, dest, with_extend)
@@ -421,7 +437,7 @@ impl<'registers, 'bus, B: BusInterface> InstructionExecutor<'registers, 'bus, B>
             OrToCcr => self.ori_to_ccr(),
             OrToSr => self.ori_to_sr(),
             PushEffectiveAddress(source) => self.pea(source),
-            Reset => Ok(controlflow::reset()),
+            Reset => self.reset(),
             Return { restore_ccr } => self.ret(restore_ccr),
             ReturnFromException => self.rte(),
             RotateMemory(direction, dest) => self.rod_memory(direction, dest),
@@ -739,3 +755,17 @@ impl Display for Instruction {
         }
     }
 }
+
+#[cfg(test)]
+fn new_test_cpu(program: &[u16]) -> (crate::M68000, crate::bus::InMemoryBus) {
+    let mut bus = crate::bus::InMemoryBus::new();
+    for (i, &word) in program.iter().enumerate() {
+        bus.write_word(0x400 + 2 * i as u32, word);
+    }
+
+    let mut cpu = crate::M68000::default();
+    cpu.set_supervisor_stack_pointer(0x8000);
+    cpu.set_pc(0x400);
+
+    (cpu, bus)
+}
@@ -769,3 +769,316 @@ macro_rules! impl_compare_fn {
 impl_compare_fn!(compare_bytes, u8, 0x7F);
 impl_compare_fn!(compare_words, u16, 0x7FFF);
 impl_compare_fn!(compare_long_words, u32, 0x7FFF_FFFF);
+
+#[cfg(test)]
+mod tests {
+    use crate::core::instructions::new_test_cpu;
+    use crate::traits::BusInterface;
+
+    const X: u16 = 0x10;
+    const N: u16 = 0x08;
+    const Z: u16 = 0x04;
+    const V: u16 = 0x02;
+    const C: u16 = 0x01;
+
+    #[test]
+    fn cmpi_long_word() {
+        // (D0, immediate, expected NZVC)
+        let cases = [
+            (0x1234_5678_u32, 0x1234_5678_u32, Z),
+            (0x1234_5678, 0x0000_0001, 0),
+            (0x0000_0001, 0x1234_5678, N | C),
+            (0x8000_0000, 0x0000_0001, V),
+            (0x7FFF_FFFF, 0x8000_0000, N | V | C),
+        ];
+
+        for (d0, immediate, expected_ccr) in cases {
+            for x in [0, X] {
+                // CMPI.l #immediate, D0
+                let (mut cpu, mut bus) =
+                    new_test_cpu(&[0x0C80, (immediate >> 16) as u16, immediate as u16]);
+                cpu.set_status_register(0x2700 | x);
+                cpu.set_data_register(0, d0);
+
+                cpu.execute_instruction(&mut bus);
+
+                // Result is discarded and X is unaffected
+                assert_eq!(cpu.data_register(0), d0);
+                assert_eq!(
+                    cpu.status_register(),
+                    0x2700 | x | expected_ccr,
+                    "{d0:08X} {immediate:08X}"
+                );
+                assert_eq!(cpu.pc(), 0x406);
+            }
+        }
+    }
+
+    #[test]
+    fn cmpi_byte_word_memory() {
+        // CMPI.b #$80, (A0); CMPI.w #$0100, (A0)
+        let (mut cpu, mut bus) = new_test_cpu(&[0x0C10, 0x0080, 0x0C50, 0x0100]);
+        cpu.set_address_register(0, 0x1000);
+        bus.write_word(0x1000, 0x00FF);
+
+        // $00 - $80 = $80 with borrow and signed overflow
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.status_register() & 0x1F, N | V | C);
+
+        // $00FF - $0100 = $FFFF with borrow
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.status_register() & 0x1F, N | C);
+        assert_eq!(bus.read_word(0x1000), 0x00FF);
+    }
+
+    #[test]
+    fn cmpi_address_register_is_illegal() {
+        // CMPI.l #0, A0
+        let (mut cpu, mut bus) = new_test_cpu(&[0x0C88, 0x0000, 0x0000]);
+        bus.write_long_word(4 * 4, 0x2000);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.pc(), 0x2000);
+    }
+
+    #[test]
+    fn divu_divs() {
+        // (opcode, D0, D1, expected D0, expected NZVC)
+        let cases = [
+            // DIVU.w D1, D0: remainder in the high word, quotient in the low word
+            (0x80C1, 100_003, 10, 0x0003_2710, 0),
+            (0x80C1, 0x0000_0005, 0x0007, 0x0005_0000, Z),
+            (0x80C1, 0x0001_0000, 0x0002, 0x0000_8000, N),
+            // Quotient does not fit in 16 bits; D0 is unchanged
+            (0x80C1, 0x0010_0000, 0x0001, 0x0010_0000, V),
+            // DIVS.w D1, D0: the remainder takes the sign of the dividend
+            (0x81C1, -7_i32 as u32, 2, 0xFFFF_FFFD, N),
+            (0x81C1, 7, -2_i32 as u32, 0x0001_FFFD, N),
+            (0x81C1, -8_i32 as u32, -2_i32 as u32, 0x0000_0004, 0),
+            (0x81C1, 0x0001_0000, 0x0001, 0x0001_0000, V),
+        ];
+
+        for (opcode, d0, d1, expected_d0, expected_ccr) in cases {
+            let (mut cpu, mut bus) = new_test_cpu(&[opcode]);
+            cpu.set_data_register(0, d0);
+            cpu.set_data_register(1, d1);
+            cpu.set_status_register(0x2700 | X | C);
+
+            cpu.execute_instruction(&mut bus);
+
+            // N is undefined after an overflow
+            let ccr_mask = if expected_ccr & V != 0 { !N } else { !0 };
+            let context = format!("{opcode:04X} {d0:08X} / {d1:04X}");
+            assert_eq!(cpu.data_register(0), expected_d0, "{context}");
+            assert_eq!(
+                cpu.status_register() & ccr_mask,
+                (0x2700 | X | expected_ccr) & ccr_mask,
+                "{context}"
+            );
+        }
+    }
+
+    #[test]
+    fn divide_by_zero_traps() {
+        // DIVU.w D1, D0; DIVS.w D1, D0
+        for opcode in [0x80C1, 0x81C1] {
+            let (mut cpu, mut bus) = new_test_cpu(&[opcode]);
+            bus.write_long_word(5 * 4, 0x2000);
+            cpu.set_data_register(0, 0x1234_5678);
+
+            cpu.execute_instruction(&mut bus);
+            assert_eq!(cpu.pc(), 0x2000, "{opcode:04X}");
+            assert_eq!(cpu.data_register(0), 0x1234_5678, "{opcode:04X}");
+        }
+    }
+
+    #[test]
+    fn sub() {
+        // (opcode, D0, D1, expected D0, expected XNZVC)
+        let cases = [
+            // SUB.w D1, D0
+            (0x9041, 0x1234_0007, 0x0000_0005, 0x1234_0002, 0),
+            (0x9041, 0x1234_0005, 0x0000_0007, 0x1234_FFFE, X | N | C),
+            (0x9041, 0x1234_8000, 0x0000_8000, 0x1234_0000, Z),
+            // SUB.l D1, D0
+            (0x9081, 0x8000_0000, 0x0000_0001, 0x7FFF_FFFF, V),
+            (0x9081, 0x7FFF_FFFF, 0xFFFF_FFFF, 0x8000_0000, X | N | V | C),
+            // SUB.b D1, D0
+            (0x9001, 0x0000_0080, 0x0000_0001, 0x0000_007F, V),
+        ];
+
+        for (opcode, d0, d1, expected_d0, expected_ccr) in cases {
+            let (mut cpu, mut bus) = new_test_cpu(&[opcode]);
+            cpu.set_data_register(0, d0);
+            cpu.set_data_register(1, d1);
+
+            cpu.execute_instruction(&mut bus);
+
+            let context = format!("{opcode:04X} {d0:08X} - {d1:08X}");
+            assert_eq!(cpu.data_register(0), expected_d0, "{context}");
+            assert_eq!(cpu.status_register(), 0x2700 | expected_ccr, "{context}");
+        }
+    }
+
+    #[test]
+    fn sub_to_memory() {
+        // SUB.w D0, (A0)
+        let (mut cpu, mut bus) = new_test_cpu(&[0x9150]);
+        cpu.set_address_register(0, 0x1000);
+        cpu.set_data_register(0, 0x0001);
+        bus.write_word(0x1000, 0x0000);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(bus.read_word(0x1000), 0xFFFF);
+        assert_eq!(cpu.data_register(0), 0x0001);
+        assert_eq!(cpu.status_register() & 0x1F, X | N | C);
+    }
+
+    #[test]
+    fn suba() {
+        // SUBA.w D1, A0; SUBA.l D1, A0
+        let (mut cpu, mut bus) = new_test_cpu(&[0x90C1, 0x91C1]);
+        cpu.set_address_register(0, 0x0000_1000);
+        cpu.set_data_register(1, 0x0001_FFFF);
+        cpu.set_status_register(0x2700 | Z | C);
+
+        // Word source is sign extended
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.address_register(0), 0x0000_1001);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.address_register(0), 0xFFFE_1002);
+
+        // Flags are unaffected
+        assert_eq!(cpu.status_register(), 0x2700 | Z | C);
+    }
+
+    #[test]
+    fn subx_multi_word_predecrement() {
+        // SUBX.w -(A1), -(A0) twice, computing a 32-bit subtraction one word at a time
+        let run = |dest: u32, source: u32| {
+            let (mut cpu, mut bus) = new_test_cpu(&[0x9149, 0x9149]);
+            cpu.set_address_register(0, 0x1004);
+            cpu.set_address_register(1, 0x2004);
+            bus.write_long_word(0x1000, dest);
+            bus.write_long_word(0x2000, source);
+
+            // Z must be set before a chain so that it only remains set if every word is zero
+            cpu.set_status_register(0x2700 | Z);
+            cpu.execute_instruction(&mut bus);
+            cpu.execute_instruction(&mut bus);
+
+            assert_eq!(cpu.address_register(0), 0x1000);
+            assert_eq!(cpu.address_register(1), 0x2000);
+            (bus.read_long_word(0x1000), cpu.status_register() & 0x1F)
+        };
+
+        // The borrow from the low word propagates through X
+        assert_eq!(run(0x0001_0000, 0x0000_0001), (0x0000_FFFF, 0));
+        assert_eq!(run(0x0000_0000, 0x0000_0001), (0xFFFF_FFFF, X | N | C));
+        assert_eq!(run(0x0001_0001, 0x0001_0001), (0x0000_0000, Z));
+        // Low word is nonzero, so Z is cleared even though the high word result is zero
+        assert_eq!(run(0x0001_0002, 0x0001_0001), (0x0000_0001, 0));
+    }
+
+    #[test]
+    fn neg() {
+        // (opcode, D0, expected D0, expected XNZVC)
+        let cases = [
+            // NEG.b D0
+            (0x4400, 0x1234_5601, 0x1234_56FF, X | N | C),
+            (0x4400, 0x1234_5600, 0x1234_5600, Z),
+            // NEG.w D0
+            (0x4440, 0x1234_8000, 0x1234_8000, X | N | V | C),
+            // NEG.l D0
+            (0x4480, 0xFFFF_FFFF, 0x0000_0001, X | C),
+            (0x4480, 0x8000_0000, 0x8000_0000, X | N | V | C),
+        ];
+
+        for (opcode, d0, expected_d0, expected_ccr) in cases {
+            let (mut cpu, mut bus) = new_test_cpu(&[opcode]);
+            cpu.set_data_register(0, d0);
+
+            cpu.execute_instruction(&mut bus);
+            assert_eq!(cpu.data_register(0), expected_d0, "{opcode:04X} {d0:08X}");
+            assert_eq!(cpu.status_register(), 0x2700 | expected_ccr, "{opcode:04X} {d0:08X}");
+        }
+    }
+
+    #[test]
+    fn negx_multi_long_word() {
+        // NEG.l D0; NEGX.l D1: negates the 64-bit value D1:D0
+        let run = |value: u64| {
+            let (mut cpu, mut bus) = new_test_cpu(&[0x4480, 0x4081]);
+            cpu.set_data_register(0, value as u32);
+            cpu.set_data_register(1, (value >> 32) as u32);
+
+            cpu.execute_instruction(&mut bus);
+            cpu.execute_instruction(&mut bus);
+
+            let result = (u64::from(cpu.data_register(1)) << 32) | u64::from(cpu.data_register(0));
+            (result, cpu.status_register() & 0x1F)
+        };
+
+        assert_eq!(run(0x0000_0001_0000_0000), (0xFFFF_FFFF_0000_0000, X | N | C));
+        assert_eq!(run(0x0000_0000_0000_0001), (0xFFFF_FFFF_FFFF_FFFF, X | N | C));
+        assert_eq!(run(0x0000_0000_0000_0000), (0x0000_0000_0000_0000, Z));
+        // NEGX leaves Z clear when its own result is zero, so Z reflects the full 64-bit result
+        assert_eq!(run(0xFFFF_FFFF_0000_0001), (0x0000_0000_FFFF_FFFF, X | C));
+        assert_eq!(run(0x8000_0000_0000_0000), (0x8000_0000_0000_0000, X | N | V | C));
+    }
+
+    #[test]
+    fn abcd_multi_byte_predecrement() {
+        // ABCD -(A1), -(A0) twice: $1999 + $0001
+        let (mut cpu, mut bus) = new_test_cpu(&[0xC109, 0xC109]);
+        cpu.set_address_register(0, 0x1002);
+        cpu.set_address_register(1, 0x2002);
+        bus.write_word(0x1000, 0x1999);
+        bus.write_word(0x2000, 0x0001);
+        cpu.set_status_register(0x2700 | Z);
+
+        // Low byte carries; Z is not set by a zero result, only left alone
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(bus.read_byte(0x1001), 0x00);
+        assert_eq!(cpu.status_register() & (X | Z | C), X | Z | C);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(bus.read_word(0x1000), 0x2000);
+        assert_eq!(cpu.status_register() & (X | Z | C), 0);
+        assert_eq!(cpu.address_register(0), 0x1000);
+        assert_eq!(cpu.address_register(1), 0x2000);
+    }
+
+    #[test]
+    fn bcd_register() {
+        // (opcode, D0, D1, X, expected D0 low byte, expected XZC); Z starts set
+        let cases = [
+            // ABCD D1, D0
+            (0xC101, 0x45, 0x38, 0, 0x83, 0),
+            (0xC101, 0x45, 0x38, X, 0x84, 0),
+            (0xC101, 0x50, 0x50, 0, 0x00, X | Z | C),
+            // SBCD D1, D0
+            (0x8101, 0x42, 0x17, 0, 0x25, 0),
+            (0x8101, 0x00, 0x01, 0, 0x99, X | C),
+            (0x8101, 0x10, 0x09, X, 0x00, Z),
+            // NBCD D0
+            (0x4800, 0x25, 0x00, 0, 0x75, X | C),
+            (0x4800, 0x00, 0x00, 0, 0x00, Z),
+            (0x4800, 0x00, 0x00, X, 0x99, X | C),
+        ];
+
+        for (opcode, d0, d1, x, expected_d0, expected_ccr) in cases {
+            let (mut cpu, mut bus) = new_test_cpu(&[opcode]);
+            cpu.set_data_register(0, 0x1234_5600 | d0);
+            cpu.set_data_register(1, d1);
+            cpu.set_status_register(0x2700 | x | Z);
+
+            cpu.execute_instruction(&mut bus);
+
+            let context = format!("{opcode:04X} {d0:02X} {d1:02X} X={x}");
+            assert_eq!(cpu.data_register(0), 0x1234_5600 | expected_d0, "{context}");
+            assert_eq!(cpu.status_register() & (X | Z | C), expected_ccr, "{context}");
+        }
+    }
+}
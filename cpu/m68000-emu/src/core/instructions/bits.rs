@@ -655,3 +655,329 @@ fn shift_register_cycles(size: OpSize, shifts: u32) -> u32 {
 fn shift_memory_cycles(dest: AddressingMode) -> u32 {
     8 + dest.address_calculation_cycles(OpSize::Word)
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::bus::InMemoryBus;
+    use crate::core::instructions::new_test_cpu;
+    use crate::traits::BusInterface;
+
+    const X: u16 = 0x10;
+    const N: u16 = 0x08;
+    const Z: u16 = 0x04;
+    const V: u16 = 0x02;
+    const C: u16 = 0x01;
+
+    // Counts bus accesses outside of the program area
+    struct CountingBus {
+        inner: InMemoryBus,
+        data_reads: u32,
+        data_writes: u32,
+    }
+
+    impl CountingBus {
+        fn new(inner: InMemoryBus) -> Self {
+            Self { inner, data_reads: 0, data_writes: 0 }
+        }
+
+        fn count_read(&mut self, address: u32) {
+            if address >= 0x1000 {
+                self.data_reads += 1;
+            }
+        }
+
+        fn count_write(&mut self, address: u32) {
+            if address >= 0x1000 {
+                self.data_writes += 1;
+            }
+        }
+    }
+
+    impl BusInterface for CountingBus {
+        fn read_byte(&mut self, address: u32) -> u8 {
+            self.count_read(address);
+            self.inner.read_byte(address)
+        }
+
+        fn read_word(&mut self, address: u32) -> u16 {
+            self.count_read(address);
+            self.inner.read_word(address)
+        }
+
+        fn write_byte(&mut self, address: u32, value: u8) {
+            self.count_write(address);
+            self.inner.write_byte(address, value);
+        }
+
+        fn write_word(&mut self, address: u32, value: u16) {
+            self.count_write(address);
+            self.inner.write_word(address, value);
+        }
+
+        fn interrupt_level(&self) -> u8 {
+            0
+        }
+
+        fn acknowledge_interrupt(&mut self) {}
+
+        fn halt(&self) -> bool {
+            false
+        }
+
+        fn reset(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn btst_register_bit_number_is_mod_32() {
+        // BTST #3, D0; BTST #35, D0; BTST D1, D0
+        let (mut cpu, mut bus) = new_test_cpu(&[0x0800, 0x0003, 0x0800, 0x0023, 0x0300]);
+        cpu.set_data_register(0, 0x0000_0008);
+        cpu.set_data_register(1, 0x0000_0024);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.status_register() & Z, 0);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.status_register() & Z, 0);
+
+        // Bit 36 % 32 = 4 is clear
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.status_register() & Z, Z);
+        assert_eq!(cpu.data_register(0), 0x0000_0008);
+    }
+
+    #[test]
+    fn btst_does_not_write_memory() {
+        // BTST #11, (A0); BTST D1, (A0)
+        let (mut cpu, bus) = new_test_cpu(&[0x0810, 0x000B, 0x0310]);
+        let mut bus = CountingBus::new(bus);
+        cpu.set_address_register(0, 0x1000);
+        cpu.set_data_register(1, 2);
+        bus.inner.write_byte(0x1000, 0x08);
+
+        // Bit 11 % 8 = 3 is set
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.status_register() & Z, 0);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.status_register() & Z, Z);
+
+        assert_eq!(bus.data_reads, 2);
+        assert_eq!(bus.data_writes, 0);
+        assert_eq!(bus.inner.read_byte(0x1000), 0x08);
+    }
+
+    #[test]
+    fn bset_bclr_bchg_memory() {
+        // BSET #10, (A0); BSET #2, (A0); BCHG #7, (A0); BCLR D1, (A0)
+        let (mut cpu, mut bus) =
+            new_test_cpu(&[0x08D0, 0x000A, 0x08D0, 0x0002, 0x0850, 0x0007, 0x0390]);
+        cpu.set_address_register(0, 0x1000);
+        cpu.set_data_register(1, 2);
+        bus.write_word(0x1000, 0x0000);
+
+        // Z reflects the bit before it was modified
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(bus.read_byte(0x1000), 0x04);
+        assert_eq!(cpu.status_register() & Z, Z);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(bus.read_byte(0x1000), 0x04);
+        assert_eq!(cpu.status_register() & Z, 0);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(bus.read_byte(0x1000), 0x84);
+        assert_eq!(cpu.status_register() & Z, Z);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(bus.read_byte(0x1000), 0x80);
+        assert_eq!(cpu.status_register() & Z, 0);
+
+        // Byte-sized; the following byte is untouched
+        assert_eq!(bus.read_byte(0x1001), 0x00);
+    }
+
+    #[test]
+    fn bchg_register() {
+        // BCHG D1, D0
+        let (mut cpu, mut bus) = new_test_cpu(&[0x0340, 0x0340]);
+        cpu.set_data_register(0, 0x0000_0000);
+        cpu.set_data_register(1, 63);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.data_register(0), 0x8000_0000);
+        assert_eq!(cpu.status_register() & Z, Z);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.data_register(0), 0x0000_0000);
+        assert_eq!(cpu.status_register() & Z, 0);
+    }
+
+    #[test]
+    fn ext() {
+        // (opcode, D0, expected D0, expected NZ)
+        let cases = [
+            // EXT.w D0
+            (0x4880, 0xAAAA_AA7F, 0xAAAA_007F, 0),
+            (0x4880, 0x5555_5580, 0x5555_FF80, N),
+            (0x4880, 0x1234_5600, 0x1234_0000, Z),
+            // EXT.l D0
+            (0x48C0, 0xAAAA_7FFF, 0x0000_7FFF, 0),
+            (0x48C0, 0x5555_8000, 0xFFFF_8000, N),
+            (0x48C0, 0x1234_0000, 0x0000_0000, Z),
+        ];
+
+        for (opcode, d0, expected_d0, expected_ccr) in cases {
+            let (mut cpu, mut bus) = new_test_cpu(&[opcode]);
+            cpu.set_data_register(0, d0);
+            // V and C are cleared, X is unaffected
+            cpu.set_status_register(0x2700 | X | V | C);
+
+            cpu.execute_instruction(&mut bus);
+            assert_eq!(cpu.data_register(0), expected_d0, "{opcode:04X} {d0:08X}");
+            assert_eq!(cpu.status_register(), 0x2700 | X | expected_ccr, "{opcode:04X} {d0:08X}");
+        }
+    }
+
+    #[test]
+    fn shift_rotate_register() {
+        // (name, opcode, count, expected D0, expected XNZVC); all cases start with
+        // D0=$80000001 and X set. Opcodes use D1 as the count and D0 as the destination.
+        let cases = [
+            // Count 0 clears C, except for ROXd which copies X into C
+            ("ASL.l", 0xE3A0, 0, 0x8000_0001, X | N),
+            ("ASR.l", 0xE2A0, 0, 0x8000_0001, X | N),
+            ("LSL.l", 0xE3A8, 0, 0x8000_0001, X | N),
+            ("LSR.l", 0xE2A8, 0, 0x8000_0001, X | N),
+            ("ROXL.l", 0xE3B0, 0, 0x8000_0001, X | N | C),
+            ("ROXR.l", 0xE2B0, 0, 0x8000_0001, X | N | C),
+            ("ROL.l", 0xE3B8, 0, 0x8000_0001, X | N),
+            ("ROR.l", 0xE2B8, 0, 0x8000_0001, X | N),
+            // Count 1
+            ("ASL.l", 0xE3A0, 1, 0x0000_0002, X | V | C),
+            ("ASR.l", 0xE2A0, 1, 0xC000_0000, X | N | C),
+            ("LSL.l", 0xE3A8, 1, 0x0000_0002, X | C),
+            ("LSR.l", 0xE2A8, 1, 0x4000_0000, X | C),
+            ("ROXL.l", 0xE3B0, 1, 0x0000_0003, X | C),
+            ("ROXR.l", 0xE2B0, 1, 0xC000_0000, X | N | C),
+            ("ROL.l", 0xE3B8, 1, 0x0000_0003, X | C),
+            ("ROR.l", 0xE2B8, 1, 0xC000_0000, X | N | C),
+            // Count 33: shifts empty the register, ROXd rotates through all 33 bits back to
+            // the original value, and ROd rotates by 33 % 32
+            ("ASL.l", 0xE3A0, 33, 0x0000_0000, Z | V),
+            ("ASR.l", 0xE2A0, 33, 0xFFFF_FFFF, X | N | C),
+            ("LSL.l", 0xE3A8, 33, 0x0000_0000, Z),
+            ("LSR.l", 0xE2A8, 33, 0x0000_0000, Z),
+            ("ROXL.l", 0xE3B0, 33, 0x8000_0001, X | N | C),
+            ("ROXR.l", 0xE2B0, 33, 0x8000_0001, X | N | C),
+            ("ROL.l", 0xE3B8, 33, 0x0000_0003, X | C),
+            ("ROR.l", 0xE2B8, 33, 0xC000_0000, X | N | C),
+        ];
+
+        for (name, opcode, count, expected_d0, expected_ccr) in cases {
+            let (mut cpu, mut bus) = new_test_cpu(&[opcode]);
+            cpu.set_data_register(0, 0x8000_0001);
+            cpu.set_data_register(1, count);
+            cpu.set_status_register(0x2700 | X);
+
+            cpu.execute_instruction(&mut bus);
+            assert_eq!(cpu.data_register(0), expected_d0, "{name} {count}");
+            assert_eq!(cpu.status_register(), 0x2700 | expected_ccr, "{name} {count}");
+        }
+    }
+
+    #[test]
+    fn roxl_chains_through_extend() {
+        // LSL.w #1, D0; ROXL.w #1, D1: a 32-bit shift split across two registers
+        let (mut cpu, mut bus) = new_test_cpu(&[0xE348, 0xE351]);
+        cpu.set_data_register(0, 0x0000_8001);
+        cpu.set_data_register(1, 0x0000_4000);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.data_register(0), 0x0000_0002);
+        assert_eq!(cpu.status_register() & 0x1F, X | C);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.data_register(1), 0x0000_8001);
+        assert_eq!(cpu.status_register() & 0x1F, N);
+    }
+
+    #[test]
+    fn shift_memory_by_one() {
+        // LSR.w (A0); ASL.w (A0)
+        let (mut cpu, mut bus) = new_test_cpu(&[0xE2D0, 0xE1D0]);
+        cpu.set_address_register(0, 0x1000);
+        bus.write_word(0x1000, 0x0001);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(bus.read_word(0x1000), 0x0000);
+        assert_eq!(cpu.status_register() & 0x1F, X | Z | C);
+
+        bus.write_word(0x1000, 0x4000);
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(bus.read_word(0x1000), 0x8000);
+        assert_eq!(cpu.status_register() & 0x1F, N | V);
+    }
+
+    #[test]
+    fn clr_reads_before_writing() {
+        // CLR.b (A0)
+        let (mut cpu, bus) = new_test_cpu(&[0x4210]);
+        let mut bus = CountingBus::new(bus);
+        cpu.set_address_register(0, 0x1000);
+        cpu.set_status_register(0x2700 | X | N | V | C);
+        bus.inner.write_word(0x1000, 0xFFFF);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(bus.data_reads, 1);
+        assert_eq!(bus.data_writes, 1);
+        assert_eq!(bus.inner.read_word(0x1000), 0x00FF);
+        assert_eq!(cpu.status_register(), 0x2700 | X | Z);
+    }
+
+    #[test]
+    fn clr_data_register() {
+        // CLR.w D0; CLR.l D1
+        let (mut cpu, mut bus) = new_test_cpu(&[0x4240, 0x4281]);
+        cpu.set_data_register(0, 0x1234_5678);
+        cpu.set_data_register(1, 0x1234_5678);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.data_register(0), 0x1234_0000);
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.data_register(1), 0x0000_0000);
+        assert_eq!(cpu.status_register(), 0x2700 | Z);
+    }
+
+    #[test]
+    fn clr_address_register_is_illegal() {
+        // CLR.w A0
+        let (mut cpu, mut bus) = new_test_cpu(&[0x4248]);
+        bus.write_long_word(4 * 4, 0x2000);
+        cpu.set_address_register(0, 0x1234_5678);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.pc(), 0x2000);
+        assert_eq!(cpu.address_register(0), 0x1234_5678);
+    }
+
+    #[test]
+    fn swap() {
+        // SWAP D0
+        let (mut cpu, mut bus) = new_test_cpu(&[0x4840, 0x4840]);
+        cpu.set_data_register(0, 0x1234_5678);
+        cpu.set_status_register(0x2700 | X | V | C);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.data_register(0), 0x5678_1234);
+        assert_eq!(cpu.status_register(), 0x2700 | X);
+
+        // N is taken from bit 31 of the full result
+        cpu.set_data_register(0, 0x0000_8000);
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.data_register(0), 0x8000_0000);
+        assert_eq!(cpu.status_register(), 0x2700 | X | N);
+    }
+}
@@ -227,6 +227,16 @@ impl<'registers, 'bus, B: BusInterface> InstructionExecutor<'registers, 'bus, B>
         })
     }
 
+    pub(super) fn reset(&self) -> ExecuteResult<u32> {
+        if !self.registers.supervisor_mode {
+            return Err(Exception::PrivilegeViolation);
+        }
+
+        // The RESET instruction is intended to reset external devices, but this functionality isn't
+        // used in the Genesis, so treat it as an extremely long NOP
+        Ok(132)
+    }
+
     pub(super) fn stop(&mut self) -> ExecuteResult<u32> {
         if !self.registers.supervisor_mode {
             return Err(Exception::PrivilegeViolation);
@@ -263,12 +273,110 @@ pub(super) const fn nop() -> u32 {
     4
 }
 
-pub(super) const fn reset() -> u32 {
-    // The RESET instruction is intended to reset external devices, but this functionality isn't
-    // used in the Genesis, so treat it as an extremely long NOP
-    132
-}
-
 pub(super) fn trap(vector: u32) -> ExecuteResult<u32> {
     Err(Exception::Trap(TRAP_VECTOR_OFFSET + vector))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::core::instructions::new_test_cpu;
+    use crate::traits::BusInterface;
+
+    #[test]
+    fn jsr_rts() {
+        // JSR ($1000).l; NOP
+        let (mut cpu, mut bus) = new_test_cpu(&[0x4EB9, 0x0000, 0x1000, 0x4E71]);
+        // RTS
+        bus.write_word(0x1000, 0x4E75);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.pc(), 0x1000);
+        assert_eq!(cpu.stack_pointer(), 0x8000 - 4);
+        assert_eq!(bus.read_long_word(0x8000 - 4), 0x406);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.pc(), 0x406);
+        assert_eq!(cpu.stack_pointer(), 0x8000);
+    }
+
+    #[test]
+    fn jmp_pc_relative_uses_extension_word_address() {
+        // JMP ($100,PC)
+        let (mut cpu, mut bus) = new_test_cpu(&[0x4EFA, 0x0100]);
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.pc(), 0x402 + 0x100);
+
+        // JSR ($04,PC,D0.w)
+        let (mut cpu, mut bus) = new_test_cpu(&[0x4EBB, 0x0004]);
+        cpu.set_data_register(0, 0xFFFF_0010);
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.pc(), 0x402 + 0x04 + 0x10);
+        assert_eq!(bus.read_long_word(cpu.stack_pointer()), 0x404);
+    }
+
+    #[test]
+    fn jmp_non_control_modes_are_illegal() {
+        // JMP D0; JMP (A0)+; JMP -(A0); JSR D0
+        for opcode in [0x4EC0, 0x4ED8, 0x4EE0, 0x4E80] {
+            let (mut cpu, mut bus) = new_test_cpu(&[opcode]);
+            bus.write_long_word(4 * 4, 0x2000);
+            cpu.set_address_register(0, 0x1000);
+
+            cpu.execute_instruction(&mut bus);
+            assert_eq!(cpu.pc(), 0x2000, "{opcode:04X}");
+            assert_eq!(cpu.address_register(0), 0x1000, "{opcode:04X}");
+        }
+    }
+
+    #[test]
+    fn pea() {
+        // PEA ($12345678).l; PEA ($10,A0)
+        let (mut cpu, mut bus) = new_test_cpu(&[0x4879, 0x1234, 0x5678, 0x4868, 0x0010]);
+        cpu.set_address_register(0, 0x1000);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.stack_pointer(), 0x8000 - 4);
+        assert_eq!(bus.read_long_word(0x8000 - 4), 0x1234_5678);
+
+        // The address is pushed without being dereferenced
+        bus.write_long_word(0x1010, 0xFFFF_FFFF);
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.stack_pointer(), 0x8000 - 8);
+        assert_eq!(bus.read_long_word(0x8000 - 8), 0x1010);
+    }
+
+    #[test]
+    fn pea_non_control_modes_are_illegal() {
+        // PEA (A0)+; PEA -(A0)
+        for opcode in [0x4858, 0x4860] {
+            let (mut cpu, mut bus) = new_test_cpu(&[opcode]);
+            bus.write_long_word(4 * 4, 0x2000);
+            cpu.set_address_register(0, 0x1000);
+
+            cpu.execute_instruction(&mut bus);
+            assert_eq!(cpu.pc(), 0x2000, "{opcode:04X}");
+            assert_eq!(cpu.address_register(0), 0x1000, "{opcode:04X}");
+        }
+    }
+
+    #[test]
+    fn link_unlk() {
+        // LINK A6, #-8; MOVE.l D0, (-4,A6); UNLK A6
+        let (mut cpu, mut bus) = new_test_cpu(&[0x4E56, 0xFFF8, 0x2D40, 0xFFFC, 0x4E5E]);
+        cpu.set_address_register(6, 0x1234_5678);
+        cpu.set_data_register(0, 0xAAAA_AAAA);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(bus.read_long_word(0x8000 - 4), 0x1234_5678);
+        assert_eq!(cpu.address_register(6), 0x8000 - 4);
+        assert_eq!(cpu.stack_pointer(), 0x8000 - 4 - 8);
+
+        // Local variable lives below the frame pointer
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(bus.read_long_word(0x8000 - 8), 0xAAAA_AAAA);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.address_register(6), 0x1234_5678);
+        assert_eq!(cpu.stack_pointer(), 0x8000);
+    }
+}
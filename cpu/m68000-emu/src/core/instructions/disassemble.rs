@@ -0,0 +1,565 @@
+use crate::core::instructions::{
+    table, BranchCondition, Direction, Instruction, ShiftCount, ShiftDirection, UspDirection,
+};
+use crate::core::{parse_index, AddressingMode, IndexRegister, IndexSize, OpSize};
+
+struct WordStream<'a> {
+    words: &'a [u16],
+    position: usize,
+}
+
+impl<'a> WordStream<'a> {
+    fn new(words: &'a [u16]) -> Self {
+        Self { words, position: 0 }
+    }
+
+    fn fetch(&mut self) -> Option<u16> {
+        let word = self.words.get(self.position).copied()?;
+        self.position += 1;
+        Some(word)
+    }
+
+    fn fetch_long(&mut self) -> Option<u32> {
+        let high = self.fetch()?;
+        let low = self.fetch()?;
+        Some((u32::from(high) << 16) | u32::from(low))
+    }
+}
+
+/// Disassemble the instruction at the start of the given word stream without touching a bus.
+///
+/// Returns the decoded instruction, its text in Motorola syntax (e.g. `move.w $1234(a0),d1`),
+/// and the number of words consumed including extension words. Returns `None` if the stream is
+/// empty or ends before all of the instruction's extension words.
+///
+/// Branch targets are rendered relative to the start of the instruction, e.g. `beq.s *+$8`.
+#[must_use]
+pub fn disassemble(opcode_stream: &[u16]) -> Option<(Instruction, String, usize)> {
+    let mut stream = WordStream::new(opcode_stream);
+
+    let opcode = stream.fetch()?;
+    let instruction = table::decode(opcode);
+    let text = format_instruction(instruction, opcode, &mut stream)?;
+
+    Some((instruction, text, stream.position))
+}
+
+fn format_instruction(
+    instruction: Instruction,
+    opcode: u16,
+    stream: &mut WordStream<'_>,
+) -> Option<String> {
+    // ORI, ANDI, SUBI, ADDI, EORI, and CMPI are all encoded in the $0xxx opcode line. The
+    // register forms can also take an immediate source (e.g. CMP #imm, Dn) and must not be
+    // rendered as the immediate forms
+    let immediate_form = opcode & 0xF000 == 0;
+
+    let text = match instruction {
+        Instruction::Add { size, source, dest, with_extend } => {
+            let mnemonic = arithmetic_mnemonic("add", source, dest, with_extend, immediate_form);
+            format_binary(&format!("{mnemonic}.{size}"), size, source, dest, stream)?
+        }
+        Instruction::AddDecimal { source, dest } => {
+            format_binary("abcd", OpSize::Byte, source, dest, stream)?
+        }
+        Instruction::And { size, source, dest } => {
+            let mnemonic = logical_mnemonic("and", immediate_form);
+            format_binary(&format!("{mnemonic}.{size}"), size, source, dest, stream)?
+        }
+        Instruction::AndToCcr => format!("andi #${:02X},ccr", stream.fetch()? as u8),
+        Instruction::AndToSr => format!("andi #${:04X},sr", stream.fetch()?),
+        Instruction::ArithmeticShiftMemory(direction, dest) => {
+            format_shift_memory("as", direction, dest, stream)?
+        }
+        Instruction::ArithmeticShiftRegister(size, direction, register, count) => {
+            format_shift_register("as", size, direction, register.0, count)
+        }
+        Instruction::BitTest { source, dest } => {
+            format_binary("btst", OpSize::Byte, source, dest, stream)?
+        }
+        Instruction::BitTestAndChange { source, dest } => {
+            format_binary("bchg", OpSize::Byte, source, dest, stream)?
+        }
+        Instruction::BitTestAndClear { source, dest } => {
+            format_binary("bclr", OpSize::Byte, source, dest, stream)?
+        }
+        Instruction::BitTestAndSet { source, dest } => {
+            format_binary("bset", OpSize::Byte, source, dest, stream)?
+        }
+        Instruction::Branch(condition, displacement) => {
+            let mnemonic = match condition {
+                BranchCondition::True => "bra".into(),
+                _ => format!("b{}", condition_suffix(condition)),
+            };
+            format_branch(&mnemonic, displacement, stream)?
+        }
+        Instruction::BranchDecrement(condition, register) => {
+            let displacement = stream.fetch()? as i16;
+            format!(
+                "db{} d{},{}",
+                condition_suffix(condition),
+                register.0,
+                format_relative(i32::from(displacement) + 2)
+            )
+        }
+        Instruction::BranchToSubroutine(displacement) => {
+            format_branch("bsr", displacement, stream)?
+        }
+        Instruction::CheckRegister(register, source) => {
+            format!("chk.w {},d{}", format_operand(source, OpSize::Word, stream)?, register.0)
+        }
+        Instruction::Clear(size, dest) => {
+            format!("clr.{size} {}", format_operand(dest, size, stream)?)
+        }
+        Instruction::Compare { size, source, dest } => {
+            let mnemonic = match (source, dest) {
+                _ if immediate_form => "cmpi",
+                (_, AddressingMode::AddressDirect(..)) => "cmpa",
+                (
+                    AddressingMode::AddressIndirectPostincrement(..),
+                    AddressingMode::AddressIndirectPostincrement(..),
+                ) => "cmpm",
+                _ => "cmp",
+            };
+            format_binary(&format!("{mnemonic}.{size}"), size, source, dest, stream)?
+        }
+        Instruction::DivideSigned(register, source) => {
+            format!("divs.w {},d{}", format_operand(source, OpSize::Word, stream)?, register.0)
+        }
+        Instruction::DivideUnsigned(register, source) => {
+            format!("divu.w {},d{}", format_operand(source, OpSize::Word, stream)?, register.0)
+        }
+        Instruction::ExchangeAddress(rx, ry) => format!("exg a{},a{}", rx.0, ry.0),
+        Instruction::ExchangeData(rx, ry) => format!("exg d{},d{}", rx.0, ry.0),
+        Instruction::ExchangeDataAddress(rx, ry) => format!("exg d{},a{}", rx.0, ry.0),
+        Instruction::ExclusiveOr { size, source, dest } => {
+            let mnemonic = logical_mnemonic("eor", immediate_form);
+            format_binary(&format!("{mnemonic}.{size}"), size, source, dest, stream)?
+        }
+        Instruction::ExclusiveOrToCcr => format!("eori #${:02X},ccr", stream.fetch()? as u8),
+        Instruction::ExclusiveOrToSr => format!("eori #${:04X},sr", stream.fetch()?),
+        Instruction::Extend(size, register) => format!("ext.{size} d{}", register.0),
+        Instruction::Illegal { opcode: 0x4AFC } => "illegal".into(),
+        Instruction::Illegal { opcode } => format!("dc.w ${opcode:04X}"),
+        Instruction::Jump(dest) => {
+            format!("jmp {}", format_operand(dest, OpSize::LongWord, stream)?)
+        }
+        Instruction::JumpToSubroutine(dest) => {
+            format!("jsr {}", format_operand(dest, OpSize::LongWord, stream)?)
+        }
+        Instruction::Link(register) => {
+            let displacement = stream.fetch()? as i16;
+            format!("link a{},#{}", register.0, format_signed_hex(displacement.into()))
+        }
+        Instruction::LoadEffectiveAddress(source, register) => {
+            format!("lea {},a{}", format_operand(source, OpSize::LongWord, stream)?, register.0)
+        }
+        Instruction::LogicalShiftMemory(direction, dest) => {
+            format_shift_memory("ls", direction, dest, stream)?
+        }
+        Instruction::LogicalShiftRegister(size, direction, register, count) => {
+            format_shift_register("ls", size, direction, register.0, count)
+        }
+        Instruction::Move { size, source, dest } => {
+            let mnemonic = if dest.is_address_direct() { "movea" } else { "move" };
+            format_binary(&format!("{mnemonic}.{size}"), size, source, dest, stream)?
+        }
+        Instruction::MoveFromSr(dest) => {
+            format!("move.w sr,{}", format_operand(dest, OpSize::Word, stream)?)
+        }
+        Instruction::MoveMultiple(size, addressing_mode, direction) => {
+            let mask = stream.fetch()?;
+            let mask = match addressing_mode {
+                AddressingMode::AddressIndirectPredecrement(..) => mask.reverse_bits(),
+                _ => mask,
+            };
+            let registers = format_register_list(mask);
+            let operand = format_operand(addressing_mode, size, stream)?;
+            match direction {
+                Direction::RegisterToMemory => format!("movem.{size} {registers},{operand}"),
+                Direction::MemoryToRegister => format!("movem.{size} {operand},{registers}"),
+            }
+        }
+        Instruction::MovePeripheral(size, data, address, direction) => {
+            let displacement = stream.fetch()? as i16;
+            let operand = format!("{}(a{})", format_signed_hex(displacement.into()), address.0);
+            match direction {
+                Direction::RegisterToMemory => format!("movep.{size} d{},{operand}", data.0),
+                Direction::MemoryToRegister => format!("movep.{size} {operand},d{}", data.0),
+            }
+        }
+        Instruction::MoveQuick(value, register) => format!("moveq #{value},d{}", register.0),
+        Instruction::MoveToCcr(source) => {
+            format!("move.w {},ccr", format_operand(source, OpSize::Word, stream)?)
+        }
+        Instruction::MoveToSr(source) => {
+            format!("move.w {},sr", format_operand(source, OpSize::Word, stream)?)
+        }
+        Instruction::MoveUsp(UspDirection::RegisterToUsp, register) => {
+            format!("move.l a{},usp", register.0)
+        }
+        Instruction::MoveUsp(UspDirection::UspToRegister, register) => {
+            format!("move.l usp,a{}", register.0)
+        }
+        Instruction::MultiplySigned(register, source) => {
+            format!("muls.w {},d{}", format_operand(source, OpSize::Word, stream)?, register.0)
+        }
+        Instruction::MultiplyUnsigned(register, source) => {
+            format!("mulu.w {},d{}", format_operand(source, OpSize::Word, stream)?, register.0)
+        }
+        Instruction::Negate { size, dest, with_extend } => {
+            let mnemonic = if with_extend { "negx" } else { "neg" };
+            format!("{mnemonic}.{size} {}", format_operand(dest, size, stream)?)
+        }
+        Instruction::NegateDecimal(dest) => {
+            format!("nbcd {}", format_operand(dest, OpSize::Byte, stream)?)
+        }
+        Instruction::NoOp => "nop".into(),
+        Instruction::Not(size, dest) => {
+            format!("not.{size} {}", format_operand(dest, size, stream)?)
+        }
+        Instruction::Or { size, source, dest } => {
+            let mnemonic = logical_mnemonic("or", immediate_form);
+            format_binary(&format!("{mnemonic}.{size}"), size, source, dest, stream)?
+        }
+        Instruction::OrToCcr => format!("ori #${:02X},ccr", stream.fetch()? as u8),
+        Instruction::OrToSr => format!("ori #${:04X},sr", stream.fetch()?),
+        Instruction::PushEffectiveAddress(source) => {
+            format!("pea {}", format_operand(source, OpSize::LongWord, stream)?)
+        }
+        Instruction::Reset => "reset".into(),
+        Instruction::Return { restore_ccr: false } => "rts".into(),
+        Instruction::Return { restore_ccr: true } => "rtr".into(),
+        Instruction::ReturnFromException => "rte".into(),
+        Instruction::RotateMemory(direction, dest) => {
+            format_shift_memory("ro", direction, dest, stream)?
+        }
+        Instruction::RotateRegister(size, direction, register, count) => {
+            format_shift_register("ro", size, direction, register.0, count)
+        }
+        Instruction::RotateThruExtendMemory(direction, dest) => {
+            format_shift_memory("rox", direction, dest, stream)?
+        }
+        Instruction::RotateThruExtendRegister(size, direction, register, count) => {
+            format_shift_register("rox", size, direction, register.0, count)
+        }
+        Instruction::Set(condition, dest) => {
+            format!(
+                "s{} {}",
+                condition_suffix(condition),
+                format_operand(dest, OpSize::Byte, stream)?
+            )
+        }
+        Instruction::Subtract { size, source, dest, with_extend } => {
+            let mnemonic = arithmetic_mnemonic("sub", source, dest, with_extend, immediate_form);
+            format_binary(&format!("{mnemonic}.{size}"), size, source, dest, stream)?
+        }
+        Instruction::SubtractDecimal { source, dest } => {
+            format_binary("sbcd", OpSize::Byte, source, dest, stream)?
+        }
+        Instruction::Swap(register) => format!("swap d{}", register.0),
+        Instruction::Stop => format!("stop #${:04X}", stream.fetch()?),
+        Instruction::Test(size, source) => {
+            format!("tst.{size} {}", format_operand(source, size, stream)?)
+        }
+        Instruction::TestAndSet(dest) => {
+            format!("tas {}", format_operand(dest, OpSize::Byte, stream)?)
+        }
+        Instruction::Trap(vector) => format!("trap #{vector}"),
+        Instruction::TrapOnOverflow => "trapv".into(),
+        Instruction::Unlink(register) => format!("unlk a{}", register.0),
+    };
+
+    Some(text)
+}
+
+fn arithmetic_mnemonic(
+    base: &str,
+    source: AddressingMode,
+    dest: AddressingMode,
+    with_extend: bool,
+    immediate_form: bool,
+) -> String {
+    let suffix = match (source, dest) {
+        _ if with_extend => "x",
+        _ if immediate_form => "i",
+        (AddressingMode::Quick(..), _) => "q",
+        (_, AddressingMode::AddressDirect(..)) => "a",
+        _ => "",
+    };
+
+    format!("{base}{suffix}")
+}
+
+fn logical_mnemonic(base: &str, immediate_form: bool) -> String {
+    if immediate_form { format!("{base}i") } else { base.into() }
+}
+
+// Extension words are always ordered source first, then destination
+fn format_binary(
+    mnemonic: &str,
+    size: OpSize,
+    source: AddressingMode,
+    dest: AddressingMode,
+    stream: &mut WordStream<'_>,
+) -> Option<String> {
+    let source = format_operand(source, size, stream)?;
+    let dest = format_operand(dest, size, stream)?;
+    Some(format!("{mnemonic} {source},{dest}"))
+}
+
+fn format_branch(mnemonic: &str, displacement: i8, stream: &mut WordStream<'_>) -> Option<String> {
+    // A displacement of 0 in the opcode indicates that the displacement is in an extension word
+    let (displacement, suffix) = if displacement == 0 {
+        (i32::from(stream.fetch()? as i16), "w")
+    } else {
+        (i32::from(displacement), "s")
+    };
+
+    // Displacements are relative to the address immediately after the opcode
+    Some(format!("{mnemonic}.{suffix} {}", format_relative(displacement + 2)))
+}
+
+fn format_shift_memory(
+    base: &str,
+    direction: ShiftDirection,
+    dest: AddressingMode,
+    stream: &mut WordStream<'_>,
+) -> Option<String> {
+    Some(format!(
+        "{base}{}.w {}",
+        direction_suffix(direction),
+        format_operand(dest, OpSize::Word, stream)?
+    ))
+}
+
+fn format_shift_register(
+    base: &str,
+    size: OpSize,
+    direction: ShiftDirection,
+    register: u8,
+    count: ShiftCount,
+) -> String {
+    let count = match count {
+        ShiftCount::Constant(count) => format!("#{count}"),
+        ShiftCount::Register(register) => format!("d{}", register.0),
+    };
+
+    format!("{base}{}.{size} {count},d{register}", direction_suffix(direction))
+}
+
+fn format_operand(
+    addressing_mode: AddressingMode,
+    size: OpSize,
+    stream: &mut WordStream<'_>,
+) -> Option<String> {
+    let operand = match addressing_mode {
+        AddressingMode::DataDirect(register) => format!("d{}", register.0),
+        AddressingMode::AddressDirect(register) => format!("a{}", register.0),
+        AddressingMode::AddressIndirect(register) => format!("(a{})", register.0),
+        AddressingMode::AddressIndirectPostincrement(register) => format!("(a{})+", register.0),
+        AddressingMode::AddressIndirectPredecrement(register) => format!("-(a{})", register.0),
+        AddressingMode::AddressIndirectDisplacement(register) => {
+            let displacement = stream.fetch()? as i16;
+            format!("{}(a{})", format_signed_hex(displacement.into()), register.0)
+        }
+        AddressingMode::AddressIndirectIndexed(register) => {
+            let extension = stream.fetch()?;
+            format!(
+                "{}(a{},{})",
+                format_signed_hex((extension as i8).into()),
+                register.0,
+                format_index(extension)
+            )
+        }
+        AddressingMode::PcRelativeDisplacement => {
+            let displacement = stream.fetch()? as i16;
+            format!("{}(pc)", format_signed_hex(displacement.into()))
+        }
+        AddressingMode::PcRelativeIndexed => {
+            let extension = stream.fetch()?;
+            format!(
+                "{}(pc,{})",
+                format_signed_hex((extension as i8).into()),
+                format_index(extension)
+            )
+        }
+        AddressingMode::AbsoluteShort => format!("${:04X}.w", stream.fetch()?),
+        AddressingMode::AbsoluteLong => format!("${:08X}.l", stream.fetch_long()?),
+        AddressingMode::Immediate => match size {
+            OpSize::Byte => format!("#${:02X}", stream.fetch()? as u8),
+            OpSize::Word => format!("#${:04X}", stream.fetch()?),
+            OpSize::LongWord => format!("#${:08X}", stream.fetch_long()?),
+        },
+        AddressingMode::Quick(value) => format!("#{value}"),
+    };
+
+    Some(operand)
+}
+
+fn format_index(extension: u16) -> String {
+    let (register, size) = parse_index(extension);
+    let register = match register {
+        IndexRegister::Data(register) => format!("d{}", register.0),
+        IndexRegister::Address(register) => format!("a{}", register.0),
+    };
+    let size = match size {
+        IndexSize::SignExtendedWord => "w",
+        IndexSize::LongWord => "l",
+    };
+
+    format!("{register}.{size}")
+}
+
+// Mask is expected in D0-A7 order, i.e. already reversed for predecrement mode
+fn format_register_list(mask: u16) -> String {
+    let mut ranges = Vec::new();
+
+    for (base, prefix) in [(0, 'd'), (8, 'a')] {
+        let mut i = 0;
+        while i < 8 {
+            if mask & (1 << (base + i)) == 0 {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            while i < 8 && mask & (1 << (base + i)) != 0 {
+                i += 1;
+            }
+            let end = i - 1;
+
+            if start == end {
+                ranges.push(format!("{prefix}{start}"));
+            } else {
+                ranges.push(format!("{prefix}{start}-{prefix}{end}"));
+            }
+        }
+    }
+
+    ranges.join("/")
+}
+
+fn format_signed_hex(value: i32) -> String {
+    if value < 0 { format!("-${:X}", value.unsigned_abs()) } else { format!("${value:X}") }
+}
+
+fn format_relative(offset: i32) -> String {
+    if offset < 0 { format!("*-${:X}", offset.unsigned_abs()) } else { format!("*+${offset:X}") }
+}
+
+fn direction_suffix(direction: ShiftDirection) -> char {
+    match direction {
+        ShiftDirection::Left => 'l',
+        ShiftDirection::Right => 'r',
+    }
+}
+
+fn condition_suffix(condition: BranchCondition) -> &'static str {
+    match condition {
+        BranchCondition::True => "t",
+        BranchCondition::False => "f",
+        BranchCondition::Higher => "hi",
+        BranchCondition::LowerOrSame => "ls",
+        BranchCondition::CarryClear => "cc",
+        BranchCondition::CarrySet => "cs",
+        BranchCondition::NotEqual => "ne",
+        BranchCondition::Equal => "eq",
+        BranchCondition::OverflowClear => "vc",
+        BranchCondition::OverflowSet => "vs",
+        BranchCondition::Plus => "pl",
+        BranchCondition::Minus => "mi",
+        BranchCondition::GreaterOrEqual => "ge",
+        BranchCondition::LessThan => "lt",
+        BranchCondition::GreaterThan => "gt",
+        BranchCondition::LessOrEqual => "le",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn disassemble_text(words: &[u16]) -> (String, usize) {
+        let (_, text, len) = disassemble(words).expect("instruction should disassemble");
+        (text, len)
+    }
+
+    #[test]
+    fn move_with_displacement() {
+        let (instruction, text, len) = disassemble(&[0x3228, 0x1234]).unwrap();
+        assert!(matches!(instruction, Instruction::Move { size: OpSize::Word, .. }));
+        assert_eq!(text, "move.w $1234(a0),d1");
+        assert_eq!(len, 2);
+    }
+
+    #[test]
+    fn single_word_instructions() {
+        assert_eq!(disassemble_text(&[0x4E71]), ("nop".into(), 1));
+        assert_eq!(disassemble_text(&[0x4E75]), ("rts".into(), 1));
+        assert_eq!(disassemble_text(&[0x72FF]), ("moveq #-1,d1".into(), 1));
+        assert_eq!(disassemble_text(&[0x4840]), ("swap d0".into(), 1));
+    }
+
+    #[test]
+    fn long_immediate() {
+        assert_eq!(disassemble_text(&[0x0C80, 0x8000, 0x0000]), ("cmpi.l #$80000000,d0".into(), 3));
+    }
+
+    #[test]
+    fn immediate_source_register_forms() {
+        // CMPI.w #$1234, D0 and CMP.w #$1234, D0 decode to the same instruction
+        assert_eq!(disassemble_text(&[0x0C40, 0x1234]), ("cmpi.w #$1234,d0".into(), 2));
+        assert_eq!(disassemble_text(&[0xB07C, 0x1234]), ("cmp.w #$1234,d0".into(), 2));
+
+        assert_eq!(disassemble_text(&[0x0640, 0x0001]), ("addi.w #$0001,d0".into(), 2));
+        assert_eq!(disassemble_text(&[0xD07C, 0x0001]), ("add.w #$0001,d0".into(), 2));
+        assert_eq!(disassemble_text(&[0xD0FC, 0x0001]), ("adda.w #$0001,a0".into(), 2));
+
+        assert_eq!(disassemble_text(&[0x0240, 0x00FF]), ("andi.w #$00FF,d0".into(), 2));
+        assert_eq!(disassemble_text(&[0xC07C, 0x00FF]), ("and.w #$00FF,d0".into(), 2));
+    }
+
+    #[test]
+    fn movem_predecrement() {
+        assert_eq!(disassemble_text(&[0x48E7, 0xFFFE]), ("movem.l d0-d7/a0-a6,-(a7)".into(), 2));
+        assert_eq!(disassemble_text(&[0x4CDF, 0x7FFF]), ("movem.l (a7)+,d0-d7/a0-a6".into(), 2));
+    }
+
+    #[test]
+    fn branches() {
+        assert_eq!(disassemble_text(&[0x6706]), ("beq.s *+$8".into(), 1));
+        assert_eq!(disassemble_text(&[0x6000, 0xFFFC]), ("bra.w *-$2".into(), 2));
+        assert_eq!(disassemble_text(&[0x51C8, 0xFFFC]), ("dbf d0,*-$2".into(), 2));
+    }
+
+    #[test]
+    fn instruction_parts_are_public() {
+        use crate::instruction::{AddressingMode, BranchCondition, Instruction, OpSize};
+
+        let (instruction, _, _) = disassemble(&[0x3228, 0x1234]).unwrap();
+        let Instruction::Move {
+            size: OpSize::Word,
+            source: AddressingMode::AddressIndirectDisplacement(source),
+            dest: AddressingMode::DataDirect(dest),
+        } = instruction
+        else {
+            panic!("expected move.w (d, An), Dn, was {instruction:?}");
+        };
+        assert_eq!(source.number(), 0);
+        assert_eq!(dest.number(), 1);
+
+        let (instruction, _, _) = disassemble(&[0x51C8, 0xFFFC]).unwrap();
+        assert!(matches!(
+            instruction,
+            Instruction::BranchDecrement(BranchCondition::False, register) if register.number() == 0
+        ));
+    }
+
+    #[test]
+    fn truncated_stream() {
+        assert!(disassemble(&[]).is_none());
+        assert!(disassemble(&[0x3228]).is_none());
+        assert!(disassemble(&[0x0C80, 0x8000]).is_none());
+    }
+}
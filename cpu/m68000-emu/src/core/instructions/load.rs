@@ -419,3 +419,51 @@ impl Iterator for MultipleRegisterIter {
 fn to_register(i: u8) -> Register {
     if i < 8 { Register::Data(DataRegister(i)) } else { Register::Address(AddressRegister(i - 8)) }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::core::instructions::new_test_cpu;
+    use crate::traits::BusInterface;
+
+    #[test]
+    fn movem_long_word_round_trip() {
+        // MOVEM.l D0-D7/A0-A6, -(A7); MOVEM.l (A7)+, D0-D7/A0-A6
+        let (mut cpu, mut bus) = new_test_cpu(&[0x48E7, 0xFFFE, 0x4CDF, 0x7FFF]);
+        let data_registers = std::array::from_fn(|i| 0x1111_1111 * i as u32);
+        let address_registers = std::array::from_fn(|i| 0x0100_0000 | (i as u32 + 1));
+        cpu.set_data_registers(data_registers);
+        cpu.set_address_registers(address_registers, 0, 0x8000);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.stack_pointer(), 0x8000 - 15 * 4);
+
+        // Predecrement stores in A6..D0 order, leaving D0 at the lowest address
+        assert_eq!(bus.read_long_word(0x8000 - 60), data_registers[0]);
+        assert_eq!(bus.read_long_word(0x8000 - 32), data_registers[7]);
+        assert_eq!(bus.read_long_word(0x8000 - 28), address_registers[0]);
+        assert_eq!(bus.read_long_word(0x8000 - 4), address_registers[6]);
+
+        cpu.set_data_registers([0; 8]);
+        cpu.set_address_registers([0; 7], 0, 0x8000 - 60);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.data_registers(), data_registers);
+        assert_eq!(cpu.address_registers(), address_registers);
+        assert_eq!(cpu.stack_pointer(), 0x8000);
+    }
+
+    #[test]
+    fn movem_word_sign_extends() {
+        // MOVEM.w (A0)+, D0/A1
+        let (mut cpu, mut bus) = new_test_cpu(&[0x4C98, 0x0201]);
+        cpu.set_address_register(0, 0x1000);
+        cpu.set_data_register(0, 0x1234_5678);
+        bus.write_word(0x1000, 0x8000);
+        bus.write_word(0x1002, 0x7FFF);
+
+        cpu.execute_instruction(&mut bus);
+        assert_eq!(cpu.data_register(0), 0xFFFF_8000);
+        assert_eq!(cpu.address_register(1), 0x0000_7FFF);
+        assert_eq!(cpu.address_register(0), 0x1004);
+    }
+}
@@ -3,5 +3,13 @@ pub mod bus;
 mod core;
 pub mod traits;
 
-pub use crate::core::M68000;
+pub use crate::core::{disassemble, BreakpointHit, Breakpoints, Instruction, TraceState, M68000};
+
+/// Types that make up a decoded [`Instruction`], as returned by [`disassemble`].
+pub mod instruction {
+    pub use crate::core::{
+        AddressRegister, AddressingMode, BranchCondition, DataRegister, Direction, Instruction,
+        OpSize, ShiftCount, ShiftDirection, UspDirection,
+    };
+}
 pub use traits::BusInterface;
@@ -183,6 +183,14 @@ impl Mos6502 {
     /// Run the CPU for 1 cycle.
     #[inline]
     pub fn tick<B: BusInterface>(&mut self, bus: &mut B) {
+        self.tick_traced(bus, |_| {});
+    }
+
+    /// Same as [`Self::tick`], but calls `trace_hook` with the opcode's PC when this cycle starts
+    /// a new instruction. The hook is not called when the CPU is starting an interrupt or is
+    /// frozen.
+    #[inline]
+    pub fn tick_traced<B: BusInterface, F: FnMut(u16)>(&mut self, bus: &mut B, mut trace_hook: F) {
         if self.frozen {
             // CPU was frozen by an illegal KIL opcode; do nothing
             return;
@@ -196,6 +204,7 @@ impl Mos6502 {
                 self.state.pending_interrupt = false;
                 self.state.executing_interrupt = true;
             } else {
+                trace_hook(self.registers.pc);
                 self.registers.pc = self.registers.pc.wrapping_add(1);
                 self.state.opcode = opcode;
             }
@@ -184,12 +184,16 @@ impl Wdc65816 {
         Self { registers: Registers::new(), state: State::default() }
     }
 
+    /// Perform the 65816 reset sequence: load PC from the RESET vector at $00FFFC-$00FFFD and
+    /// put the CPU into emulation mode with the documented power-on register values.
     pub fn reset<B: BusInterface>(&mut self, bus: &mut B) {
         let reset_vector: u32 = InterruptType::Reset.emulation_vector().into();
         let address_lsb = bus.read(reset_vector);
         let address_msb = bus.read(reset_vector + 1);
         self.registers.pc = u16::from_le_bytes([address_lsb, address_msb]);
         self.registers.pbr = 0;
+        self.registers.dbr = 0;
+        self.registers.d = 0;
 
         self.registers.p.irq_disabled = true;
         self.registers.p.decimal_mode = false;
@@ -207,6 +211,14 @@ impl Wdc65816 {
 
     #[inline]
     pub fn tick<B: BusInterface>(&mut self, bus: &mut B) {
+        self.tick_traced(bus, |_| {});
+    }
+
+    /// Same as [`Self::tick`], but calls `trace_hook` with the 24-bit address of the opcode when
+    /// this cycle starts a new instruction. The hook is not called when the CPU is starting an
+    /// interrupt, is waiting or stopped, or is halted/reset by the bus.
+    #[inline]
+    pub fn tick_traced<B: BusInterface, F: FnMut(u32)>(&mut self, bus: &mut B, trace_hook: F) {
         if bus.reset() {
             self.state.reset_pending = true;
             bus.idle();
@@ -225,7 +237,7 @@ impl Wdc65816 {
             return;
         }
 
-        instructions::execute(self, bus);
+        instructions::execute(self, bus, trace_hook);
     }
 
     #[inline]
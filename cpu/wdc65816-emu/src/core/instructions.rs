@@ -1765,7 +1765,7 @@ fn wdm<B: BusInterface>(cpu: &mut Wdc65816, bus: &mut B) {
     cpu.registers.pc = cpu.registers.pc.wrapping_add(1);
 }
 
-pub fn execute<B: BusInterface>(cpu: &mut Wdc65816, bus: &mut B) {
+pub fn execute<B: BusInterface, F: FnMut(u32)>(cpu: &mut Wdc65816, bus: &mut B, mut trace_hook: F) {
     if cpu.state.cycle != 0 {
         // If mid-instruction, continue to execute that instruction
         execute_cycle(cpu, bus);
@@ -1815,6 +1815,7 @@ pub fn execute<B: BusInterface>(cpu: &mut Wdc65816, bus: &mut B) {
     }
 
     // Cycle 0 and not handling an interrupt is always opcode fetch
+    trace_hook((u32::from(cpu.registers.pbr) << 16) | u32::from(cpu.registers.pc));
     cpu.state.opcode = fetch_operand(cpu, bus);
     cpu.state.cycle = 1;
 
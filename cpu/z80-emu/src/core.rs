@@ -339,6 +339,18 @@ impl Z80 {
 
     /// Execute a single instruction (or the interrupt service routine) and return how many T-cycles it took.
     pub fn execute_instruction<B: BusInterface>(&mut self, bus: &mut B) -> u32 {
+        self.execute_instruction_traced(bus, |_| {})
+    }
+
+    /// Same as [`Self::execute_instruction`], but calls `trace_hook` with the instruction's PC
+    /// before it is executed. The hook is not called when the Z80 is handling an interrupt, is
+    /// halted, or is stalled by RESET or BUSREQ.
+    #[inline]
+    pub fn execute_instruction_traced<B: BusInterface, F: FnMut(u16)>(
+        &mut self,
+        bus: &mut B,
+        trace_hook: F,
+    ) -> u32 {
         if bus.reset() {
             // RESET is asserted; reset internal state
             self.registers.i = 0;
@@ -360,7 +372,7 @@ impl Z80 {
 
         self.stalled = false;
 
-        instructions::execute(&mut self.registers, bus)
+        instructions::execute(&mut self.registers, bus, trace_hook)
     }
 
     /// Tick the Z80 for a single T-cycle.
@@ -384,3 +396,61 @@ impl Default for Z80 {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::InMemoryBus;
+
+    #[test]
+    fn nmi_is_edge_triggered() {
+        let mut z80 = Z80::new();
+        z80.set_sp(0x8000);
+
+        // All NOPs
+        let mut bus = InMemoryBus::new();
+
+        // Holding NMI low should only trigger a single NMI
+        bus.nmi = InterruptLine::Low;
+        for _ in 0..100 {
+            z80.execute_instruction(&mut bus);
+        }
+        assert_eq!(z80.registers.sp, 0x7FFE);
+
+        bus.nmi = InterruptLine::High;
+        for _ in 0..10 {
+            z80.execute_instruction(&mut bus);
+        }
+        assert_eq!(z80.registers.sp, 0x7FFE);
+
+        // Releasing and re-asserting NMI should trigger another NMI
+        bus.nmi = InterruptLine::Low;
+        for _ in 0..100 {
+            z80.execute_instruction(&mut bus);
+        }
+        assert_eq!(z80.registers.sp, 0x7FFC);
+    }
+
+    #[test]
+    fn trace_hook_skips_halt_and_interrupts() {
+        let mut z80 = Z80::new();
+        z80.set_sp(0x8000);
+
+        // NOP; HALT; NMI handler at $0066 is all NOPs
+        let mut bus = InMemoryBus::new();
+        bus.memory[0x0001] = 0x76;
+
+        let mut trace = Vec::new();
+        for _ in 0..5 {
+            z80.execute_instruction_traced(&mut bus, |pc| trace.push(pc));
+        }
+        assert_eq!(trace, vec![0x0000, 0x0001]);
+
+        // The interrupt service routine is not traced, but the handler's first instruction is
+        bus.nmi = InterruptLine::Low;
+        for _ in 0..2 {
+            z80.execute_instruction_traced(&mut bus, |pc| trace.push(pc));
+        }
+        assert_eq!(trace, vec![0x0000, 0x0001, 0x0066]);
+    }
+}
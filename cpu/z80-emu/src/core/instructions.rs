@@ -329,7 +329,7 @@ impl<'registers, 'bus, B: BusInterface> InstructionExecutor<'registers, 'bus, B>
         }
     }
 
-    fn execute(mut self) -> u32 {
+    fn execute<F: FnMut(u16)>(mut self, mut trace_hook: F) -> u32 {
         self.registers.r = (self.registers.r.wrapping_add(1) & 0x7F) | (self.registers.r & 0x80);
 
         let interrupt_type = self.check_pending_interrupt();
@@ -345,6 +345,8 @@ impl<'registers, 'bus, B: BusInterface> InstructionExecutor<'registers, 'bus, B>
             return control::nop();
         }
 
+        trace_hook(self.registers.pc);
+
         let ParseResult { opcode, index_prefix: index, index_fetch_t_cycles } = self.parse_opcode();
 
         log::trace!(
@@ -460,6 +462,10 @@ impl<'registers, 'bus, B: BusInterface> InstructionExecutor<'registers, 'bus, B>
     }
 }
 
-pub fn execute<B: BusInterface>(registers: &mut Registers, bus: &mut B) -> u32 {
-    InstructionExecutor::new(registers, bus).execute()
+pub fn execute<B: BusInterface, F: FnMut(u16)>(
+    registers: &mut Registers,
+    bus: &mut B,
+    trace_hook: F,
+) -> u32 {
+    InstructionExecutor::new(registers, bus).execute(trace_hook)
 }
@@ -21,6 +21,7 @@ use jgenesis_renderer::config::{
     VSyncMode, WgpuBackend,
 };
 use nes_core::api::{NesAspectRatio, Overscan};
+use smsgg_core::audio::DEFAULT_PSG_LPF_CUTOFF;
 use smsgg_core::psg::PsgVersion;
 use smsgg_core::{SmsRegion, VdpVersion};
 use snes_core::api::SnesAspectRatio;
@@ -72,6 +73,10 @@ struct Args {
     #[arg(long, default_value_t)]
     hide_cursor_over_window: bool,
 
+    /// Fixed seed for pseudorandom power-on state such as initial RAM contents; a random seed is used on every run if not set
+    #[arg(long)]
+    rng_seed: Option<u64>,
+
     /// Force VDP version (NtscMasterSystem2 / NtscMasterSystem1 / PalMasterSystem2 / PalMasterSystem1 / GameGear)
     #[arg(long, help_heading = SMSGG_OPTIONS_HEADING)]
     vdp_version: Option<VdpVersion>,
@@ -104,6 +109,22 @@ struct Args {
     #[arg(long = "disable-sms-fm-unit", default_value_t = true, action = clap::ArgAction::SetFalse, help_heading = SMSGG_OPTIONS_HEADING)]
     sms_fm_unit_enabled: bool,
 
+    /// SMS/GG PSG volume multiplier
+    #[arg(long, default_value_t = 1.0, help_heading = SMSGG_OPTIONS_HEADING)]
+    smsgg_psg_gain: f64,
+
+    /// SMS FM sound unit volume multiplier
+    #[arg(long, default_value_t = 1.0, help_heading = SMSGG_OPTIONS_HEADING)]
+    sms_fm_gain: f64,
+
+    /// SMS/GG audio low-pass filter cutoff frequency in Hz
+    #[arg(long, default_value_t = DEFAULT_PSG_LPF_CUTOFF, help_heading = SMSGG_OPTIONS_HEADING)]
+    smsgg_lpf_cutoff: u32,
+
+    /// Disable SMS/GG audio low-pass filtering
+    #[arg(long = "disable-smsgg-lpf", default_value_t = true, action = clap::ArgAction::SetFalse, help_heading = SMSGG_OPTIONS_HEADING)]
+    smsgg_lpf_enabled: bool,
+
     /// Overclock the Z80 CPU to 2x speed
     #[arg(long, default_value_t, help_heading = SMSGG_OPTIONS_HEADING)]
     smsgg_overclock_z80: bool,
@@ -120,10 +141,26 @@ struct Args {
     #[arg(long, default_value_t, help_heading = GENESIS_OPTIONS_HEADING)]
     genesis_render_horizontal_border: bool,
 
+    /// Apply an NTSC composite video filter, which blends dithered patterns and produces artifact colors
+    #[arg(long, default_value_t, help_heading = GENESIS_OPTIONS_HEADING)]
+    genesis_ntsc_composite_filter: bool,
+
     /// Disable YM2612 output quantization, letting outputs cover the full 14-bit range instead of only using the highest 9 bits
     #[arg(long = "no-ym2612-quantization", default_value_t = true, action = clap::ArgAction::SetFalse, help_heading = GENESIS_OPTIONS_HEADING)]
     quantize_ym2612_output: bool,
 
+    /// Apply a high-pass filter that removes DC offset from Genesis / Sega CD audio output
+    #[arg(long, default_value_t, help_heading = GENESIS_OPTIONS_HEADING)]
+    genesis_dc_blocking_filter: bool,
+
+    /// Genesis / Sega CD PSG balance, from -1.0 (left only) to 1.0 (right only)
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true, help_heading = GENESIS_OPTIONS_HEADING)]
+    genesis_psg_pan: f64,
+
+    /// Downmix Genesis / Sega CD audio output to mono
+    #[arg(long, default_value_t, help_heading = GENESIS_OPTIONS_HEADING)]
+    genesis_mono_audio: bool,
+
     /// Aspect ratio (Ntsc / Pal / SquarePixels / Stretched)
     #[arg(long, default_value_t, help_heading = GENESIS_OPTIONS_HEADING)]
     genesis_aspect_ratio: GenesisAspectRatio,
@@ -137,6 +174,18 @@ struct Args {
     #[arg(long, help_heading = GENESIS_OPTIONS_HEADING)]
     genesis_region: Option<GenesisRegion>,
 
+    /// Force console region independently of cartridge region (Americas / Japan / Europe)
+    #[arg(long, help_heading = GENESIS_OPTIONS_HEADING)]
+    genesis_console_region: Option<GenesisRegion>,
+
+    /// Emulate a console with TMSS, which locks the VDP until the game writes "SEGA" to $A14000
+    #[arg(long, default_value_t, help_heading = GENESIS_OPTIONS_HEADING)]
+    genesis_tmss: bool,
+
+    /// ROM to lock on top of the main ROM, which must be Sonic & Knuckles
+    #[arg(long, help_heading = GENESIS_OPTIONS_HEADING)]
+    genesis_lock_on_path: Option<String>,
+
     /// Sega CD BIOS path (required for Sega CD emulation)
     #[arg(short = 'b', long, help_heading = SCD_OPTIONS_HEADING)]
     bios_path: Option<String>,
@@ -547,6 +596,7 @@ impl Args {
             joystick_inputs,
             hotkeys: self.hotkey_config(),
             hide_cursor_over_window: self.hide_cursor_over_window,
+            rng_seed: self.rng_seed,
         }
     }
 
@@ -555,8 +605,11 @@ impl Args {
         let common = self.common_config(keyboard_inputs, GenesisInputConfig::default());
         GenesisConfig {
             common,
+            lock_on_rom_file_path: self.genesis_lock_on_path.clone(),
             forced_timing_mode: self.forced_timing_mode,
             forced_region: self.genesis_region,
+            console_region: self.genesis_console_region,
+            tmss_enabled: self.genesis_tmss,
             p1_controller_type: self.input_p1_type,
             p2_controller_type: GenesisControllerType::default(),
             aspect_ratio: self.genesis_aspect_ratio,
@@ -565,7 +618,15 @@ impl Args {
             emulate_non_linear_vdp_dac: self.emulate_non_linear_vdp_dac,
             render_vertical_border: self.genesis_render_vertical_border,
             render_horizontal_border: self.genesis_render_horizontal_border,
+            ntsc_composite_filter: self.genesis_ntsc_composite_filter,
+            plane_a_enabled: true,
+            plane_b_enabled: true,
+            window_enabled: true,
+            sprites_enabled: true,
             quantize_ym2612_output: self.quantize_ym2612_output,
+            dc_blocking_filter: self.genesis_dc_blocking_filter,
+            psg_pan: self.genesis_psg_pan,
+            mono_audio: self.genesis_mono_audio,
         }
     }
 }
@@ -617,14 +678,21 @@ fn run_sms(args: Args) -> anyhow::Result<()> {
     let config = SmsGgConfig {
         common,
         vdp_version: args.vdp_version,
+        forced_timing_mode: args.forced_timing_mode,
         psg_version: args.psg_version,
         remove_sprite_limit: args.remove_sprite_limit,
+        background_enabled: true,
+        sprites_enabled: true,
+        left_column_blanking_enabled: true,
         sms_aspect_ratio: args.sms_aspect_ratio,
         gg_aspect_ratio: args.gg_aspect_ratio,
         sms_region: args.sms_region,
         sms_crop_vertical_border: args.sms_crop_vertical_border,
         sms_crop_left_border: args.sms_crop_left_border,
         fm_sound_unit_enabled: args.sms_fm_unit_enabled,
+        psg_gain: args.smsgg_psg_gain,
+        fm_gain: args.sms_fm_gain,
+        psg_lpf_cutoff: args.smsgg_lpf_enabled.then_some(args.smsgg_lpf_cutoff),
         overclock_z80: args.smsgg_overclock_z80,
     };
 
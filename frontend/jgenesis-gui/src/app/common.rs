@@ -47,6 +47,8 @@ pub struct CommonAppConfig {
     pub rewind_buffer_length_seconds: u64,
     #[serde(default)]
     pub hide_cursor_over_window: bool,
+    #[serde(default)]
+    pub rng_seed: Option<u64>,
 }
 
 impl CommonAppConfig {
@@ -129,6 +131,7 @@ impl AppConfig {
             joystick_inputs,
             hotkeys: self.inputs.hotkeys.clone(),
             hide_cursor_over_window: self.common.hide_cursor_over_window,
+            rng_seed: self.common.rng_seed,
         }
     }
 }
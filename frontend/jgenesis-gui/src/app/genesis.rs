@@ -1,19 +1,23 @@
 use crate::app::{App, AppConfig, OpenWindow};
 use crate::emuthread::EmuThreadStatus;
-use egui::{Context, Window};
+use egui::{Context, Slider, Window};
 use genesis_core::{GenesisAspectRatio, GenesisRegion};
 use jgenesis_common::frontend::TimingMode;
 use jgenesis_native_driver::config::{GenesisConfig, SegaCdConfig};
 use rfd::FileDialog;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GenesisAppConfig {
     #[serde(default)]
     forced_timing_mode: Option<TimingMode>,
     #[serde(default)]
     forced_region: Option<GenesisRegion>,
     #[serde(default)]
+    console_region: Option<GenesisRegion>,
+    #[serde(default)]
+    tmss_enabled: bool,
+    #[serde(default)]
     aspect_ratio: GenesisAspectRatio,
     #[serde(default = "true_fn")]
     adjust_aspect_ratio_in_2x_resolution: bool,
@@ -25,8 +29,24 @@ pub struct GenesisAppConfig {
     render_vertical_border: bool,
     #[serde(default)]
     render_horizontal_border: bool,
+    #[serde(default)]
+    ntsc_composite_filter: bool,
+    #[serde(default = "true_fn")]
+    plane_a_enabled: bool,
+    #[serde(default = "true_fn")]
+    plane_b_enabled: bool,
+    #[serde(default = "true_fn")]
+    window_enabled: bool,
+    #[serde(default = "true_fn")]
+    sprites_enabled: bool,
     #[serde(default = "true_fn")]
     quantize_ym2612_output: bool,
+    #[serde(default)]
+    dc_blocking_filter: bool,
+    #[serde(default)]
+    psg_pan: f64,
+    #[serde(default)]
+    mono_audio: bool,
 }
 
 const fn true_fn() -> bool {
@@ -62,17 +82,28 @@ impl AppConfig {
                 self.inputs.genesis_keyboard.clone(),
                 self.inputs.genesis_joystick.clone(),
             ),
+            lock_on_rom_file_path: None,
             p1_controller_type: self.inputs.genesis_p1_type,
             p2_controller_type: self.inputs.genesis_p2_type,
             forced_timing_mode: self.genesis.forced_timing_mode,
             forced_region: self.genesis.forced_region,
+            console_region: self.genesis.console_region,
+            tmss_enabled: self.genesis.tmss_enabled,
             aspect_ratio: self.genesis.aspect_ratio,
             adjust_aspect_ratio_in_2x_resolution: self.genesis.adjust_aspect_ratio_in_2x_resolution,
             remove_sprite_limits: self.genesis.remove_sprite_limits,
             emulate_non_linear_vdp_dac: self.genesis.emulate_non_linear_vdp_dac,
             render_vertical_border: self.genesis.render_vertical_border,
             render_horizontal_border: self.genesis.render_horizontal_border,
+            ntsc_composite_filter: self.genesis.ntsc_composite_filter,
+            plane_a_enabled: self.genesis.plane_a_enabled,
+            plane_b_enabled: self.genesis.plane_b_enabled,
+            window_enabled: self.genesis.window_enabled,
+            sprites_enabled: self.genesis.sprites_enabled,
             quantize_ym2612_output: self.genesis.quantize_ym2612_output,
+            dc_blocking_filter: self.genesis.dc_blocking_filter,
+            psg_pan: self.genesis.psg_pan,
+            mono_audio: self.genesis.mono_audio,
         })
     }
 
@@ -140,6 +171,32 @@ impl App {
                 });
             });
 
+            ui.group(|ui| {
+                ui.label("Console region");
+
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.config.genesis.console_region, None, "Same as region");
+                    ui.radio_value(
+                        &mut self.config.genesis.console_region,
+                        Some(GenesisRegion::Americas),
+                        "Americas",
+                    );
+                    ui.radio_value(
+                        &mut self.config.genesis.console_region,
+                        Some(GenesisRegion::Japan),
+                        "Japan",
+                    );
+                    ui.radio_value(
+                        &mut self.config.genesis.console_region,
+                        Some(GenesisRegion::Europe),
+                        "Europe",
+                    );
+                });
+            });
+
+            ui.checkbox(&mut self.config.genesis.tmss_enabled, "Emulate TMSS")
+                .on_hover_text("Lock the VDP until the game writes \"SEGA\" to $A14000");
+
             ui.add_space(5.0);
             ui.horizontal(|ui| {
                 ui.set_enabled(self.emu_thread.status() != EmuThreadStatus::RunningSegaCd);
@@ -235,6 +292,23 @@ impl App {
                 &mut self.config.genesis.render_horizontal_border,
                 "Render horizontal border",
             );
+
+            ui.checkbox(
+                &mut self.config.genesis.ntsc_composite_filter,
+                "NTSC composite video filter",
+            )
+            .on_hover_text("Blends dithered patterns and produces composite artifact colors");
+
+            ui.group(|ui| {
+                ui.label("Enabled layers");
+
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.config.genesis.plane_a_enabled, "Plane A");
+                    ui.checkbox(&mut self.config.genesis.plane_b_enabled, "Plane B");
+                    ui.checkbox(&mut self.config.genesis.window_enabled, "Window");
+                    ui.checkbox(&mut self.config.genesis.sprites_enabled, "Sprites");
+                });
+            });
         });
         if !open {
             self.state.open_windows.remove(&OpenWindow::GenesisVideo);
@@ -251,6 +325,17 @@ impl App {
             .on_hover_text(
                 "Quantize channel outputs from 14 bits to 9 bits to emulate the YM2612's 9-bit DAC",
             );
+
+            ui.checkbox(&mut self.config.genesis.dc_blocking_filter, "DC blocking filter")
+                .on_hover_text(
+                    "Remove DC offset from audio output to avoid clicks on pause/resume",
+                );
+
+            ui.add(
+                Slider::new(&mut self.config.genesis.psg_pan, -1.0..=1.0)
+                    .text("PSG balance (left / right)"),
+            );
+            ui.checkbox(&mut self.config.genesis.mono_audio, "Mono audio output");
         });
         if !open {
             self.state.open_windows.remove(&OpenWindow::GenesisAudio);
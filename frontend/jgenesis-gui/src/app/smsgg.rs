@@ -1,9 +1,10 @@
 use crate::app::{App, AppConfig, OpenWindow};
 use crate::emuthread::EmuThreadStatus;
-use egui::{Context, Window};
+use egui::{Context, Slider, Window};
 use jgenesis_common::frontend::TimingMode;
 use jgenesis_native_driver::config::{GgAspectRatio, SmsAspectRatio, SmsGgConfig};
 use serde::{Deserialize, Serialize};
+use smsgg_core::audio::DEFAULT_PSG_LPF_CUTOFF;
 use smsgg_core::psg::PsgVersion;
 use smsgg_core::{SmsRegion, VdpVersion};
 use std::ffi::OsStr;
@@ -16,7 +17,7 @@ pub enum SmsModel {
     Sms2,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SmsGgAppConfig {
     psg_version: Option<PsgVersion>,
     #[serde(default)]
@@ -36,7 +37,21 @@ pub struct SmsGgAppConfig {
     #[serde(default)]
     sms_crop_left_border: bool,
     #[serde(default = "true_fn")]
+    background_enabled: bool,
+    #[serde(default = "true_fn")]
+    sprites_enabled: bool,
+    #[serde(default = "true_fn")]
+    left_column_blanking_enabled: bool,
+    #[serde(default = "true_fn")]
     fm_sound_unit_enabled: bool,
+    #[serde(default = "default_gain")]
+    psg_gain: f64,
+    #[serde(default = "default_gain")]
+    fm_gain: f64,
+    #[serde(default = "true_fn")]
+    lpf_enabled: bool,
+    #[serde(default = "default_lpf_cutoff")]
+    lpf_cutoff: u32,
     #[serde(default)]
     overclock_z80: bool,
 }
@@ -45,6 +60,14 @@ const fn true_fn() -> bool {
     true
 }
 
+const fn default_gain() -> f64 {
+    1.0
+}
+
+const fn default_lpf_cutoff() -> u32 {
+    DEFAULT_PSG_LPF_CUTOFF
+}
+
 impl Default for SmsGgAppConfig {
     fn default() -> Self {
         toml::from_str("").unwrap()
@@ -71,14 +94,21 @@ impl AppConfig {
                 self.inputs.smsgg_joystick.clone(),
             ),
             vdp_version,
+            forced_timing_mode: None,
             psg_version: self.smsgg.psg_version,
             remove_sprite_limit: self.smsgg.remove_sprite_limit,
+            background_enabled: self.smsgg.background_enabled,
+            sprites_enabled: self.smsgg.sprites_enabled,
+            left_column_blanking_enabled: self.smsgg.left_column_blanking_enabled,
             sms_aspect_ratio: self.smsgg.sms_aspect_ratio,
             gg_aspect_ratio: self.smsgg.gg_aspect_ratio,
             sms_region: self.smsgg.sms_region,
             sms_crop_vertical_border: self.smsgg.sms_crop_vertical_border,
             sms_crop_left_border: self.smsgg.sms_crop_left_border,
             fm_sound_unit_enabled: self.smsgg.fm_sound_unit_enabled,
+            psg_gain: self.smsgg.psg_gain,
+            fm_gain: self.smsgg.fm_gain,
+            psg_lpf_cutoff: self.smsgg.lpf_enabled.then_some(self.smsgg.lpf_cutoff),
             overclock_z80: self.smsgg.overclock_z80,
         })
     }
@@ -208,6 +238,19 @@ impl App {
                 "(SMS) Crop vertical border",
             );
             ui.checkbox(&mut self.config.smsgg.sms_crop_left_border, "(SMS) Crop left border");
+
+            ui.group(|ui| {
+                ui.label("Enabled layers");
+
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.config.smsgg.background_enabled, "Background");
+                    ui.checkbox(&mut self.config.smsgg.sprites_enabled, "Sprites");
+                    ui.checkbox(
+                        &mut self.config.smsgg.left_column_blanking_enabled,
+                        "Left column blanking",
+                    );
+                });
+            });
         });
         if !open {
             self.state.open_windows.remove(&OpenWindow::SmsGgVideo);
@@ -239,6 +282,16 @@ impl App {
                 });
             });
 
+            ui.add(Slider::new(&mut self.config.smsgg.psg_gain, 0.0..=2.0).text("PSG volume"));
+            ui.add(Slider::new(&mut self.config.smsgg.fm_gain, 0.0..=2.0).text("FM volume"));
+
+            ui.checkbox(&mut self.config.smsgg.lpf_enabled, "Low-pass filter enabled");
+            ui.add_enabled(
+                self.config.smsgg.lpf_enabled,
+                Slider::new(&mut self.config.smsgg.lpf_cutoff, 2000..=24000)
+                    .text("Low-pass filter cutoff (Hz)"),
+            );
+
             ui.set_enabled(self.emu_thread.status() != EmuThreadStatus::RunningSmsGg);
             ui.checkbox(
                 &mut self.config.smsgg.fm_sound_unit_enabled,
@@ -5,10 +5,12 @@ use crate::config::input::{
     NesInputConfig, SmsGgInputConfig, SnesControllerType, SnesInputConfig, SuperScopeConfig,
 };
 use gb_core::api::{GameBoyEmulatorConfig, GbAspectRatio, GbPalette, GbcColorCorrection};
+use genesis_core::deinterlace::DeinterlacingMode;
 use genesis_core::{
     GenesisAspectRatio, GenesisControllerType, GenesisEmulatorConfig, GenesisRegion,
 };
 use jgenesis_common::frontend::{PixelAspectRatio, TimingMode};
+use jgenesis_common::ram::RamInitPattern;
 use jgenesis_proc_macros::{ConfigDisplay, EnumDisplay, EnumFromStr};
 use jgenesis_renderer::config::RendererConfig;
 use nes_core::api::{NesAspectRatio, NesEmulatorConfig, Overscan};
@@ -101,6 +103,15 @@ pub struct CommonConfig<KeyboardConfig, JoystickConfig> {
     #[indent_nested]
     pub hotkeys: HotkeyConfig,
     pub hide_cursor_over_window: bool,
+    // Fixed seed for pseudorandom power-on state such as initial RAM contents; if not set, a new
+    // seed is generated on every run so that power-on state varies like on actual hardware
+    pub rng_seed: Option<u64>,
+}
+
+impl<KeyboardConfig, JoystickConfig> CommonConfig<KeyboardConfig, JoystickConfig> {
+    pub(crate) fn rng_seed(&self) -> u64 {
+        self.rng_seed.unwrap_or_else(rand::random)
+    }
 }
 
 #[derive(Debug, Clone, ConfigDisplay)]
@@ -108,14 +119,21 @@ pub struct SmsGgConfig {
     #[indent_nested]
     pub common: CommonConfig<SmsGgInputConfig<KeyboardInput>, SmsGgInputConfig<JoystickInput>>,
     pub vdp_version: Option<VdpVersion>,
+    pub forced_timing_mode: Option<TimingMode>,
     pub psg_version: Option<PsgVersion>,
     pub remove_sprite_limit: bool,
+    pub background_enabled: bool,
+    pub sprites_enabled: bool,
+    pub left_column_blanking_enabled: bool,
     pub sms_aspect_ratio: SmsAspectRatio,
     pub gg_aspect_ratio: GgAspectRatio,
     pub sms_region: SmsRegion,
     pub sms_crop_vertical_border: bool,
     pub sms_crop_left_border: bool,
     pub fm_sound_unit_enabled: bool,
+    pub psg_gain: f64,
+    pub fm_gain: f64,
+    pub psg_lpf_cutoff: Option<u32>,
     pub overclock_z80: bool,
 }
 
@@ -132,13 +150,23 @@ impl SmsGgConfig {
         };
         SmsGgEmulatorConfig {
             vdp_version,
+            forced_timing_mode: self.forced_timing_mode,
             psg_version,
             pixel_aspect_ratio,
             remove_sprite_limit: self.remove_sprite_limit,
+            background_enabled: self.background_enabled,
+            sprites_enabled: self.sprites_enabled,
+            left_column_blanking_enabled: self.left_column_blanking_enabled,
             sms_region: self.sms_region,
+            ram_init_pattern: RamInitPattern::default(),
+            rng_seed: self.common.rng_seed(),
             sms_crop_vertical_border: self.sms_crop_vertical_border,
             sms_crop_left_border: self.sms_crop_left_border,
+            overscan_border_width: 0,
             fm_sound_unit_enabled: self.fm_sound_unit_enabled,
+            psg_gain: self.psg_gain,
+            fm_gain: self.fm_gain,
+            psg_lpf_cutoff: self.psg_lpf_cutoff,
             overclock_z80: self.overclock_z80,
         }
     }
@@ -178,10 +206,14 @@ pub(crate) fn default_smsgg_window_size(vdp_version: VdpVersion) -> WindowSize {
 pub struct GenesisConfig {
     #[indent_nested]
     pub common: CommonConfig<GenesisInputConfig<KeyboardInput>, GenesisInputConfig<JoystickInput>>,
+    // Cartridge ROM to lock on top of the main ROM, which must be Sonic & Knuckles
+    pub lock_on_rom_file_path: Option<String>,
     pub p1_controller_type: GenesisControllerType,
     pub p2_controller_type: GenesisControllerType,
     pub forced_timing_mode: Option<TimingMode>,
     pub forced_region: Option<GenesisRegion>,
+    pub console_region: Option<GenesisRegion>,
+    pub tmss_enabled: bool,
     pub aspect_ratio: GenesisAspectRatio,
     // Whether or not to automatically double the pixel aspect ratio when the VDP is in interlaced
     // double resolution mode
@@ -190,7 +222,15 @@ pub struct GenesisConfig {
     pub emulate_non_linear_vdp_dac: bool,
     pub render_vertical_border: bool,
     pub render_horizontal_border: bool,
+    pub ntsc_composite_filter: bool,
+    pub plane_a_enabled: bool,
+    pub plane_b_enabled: bool,
+    pub window_enabled: bool,
+    pub sprites_enabled: bool,
     pub quantize_ym2612_output: bool,
+    pub dc_blocking_filter: bool,
+    pub psg_pan: f64,
+    pub mono_audio: bool,
 }
 
 impl GenesisConfig {
@@ -198,13 +238,27 @@ impl GenesisConfig {
         GenesisEmulatorConfig {
             forced_timing_mode: self.forced_timing_mode,
             forced_region: self.forced_region,
+            console_region: self.console_region,
+            tmss_enabled: self.tmss_enabled,
+            ram_init_pattern: RamInitPattern::default(),
+            rng_seed: self.common.rng_seed(),
             aspect_ratio: self.aspect_ratio,
             adjust_aspect_ratio_in_2x_resolution: self.adjust_aspect_ratio_in_2x_resolution,
+            deinterlacing_mode: DeinterlacingMode::default(),
             remove_sprite_limits: self.remove_sprite_limits,
             emulate_non_linear_vdp_dac: self.emulate_non_linear_vdp_dac,
             render_vertical_border: self.render_vertical_border,
             render_horizontal_border: self.render_horizontal_border,
+            ntsc_composite_filter: self.ntsc_composite_filter,
+            overscan_border_width: 0,
+            plane_a_enabled: self.plane_a_enabled,
+            plane_b_enabled: self.plane_b_enabled,
+            window_enabled: self.window_enabled,
+            sprites_enabled: self.sprites_enabled,
             quantize_ym2612_output: self.quantize_ym2612_output,
+            dc_blocking_filter: self.dc_blocking_filter,
+            psg_pan: self.psg_pan,
+            mono_audio: self.mono_audio,
             p1_controller_type: self.p1_controller_type,
             p2_controller_type: self.p2_controller_type,
         }
@@ -254,6 +308,7 @@ impl NesConfig {
             remove_sprite_limit: self.remove_sprite_limit,
             pal_black_border: self.pal_black_border,
             silence_ultrasonic_triangle_output: self.silence_ultrasonic_triangle_output,
+            rng_seed: self.common.rng_seed(),
             audio_refresh_rate_adjustment: self.audio_refresh_rate_adjustment,
             allow_opposing_joypad_inputs: self.allow_opposing_joypad_inputs,
         }
@@ -286,6 +341,8 @@ impl SnesConfig {
             aspect_ratio: self.aspect_ratio,
             audio_60hz_hack: self.audio_60hz_hack,
             gsu_overclock_factor: self.gsu_overclock_factor,
+            ram_init_pattern: RamInitPattern::default(),
+            rng_seed: self.common.rng_seed(),
         }
     }
 
@@ -326,6 +383,7 @@ impl GameBoyConfig {
             gb_palette: self.gb_palette,
             gbc_color_correction: self.gbc_color_correction,
             audio_60hz_hack: self.audio_60hz_hack,
+            rng_seed: self.common.rng_seed(),
         }
     }
 }
@@ -21,10 +21,11 @@ use crate::mainloop::debug::{DebugRenderFn, DebuggerWindow};
 use crate::mainloop::rewind::Rewinder;
 use crate::mainloop::save::FsSaveWriter;
 pub use audio::AudioError;
-use bincode::error::{DecodeError, EncodeError};
 use bincode::{Decode, Encode};
 use gb_core::api::GameBoyLoadError;
+use genesis_core::GenesisLoadError;
 use jgenesis_common::frontend::{EmulatorTrait, PartialClone, TickEffect};
+use jgenesis_common::save_state::{read_state, write_state, SaveStateError, VersionedState};
 use jgenesis_renderer::renderer::{RendererError, WgpuRenderer};
 use nes_core::api::NesInitializationError;
 pub use save::SaveWriteError;
@@ -249,6 +250,8 @@ pub enum NativeEmulatorError {
         source: io::Error,
     },
 
+    #[error("{0}")]
+    GenesisLoad(#[from] GenesisLoadError),
     #[error("{0}")]
     SegaCdDisc(#[from] SegaCdLoadError),
     #[error("{0}")]
@@ -264,9 +267,9 @@ pub enum NativeEmulatorError {
         source: io::Error,
     },
     #[error("Error saving state: {0}")]
-    SaveState(#[from] EncodeError),
+    SaveState(#[source] SaveStateError),
     #[error("Error loading state: {0}")]
-    LoadState(#[from] DecodeError),
+    LoadState(#[source] SaveStateError),
     #[error("Error in emulation core: {0}")]
     Emulator(#[source] Box<dyn Error + Send + Sync + 'static>),
 }
@@ -694,7 +697,7 @@ use bincode_config;
 
 fn save_state<E, P>(emulator: &E, path: P) -> NativeEmulatorResult<()>
 where
-    E: Encode,
+    E: Encode + VersionedState,
     P: AsRef<Path>,
 {
     let path = path.as_ref();
@@ -703,8 +706,7 @@ where
         NativeEmulatorError::StateFileOpen { path: path.display().to_string(), source }
     })?);
 
-    let conf = bincode_config!();
-    bincode::encode_into_std_write(emulator, &mut file, conf)?;
+    write_state(emulator, &mut file, bincode_config!()).map_err(NativeEmulatorError::SaveState)?;
 
     log::info!("Saved state to {}", path.display());
 
@@ -713,7 +715,7 @@ where
 
 fn load_state<D, P>(path: P) -> NativeEmulatorResult<D>
 where
-    D: Decode,
+    D: Decode + VersionedState,
     P: AsRef<Path>,
 {
     let path = path.as_ref();
@@ -722,8 +724,8 @@ where
         NativeEmulatorError::StateFileOpen { path: path.display().to_string(), source }
     })?);
 
-    let conf = bincode_config!();
-    let emulator = bincode::decode_from_std_read(&mut file, conf)?;
+    let emulator =
+        read_state(&mut file, bincode_config!()).map_err(NativeEmulatorError::LoadState)?;
 
     log::info!("Loaded state from {}", path.display());
 
@@ -125,7 +125,20 @@ pub fn create_genesis(config: Box<GenesisConfig>) -> NativeEmulatorResult<Native
     let mut save_writer = FsSaveWriter::new(save_path);
 
     let emulator_config = config.to_emulator_config();
-    let emulator = GenesisEmulator::create(rom, emulator_config, &mut save_writer);
+    let emulator = match &config.lock_on_rom_file_path {
+        Some(lock_on_rom_file_path) => {
+            let lock_on_rom = fs::read(lock_on_rom_file_path).map_err(|source| {
+                NativeEmulatorError::RomRead { path: lock_on_rom_file_path.clone(), source }
+            })?;
+            GenesisEmulator::create_with_lock_on(
+                rom,
+                lock_on_rom,
+                emulator_config,
+                &mut save_writer,
+            )?
+        }
+        None => GenesisEmulator::create(rom, emulator_config, &mut save_writer)?,
+    };
 
     let window_size = config.common.window_size.unwrap_or(config::DEFAULT_GENESIS_WINDOW_SIZE);
     let mut cartridge_title = emulator.cartridge_title();
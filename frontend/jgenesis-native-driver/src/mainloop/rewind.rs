@@ -1,43 +1,22 @@
-use jgenesis_common::frontend::{EmulatorTrait, PartialClone, Renderer};
-use std::collections::VecDeque;
+use jgenesis_common::frontend::{EmulatorTrait, Renderer};
+use jgenesis_common::rewind::RewindBuffer;
 use std::time::{Duration, Instant};
 
 const FRAME_DIVIDER: u64 = 10;
 
 pub struct Rewinder<Emulator> {
-    previous_states: VecDeque<Emulator>,
-    buffer_len: usize,
-    frame_count: u64,
+    buffer: RewindBuffer<Emulator>,
     last_rewind_time: Option<Instant>,
 }
 
-impl<Emulator: PartialClone> Rewinder<Emulator> {
+impl<Emulator> Rewinder<Emulator> {
     pub fn new(buffer_duration: Duration) -> Self {
-        let buffer_len = duration_to_buffer_len(buffer_duration);
         Self {
-            previous_states: VecDeque::with_capacity(buffer_len + 1),
-            buffer_len,
-            frame_count: 0,
+            buffer: RewindBuffer::new(duration_to_buffer_len(buffer_duration), FRAME_DIVIDER),
             last_rewind_time: None,
         }
     }
 
-    pub fn record_frame(&mut self, emulator: &Emulator) {
-        if self.buffer_len == 0 {
-            return;
-        }
-
-        self.frame_count += 1;
-
-        if self.frame_count % FRAME_DIVIDER == 0 {
-            self.previous_states.push_back(emulator.partial_clone());
-
-            while self.previous_states.len() > self.buffer_len {
-                self.previous_states.pop_front();
-            }
-        }
-    }
-
     pub fn start_rewinding(&mut self) {
         if self.last_rewind_time.is_none() {
             self.last_rewind_time = Some(Instant::now());
@@ -52,6 +31,16 @@ impl<Emulator: PartialClone> Rewinder<Emulator> {
         self.last_rewind_time.is_some()
     }
 
+    pub fn set_buffer_duration(&mut self, duration: Duration) {
+        self.buffer.set_capacity(duration_to_buffer_len(duration));
+    }
+}
+
+impl<Emulator: EmulatorTrait> Rewinder<Emulator> {
+    pub fn record_frame(&mut self, emulator: &Emulator) {
+        self.buffer.push(emulator);
+    }
+
     pub fn tick<R>(
         &mut self,
         emulator: &mut Emulator,
@@ -59,7 +48,6 @@ impl<Emulator: PartialClone> Rewinder<Emulator> {
         config: &Emulator::Config,
     ) -> Result<(), R::Err>
     where
-        Emulator: EmulatorTrait,
         R: Renderer,
     {
         let Some(last_rewind_time) = self.last_rewind_time else { return Ok(()) };
@@ -69,9 +57,9 @@ impl<Emulator: PartialClone> Rewinder<Emulator> {
 
         let now = Instant::now();
         if now.duration_since(last_rewind_time) >= Duration::from_secs_f64(rewind_interval_secs) {
-            let Some(mut clone) = self.previous_states.pop_back() else { return Ok(()) };
-            clone.take_rom_from(emulator);
-            *emulator = clone;
+            if !self.buffer.restore_into(emulator) {
+                return Ok(());
+            }
 
             emulator.reload_config(config);
             emulator.force_render(renderer)?;
@@ -81,21 +69,6 @@ impl<Emulator: PartialClone> Rewinder<Emulator> {
 
         Ok(())
     }
-
-    pub fn set_buffer_duration(&mut self, duration: Duration) {
-        self.set_buffer_len(duration_to_buffer_len(duration));
-    }
-
-    fn set_buffer_len(&mut self, buffer_len: usize) {
-        self.buffer_len = buffer_len;
-
-        if buffer_len + 1 > self.previous_states.capacity() {
-            // Immediately resize deque to avoid incremental allocations later
-            let mut resized = VecDeque::with_capacity(buffer_len + 1);
-            resized.extend(self.previous_states.drain(..));
-            self.previous_states = resized;
-        }
-    }
 }
 
 fn duration_to_buffer_len(duration: Duration) -> usize {
@@ -1,12 +1,15 @@
 use crate::SmsGgConsole;
+use genesis_core::deinterlace::DeinterlacingMode;
 use genesis_core::input::GenesisControllerType;
 use genesis_core::{GenesisAspectRatio, GenesisEmulatorConfig};
 use jgenesis_common::frontend::{PixelAspectRatio, TimingMode};
+use jgenesis_common::ram::RamInitPattern;
 use jgenesis_proc_macros::{EnumDisplay, EnumFromStr};
 use jgenesis_renderer::config::{
     FilterMode, PreprocessShader, PrescaleFactor, PrescaleMode, RendererConfig, Scanlines,
     VSyncMode, WgpuBackend,
 };
+use smsgg_core::audio::DEFAULT_PSG_LPF_CUTOFF;
 use smsgg_core::psg::PsgVersion;
 use smsgg_core::{SmsGgEmulatorConfig, SmsRegion, VdpVersion};
 use snes_core::api::{SnesAspectRatio, SnesEmulatorConfig};
@@ -58,6 +61,7 @@ pub struct CommonWebConfig {
     pub filter_mode: FilterMode,
     pub preprocess_shader: PreprocessShader,
     pub prescale_factor: PrescaleFactor,
+    pub rng_seed: Option<u64>,
 }
 
 impl Default for CommonWebConfig {
@@ -66,11 +70,17 @@ impl Default for CommonWebConfig {
             filter_mode: FilterMode::default(),
             preprocess_shader: PreprocessShader::default(),
             prescale_factor: PrescaleFactor::try_from(3).unwrap(),
+            rng_seed: None,
         }
     }
 }
 
 impl CommonWebConfig {
+    // Fixed seed if one was set, otherwise a new random seed for every emulator instance
+    pub fn rng_seed(&self) -> u64 {
+        self.rng_seed.unwrap_or_else(rand::random)
+    }
+
     pub fn to_renderer_config(&self) -> RendererConfig {
         RendererConfig {
             wgpu_backend: WgpuBackend::OpenGl,
@@ -121,7 +131,11 @@ impl SmsGgWebConfig {
         }
     }
 
-    pub(crate) fn to_emulator_config(&self, console: SmsGgConsole) -> SmsGgEmulatorConfig {
+    pub(crate) fn to_emulator_config(
+        &self,
+        console: SmsGgConsole,
+        rng_seed: u64,
+    ) -> SmsGgEmulatorConfig {
         let vdp_version = self.vdp_version(console);
         let (psg_version, pixel_aspect_ratio) = if vdp_version.is_master_system() {
             (PsgVersion::MasterSystem2, self.sms_aspect_ratio.to_pixel_aspect_ratio())
@@ -131,13 +145,23 @@ impl SmsGgWebConfig {
 
         SmsGgEmulatorConfig {
             vdp_version,
+            forced_timing_mode: None,
             psg_version,
             pixel_aspect_ratio: Some(pixel_aspect_ratio),
             sms_region: self.region,
+            ram_init_pattern: RamInitPattern::default(),
+            rng_seed,
             remove_sprite_limit: self.remove_sprite_limit,
+            background_enabled: true,
+            sprites_enabled: true,
+            left_column_blanking_enabled: true,
             sms_crop_left_border: self.sms_crop_left_border,
+            overscan_border_width: 0,
             sms_crop_vertical_border: self.sms_crop_vertical_border,
             fm_sound_unit_enabled: self.fm_unit_enabled,
+            psg_gain: 1.0,
+            fm_gain: 1.0,
+            psg_lpf_cutoff: Some(DEFAULT_PSG_LPF_CUTOFF),
             overclock_z80: false,
         }
     }
@@ -153,19 +177,33 @@ pub struct GenesisWebConfig {
 }
 
 impl GenesisWebConfig {
-    pub fn to_emulator_config(&self) -> GenesisEmulatorConfig {
+    pub fn to_emulator_config(&self, rng_seed: u64) -> GenesisEmulatorConfig {
         GenesisEmulatorConfig {
             p1_controller_type: GenesisControllerType::default(),
             p2_controller_type: GenesisControllerType::default(),
             forced_timing_mode: None,
             forced_region: None,
+            console_region: None,
+            tmss_enabled: false,
+            ram_init_pattern: RamInitPattern::default(),
+            rng_seed,
             aspect_ratio: self.aspect_ratio,
             adjust_aspect_ratio_in_2x_resolution: true,
+            deinterlacing_mode: DeinterlacingMode::default(),
             remove_sprite_limits: self.remove_sprite_limits,
             emulate_non_linear_vdp_dac: self.emulate_non_linear_vdp_dac,
             render_vertical_border: self.render_vertical_border,
             render_horizontal_border: self.render_horizontal_border,
+            ntsc_composite_filter: false,
+            overscan_border_width: 0,
+            plane_a_enabled: true,
+            plane_b_enabled: true,
+            window_enabled: true,
+            sprites_enabled: true,
             quantize_ym2612_output: true,
+            dc_blocking_filter: false,
+            psg_pan: 0.0,
+            mono_audio: false,
         }
     }
 }
@@ -176,12 +214,14 @@ pub struct SnesWebConfig {
 }
 
 impl SnesWebConfig {
-    pub fn to_emulator_config(&self) -> SnesEmulatorConfig {
+    pub fn to_emulator_config(&self, rng_seed: u64) -> SnesEmulatorConfig {
         SnesEmulatorConfig {
             forced_timing_mode: None,
             aspect_ratio: self.aspect_ratio,
             audio_60hz_hack: true,
             gsu_overclock_factor: NonZeroU64::new(1).unwrap(),
+            ram_init_pattern: RamInitPattern::default(),
+            rng_seed,
         }
     }
 }
@@ -219,6 +259,12 @@ impl WebConfigRef {
         self.borrow_mut().common.prescale_factor = prescale_factor;
     }
 
+    /// Use a fixed seed for pseudorandom power-on state, or a new random seed for every emulator
+    /// instance if `None`.
+    pub fn set_rng_seed(&self, rng_seed: Option<u64>) {
+        self.borrow_mut().common.rng_seed = rng_seed;
+    }
+
     pub fn set_sms_timing_mode(&self, timing_mode: &str) {
         let Ok(timing_mode) = timing_mode.parse() else { return };
         self.borrow_mut().smsgg.timing_mode = timing_mode;
@@ -325,23 +325,25 @@ impl Emulator {
     }
 
     fn reload_config(&mut self, config: &WebConfig) {
+        // The seed only affects power-on state, so it does not matter for already running emulators
+        let rng_seed = config.common.rng_seed();
         match self {
             Self::None(..) => {}
             Self::SmsGg(emulator, _, console) => {
-                emulator.reload_config(&config.smsgg.to_emulator_config(*console));
+                emulator.reload_config(&config.smsgg.to_emulator_config(*console, rng_seed));
             }
             Self::Genesis(emulator, ..) => {
-                emulator.reload_config(&config.genesis.to_emulator_config());
+                emulator.reload_config(&config.genesis.to_emulator_config(rng_seed));
             }
             Self::SegaCd(emulator, ..) => {
                 emulator.reload_config(&SegaCdEmulatorConfig {
-                    genesis: config.genesis.to_emulator_config(),
+                    genesis: config.genesis.to_emulator_config(rng_seed),
                     enable_ram_cartridge: true,
                     load_disc_into_ram: true,
                 });
             }
             Self::Snes(emulator, ..) => {
-                emulator.reload_config(&config.snes.to_emulator_config());
+                emulator.reload_config(&config.snes.to_emulator_config(rng_seed));
             }
         }
     }
@@ -707,6 +709,7 @@ fn open_emulator(
         log::warn!("Unable to determine file extension of uploaded file; defaulting to Genesis emulator");
         "md".into()
     });
+    let rng_seed = config_ref.borrow().common.rng_seed();
 
     match file_ext.as_str() {
         file_ext @ ("sms" | "gg") => {
@@ -719,7 +722,7 @@ fn open_emulator(
             };
             let emulator = SmsGgEmulator::create(
                 rom,
-                config_ref.borrow().smsgg.to_emulator_config(console),
+                config_ref.borrow().smsgg.to_emulator_config(console, rng_seed),
                 save_writer,
             );
             Ok(Emulator::SmsGg(emulator, SmsGgInputs::default(), console))
@@ -729,9 +732,9 @@ fn open_emulator(
 
             let emulator = GenesisEmulator::create(
                 rom,
-                config_ref.borrow().genesis.to_emulator_config(),
+                config_ref.borrow().genesis.to_emulator_config(rng_seed),
                 save_writer,
-            );
+            )?;
             Ok(Emulator::Genesis(emulator, GenesisInputs::default()))
         }
         "chd" => {
@@ -741,7 +744,7 @@ fn open_emulator(
                 bios,
                 rom,
                 SegaCdEmulatorConfig {
-                    genesis: config_ref.borrow().genesis.to_emulator_config(),
+                    genesis: config_ref.borrow().genesis.to_emulator_config(rng_seed),
                     enable_ram_cartridge: true,
                     load_disc_into_ram: true,
                 },
@@ -755,7 +758,7 @@ fn open_emulator(
         "sfc" | "smc" => {
             let emulator = SnesEmulator::create(
                 rom,
-                config_ref.borrow().snes.to_emulator_config(),
+                config_ref.borrow().snes.to_emulator_config(rng_seed),
                 CoprocessorRoms::none(),
                 save_writer,
             )?;
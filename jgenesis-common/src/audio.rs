@@ -13,6 +13,7 @@ pub struct SignalResampler<const LPF_TAPS: usize, const ZERO_PADDING: usize> {
     output: VecDeque<(f64, f64)>,
     sample_count_product: u64,
     padded_scaled_source_frequency: u64,
+    output_frequency: u64,
     hpf_charge_factor: f64,
     hpf_capacitor_l: f64,
     hpf_capacitor_r: f64,
@@ -35,6 +36,7 @@ impl<const LPF_TAPS: usize, const ZERO_PADDING: usize> SignalResampler<LPF_TAPS,
             output: VecDeque::with_capacity((OUTPUT_FREQUENCY / 30) as usize),
             sample_count_product: 0,
             padded_scaled_source_frequency,
+            output_frequency: OUTPUT_FREQUENCY,
             hpf_charge_factor,
             hpf_capacitor_l: 0.0,
             hpf_capacitor_r: 0.0,
@@ -59,7 +61,7 @@ impl<const LPF_TAPS: usize, const ZERO_PADDING: usize> SignalResampler<LPF_TAPS,
             self.samples_r.pop_front();
         }
 
-        self.sample_count_product += OUTPUT_FREQUENCY * RESAMPLE_SCALING_FACTOR;
+        self.sample_count_product += self.output_frequency * RESAMPLE_SCALING_FACTOR;
         while self.sample_count_product >= self.padded_scaled_source_frequency {
             self.sample_count_product -= self.padded_scaled_source_frequency;
 
@@ -103,9 +105,36 @@ impl<const LPF_TAPS: usize, const ZERO_PADDING: usize> SignalResampler<LPF_TAPS,
         self.output.pop_front()
     }
 
+    /// Source frequency in Hz, not including zero padding.
+    #[must_use]
+    pub fn source_frequency(&self) -> f64 {
+        self.padded_scaled_source_frequency as f64
+            / RESAMPLE_SCALING_FACTOR as f64
+            / (ZERO_PADDING + 1) as f64
+    }
+
     pub fn update_source_frequency(&mut self, source_frequency: f64) {
         self.padded_scaled_source_frequency = Self::pad_and_scale_frequency(source_frequency);
     }
+
+    pub fn update_lpf_coefficients(
+        &mut self,
+        lpf_coefficient_0: f64,
+        lpf_coefficients: [f64; LPF_TAPS],
+    ) {
+        self.lpf_coefficient_0 = lpf_coefficient_0;
+        self.lpf_coefficients = lpf_coefficients;
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn output_frequency(&self) -> u64 {
+        self.output_frequency
+    }
+
+    pub fn update_output_frequency(&mut self, output_frequency: u64) {
+        self.output_frequency = output_frequency;
+    }
 }
 
 fn high_pass_filter(sample: f64, charge_factor: f64, capacitor: &mut f64) -> f64 {
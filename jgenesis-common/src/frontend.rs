@@ -1,5 +1,7 @@
+use crate::save_state::VersionedState;
 use bincode::{Decode, Encode};
-use jgenesis_proc_macros::{EnumDisplay, EnumFromStr};
+use jgenesis_proc_macros::{EnumDisplay, EnumFromStr, FakeDecode, FakeEncode};
+use std::convert::Infallible;
 use std::error::Error;
 use std::fmt::{Debug, Display};
 use std::num::NonZeroU32;
@@ -44,6 +46,59 @@ pub struct FrameSize {
     pub height: u32,
 }
 
+/// Pack the first (`width` * `height`) colors of a frame buffer into RGB8 bytes, discarding alpha.
+#[must_use]
+pub fn frame_buffer_to_rgb8(frame_buffer: &[Color], frame_size: FrameSize) -> Vec<u8> {
+    let len = frame_size.width as usize * frame_size.height as usize;
+    frame_buffer[..len].iter().flat_map(|color| [color.r, color.g, color.b]).collect()
+}
+
+/// Reusable buffer for surrounding frames with a solid-color overscan border. Contents are not
+/// saved in save states.
+#[derive(Debug, Clone, Default, FakeEncode, FakeDecode)]
+pub struct OverscanBuffer(Vec<Color>);
+
+impl OverscanBuffer {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Surround the frame with a border that is `border_width` pixels wide on every edge and
+    /// filled with `border_color`. Returns the bordered frame and its size, or the original frame
+    /// if `border_width` is 0.
+    pub fn apply<'a>(
+        &'a mut self,
+        frame_buffer: &'a [Color],
+        frame_size: FrameSize,
+        border_width: u32,
+        border_color: Color,
+    ) -> (&'a [Color], FrameSize) {
+        if border_width == 0 {
+            return (frame_buffer, frame_size);
+        }
+
+        let width = frame_size.width as usize;
+        let border = border_width as usize;
+        let bordered_size = FrameSize {
+            width: frame_size.width + 2 * border_width,
+            height: frame_size.height + 2 * border_width,
+        };
+        let bordered_width = bordered_size.width as usize;
+
+        self.0.clear();
+        self.0.resize(bordered_width * bordered_size.height as usize, border_color);
+        for (row, line) in
+            frame_buffer.chunks_exact(width).take(frame_size.height as usize).enumerate()
+        {
+            let start = (row + border) * bordered_width + border;
+            self.0[start..start + width].copy_from_slice(line);
+        }
+
+        (&self.0, bordered_size)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
 pub struct PixelAspectRatio(f64);
 
@@ -157,6 +212,11 @@ pub trait PartialClone {
 
 pub use jgenesis_proc_macros::PartialClone;
 
+/// Fast in-memory save state that shares the ROM with the emulator it was taken from. Created with
+/// [`EmulatorTrait::snapshot`] and loaded with [`EmulatorTrait::restore`].
+#[derive(Debug)]
+pub struct EmulatorSnapshot<Emulator>(Emulator);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumDisplay, EnumFromStr, Encode, Decode)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TimingMode {
@@ -169,11 +229,63 @@ pub enum TimingMode {
 pub enum TickEffect {
     None,
     FrameRendered,
+    /// A frame completed but was not rendered; only returned by
+    /// [`EmulatorTrait::tick_skip_render`]
+    FrameSkipped,
+    /// A CPU stopped at a debugger breakpoint; only returned by emulators that support
+    /// breakpoints. If a frame also completed during the same tick, the frame effect is returned
+    /// instead
+    BreakpointHit,
 }
 
 pub type TickResult<Err> = Result<TickEffect, Err>;
 
-pub trait EmulatorTrait: Encode + Decode + PartialClone {
+/// Execution counters accumulated since the last time they were read, for benchmarking.
+///
+/// Counters are host-side diagnostics and are never written to save states, so states are
+/// interchangeable between builds with and without the `perf` feature. Collected by the Genesis,
+/// SMS/GG, NES, SNES, and Game Boy cores. Instructions are only counted when the main CPU
+/// actually executes one, not while it is halted, stalled, or servicing an interrupt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, FakeEncode, FakeDecode)]
+pub struct PerfCounters {
+    pub master_clock_cycles: u64,
+    pub instructions: u64,
+}
+
+/// A named block of emulated memory, for generic memory viewers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryRegion {
+    pub name: &'static str,
+    /// Address of the first byte in the system's CPU address space, for display only. Regions
+    /// that are not directly mapped or that move with banking report where they are usually seen
+    pub base_address: u32,
+    pub len: usize,
+}
+
+impl MemoryRegion {
+    #[must_use]
+    pub const fn new(name: &'static str, base_address: u32, len: usize) -> Self {
+        Self { name, base_address, len }
+    }
+}
+
+// Renderer that drops every frame, for EmulatorTrait::tick_skip_render
+struct SkipRenderer;
+
+impl Renderer for SkipRenderer {
+    type Err = Infallible;
+
+    fn render_frame(
+        &mut self,
+        _frame_buffer: &[Color],
+        _frame_size: FrameSize,
+        _pixel_aspect_ratio: Option<PixelAspectRatio>,
+    ) -> Result<(), Self::Err> {
+        Ok(())
+    }
+}
+
+pub trait EmulatorTrait: Encode + Decode + PartialClone + VersionedState {
     type Inputs;
     type Config;
 
@@ -201,6 +313,68 @@ pub trait EmulatorTrait: Encode + Decode + PartialClone {
         S: SaveWriter,
         S::Err: Debug + Display + Send + Sync + 'static;
 
+    /// Tick the emulator exactly like [`Self::tick`], including pushing audio samples and
+    /// persisting save files, but drop completed frames instead of rendering them. Returns
+    /// [`TickEffect::FrameSkipped`] where `tick` would return [`TickEffect::FrameRendered`].
+    ///
+    /// This is for frame skipping and fast-forward, where video frames can be dropped but audio
+    /// needs to stay continuous.
+    ///
+    /// # Errors
+    ///
+    /// This method will propagate any errors returned by `tick`.
+    #[allow(clippy::type_complexity)]
+    fn tick_skip_render<A, S>(
+        &mut self,
+        audio_output: &mut A,
+        inputs: &Self::Inputs,
+        save_writer: &mut S,
+    ) -> TickResult<Self::Err<Infallible, A::Err, S::Err>>
+    where
+        A: AudioOutput,
+        A::Err: Debug + Display + Send + Sync + 'static,
+        S: SaveWriter,
+        S::Err: Debug + Display + Send + Sync + 'static,
+    {
+        match self.tick(&mut SkipRenderer, audio_output, inputs, save_writer)? {
+            TickEffect::FrameRendered => Ok(TickEffect::FrameSkipped),
+            tick_effect => Ok(tick_effect),
+        }
+    }
+
+    /// Tick the emulator until `frames` frames have been rendered.
+    ///
+    /// # Errors
+    ///
+    /// This method will propagate any errors returned by `tick`.
+    #[allow(clippy::type_complexity)]
+    fn run_frames<R, A, S>(
+        &mut self,
+        frames: u32,
+        renderer: &mut R,
+        audio_output: &mut A,
+        inputs: &Self::Inputs,
+        save_writer: &mut S,
+    ) -> Result<(), Self::Err<R::Err, A::Err, S::Err>>
+    where
+        R: Renderer,
+        R::Err: Debug + Display + Send + Sync + 'static,
+        A: AudioOutput,
+        A::Err: Debug + Display + Send + Sync + 'static,
+        S: SaveWriter,
+        S::Err: Debug + Display + Send + Sync + 'static,
+    {
+        let mut frames_rendered = 0;
+        while frames_rendered < frames {
+            if self.tick(renderer, audio_output, inputs, save_writer)? == TickEffect::FrameRendered
+            {
+                frames_rendered += 1;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Forcibly render the current frame buffer.
     ///
     /// # Errors
@@ -214,9 +388,32 @@ pub trait EmulatorTrait: Encode + Decode + PartialClone {
 
     fn take_rom_from(&mut self, other: &mut Self);
 
+    /// Capture the current emulation state without copying the ROM.
+    #[must_use]
+    fn snapshot(&self) -> EmulatorSnapshot<Self> {
+        EmulatorSnapshot(self.partial_clone())
+    }
+
+    /// Restore a snapshot taken with [`Self::snapshot`], keeping this emulator's ROM. The
+    /// snapshot must have been taken from an emulator running the same ROM.
+    fn restore(&mut self, snapshot: EmulatorSnapshot<Self>) {
+        let EmulatorSnapshot(mut emulator) = snapshot;
+        emulator.take_rom_from(self);
+        *self = emulator;
+    }
+
     fn soft_reset(&mut self);
 
     fn hard_reset<S: SaveWriter>(&mut self, save_writer: &mut S);
 
     fn timing_mode(&self) -> TimingMode;
+
+    /// Memory regions that can be read with [`Self::read_region`]. A region's ID is its index in
+    /// the returned list; the list for a given emulator never changes, though some regions may be
+    /// empty (e.g. cartridge RAM for a cartridge without any).
+    fn memory_regions(&self) -> Vec<MemoryRegion>;
+
+    /// Read the byte at `offset` within region `region_id` without side effects. Returns `None` if
+    /// `region_id` or `offset` is out of range.
+    fn read_region(&self, region_id: usize, offset: usize) -> Option<u8>;
 }
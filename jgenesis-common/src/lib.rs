@@ -1,5 +1,12 @@
 pub mod audio;
 pub mod frontend;
 pub mod input;
+pub mod movie;
 pub mod num;
+pub mod ram;
+pub mod rewind;
+pub mod rng;
+pub mod save_state;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod timeutils;
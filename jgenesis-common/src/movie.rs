@@ -0,0 +1,384 @@
+//! Input movie recording and playback
+//!
+//! A movie is a header followed by the bincode-encoded initial state and the inputs for every
+//! frame. The header records the emulated system and the CRC32 of the ROM that the movie was
+//! recorded against so that playing back on a different ROM fails with a clear error instead of
+//! desyncing immediately.
+//!
+//! Playback is deterministic as long as the emulator is started from the same state: either a
+//! fresh power-on with the same configuration, or the optional initial save state.
+//! [`MoviePlayer`] applies the initial state and feeds each frame's inputs to the emulator.
+
+use crate::frontend::{AudioOutput, EmulatorTrait, Renderer, SaveWriter};
+use crate::save_state;
+use crate::save_state::SaveStateError;
+use bincode::config::Config;
+use bincode::error::{DecodeError, EncodeError};
+use bincode::{Decode, Encode};
+use std::fmt::{Debug, Display};
+use std::io;
+use std::io::{Read, Write};
+use thiserror::Error;
+
+const MAGIC: [u8; 4] = *b"JGMV";
+
+const FORMAT_VERSION: u32 = 1;
+
+const HEADER_LEN: usize = 20;
+
+// Upper bound on the encoded initial state and frames so that a corrupt length prefix fails to
+// decode instead of attempting a huge allocation
+const MAX_DATA_LEN: usize = 256 * 1024 * 1024;
+
+// Movies always use the same encoding so that the file format does not depend on the caller
+fn bincode_config() -> impl Config {
+    bincode::config::standard().with_limit::<MAX_DATA_LEN>()
+}
+
+#[derive(Debug, Error)]
+pub enum MovieError {
+    #[error("I/O error reading/writing movie: {0}")]
+    Io(#[from] io::Error),
+    #[error("Movie header is invalid; file is not a movie")]
+    InvalidMagic,
+    #[error("Movie format version {0} is not supported; current version is {FORMAT_VERSION}")]
+    UnsupportedVersion(u32),
+    #[error(
+        "Movie is for a different system: expected '{}', found '{}'",
+        String::from_utf8_lossy(.expected),
+        String::from_utf8_lossy(.actual)
+    )]
+    SystemMismatch { expected: [u8; 4], actual: [u8; 4] },
+    #[error(
+        "Movie was recorded with a different ROM: expected CRC32 {expected:08X}, found {actual:08X}"
+    )]
+    RomMismatch { expected: u32, actual: u32 },
+    #[error("Error encoding movie: {0}")]
+    Encode(#[from] EncodeError),
+    #[error("Error decoding movie: {0}")]
+    Decode(#[from] DecodeError),
+    #[error("Error loading movie initial state: {0}")]
+    InitialState(#[from] SaveStateError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Movie<Inputs> {
+    system_id: [u8; 4],
+    rom_crc32: u32,
+    rerecord_count: u32,
+    initial_state: Option<Vec<u8>>,
+    frames: Vec<Inputs>,
+}
+
+impl<Inputs> Movie<Inputs> {
+    /// Create an empty movie. `system_id` should be the emulator's
+    /// [`VersionedState::SYSTEM_ID`](crate::save_state::VersionedState::SYSTEM_ID), and
+    /// `initial_state` is an optional save state (written with [`save_state::write_state`]) to load
+    /// before playback starts. If there is no initial state, playback starts from power-on.
+    #[must_use]
+    pub fn new(system_id: [u8; 4], rom_crc32: u32, initial_state: Option<Vec<u8>>) -> Self {
+        Self { system_id, rom_crc32, rerecord_count: 0, initial_state, frames: Vec::new() }
+    }
+
+    #[must_use]
+    pub fn system_id(&self) -> [u8; 4] {
+        self.system_id
+    }
+
+    #[must_use]
+    pub fn rom_crc32(&self) -> u32 {
+        self.rom_crc32
+    }
+
+    #[must_use]
+    pub fn rerecord_count(&self) -> u32 {
+        self.rerecord_count
+    }
+
+    #[must_use]
+    pub fn initial_state(&self) -> Option<&[u8]> {
+        self.initial_state.as_deref()
+    }
+
+    /// The recorded inputs, one entry per frame in playback order.
+    #[must_use]
+    pub fn frames(&self) -> &[Inputs] {
+        &self.frames
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Append the inputs for the next frame.
+    pub fn record_frame(&mut self, inputs: Inputs) {
+        self.frames.push(inputs);
+    }
+
+    /// Discard all frames from `frame` onwards so that recording can resume from there, and
+    /// increment the rerecord count. This should be called after loading a save state that was
+    /// created while recording at frame `frame`.
+    pub fn rerecord_from(&mut self, frame: usize) {
+        self.frames.truncate(frame);
+        self.rerecord_count += 1;
+    }
+}
+
+impl<Inputs: Encode + 'static> Movie<Inputs> {
+    /// Write the movie in its binary format.
+    ///
+    /// # Errors
+    ///
+    /// Will propagate any I/O or encoding errors.
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), MovieError> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        writer.write_all(&self.system_id)?;
+        writer.write_all(&self.rom_crc32.to_le_bytes())?;
+        writer.write_all(&self.rerecord_count.to_le_bytes())?;
+        bincode::encode_into_std_write(&self.initial_state, writer, bincode_config())?;
+        bincode::encode_into_std_write(&self.frames, writer, bincode_config())?;
+
+        Ok(())
+    }
+}
+
+impl<Inputs: Decode + 'static> Movie<Inputs> {
+    /// Read a movie for the given system, checking that it was recorded with a ROM that has the
+    /// given CRC32.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the header is invalid, if the movie is for a different system or a
+    /// different ROM, or if any I/O or decoding errors occur.
+    pub fn read<R: Read>(
+        reader: &mut R,
+        system_id: [u8; 4],
+        rom_crc32: u32,
+    ) -> Result<Self, MovieError> {
+        let mut header = [0; HEADER_LEN];
+        reader.read_exact(&mut header)?;
+
+        // The header is five 4-byte fields
+        let field = |offset: usize| {
+            [header[offset], header[offset + 1], header[offset + 2], header[offset + 3]]
+        };
+
+        if field(0) != MAGIC {
+            return Err(MovieError::InvalidMagic);
+        }
+
+        let version = u32::from_le_bytes(field(4));
+        if version != FORMAT_VERSION {
+            return Err(MovieError::UnsupportedVersion(version));
+        }
+
+        let movie_system_id = field(8);
+        if movie_system_id != system_id {
+            return Err(MovieError::SystemMismatch {
+                expected: system_id,
+                actual: movie_system_id,
+            });
+        }
+
+        let movie_rom_crc32 = u32::from_le_bytes(field(12));
+        if movie_rom_crc32 != rom_crc32 {
+            return Err(MovieError::RomMismatch { expected: movie_rom_crc32, actual: rom_crc32 });
+        }
+
+        let rerecord_count = u32::from_le_bytes(field(16));
+
+        let initial_state: Option<Vec<u8>> =
+            bincode::decode_from_std_read(reader, bincode_config())?;
+        let frames: Vec<Inputs> = bincode::decode_from_std_read(reader, bincode_config())?;
+
+        Ok(Self { system_id, rom_crc32, rerecord_count, initial_state, frames })
+    }
+}
+
+/// Emulators that can play back movies with per-frame inputs of type `Frame`.
+pub trait MoviePlayback<Frame>: EmulatorTrait {
+    /// Apply any part of `frame` that is not passed through [`EmulatorTrait::tick`] (e.g. raw
+    /// controller bytes) and return the inputs to use while running this frame.
+    fn prepare_movie_frame(&mut self, frame: &Frame) -> Self::Inputs;
+}
+
+/// Plays back a [`Movie`] one frame at a time.
+#[derive(Debug, Clone)]
+pub struct MoviePlayer<Frame> {
+    movie: Movie<Frame>,
+    next_frame: usize,
+}
+
+impl<Frame> MoviePlayer<Frame> {
+    #[must_use]
+    pub fn new(movie: Movie<Frame>) -> Self {
+        Self { movie, next_frame: 0 }
+    }
+
+    #[must_use]
+    pub fn movie(&self) -> &Movie<Frame> {
+        &self.movie
+    }
+
+    /// Index of the next frame that [`Self::play_frame`] will play.
+    #[must_use]
+    pub fn next_frame(&self) -> usize {
+        self.next_frame
+    }
+
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.next_frame >= self.movie.len()
+    }
+
+    /// Prepare `emulator` for playback from the first frame. If the movie has an initial state, it
+    /// is loaded into `emulator` (keeping the emulator's ROM) using `state_config`, which must be
+    /// the bincode configuration that the state was written with. Otherwise `emulator` must be
+    /// freshly powered on with the same config and RNG seed that were used while recording.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the initial state cannot be loaded.
+    pub fn start<E, C>(&mut self, emulator: &mut E, state_config: C) -> Result<(), MovieError>
+    where
+        E: EmulatorTrait,
+        C: Config,
+    {
+        if let Some(initial_state) = self.movie.initial_state() {
+            let mut loaded: E = save_state::read_state(&mut &initial_state[..], state_config)?;
+            loaded.take_rom_from(emulator);
+            *emulator = loaded;
+        }
+
+        self.next_frame = 0;
+
+        Ok(())
+    }
+
+    /// Run `emulator` until it renders the next movie frame. Returns `false` without running the
+    /// emulator if every frame has already been played.
+    ///
+    /// # Errors
+    ///
+    /// Will propagate any errors returned by the emulator.
+    #[allow(clippy::type_complexity)]
+    pub fn play_frame<E, R, A, S>(
+        &mut self,
+        emulator: &mut E,
+        renderer: &mut R,
+        audio_output: &mut A,
+        save_writer: &mut S,
+    ) -> Result<bool, E::Err<R::Err, A::Err, S::Err>>
+    where
+        E: MoviePlayback<Frame>,
+        R: Renderer,
+        R::Err: Debug + Display + Send + Sync + 'static,
+        A: AudioOutput,
+        A::Err: Debug + Display + Send + Sync + 'static,
+        S: SaveWriter,
+        S::Err: Debug + Display + Send + Sync + 'static,
+    {
+        let Some(frame) = self.movie.frames().get(self.next_frame) else { return Ok(false) };
+
+        let inputs = emulator.prepare_movie_frame(frame);
+        emulator.run_frames(1, renderer, audio_output, &inputs, save_writer)?;
+        self.next_frame += 1;
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SYSTEM_ID: [u8; 4] = *b"TEST";
+    const ROM_CRC32: u32 = 0x12345678;
+
+    fn test_movie() -> Movie<(bool, u16)> {
+        let mut movie = Movie::new(SYSTEM_ID, ROM_CRC32, Some(vec![1, 2, 3]));
+        for i in 0..10 {
+            movie.record_frame((i % 3 == 0, i * 100));
+        }
+        movie
+    }
+
+    fn write_to_vec(movie: &Movie<(bool, u16)>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        movie.write(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn read_from_slice(
+        bytes: &[u8],
+        system_id: [u8; 4],
+        rom_crc32: u32,
+    ) -> Result<Movie<(bool, u16)>, MovieError> {
+        Movie::read(&mut &bytes[..], system_id, rom_crc32)
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut movie = test_movie();
+        movie.rerecord_from(7);
+        movie.record_frame((true, 0xFFFF));
+        assert_eq!(movie.len(), 8);
+        assert_eq!(movie.rerecord_count(), 1);
+
+        let bytes = write_to_vec(&movie);
+        assert_eq!(&bytes[0..8], b"JGMV\x01\x00\x00\x00");
+
+        let loaded = read_from_slice(&bytes, SYSTEM_ID, ROM_CRC32).unwrap();
+        assert_eq!(loaded, movie);
+        assert_eq!(loaded.initial_state(), Some([1, 2, 3].as_slice()));
+        assert_eq!(loaded.frames()[7], (true, 0xFFFF));
+    }
+
+    #[test]
+    fn rom_mismatch_rejected() {
+        let bytes = write_to_vec(&test_movie());
+
+        let err = read_from_slice(&bytes, SYSTEM_ID, 0x87654321).unwrap_err();
+        assert!(matches!(err, MovieError::RomMismatch { expected: ROM_CRC32, actual: 0x87654321 }));
+        assert_eq!(
+            err.to_string(),
+            "Movie was recorded with a different ROM: expected CRC32 12345678, found 87654321"
+        );
+    }
+
+    #[test]
+    fn invalid_header_rejected() {
+        let mut bytes = write_to_vec(&test_movie());
+
+        let err = read_from_slice(&bytes, *b"OTHR", ROM_CRC32).unwrap_err();
+        assert!(matches!(err, MovieError::SystemMismatch { .. }));
+
+        bytes[4] = 2;
+        let err = read_from_slice(&bytes, SYSTEM_ID, ROM_CRC32).unwrap_err();
+        assert!(matches!(err, MovieError::UnsupportedVersion(2)));
+
+        bytes[0] = b'X';
+        let err = read_from_slice(&bytes, SYSTEM_ID, ROM_CRC32).unwrap_err();
+        assert!(matches!(err, MovieError::InvalidMagic));
+    }
+
+    #[test]
+    fn oversized_length_rejected() {
+        let mut bytes = write_to_vec(&Movie::new(SYSTEM_ID, ROM_CRC32, None));
+
+        // No initial state, then a varint frame count far above the limit
+        bytes.truncate(HEADER_LEN + 1);
+        bytes.push(0xFD);
+        bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+
+        let err = read_from_slice(&bytes, SYSTEM_ID, ROM_CRC32).unwrap_err();
+        assert!(matches!(err, MovieError::Decode(DecodeError::LimitExceeded)));
+    }
+}
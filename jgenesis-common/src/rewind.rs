@@ -0,0 +1,322 @@
+//! Rewind buffer built on partial clones of the emulator state
+//!
+//! [`RewindBuffer`] takes a snapshot every N frames and keeps the most recent snapshots in a ring
+//! buffer. Snapshots are [partial clones](crate::frontend::PartialClone), which do not copy the
+//! ROM, and the ROM is moved back from the live emulator on restore with
+//! [`EmulatorTrait::take_rom_from`].
+
+use crate::frontend::EmulatorTrait;
+use std::collections::VecDeque;
+
+#[derive(Debug)]
+pub struct RewindBuffer<Emulator> {
+    snapshots: VecDeque<Emulator>,
+    capacity: usize,
+    frame_interval: u64,
+    frames_since_snapshot: u64,
+}
+
+impl<Emulator> RewindBuffer<Emulator> {
+    /// Create a buffer that holds up to `capacity` snapshots, taking one snapshot every
+    /// `frame_interval` frames. A capacity of 0 disables snapshotting.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frame_interval` is 0.
+    #[must_use]
+    pub fn new(capacity: usize, frame_interval: u64) -> Self {
+        assert_ne!(frame_interval, 0, "Rewind frame interval must not be 0");
+
+        Self {
+            snapshots: VecDeque::with_capacity(capacity + 1),
+            capacity,
+            frame_interval,
+            frames_since_snapshot: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Change the maximum number of snapshots, discarding the oldest snapshots if the buffer is
+    /// now over capacity.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+
+        while self.snapshots.len() > capacity {
+            self.snapshots.pop_front();
+        }
+
+        if capacity + 1 > self.snapshots.capacity() {
+            // Immediately resize deque to avoid incremental allocations later
+            self.snapshots.reserve_exact(capacity + 1 - self.snapshots.len());
+        }
+    }
+
+    #[must_use]
+    pub fn frame_interval(&self) -> u64 {
+        self.frame_interval
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+        self.frames_since_snapshot = 0;
+    }
+}
+
+impl<Emulator: EmulatorTrait> RewindBuffer<Emulator> {
+    /// Record that the emulator has advanced by one frame. Every `frame_interval` frames, a
+    /// snapshot of the emulator is pushed and the oldest snapshot is dropped if the buffer is full.
+    pub fn push(&mut self, emulator: &Emulator) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        self.frames_since_snapshot += 1;
+        if self.frames_since_snapshot < self.frame_interval {
+            return;
+        }
+        self.frames_since_snapshot = 0;
+
+        self.snapshots.push_back(emulator.partial_clone());
+        while self.snapshots.len() > self.capacity {
+            self.snapshots.pop_front();
+        }
+    }
+
+    /// Remove and return the most recent snapshot. The snapshot is a partial clone and does not
+    /// have a ROM; use [`Self::restore_into`] to load it into a running emulator.
+    pub fn pop(&mut self) -> Option<Emulator> {
+        self.frames_since_snapshot = 0;
+        self.snapshots.pop_back()
+    }
+
+    /// Restore the most recent snapshot into `emulator`, keeping its ROM. Returns `false` if the
+    /// buffer is empty, in which case `emulator` is unchanged.
+    pub fn restore_into(&mut self, emulator: &mut Emulator) -> bool {
+        let Some(mut snapshot) = self.pop() else { return false };
+        snapshot.take_rom_from(emulator);
+        *emulator = snapshot;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::{
+        AudioOutput, MemoryRegion, PartialClone, Renderer, SaveWriter, TickEffect, TickResult,
+        TimingMode,
+    };
+    use crate::save_state::VersionedState;
+    use bincode::{Decode, Encode};
+    use std::fmt::{Debug, Display};
+    use std::{io, mem};
+
+    // Emulator whose frame buffer is derived from a frame counter; the ROM is not included in
+    // partial clones
+    #[derive(Debug, Clone, PartialEq, Encode, Decode)]
+    struct TestEmulator {
+        rom: Vec<u8>,
+        frame: u32,
+        frame_buffer: Vec<u8>,
+    }
+
+    impl TestEmulator {
+        fn new() -> Self {
+            Self { rom: vec![1, 2, 3], frame: 0, frame_buffer: vec![0; 4] }
+        }
+
+        fn run_frame(&mut self) {
+            self.frame += 1;
+            self.frame_buffer = self.frame.to_le_bytes().to_vec();
+        }
+    }
+
+    impl PartialClone for TestEmulator {
+        fn partial_clone(&self) -> Self {
+            Self { rom: vec![], frame: self.frame, frame_buffer: self.frame_buffer.clone() }
+        }
+    }
+
+    impl VersionedState for TestEmulator {
+        const SYSTEM_ID: [u8; 4] = *b"TEST";
+        const STATE_VERSION: u32 = 1;
+    }
+
+    impl EmulatorTrait for TestEmulator {
+        type Inputs = ();
+        type Config = ();
+
+        type Err<
+            RErr: Debug + Display + Send + Sync + 'static,
+            AErr: Debug + Display + Send + Sync + 'static,
+            SErr: Debug + Display + Send + Sync + 'static,
+        > = io::Error;
+
+        fn tick<R, A, S>(
+            &mut self,
+            _renderer: &mut R,
+            _audio_output: &mut A,
+            _inputs: &Self::Inputs,
+            _save_writer: &mut S,
+        ) -> TickResult<Self::Err<R::Err, A::Err, S::Err>>
+        where
+            R: Renderer,
+            R::Err: Debug + Display + Send + Sync + 'static,
+            A: AudioOutput,
+            A::Err: Debug + Display + Send + Sync + 'static,
+            S: SaveWriter,
+            S::Err: Debug + Display + Send + Sync + 'static,
+        {
+            self.run_frame();
+            Ok(TickEffect::FrameRendered)
+        }
+
+        fn force_render<R>(&mut self, _renderer: &mut R) -> Result<(), R::Err>
+        where
+            R: Renderer,
+        {
+            Ok(())
+        }
+
+        fn reload_config(&mut self, _config: &Self::Config) {}
+
+        fn take_rom_from(&mut self, other: &mut Self) {
+            self.rom = mem::take(&mut other.rom);
+        }
+
+        fn soft_reset(&mut self) {}
+
+        fn hard_reset<S: SaveWriter>(&mut self, _save_writer: &mut S) {
+            *self = Self { rom: mem::take(&mut self.rom), ..Self::new() };
+        }
+
+        fn timing_mode(&self) -> TimingMode {
+            TimingMode::Ntsc
+        }
+
+        fn memory_regions(&self) -> Vec<MemoryRegion> {
+            vec![]
+        }
+
+        fn read_region(&self, _region_id: usize, _offset: usize) -> Option<u8> {
+            None
+        }
+    }
+
+    fn advance(emulator: &mut TestEmulator, buffer: &mut RewindBuffer<TestEmulator>, frames: u32) {
+        for _ in 0..frames {
+            emulator.run_frame();
+            buffer.push(emulator);
+        }
+    }
+
+    #[test]
+    fn snapshots_every_interval() {
+        let mut emulator = TestEmulator::new();
+        let mut buffer = RewindBuffer::new(10, 5);
+
+        advance(&mut emulator, &mut buffer, 4);
+        assert!(buffer.is_empty());
+
+        advance(&mut emulator, &mut buffer, 11);
+        assert_eq!(buffer.len(), 3);
+    }
+
+    #[test]
+    fn drops_oldest_at_capacity() {
+        let mut emulator = TestEmulator::new();
+        let mut buffer = RewindBuffer::new(3, 1);
+
+        advance(&mut emulator, &mut buffer, 10);
+        assert_eq!(buffer.len(), 3);
+
+        assert!(buffer.restore_into(&mut emulator));
+        assert_eq!(emulator.frame, 10);
+        assert!(buffer.restore_into(&mut emulator));
+        assert_eq!(emulator.frame, 9);
+        assert!(buffer.restore_into(&mut emulator));
+        assert_eq!(emulator.frame, 8);
+        assert!(!buffer.restore_into(&mut emulator));
+        assert_eq!(emulator.frame, 8);
+    }
+
+    #[test]
+    fn zero_capacity_disables_snapshots() {
+        let mut emulator = TestEmulator::new();
+        let mut buffer = RewindBuffer::new(0, 1);
+
+        advance(&mut emulator, &mut buffer, 10);
+        assert!(buffer.is_empty());
+        assert!(buffer.pop().is_none());
+    }
+
+    #[test]
+    fn pop() {
+        let mut emulator = TestEmulator::new();
+        let mut buffer = RewindBuffer::new(10, 2);
+
+        advance(&mut emulator, &mut buffer, 6);
+        assert_eq!(buffer.len(), 3);
+
+        // Popped snapshots do not carry the ROM
+        let snapshot = buffer.pop().unwrap();
+        assert_eq!(snapshot.frame, 6);
+        assert!(snapshot.rom.is_empty());
+        assert_eq!(buffer.len(), 2);
+
+        assert!(buffer.restore_into(&mut emulator));
+        assert_eq!(emulator.frame, 4);
+    }
+
+    #[test]
+    fn restore_matches_earlier_frame() {
+        let mut emulator = TestEmulator::new();
+        let mut buffer = RewindBuffer::new(10, 3);
+
+        let mut frame_buffers = Vec::new();
+        for _ in 0..8 {
+            advance(&mut emulator, &mut buffer, 1);
+            frame_buffers.push(emulator.frame_buffer.clone());
+        }
+
+        // The most recent snapshot was taken after frame 6, and the ROM is kept
+        assert!(buffer.restore_into(&mut emulator));
+        assert_eq!(emulator.frame_buffer, frame_buffers[5]);
+        assert_eq!(emulator.rom, vec![1, 2, 3]);
+
+        // Running forward from the restored state reproduces the original frames
+        emulator.run_frame();
+        assert_eq!(emulator.frame_buffer, frame_buffers[6]);
+    }
+
+    #[test]
+    fn set_capacity_truncates() {
+        let mut emulator = TestEmulator::new();
+        let mut buffer = RewindBuffer::new(10, 1);
+
+        advance(&mut emulator, &mut buffer, 8);
+        buffer.set_capacity(2);
+        assert_eq!(buffer.capacity(), 2);
+        assert_eq!(buffer.len(), 2);
+
+        // The newest snapshots are kept
+        assert!(buffer.restore_into(&mut emulator));
+        assert_eq!(emulator.frame, 8);
+        assert!(buffer.restore_into(&mut emulator));
+        assert_eq!(emulator.frame, 7);
+    }
+}
@@ -0,0 +1,247 @@
+//! Versioned save state serialization
+//!
+//! Save states are written as a fixed-size header followed by the bincode-encoded emulator state.
+//! The header identifies the emulated system and the state layout version so that incompatible
+//! states are rejected with a clear error instead of being decoded as garbage.
+
+use bincode::config::Config;
+use bincode::error::{DecodeError, EncodeError};
+use bincode::{Decode, Encode};
+use std::io;
+use std::io::{Read, Write};
+use thiserror::Error;
+
+const MAGIC: [u8; 4] = *b"JGST";
+
+#[derive(Debug, Error)]
+pub enum SaveStateError {
+    #[error("I/O error reading/writing save state: {0}")]
+    Io(#[from] io::Error),
+    #[error(
+        "Save state header is invalid; file is not a save state or was written by an older version"
+    )]
+    InvalidMagic,
+    #[error(
+        "Save state is for a different system: expected '{}', found '{}'",
+        String::from_utf8_lossy(.expected),
+        String::from_utf8_lossy(.actual)
+    )]
+    SystemMismatch { expected: [u8; 4], actual: [u8; 4] },
+    #[error(
+        "Save state version {actual} is not supported for system '{}'; current version is {expected}",
+        String::from_utf8_lossy(.system_id)
+    )]
+    UnsupportedVersion { system_id: [u8; 4], expected: u32, actual: u32 },
+    #[error("Error encoding save state: {0}")]
+    Encode(#[from] EncodeError),
+    #[error("Error decoding save state: {0}")]
+    Decode(#[from] DecodeError),
+}
+
+pub trait VersionedState: Sized {
+    /// Identifier for the emulated system, checked when loading states.
+    const SYSTEM_ID: [u8; 4];
+
+    /// Version of the serialized state layout. This should be incremented when the layout changes
+    /// relative to a released version, so that states saved by that release are rejected or
+    /// migrated rather than decoded incorrectly.
+    const STATE_VERSION: u32;
+
+    /// Attempt to load a state with a version other than `STATE_VERSION`. `reader` is positioned
+    /// immediately after the header.
+    ///
+    /// The default implementation rejects all other versions.
+    ///
+    /// # Errors
+    ///
+    /// Should return an error if the state cannot be migrated to the current version.
+    fn migrate(version: u32, reader: &mut dyn Read) -> Result<Self, SaveStateError> {
+        let _ = reader;
+        Err(SaveStateError::UnsupportedVersion {
+            system_id: Self::SYSTEM_ID,
+            expected: Self::STATE_VERSION,
+            actual: version,
+        })
+    }
+}
+
+/// Write a versioned save state.
+///
+/// # Errors
+///
+/// Will propagate any I/O or encoding errors.
+pub fn write_state<S, W, C>(state: &S, writer: &mut W, config: C) -> Result<(), SaveStateError>
+where
+    S: Encode + VersionedState,
+    W: Write,
+    C: Config,
+{
+    writer.write_all(&MAGIC)?;
+    writer.write_all(&S::SYSTEM_ID)?;
+    writer.write_all(&S::STATE_VERSION.to_le_bytes())?;
+    bincode::encode_into_std_write(state, writer, config)?;
+
+    Ok(())
+}
+
+/// Read a versioned save state, migrating it through [`VersionedState::migrate`] if the version
+/// does not match.
+///
+/// # Errors
+///
+/// Will return an error if the header is invalid, if the state is for a different system, if the
+/// version does not match and cannot be migrated, or if any I/O or decoding errors occur.
+pub fn read_state<S, R, C>(reader: &mut R, config: C) -> Result<S, SaveStateError>
+where
+    S: Decode + VersionedState,
+    R: Read,
+    C: Config,
+{
+    let mut header = [0; 12];
+    reader.read_exact(&mut header)?;
+
+    // The header is three 4-byte fields
+    let field = |offset: usize| {
+        [header[offset], header[offset + 1], header[offset + 2], header[offset + 3]]
+    };
+
+    if field(0) != MAGIC {
+        return Err(SaveStateError::InvalidMagic);
+    }
+
+    let system_id = field(4);
+    if system_id != S::SYSTEM_ID {
+        return Err(SaveStateError::SystemMismatch { expected: S::SYSTEM_ID, actual: system_id });
+    }
+
+    let version = u32::from_le_bytes(field(8));
+    if version != S::STATE_VERSION {
+        log::info!(
+            "Save state version is {version}, current version is {}; attempting to migrate",
+            S::STATE_VERSION
+        );
+        return S::migrate(version, reader);
+    }
+
+    Ok(bincode::decode_from_std_read(reader, config)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct TestState {
+        a: u32,
+        b: Vec<u8>,
+    }
+
+    impl VersionedState for TestState {
+        const SYSTEM_ID: [u8; 4] = *b"TEST";
+        const STATE_VERSION: u32 = 2;
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct TestStateV1 {
+        a: u16,
+    }
+
+    impl VersionedState for TestStateV1 {
+        const SYSTEM_ID: [u8; 4] = *b"TEST";
+        const STATE_VERSION: u32 = 1;
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct OtherSystemState;
+
+    impl VersionedState for OtherSystemState {
+        const SYSTEM_ID: [u8; 4] = *b"OTHR";
+        const STATE_VERSION: u32 = 2;
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct MigratingState {
+        a: u32,
+    }
+
+    impl VersionedState for MigratingState {
+        const SYSTEM_ID: [u8; 4] = *b"TEST";
+        const STATE_VERSION: u32 = 2;
+
+        fn migrate(version: u32, mut reader: &mut dyn Read) -> Result<Self, SaveStateError> {
+            match version {
+                1 => {
+                    let old: TestStateV1 =
+                        bincode::decode_from_std_read(&mut reader, bincode::config::standard())?;
+                    Ok(Self { a: old.a.into() })
+                }
+                _ => Err(SaveStateError::UnsupportedVersion {
+                    system_id: Self::SYSTEM_ID,
+                    expected: Self::STATE_VERSION,
+                    actual: version,
+                }),
+            }
+        }
+    }
+
+    fn write_to_vec<S: Encode + VersionedState>(state: &S) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_state(state, &mut bytes, bincode::config::standard()).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn round_trip() {
+        let state = TestState { a: 0x12345678, b: vec![1, 2, 3] };
+        let bytes = write_to_vec(&state);
+        assert_eq!(&bytes[0..12], b"JGSTTEST\x02\x00\x00\x00");
+
+        let loaded: TestState =
+            read_state(&mut bytes.as_slice(), bincode::config::standard()).unwrap();
+        assert_eq!(loaded, state);
+    }
+
+    #[test]
+    fn version_mismatch_rejected() {
+        let bytes = write_to_vec(&TestStateV1 { a: 5 });
+
+        let err = read_state::<TestState, _, _>(&mut bytes.as_slice(), bincode::config::standard())
+            .unwrap_err();
+        assert!(matches!(err, SaveStateError::UnsupportedVersion { expected: 2, actual: 1, .. }));
+        assert_eq!(
+            err.to_string(),
+            "Save state version 1 is not supported for system 'TEST'; current version is 2"
+        );
+    }
+
+    #[test]
+    fn system_mismatch_rejected() {
+        let bytes = write_to_vec(&OtherSystemState);
+
+        let err = read_state::<TestState, _, _>(&mut bytes.as_slice(), bincode::config::standard())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Save state is for a different system: expected 'TEST', found 'OTHR'"
+        );
+    }
+
+    #[test]
+    fn invalid_magic_rejected() {
+        let mut bytes = write_to_vec(&TestState { a: 0, b: vec![] });
+        bytes[0] = b'X';
+
+        let err = read_state::<TestState, _, _>(&mut bytes.as_slice(), bincode::config::standard())
+            .unwrap_err();
+        assert!(matches!(err, SaveStateError::InvalidMagic));
+    }
+
+    #[test]
+    fn migration() {
+        let bytes = write_to_vec(&TestStateV1 { a: 1234 });
+
+        let loaded: MigratingState =
+            read_state(&mut bytes.as_slice(), bincode::config::standard()).unwrap();
+        assert_eq!(loaded, MigratingState { a: 1234 });
+    }
+}
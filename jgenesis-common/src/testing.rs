@@ -0,0 +1,160 @@
+//! No-op and capturing implementations of the frontend traits, for driving emulators in tests
+
+use crate::frontend::{AudioOutput, Color, FrameSize, PixelAspectRatio, Renderer, SaveWriter};
+use bincode::{Decode, Encode};
+use std::convert::Infallible;
+use std::io;
+
+/// Renderer that discards all frames.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NullRenderer;
+
+impl Renderer for NullRenderer {
+    type Err = Infallible;
+
+    fn render_frame(
+        &mut self,
+        _frame_buffer: &[Color],
+        _frame_size: FrameSize,
+        _pixel_aspect_ratio: Option<PixelAspectRatio>,
+    ) -> Result<(), Self::Err> {
+        Ok(())
+    }
+}
+
+/// Audio output that discards all samples.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NullAudioOutput;
+
+impl AudioOutput for NullAudioOutput {
+    type Err = Infallible;
+
+    fn push_sample(&mut self, _sample_l: f64, _sample_r: f64) -> Result<(), Self::Err> {
+        Ok(())
+    }
+}
+
+/// Save writer that has no saved data and discards all writes. Loads always fail with
+/// [`io::ErrorKind::NotFound`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NullSaveWriter;
+
+fn not_found(extension: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, format!("no saved data for extension '{extension}'"))
+}
+
+impl SaveWriter for NullSaveWriter {
+    type Err = io::Error;
+
+    fn load_bytes(&mut self, extension: &str) -> Result<Vec<u8>, Self::Err> {
+        Err(not_found(extension))
+    }
+
+    fn persist_bytes(&mut self, _extension: &str, _bytes: &[u8]) -> Result<(), Self::Err> {
+        Ok(())
+    }
+
+    fn load_serialized<D: Decode>(&mut self, extension: &str) -> Result<D, Self::Err> {
+        Err(not_found(extension))
+    }
+
+    fn persist_serialized<E: Encode>(
+        &mut self,
+        _extension: &str,
+        _data: E,
+    ) -> Result<(), Self::Err> {
+        Ok(())
+    }
+}
+
+/// Renderer that stores a copy of the most recently rendered frame.
+#[derive(Debug, Clone, Default)]
+pub struct CapturingRenderer {
+    frame: Vec<Color>,
+    frame_size: Option<FrameSize>,
+    pixel_aspect_ratio: Option<PixelAspectRatio>,
+    frames_rendered: u64,
+}
+
+impl CapturingRenderer {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The most recently rendered frame, trimmed to (`width` * `height`) pixels. Empty if no
+    /// frames have been rendered.
+    #[must_use]
+    pub fn last_frame(&self) -> &[Color] {
+        &self.frame
+    }
+
+    /// The size of the most recently rendered frame, or None if no frames have been rendered.
+    #[must_use]
+    pub fn last_frame_size(&self) -> Option<FrameSize> {
+        self.frame_size
+    }
+
+    #[must_use]
+    pub fn last_pixel_aspect_ratio(&self) -> Option<PixelAspectRatio> {
+        self.pixel_aspect_ratio
+    }
+
+    #[must_use]
+    pub fn frames_rendered(&self) -> u64 {
+        self.frames_rendered
+    }
+}
+
+impl Renderer for CapturingRenderer {
+    type Err = Infallible;
+
+    fn render_frame(
+        &mut self,
+        frame_buffer: &[Color],
+        frame_size: FrameSize,
+        pixel_aspect_ratio: Option<PixelAspectRatio>,
+    ) -> Result<(), Self::Err> {
+        let len = (frame_size.width * frame_size.height) as usize;
+        self.frame.clear();
+        self.frame.extend_from_slice(&frame_buffer[..len]);
+        self.frame_size = Some(frame_size);
+        self.pixel_aspect_ratio = pixel_aspect_ratio;
+        self.frames_rendered += 1;
+
+        Ok(())
+    }
+}
+
+/// Audio output that stores every sample pushed to it.
+#[derive(Debug, Clone, Default)]
+pub struct CapturingAudioOutput {
+    samples: Vec<(f64, f64)>,
+}
+
+impl CapturingAudioOutput {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All samples pushed so far, as (left, right) pairs.
+    #[must_use]
+    pub fn samples(&self) -> &[(f64, f64)] {
+        &self.samples
+    }
+
+    /// Remove and return all samples pushed so far.
+    pub fn take_samples(&mut self) -> Vec<(f64, f64)> {
+        std::mem::take(&mut self.samples)
+    }
+}
+
+impl AudioOutput for CapturingAudioOutput {
+    type Err = Infallible;
+
+    fn push_sample(&mut self, sample_l: f64, sample_r: f64) -> Result<(), Self::Err> {
+        self.samples.push((sample_l, sample_r));
+        Ok(())
+    }
+}
@@ -53,6 +53,7 @@ impl Parse for JoypadStruct {
 enum InputsField {
     Player(Ident),
     Button(Ident),
+    Other(Type),
 }
 
 struct InputsStruct {
@@ -73,26 +74,11 @@ impl Parse for InputsStruct {
         let raw_fields = content.parse_terminated(Field::parse_named, Token![,])?;
         for raw_field in raw_fields {
             let field_name = raw_field.ident.unwrap();
-            let Type::Path(path) = raw_field.ty else {
-                return Err(content.error("Expected type path"));
-            };
-
-            let segments: Vec<_> = path.path.segments.into_iter().collect();
-            if segments.len() != 2 {
-                return Err(content
-                    .error(format!("Expected type path of length 2, was {}", segments.len())));
-            }
 
-            let field = match segments[0].ident.to_string().as_str() {
-                "Player" => InputsField::Player(segments[1].ident.clone()),
-                "Button" => InputsField::Button(segments[1].ident.clone()),
-                _ => {
-                    return Err(content.error(format!(
-                        "Expected 'Player' or 'Button', got '{}'",
-                        segments[0].ident
-                    )));
-                }
-            };
+            // Fields typed as Player::<player> or Button::<button> map to controller inputs; any
+            // other type is passed through as-is (e.g. for analog inputs)
+            let field =
+                parse_input_path(&raw_field.ty).unwrap_or_else(|| InputsField::Other(raw_field.ty));
             fields.push((field_name, field));
         }
 
@@ -100,6 +86,21 @@ impl Parse for InputsStruct {
     }
 }
 
+fn parse_input_path(ty: &Type) -> Option<InputsField> {
+    let Type::Path(path) = ty else { return None };
+
+    let segments: Vec<_> = path.path.segments.iter().collect();
+    if segments.len() != 2 || !segments.iter().all(|segment| segment.arguments.is_none()) {
+        return None;
+    }
+
+    match segments[0].ident.to_string().as_str() {
+        "Player" => Some(InputsField::Player(segments[1].ident.clone())),
+        "Button" => Some(InputsField::Button(segments[1].ident.clone())),
+        _ => None,
+    }
+}
+
 struct MacroInput {
     button_enum: ButtonEnum,
     joypad_struct: JoypadStruct,
@@ -259,6 +260,11 @@ fn generate_inputs_struct(
                     (_, #button_name::#button) => self.#field_name = pressed
                 });
             }
+            InputsField::Other(ty) => {
+                fields.push(quote! {
+                    pub #field_name: #ty
+                });
+            }
         }
     }
 
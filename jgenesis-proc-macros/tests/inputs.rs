@@ -21,6 +21,7 @@ define_controller_inputs! {
         p1: Player::One,
         p2: Player::Two,
         pause: Button::Pause,
+        light_phaser: Option<(u16, u16)>,
     }
 }
 
@@ -61,4 +62,9 @@ fn inputs_struct() {
     inputs = inputs.with_button(SmsGgButton::Button2, Player::One, true);
     assert!(inputs.p1.button2);
     assert!(inputs.p2.left);
+
+    assert_eq!(inputs.light_phaser, None);
+    inputs.light_phaser = Some((10, 20));
+    inputs.set_button(SmsGgButton::Up, Player::One, true);
+    assert_eq!(inputs.light_phaser, Some((10, 20)));
 }